@@ -47,21 +47,22 @@ pub fn derive_binary_serializable(input: TokenStream) -> TokenStream {
 						parse_quote!(iter: &mut I),
 						parse_quote!(backing_source: ::source_map::SourceId),
 					],
-					Some(parse_quote!(Self)),
+					Some(parse_quote!(Result<Self, crate::DeserializeError>)),
 					|structure| {
 						let deserialize_call: Expr = parse_quote!(
-							crate::BinarySerializable::deserialize(iter, backing_source)
+							crate::BinarySerializable::deserialize(iter, backing_source)?
 						);
 
 						match structure {
 							Structure::Enum(r#enum) => {
-								let indexer: Stmt =
-									parse_quote!(let indexer = iter.next().unwrap(););
-
-								let bad_case = parse_quote!(
-									unreachable!("invalid discriminant when deserializing enum");
+								let indexer: Stmt = parse_quote!(
+									let indexer = iter.next().ok_or(crate::DeserializeError::UnexpectedEnd)?;
 								);
 
+								let bad_case = Stmt::Expr(parse_quote!(Err(
+									crate::DeserializeError::InvalidDiscriminant
+								)));
+
 								Ok(std::iter::once(indexer)
 									.chain(r#enum.get_variants().iter().map(|variant| {
 										let idx = variant.idx as u8;
@@ -70,7 +71,7 @@ pub fn derive_binary_serializable(input: TokenStream) -> TokenStream {
 											.unwrap();
 
 										parse_quote!(if indexer == #idx {
-											return #constructor;
+											return Ok(#constructor);
 										})
 									}))
 									.chain(std::iter::once(bad_case))
@@ -78,7 +79,7 @@ pub fn derive_binary_serializable(input: TokenStream) -> TokenStream {
 							}
 							Structure::Struct(r#struct) => r#struct
 								.build_constructor(|_| Ok(deserialize_call.clone()))
-								.map(|expr| vec![Stmt::Expr(expr)]),
+								.map(|expr| vec![Stmt::Expr(parse_quote!(Ok(#expr)))]),
 						}
 					},
 				),
@@ -161,6 +161,8 @@ interface Function {
 interface Symbols {
     // TODO temp
     iterator: 199
+    dispose: 200
+    asyncDispose: 201
 }
 
 declare var Symbol: Symbols;
@@ -184,8 +186,17 @@ declare var Math: Math;
 declare var console: Console;
 declare var Object: Object;
 
-declare function JSXH(tag: string, attributes: any, children?: any) performs {
-    return { tag, attributes, children }
+type Partial<T> = { [P in keyof T]?: T[P] };
+type Required<T> = { [P in keyof T]-?: T[P] };
+type Readonly<T> = { readonly [P in keyof T]: T[P] };
+type Record<K, T> = { [P in K]: T };
+type Pick<T, K extends keyof T> = { [P in K]: T[P] };
+type Exclude<T, U> = T extends U ? never : T;
+type Extract<T, U> = T extends U ? T : never;
+type Omit<T, K extends keyof any> = { [P in keyof T as Exclude<P, K>]: T[P] };
+
+declare function JSXH(tag: string, attributes: any) performs {
+    return { tag, attributes }
 }
 
 interface Document {
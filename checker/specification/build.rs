@@ -60,10 +60,14 @@ fn markdown_lines_append_test_to_rust(
 		let heading = line.strip_prefix("####").unwrap().trim_start();
 		let test_title = heading_to_rust_identifier(heading);
 
+		let mut options = Vec::new();
 		let blocks = {
 			let mut blocks = Vec::new();
 			let mut current_filename = None;
 			for (_, line) in lines.by_ref() {
+				if let Some(names) = line.strip_prefix("> Options: ") {
+					options.extend(names.split(',').map(|name| format!("\"{}\"", name.trim())));
+				}
 				if line == "```ts" {
 					break;
 				}
@@ -96,9 +100,13 @@ fn markdown_lines_append_test_to_rust(
 				if line.starts_with("#") {
 					panic!("block with no diagnostics or break between in {test_title}")
 				} else if line.starts_with('-') {
-					let error =
-						line.strip_prefix("- ").unwrap().replace('\\', "").replace('"', "\\\"");
-					errors.push(format!("\"{}\"", error))
+					errors.push(line.strip_prefix("- ").unwrap().replace('\\', ""));
+				} else if let (Some(note), Some(error)) =
+					(line.strip_prefix("  - "), errors.last_mut())
+				{
+					// Notes (labels without a position) of the previous diagnostic
+					error.push('\n');
+					error.push_str(&note.replace('\\', ""));
 				} else if !errors.is_empty() {
 					break;
 				}
@@ -106,7 +114,12 @@ fn markdown_lines_append_test_to_rust(
 			errors
 		};
 
-		let errors = errors.join(", ");
+		let errors = errors
+			.into_iter()
+			.map(|error| format!("\"{}\"", error.replace('"', "\\\"").replace('\n', "\\n")))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let options = options.join(", ");
 
 		let heading_idx = heading_idx + 1;
 		let code = blocks
@@ -120,7 +133,7 @@ fn markdown_lines_append_test_to_rust(
 		writeln!(
 			out,
 			"#[test] fn {test_title}() {{ 
-                super::check_errors(\"{heading}\", {heading_idx}, &[{code}], &[{errors}])
+                super::check_errors(\"{heading}\", {heading_idx}, &[{code}], &[{errors}], &[{options}])
             }}",
 		)?;
 	}
@@ -30,6 +30,8 @@ fn check_errors(
 	// (Path, Content)
 	code: &[(&'static str, &'static str)],
 	expected_diagnostics: &[&'static str],
+	// Names of `TypeCheckOptions` fields to enable
+	options: &[&'static str],
 ) {
 	// let global_buffer = Arc::new(Mutex::new(String::new()));
 	// let old_panic_hook = panic::take_hook();
@@ -46,8 +48,16 @@ fn check_errors(
 	// 	})
 	// });
 
-	// TODO could test these
-	let type_check_options = None;
+	let type_check_options = (!options.is_empty()).then(|| {
+		let mut type_check_options = checker::TypeCheckOptions::default();
+		for option in options {
+			match *option {
+				"allow_type_casts" => type_check_options.allow_type_casts = true,
+				option => panic!("unknown option {option:?}"),
+			}
+		}
+		type_check_options
+	});
 
 	// eprintln!("{:?}", code);
 
@@ -78,12 +88,26 @@ fn check_errors(
 		.diagnostics
 		.into_iter()
 		.map(|diag| {
+			// Notes are appended on new lines
+			let notes = if let diagnostics::Diagnostic::PositionWithAdditionalLabels {
+				ref labels,
+				..
+			} = diag
+			{
+				labels
+					.iter()
+					.filter(|(_, position)| position.is_none())
+					.map(|(note, _)| format!("\n{note}"))
+					.collect()
+			} else {
+				String::new()
+			};
 			let (reason, pos) = diag.reason_and_position();
 			if let Some(pos) = pos {
 				// TODO position
-				reason
+				reason + &notes
 			} else {
-				reason
+				reason + &notes
 			}
 		})
 		.collect();
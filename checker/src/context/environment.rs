@@ -1,5 +1,5 @@
 use source_map::{SourceId, Span, SpanWithSource};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
 	diagnostics::{NotInLoopOrCouldNotFindLabel, TypeCheckError, TypeStringRepresentation, TDZ},
@@ -111,6 +111,10 @@ pub enum FunctionScope {
 		free_this_type: TypeId,
 		is_async: bool,
 		is_generator: bool,
+		/// What `super` refers to. [`None`] for methods on object literals
+		type_of_super: Option<TypeId>,
+		/// For `#private` properties. [`None`] for methods on object literals
+		class_prototype: Option<TypeId>,
 	},
 	// is new-able
 	Function {
@@ -126,6 +130,8 @@ pub enum FunctionScope {
 		type_of_super: Option<TypeId>,
 		// This is always creates, but may not be used (or have the relevant properties & prototype)
 		this_object_type: TypeId,
+		/// For `#private` properties
+		class_prototype: TypeId,
 	},
 }
 
@@ -145,26 +151,33 @@ pub enum Scope {
 	Conditional {
 		/// Something that is truthy for this to run
 		antecedent: TypeId,
-
-		is_switch: Option<Label>,
 	},
 	/// Variables here are dependent on the iteration,
 	Iteration {
 		label: Label, // TODO on: Proofs,
 	},
+	/// Cases are [`Scope::Conditional`]s inside of this. `break`s target this
+	Switch {
+		label: Label,
+	},
 	TryBlock {},
 	// Just blocks and modules
 	Block {},
 	Module {
 		source: SourceId,
 		exported: Exported,
+		/// Specifiers to the modules they resolved to
+		imports: HashMap<String, SourceId>,
 	},
 	DefinitionModule {
 		source: SourceId,
 	},
 	/// For generic parameters
 	TypeAlias,
-	StaticBlock {},
+	StaticBlock {
+		/// For `#private` properties
+		class_prototype: TypeId,
+	},
 	/// For repl only
 	PassThrough {
 		source: SourceId,
@@ -256,16 +269,16 @@ impl<'a> Environment<'a> {
 						SetPropertyError::NotWriteable => {
 							TypeCheckError::PropertyNotWriteable(assignment_span)
 						}
-						SetPropertyError::DoesNotMeetConstraint {
-							property_constraint,
-							reason: _,
-						} => TypeCheckError::AssignmentError(AssignmentError::PropertyConstraint {
-							property_constraint,
-							value_type: TypeStringRepresentation::from_type_id(
-								new, ctx, types, false,
-							),
-							assignment_position: assignment_span,
-						}),
+						SetPropertyError::DoesNotMeetConstraint { property_constraint, reason } => {
+							TypeCheckError::AssignmentError(AssignmentError::PropertyConstraint {
+								property_constraint,
+								value_type: TypeStringRepresentation::from_type_id(
+									new, ctx, types, false,
+								),
+								assignment_position: assignment_span,
+								mismatch: reason.into_representation(ctx, types),
+							})
+						}
 					}
 				}
 
@@ -435,6 +448,15 @@ impl<'a> Environment<'a> {
 		new_type: TypeId,
 		checking_data: &mut CheckingData<T, A>,
 	) -> TypeId {
+		if checking_data.options.store_expression_type_mappings {
+			if let Some((_, _, variable)) = self.get_variable_unbound(variable_name) {
+				checking_data.type_mappings.variable_references.insert(
+					(assignment_position.source, assignment_position.start),
+					variable.get_id(),
+				);
+			}
+		}
+
 		let result = self.assign_to_variable(
 			variable_name,
 			assignment_position,
@@ -488,7 +510,7 @@ impl<'a> Environment<'a> {
 									store,
 								);
 
-								if let SubTypeResult::IsNotSubType(_mismatches) = result {
+								if let SubTypeResult::IsNotSubType(mismatch) = result {
 									return Err(AssignmentError::DoesNotMeetConstraint {
 										variable_type: TypeStringRepresentation::from_type_id(
 											reassignment_constraint,
@@ -504,6 +526,8 @@ impl<'a> Environment<'a> {
 										),
 										variable_site,
 										value_site: assignment_position,
+										mismatch: mismatch
+											.into_representation(&self.as_general_context(), store),
 									});
 								}
 							}
@@ -687,6 +711,13 @@ impl<'a> Environment<'a> {
 
 		let reference = RootReference::Variable(og_var.get_id());
 
+		if checking_data.options.store_expression_type_mappings {
+			checking_data
+				.type_mappings
+				.variable_references
+				.insert((position.source, position.start), og_var.get_id());
+		}
+
 		if let VariableOrImport::Variable { context: Some(ref context), .. } = og_var {
 			if let Some(ref current_context) = self.parents_iter().find_map(|a| {
 				if let GeneralContext::Syntax(syn) = a {
@@ -883,10 +914,15 @@ impl<'a> Environment<'a> {
 		}
 
 		let (truthy_result, truthy_events) = {
-			let mut truthy_environment = self.new_lexical_environment(Scope::Conditional {
-				antecedent: condition,
-				is_switch: None,
-			});
+			let mut truthy_environment =
+				self.new_lexical_environment(Scope::Conditional { antecedent: condition });
+
+			crate::features::narrowing::narrow_based_on_condition(
+				condition,
+				false,
+				&mut truthy_environment,
+				&mut checking_data.types,
+			);
 
 			let result = then_evaluate(&mut truthy_environment, checking_data);
 
@@ -896,9 +932,15 @@ impl<'a> Environment<'a> {
 		if let Some(else_evaluate) = else_evaluate {
 			let mut falsy_environment = self.new_lexical_environment(Scope::Conditional {
 				antecedent: checking_data.types.new_logical_negation_type(condition),
-				is_switch: None,
 			});
 
+			crate::features::narrowing::narrow_based_on_condition(
+				condition,
+				true,
+				&mut falsy_environment,
+				&mut checking_data.types,
+			);
+
 			let falsy_result = else_evaluate(&mut falsy_environment, checking_data);
 
 			let combined_result =
@@ -1028,7 +1070,7 @@ impl<'a> Environment<'a> {
 					| Scope::Module { .. }
 					| Scope::DefinitionModule { .. }
 					| Scope::TypeAlias
-					| Scope::StaticBlock {} => {
+					| Scope::StaticBlock { .. } => {
 						break;
 					}
 					Scope::Iteration { ref label } => {
@@ -1041,10 +1083,14 @@ impl<'a> Environment<'a> {
 						}
 						falling_through_structures += 1;
 					}
-					Scope::Conditional { is_switch: Some(_label @ Some(_)), .. }
-						if !is_continue && looking_for_label.is_some() =>
-					{
-						todo!("switch break")
+					Scope::Switch { ref label } => {
+						if !is_continue
+							&& (looking_for_label.is_none()
+								|| label.as_deref() == looking_for_label)
+						{
+							return Some(falling_through_structures);
+						}
+						falling_through_structures += 1;
 					}
 					Scope::PassThrough { .. }
 					| Scope::Conditional { .. }
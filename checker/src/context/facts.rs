@@ -5,8 +5,8 @@ use source_map::SpanWithSource;
 use crate::{
 	events::{Event, RootReference},
 	features::functions::{ClosureId, ThisValue},
-	types::properties::PropertyKey,
-	PropertyValue, Type, TypeId, VariableId,
+	types::properties::{PropertyKey, PropertyModifiers},
+	FunctionId, PropertyValue, Type, TypeId, VariableId,
 };
 
 /// TODO explain usage
@@ -17,7 +17,7 @@ pub enum Publicity {
 }
 
 /// Things that are currently true or have happened
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, binary_serialize_derive::BinarySerializable)]
 pub struct Facts {
 	pub(crate) events: Vec<Event>,
 	/// TODO think about tasks. These are things that may happen at next stop point
@@ -35,6 +35,13 @@ pub struct Facts {
 	pub(crate) enumerable: HashMap<(TypeId, TypeId), TypeId>,
 	pub(crate) writable: HashMap<(TypeId, TypeId), TypeId>,
 	pub(crate) frozen: HashMap<TypeId, TypeId>,
+	/// Only non default modifiers are stored
+	pub(crate) property_modifiers: HashMap<(TypeId, PropertyKey<'static>), PropertyModifiers>,
+	/// Call signatures (`(x: T): U`) of interfaces and object type annotations. In declaration
+	/// order, as overloads are tried in order
+	pub(crate) call_signatures: HashMap<TypeId, Vec<FunctionId>>,
+	/// Construct signatures (`new (x: T): U`)
+	pub(crate) construct_signatures: HashMap<TypeId, Vec<FunctionId>>,
 
 	/// For super calls etc
 	///
@@ -120,6 +127,17 @@ impl Facts {
 		self.current_properties.get(&ty)
 	}
 
+	pub(crate) fn set_property_modifiers(
+		&mut self,
+		on: TypeId,
+		under: PropertyKey<'static>,
+		modifiers: PropertyModifiers,
+	) {
+		if modifiers != PropertyModifiers::default() {
+			self.property_modifiers.insert((on, under), modifiers);
+		}
+	}
+
 	pub(crate) fn extend(&mut self, other: Facts, condition: Option<TypeId>) {
 		if condition.is_some() {
 			todo!()
@@ -134,6 +152,9 @@ impl Facts {
 		self.enumerable.extend(other.enumerable);
 		self.writable.extend(other.writable);
 		self.frozen.extend(other.frozen);
+		self.property_modifiers.extend(other.property_modifiers);
+		self.call_signatures.extend(other.call_signatures);
+		self.construct_signatures.extend(other.construct_signatures);
 	}
 
 	pub(crate) fn extend_ref(&mut self, other: &Facts) {
@@ -149,5 +170,10 @@ impl Facts {
 		self.enumerable.extend(other.enumerable.iter().clone());
 		self.writable.extend(other.writable.iter().clone());
 		self.frozen.extend(other.frozen.iter().clone());
+		self.property_modifiers
+			.extend(other.property_modifiers.iter().map(|(l, r)| (l.clone(), *r)));
+		self.call_signatures.extend(other.call_signatures.iter().map(|(l, r)| (*l, r.clone())));
+		self.construct_signatures
+			.extend(other.construct_signatures.iter().map(|(l, r)| (*l, r.clone())));
 	}
 }
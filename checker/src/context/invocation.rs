@@ -1,7 +1,10 @@
 //! When a function is called (or a group of events like function such as a iteration block) it creates a mini-environment for which events are applied into
 
 use super::facts::Facts;
-use crate::{events::FinalEvent, Environment, FunctionId};
+use crate::{
+	events::{Event, FinalEvent},
+	Environment, FunctionId, TypeId,
+};
 
 /// For anything that might involve a call, including gets, sets and actual calls
 pub trait CallCheckingBehavior {
@@ -49,6 +52,8 @@ pub(crate) enum InvocationKind {
 	Conditional(Facts),
 	Function(FunctionId),
 	LoopIteration,
+	/// Collects yielded values. `None` if they are not known
+	Generator(Option<Vec<TypeId>>),
 }
 
 impl CallCheckingBehavior for InvocationContext {
@@ -113,6 +118,81 @@ impl InvocationContext {
 		value
 	}
 
+	/// Generator bodies run as they are iterated rather than when called. So events are applied into
+	/// separate facts (which are discarded) and `yield`ed values are collected.
+	/// Ended by [`Self::end_generator`]
+	pub(crate) fn start_generator(&mut self) {
+		self.0.push(InvocationKind::Generator(Some(Vec::new())));
+		self.0.push(InvocationKind::Conditional(Facts::default()));
+	}
+
+	/// Returns the values yielded in order, if they are known
+	pub(crate) fn end_generator(&mut self, environment: &mut Environment) -> Option<Vec<TypeId>> {
+		fn contains_yield(events: &[Event]) -> bool {
+			events.iter().any(|event| match event {
+				Event::Yield { .. } => true,
+				Event::Conditionally { true_events, else_events, .. } => {
+					contains_yield(true_events) || contains_yield(else_events)
+				}
+				Event::Iterate { iterate_over: events, .. } | Event::Deferred { events, .. } => {
+					contains_yield(events)
+				}
+				_ => false,
+			})
+		}
+
+		let Some(InvocationKind::Conditional(mut facts)) = self.0.pop() else { unreachable!() };
+		let Some(InvocationKind::Generator(yielded)) = self.0.pop() else { unreachable!() };
+
+		// Objects created in the generator (which may be yielded) are kept
+		let outer = self.get_latest_facts(environment);
+		for event in &facts.events {
+			if let Event::CreateObject { referenced_in_scope_as: object, .. } = event {
+				if let Some(properties) = facts.current_properties.remove(object) {
+					outer.current_properties.insert(*object, properties);
+				}
+				if let Some(prototype) = facts.prototypes.remove(object) {
+					outer.prototypes.insert(*object, prototype);
+				}
+			}
+		}
+
+		// Yields under unknown conditions or in loops that were not unrolled make the order unknown
+		let nested_yield = facts.events.iter().any(|event| {
+			!matches!(event, Event::Yield { .. }) && contains_yield(std::slice::from_ref(event))
+		});
+		yielded.filter(|_| !nested_yield)
+	}
+
+	/// Adds to the current generator. `None` marks the yielded values as unknown
+	pub(crate) fn add_yielded(&mut self, values: Option<Vec<TypeId>>) {
+		let generator = self.0.iter_mut().rev().find_map(|kind| {
+			if let InvocationKind::Generator(yielded) = kind {
+				Some(yielded)
+			} else {
+				None
+			}
+		});
+		if let Some(yielded) = generator {
+			match (yielded.as_mut(), values) {
+				(Some(yielded), Some(values)) => yielded.extend(values),
+				_ => *yielded = None,
+			}
+		}
+	}
+
+	/// Facts from conditional (and generator) targets, latest first. These come before the
+	/// environment's facts
+	pub(crate) fn facts_chain(&self) -> impl Iterator<Item = &'_ Facts> {
+		self.0.iter().rev().filter_map(|kind| {
+			if let InvocationKind::Conditional(facts) = kind {
+				Some(facts)
+			} else {
+				None
+			}
+		})
+	}
+
 	pub(crate) fn get_iteration_depth(&self) -> u8 {
 		let depth =
 			self.0.iter().filter(|p| matches!(p, InvocationKind::LoopIteration)).count() as u8;
@@ -18,7 +18,8 @@ use source_map::{Span, SpanWithSource};
 
 use crate::{
 	diagnostics::{
-		CannotRedeclareVariable, TypeCheckError, TypeCheckWarning, TypeStringRepresentation,
+		CannotRedeclareVariable, NonEqualityRepresentation, TypeCheckError, TypeCheckWarning,
+		TypeStringRepresentation,
 	},
 	events::RootReference,
 	features::{
@@ -29,7 +30,7 @@ use crate::{
 	types::{
 		get_constraint,
 		poly_types::generic_type_arguments::StructureGenericArguments,
-		properties::{PropertyKey, PropertyValue},
+		properties::{PropertyKey, PropertyModifiers, PropertyValue},
 		FunctionType, PolyNature, Type, TypeId, TypeStore,
 	},
 	CheckingData, DiagnosticsContainer, FunctionId, VariableId,
@@ -154,6 +155,9 @@ pub struct Names {
 
 	/// For debugging only
 	pub(crate) variable_names: HashMap<VariableId, String>,
+
+	/// From `export default` or `export =` in a definition file
+	pub(crate) default_export: Option<TypeId>,
 }
 
 #[derive(Debug)]
@@ -366,6 +370,7 @@ impl<T: ContextType> Context<T> {
 					Scope::FunctionAnnotation {} => "function reference",
 					Scope::Conditional { .. } => "conditional",
 					Scope::Iteration { .. } => "iteration",
+					Scope::Switch { .. } => "switch",
 					Scope::TryBlock { .. } => "try",
 					Scope::Block {} => "block",
 					Scope::Module { .. } => "module",
@@ -421,6 +426,7 @@ impl<T: ContextType> Context<T> {
 				Scope::FunctionAnnotation {} => todo!(),
 				Scope::Conditional { .. }
 				| Scope::Iteration { .. }
+				| Scope::Switch { .. }
 				| Scope::StaticBlock { .. }
 				| Scope::Function(_)
 				| Scope::TryBlock { .. }
@@ -436,7 +442,7 @@ impl<T: ContextType> Context<T> {
 	}
 
 	/// Similar to [`Context::get_this_unbound`]
-	fn get_variable_unbound(
+	pub(crate) fn get_variable_unbound(
 		&self,
 		variable_name: &str,
 	) -> Option<(bool, Option<Boundary>, &VariableOrImport)> {
@@ -605,6 +611,35 @@ impl<T: ContextType> Context<T> {
 		self.parents_iter().find_map(|ctx| get_on_ctx!(ctx.facts.frozen.get(&value))).copied()
 	}
 
+	/// Whether the property is optional or readonly (in a type annotation)
+	pub(crate) fn get_property_modifiers(
+		&self,
+		on: TypeId,
+		under: &PropertyKey,
+	) -> PropertyModifiers {
+		let key = (on, under.into_owned());
+		self.parents_iter()
+			.find_map(|ctx| get_on_ctx!(ctx.facts.property_modifiers.get(&key)))
+			.copied()
+			.unwrap_or_default()
+	}
+
+	/// Call signatures of an interface or object type annotation (construct signatures if
+	/// `is_new`). Empty if it is not callable
+	pub(crate) fn get_signatures(&self, on: TypeId, is_new: bool) -> Vec<crate::FunctionId> {
+		self.parents_iter()
+			.find_map(|ctx| {
+				let facts = get_on_ctx!(&ctx.facts);
+				if is_new {
+					facts.construct_signatures.get(&on)
+				} else {
+					facts.call_signatures.get(&on)
+				}
+			})
+			.cloned()
+			.unwrap_or_default()
+	}
+
 	// TODO temp declaration
 	// TODO should check the TypeId::is_primitive... via aliases + open_poly
 	pub(crate) fn _is_immutable(&self, _value: TypeId) -> bool {
@@ -731,6 +766,12 @@ impl<T: ContextType> Context<T> {
 			current_closed_references.extend(closed_over_references);
 		}
 
+		// `break`s that target the switch end execution of the remaining events here
+		if let Scope::Switch { .. } = scope {
+			let events = mem::take(&mut facts.events);
+			facts.events = crate::events::helpers::consume_break_events(events);
+		}
+
 		// Run any truths through subtyping
 		let additional = match scope {
 			// TODO might go
@@ -762,6 +803,7 @@ impl<T: ContextType> Context<T> {
 			Scope::InterfaceEnvironment { .. }
 			| Scope::TypeAlias
 			| Scope::Block {}
+			| Scope::Switch { .. }
 			| Scope::TryBlock {}
 			| Scope::PassThrough { .. }
 			| Scope::Module { .. }
@@ -799,12 +841,20 @@ impl<T: ContextType> Context<T> {
 
 				// TODO don't need to clone all the time
 				self.facts.prototypes.extend(facts.prototypes.clone());
+				// For generic interfaces
+				self.facts.call_signatures.extend(mem::take(&mut facts.call_signatures));
+				self.facts.construct_signatures.extend(mem::take(&mut facts.construct_signatures));
 
 				// TODO also lift vars, regardless of scope
 				if matches!(scope, Scope::PassThrough { .. }) {
 					self.variables.extend(variables);
 					self.facts.variable_current_value.extend(facts.variable_current_value);
 					None
+				} else if matches!(scope, Scope::Switch { .. }) {
+					// Cases that are definitely run are synthesised directly in the switch scope
+					self.facts.variable_current_value.extend(facts.variable_current_value);
+					self.facts.events.append(&mut facts.events);
+					None
 				} else if self.context_type.get_parent().is_some() {
 					self.facts.events.append(&mut facts.events);
 					None
@@ -943,10 +993,10 @@ impl<T: ContextType> Context<T> {
 				.iter()
 				.map(|parameter| {
 					let name = A::type_parameter_name(parameter).to_owned();
-					let ty = Type::RootPolyType(PolyNature::Generic {
-						name: name.clone(),
-						eager_fixed: TypeId::ANY_TYPE,
-					});
+					let eager_fixed =
+						A::synthesise_type_parameter_extends(parameter, &mut env, checking_data);
+					let ty =
+						Type::RootPolyType(PolyNature::Generic { name: name.clone(), eager_fixed });
 					let ty = checking_data.types.register_type(ty);
 					// TODO declare type
 					env.named_types.insert(name, ty);
@@ -962,6 +1012,11 @@ impl<T: ContextType> Context<T> {
 			(None, to)
 		};
 
+		// Keep properties of object literal types in the alias
+		let facts = mem::take(&mut env.facts);
+		drop(env);
+		self.facts.extend(facts, None);
+
 		// Works as an alias
 		let ty = Type::AliasTo { to, name: name.to_owned(), parameters };
 		let alias_ty = checking_data.types.register_type(ty);
@@ -1061,6 +1116,84 @@ impl<T: ContextType> Context<T> {
 			.unwrap()
 	}
 
+	/// The function scope `super` is relative to. Arrow functions use the `super` of their parent
+	pub(crate) fn get_super_scope(&self) -> Option<FunctionScope> {
+		self.parents_iter()
+			.find_map(|env| {
+				if let GeneralContext::Syntax(ctx) = env {
+					match ctx.context_type.scope {
+						Scope::Function(FunctionScope::ArrowFunction { .. }) => None,
+						Scope::Function(ref scope) => Some(Some(scope.clone())),
+						_ => None,
+					}
+				} else {
+					Some(None)
+				}
+			})
+			.flatten()
+	}
+
+	/// Whether the `#private` properties of `on` can be used here. They can only be used in the body
+	/// of the class that declares them (including nested functions and classes)
+	pub(crate) fn can_use_private_properties_of(&self, on: TypeId, types: &TypeStore) -> bool {
+		let class_prototypes = self
+			.parents_iter()
+			.filter_map(|env| {
+				if let GeneralContext::Syntax(ctx) = env {
+					match ctx.context_type.scope {
+						Scope::Function(
+							FunctionScope::Constructor { class_prototype, .. }
+							| FunctionScope::MethodFunction {
+								class_prototype: Some(class_prototype),
+								..
+							},
+						)
+						| Scope::StaticBlock { class_prototype } => Some(class_prototype),
+						_ => None,
+					}
+				} else {
+					None
+				}
+			})
+			.collect::<Vec<_>>();
+
+		if class_prototypes.is_empty() {
+			return false;
+		}
+		let on = if types.get_type_by_id(on).is_dependent() {
+			get_constraint(on, types).unwrap_or(TypeId::ANY_TYPE)
+		} else {
+			on
+		};
+		// Only objects and classes belong to a class. Others, such as `this` in methods and
+		// annotations, are checked when the property is looked up
+		if !matches!(
+			types.get_type_by_id(on),
+			Type::Object(crate::types::ObjectNature::RealDeal)
+				| Type::Function(..)
+				| Type::FunctionReference(..)
+		) {
+			return true;
+		}
+
+		// Instances, through their prototype chain
+		let mut prototype = Some(on);
+		while let Some(current) = prototype {
+			if class_prototypes.contains(&current) {
+				return true;
+			}
+			prototype = self
+				.parents_iter()
+				.find_map(|ctx| get_on_ctx!(ctx.facts.prototypes.get(&current)).copied());
+		}
+
+		// Static properties, on the class itself
+		self.get_properties_on_type(on).into_iter().any(|(_, key, value)| {
+			matches!(key, PropertyKey::String(ref name) if name == "prototype")
+				&& class_prototypes.contains(&value)
+		})
+	}
+
 	pub fn get_source(&self) -> source_map::SourceId {
 		self.parents_iter()
 			.find_map(|ctx| {
@@ -1113,16 +1246,18 @@ pub enum AssignmentError {
 		variable_site: SpanWithSource,
 		value_type: TypeStringRepresentation,
 		value_site: SpanWithSource,
+		mismatch: NonEqualityRepresentation,
 	},
 	PropertyConstraint {
 		property_constraint: TypeStringRepresentation,
 		value_type: TypeStringRepresentation,
 		assignment_position: SpanWithSource,
+		mismatch: NonEqualityRepresentation,
 	},
 }
 
 /// Completely magic!
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Logical<T> {
 	Pure(T),
 	Or {
@@ -6,10 +6,10 @@ use crate::{
 		modules::{Exported, SynthesisedModule},
 		variables::VariableOrImport,
 	},
-	types::TypeId,
-	CheckingData, GeneralContext,
+	types::{TypeId, TypeStore},
+	BinarySerializable, CheckingData, GeneralContext,
 };
-use source_map::SourceId;
+use source_map::{FileSystem, MapFileStore, Nullable, SourceId, WithPathMap};
 use std::{collections::HashMap, iter::FromIterator};
 
 pub type RootContext = Context<Root>;
@@ -43,7 +43,7 @@ impl ContextType for Root {
 	}
 }
 
-const _CONTEXT_FILE_HEADER: &[u8] = b"EZNO\0CONTEXT\0FILE";
+const CONTEXT_FILE_HEADER: &[u8] = b"EZNO\0CONTEXT\0FILE";
 
 impl RootContext {
 	/// Merges two [`RootEnvironments`]. May be used for multiple `.d.ts` files
@@ -65,6 +65,8 @@ impl RootContext {
 			("undefined".to_owned(), TypeId::UNDEFINED_TYPE),
 			("void".to_owned(), TypeId::VOID_TYPE),
 			("Array".to_owned(), TypeId::ARRAY_TYPE),
+			("Promise".to_owned(), TypeId::PROMISE_TYPE),
+			("Generator".to_owned(), TypeId::GENERATOR_TYPE),
 			("Function".to_owned(), TypeId::FUNCTION_TYPE),
 			("object".to_owned(), TypeId::OBJECT_TYPE),
 		];
@@ -106,17 +108,32 @@ impl RootContext {
 		module: A::Module<'static>,
 		checking_data: &'a mut CheckingData<T, A>,
 	) -> &'a SynthesisedModule<A::OwnedModule> {
-		let module_scope = crate::Scope::Module { source, exported: Exported::default() };
+		let module_scope = crate::Scope::Module {
+			source,
+			exported: Exported::default(),
+			imports: Default::default(),
+		};
 		let mut environment = self.new_lexical_environment(module_scope);
 		A::synthesise_module(&module, source, &mut environment, checking_data);
 
-		let crate::Scope::Module { exported, .. } = environment.context_type.scope else {
+		let crate::Scope::Module { exported, imports, .. } = environment.context_type.scope else {
 			unreachable!()
 		};
 
+		let declared_variables = environment
+			.variables
+			.iter()
+			.filter(|(_, variable)| {
+				matches!(variable, crate::features::variables::VariableOrImport::Variable { .. })
+			})
+			.map(|(name, variable)| (name.clone(), variable.get_id()))
+			.collect();
+
 		let module = SynthesisedModule {
 			content: A::owned_module_from_module(module),
 			exported,
+			imports,
+			declared_variables,
 			facts: environment.facts,
 		};
 
@@ -125,48 +142,69 @@ impl RootContext {
 		checking_data.modules.synthesised_modules.get(&source).unwrap()
 	}
 
-	/// TODO working things out:
-	/// - strings could reference a big string
-	#[must_use]
-	pub fn serialize(self) -> Vec<u8> {
-		todo!()
+	/// Writes the root, alongside the types it references, to a buffer. `definition_file` is the path
+	/// and content of the file it was synthesised from, so that diagnostics can still point into it
+	pub(crate) fn serialize(
+		self,
+		types: TypeStore,
+		definition_file: (String, String),
+		declared_modules: HashMap<String, Exported>,
+	) -> Vec<u8> {
+		let mut buf = CONTEXT_FILE_HEADER.to_vec();
+		env!("CARGO_PKG_VERSION").to_owned().serialize(&mut buf);
+		definition_file.serialize(&mut buf);
+		types.serialize(&mut buf);
+		self.variables.serialize(&mut buf);
+		self.named_types.serialize(&mut buf);
+		self.variable_names.serialize(&mut buf);
+		self.facts.serialize(&mut buf);
+		declared_modules.serialize(&mut buf);
+		buf
 	}
 
-	pub fn deserialize(_source: &[u8], _backing_source: SourceId) -> Result<Self, String> {
-		todo!()
-		// let mut ctx = Root::new_with_primitive_references();
-
-		// if !source.starts_with(HEADER) {
-		// 	return Err("Missing header".to_owned());
-		// }
-
-		// let mut bytes = source.into_iter();
-
-		// {
-		// 	assert_eq!(bytes.by_ref().take(HEADER.len()).collect::<Vec<_>>(), HEADER);
-		// }
+	/// Reverse of [`RootContext::serialize`]. Registers the definition file in `files`
+	pub(crate) fn deserialize(
+		source: &[u8],
+		files: &mut MapFileStore<WithPathMap>,
+	) -> Result<(Self, TypeStore, HashMap<String, Exported>), String> {
+		let Some(rest) = source.strip_prefix(CONTEXT_FILE_HEADER) else {
+			return Err("not a definition cache".to_owned());
+		};
 
-		// // Types
-		// let count = u16::from_le_bytes([bytes.next().unwrap(), bytes.next().unwrap()]);
+		let mut bytes = rest.iter().copied();
 
-		// for _ in 0..count {
-		// 	let ty = Type::deserialize(&mut bytes, backing_source);
-		// 	ctx.new_type(ty);
-		// }
-		// crate::utils::notify!("Registered {:?} types", count);
+		let version = String::deserialize(&mut bytes, SourceId::NULL).map_err(invalid_cache)?;
+		if version != env!("CARGO_PKG_VERSION") {
+			return Err(format!(
+				"definition cache was created with version {version}, expected {}",
+				env!("CARGO_PKG_VERSION")
+			));
+		}
 
-		// ctx.variables = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// // TODO terrible
-		// VariableId::set_counter_bad((ctx.variables.len() + 1) as u16);
-		// ctx.variable_names = BinarySerializable::deserialize(&mut bytes, backing_source);
+		let (path, content) =
+			<(String, String)>::deserialize(&mut bytes, SourceId::NULL).map_err(invalid_cache)?;
+		let backing_source = files.new_source_id(path.into(), content);
+
+		let mut deserialize_rest = || {
+			let types = TypeStore::deserialize(&mut bytes, backing_source)?;
+			let mut root = Self::new_with_primitive_references();
+			root.variables = BinarySerializable::deserialize(&mut bytes, backing_source)?;
+			root.named_types = BinarySerializable::deserialize(&mut bytes, backing_source)?;
+			root.variable_names = BinarySerializable::deserialize(&mut bytes, backing_source)?;
+			root.facts = BinarySerializable::deserialize(&mut bytes, backing_source)?;
+			let declared_modules = BinarySerializable::deserialize(&mut bytes, backing_source)?;
+			Ok((root, types, declared_modules))
+		};
+		let (root, types, declared_modules) = deserialize_rest().map_err(invalid_cache)?;
 
-		// ctx.proofs = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// ctx.functions_on_type = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// ctx.subtyping_constant_proofs = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// ctx.terms_reverse = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// ctx.proxies = BinarySerializable::deserialize(&mut bytes, backing_source);
-		// ctx.can_reference_this = BinarySerializable::deserialize(&mut bytes, backing_source);
+		if bytes.next().is_some() {
+			return Err("unexpected bytes at end of definition cache".to_owned());
+		}
 
-		// Ok(ctx)
+		Ok((root, types, declared_modules))
 	}
 }
+
+fn invalid_cache(error: crate::DeserializeError) -> String {
+	format!("invalid definition cache: {error}")
+}
@@ -90,6 +90,31 @@ impl Diagnostic {
 		}
 	}
 
+	/// Adds labels without a position. A [`Diagnostic::Position`] becomes a
+	/// [`Diagnostic::PositionWithAdditionalLabels`] if there are any
+	#[must_use]
+	pub fn with_notes(self, notes: Vec<String>) -> Self {
+		if notes.is_empty() {
+			return self;
+		}
+		let notes = notes.into_iter().map(|note| (note, None));
+		match self {
+			Diagnostic::Global { .. } => self,
+			Diagnostic::Position { reason, position, kind } => {
+				Diagnostic::PositionWithAdditionalLabels {
+					reason,
+					position,
+					labels: notes.collect(),
+					kind,
+				}
+			}
+			Diagnostic::PositionWithAdditionalLabels { reason, position, mut labels, kind } => {
+				labels.extend(notes);
+				Diagnostic::PositionWithAdditionalLabels { reason, position, labels, kind }
+			}
+		}
+	}
+
 	#[must_use]
 	pub fn kind(&self) -> DiagnosticKind {
 		match self {
@@ -243,6 +268,67 @@ impl TypeStringRepresentation {
 	}
 }
 
+/// A [`crate::types::subtyping::NonEqualityReason`] with types printed. Explains why a type is
+/// not assignable
+pub enum NonEqualityRepresentation {
+	/// No more detail than the types
+	Mismatch,
+	PropertiesInvalid(Vec<(String, PropertyErrorRepresentation)>),
+}
+
+pub enum PropertyErrorRepresentation {
+	Missing,
+	Invalid {
+		expected: TypeStringRepresentation,
+		found: TypeStringRepresentation,
+		mismatch: NonEqualityRepresentation,
+	},
+}
+
+impl NonEqualityRepresentation {
+	/// One note per property that does not match. Nested properties are reported by their path (`b.c`)
+	#[must_use]
+	pub fn into_notes(self) -> Vec<String> {
+		fn add_notes(reason: NonEqualityRepresentation, path: &str, notes: &mut Vec<String>) {
+			let NonEqualityRepresentation::PropertiesInvalid(errors) = reason else {
+				return;
+			};
+			for (key, error) in errors {
+				let path = if path.is_empty() {
+					key
+				} else if key.starts_with('[') {
+					format!("{path}{key}")
+				} else {
+					format!("{path}.{key}")
+				};
+				match error {
+					PropertyErrorRepresentation::Missing => {
+						notes.push(format!("property `{path}` is missing"));
+					}
+					PropertyErrorRepresentation::Invalid {
+						mismatch: mismatch @ NonEqualityRepresentation::PropertiesInvalid(_),
+						..
+					} => {
+						add_notes(mismatch, &path, notes);
+					}
+					PropertyErrorRepresentation::Invalid {
+						expected,
+						found,
+						mismatch: NonEqualityRepresentation::Mismatch,
+					} => {
+						notes
+							.push(format!("property `{path}`: expected {expected}, found {found}"));
+					}
+				}
+			}
+		}
+
+		let mut notes = Vec::new();
+		add_notes(self, "", &mut notes);
+		notes
+	}
+}
+
 impl Display for TypeStringRepresentation {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
@@ -271,7 +357,8 @@ mod defined_errors_and_warnings {
 	use crate::Diagnostic;
 
 	use super::{
-		NotInLoopOrCouldNotFindLabel, PropertyRepresentation, TypeStringRepresentation, TDZ,
+		NonEqualityRepresentation, NotInLoopOrCouldNotFindLabel, PropertyRepresentation,
+		TypeStringRepresentation, TDZ,
 	};
 
 	/// Reasons for errors, intermediate type for generating [Diagnostic]s
@@ -302,6 +389,14 @@ mod defined_errors_and_warnings {
 			returned_type: TypeStringRepresentation,
 			annotation_position: SpanWithSource,
 			returned_position: SpanWithSource,
+			mismatch: NonEqualityRepresentation,
+		},
+		YieldedTypeDoesNotMatch {
+			expected_yield_type: TypeStringRepresentation,
+			yielded_type: TypeStringRepresentation,
+			annotation_position: SpanWithSource,
+			yielded_position: SpanWithSource,
+			mismatch: NonEqualityRepresentation,
 		},
 		// TODO are these the same errors?
 		TypeIsNotIndexable(TypeStringRepresentation),
@@ -321,6 +416,12 @@ mod defined_errors_and_warnings {
 			attribute_type_site: (),
 			value_site: SpanWithSource,
 		},
+		/// Required attributes of intrinsic elements
+		MissingJSXAttribute {
+			attribute_name: String,
+			attribute_type: TypeStringRepresentation,
+			element_site: SpanWithSource,
+		},
 		InvalidJSXInterpolatedValue {
 			interpolation_site: SpanWithSource,
 			expected: TypeStringRepresentation,
@@ -331,6 +432,7 @@ mod defined_errors_and_warnings {
 			at: SpanWithSource,
 			expected: TypeStringRepresentation,
 			found: TypeStringRepresentation,
+			mismatch: NonEqualityRepresentation,
 		},
 		Unsupported {
 			thing: &'static str,
@@ -379,6 +481,8 @@ mod defined_errors_and_warnings {
 			name: String,
 			position: SpanWithSource,
 		},
+		/// The previous member is not a numeric constant
+		EnumMemberNeedsInitializer(SpanWithSource),
 		TDZ(TDZ),
 		InvalidMathematicalOrBitwiseOperation {
 			operator: MathematicalAndBitwise,
@@ -386,6 +490,41 @@ mod defined_errors_and_warnings {
 			rhs: TypeStringRepresentation,
 			position: SpanWithSource,
 		},
+		/// `#property` outside of a class body
+		PrivatePropertyOutsideClass {
+			property: String,
+			is_set: bool,
+			position: SpanWithSource,
+		},
+		/// `super(...)` outside of a derived constructor or `super.property` outside of a class
+		InvalidSuperUsage {
+			is_call: bool,
+			position: SpanWithSource,
+		},
+		/// A method on a class which is not compatible with the one on the class it extends
+		IncompatibleOverride {
+			name: String,
+			overridden: TypeStringRepresentation,
+			overriding: TypeStringRepresentation,
+			position: SpanWithSource,
+		},
+		/// The value of a `using` (or `await using`) declaration without a dispose method
+		NotDisposable {
+			value_type: TypeStringRepresentation,
+			is_await: bool,
+			position: SpanWithSource,
+		},
+		UnsupportedImportAttribute {
+			key: &'a str,
+			value: &'a str,
+			position: SpanWithSource,
+		},
+		JSONImportMismatch {
+			path: &'a str,
+			is_json_import: bool,
+			position: SpanWithSource,
+		},
+		InvalidJSON(SpanWithSource),
 	}
 
 	impl From<TypeCheckError<'_>> for Diagnostic {
@@ -424,8 +563,9 @@ mod defined_errors_and_warnings {
 						argument_position,
 						parameter_position,
 						restriction,
+						mismatch,
 					} => {
-						if let Some((restriction_pos, restriction)) = restriction {
+						let diagnostic = if let Some((restriction_pos, restriction)) = restriction {
 							Diagnostic::PositionWithAdditionalLabels {
 								reason: format!(
 									"Argument of type {argument_type} is not assignable to parameter of type {restriction}" 
@@ -451,7 +591,8 @@ mod defined_errors_and_warnings {
 								)],
 								kind,
 							}
-						}
+						};
+						diagnostic.with_notes(mismatch.into_notes())
 					}
 					FunctionCallingError::MissingArgument { parameter_position, call_site } => {
 						Diagnostic::PositionWithAdditionalLabels {
@@ -507,7 +648,7 @@ mod defined_errors_and_warnings {
 							Some(position),
 						)],
 					},
-					FunctionCallingError::SetPropertyConstraint { property_type, value_type, assignment_position, call_site } => Diagnostic::PositionWithAdditionalLabels {
+					FunctionCallingError::SetPropertyConstraint { property_type, value_type, assignment_position, call_site, mismatch } => Diagnostic::PositionWithAdditionalLabels {
 						reason: "Assignment mismatch".to_owned(),
 						position: call_site.unwrap(),
 						kind,
@@ -517,6 +658,23 @@ mod defined_errors_and_warnings {
 							),
 							Some(assignment_position),
 						)],
+					}.with_notes(mismatch.into_notes()),
+					FunctionCallingError::SetPropertyNotWriteable { assignment_position, call_site } => if let Some(call_site) = call_site {
+						Diagnostic::PositionWithAdditionalLabels {
+							reason: "Assignment mismatch".to_owned(),
+							position: call_site,
+							kind,
+							labels: vec![(
+								"Property not writeable".to_owned(),
+								Some(assignment_position),
+							)],
+						}
+					} else {
+						Diagnostic::Position {
+							reason: "Property not writeable".to_owned(),
+							position: assignment_position,
+							kind,
+						}
 					}
 				},
 				TypeCheckError::AssignmentError(error) => match error {
@@ -525,6 +683,7 @@ mod defined_errors_and_warnings {
 						variable_site,
 						value_type,
 						value_site,
+						mismatch,
 					} => Diagnostic::PositionWithAdditionalLabels {
 						reason: format!(
 							"Type {value_type} is not assignable to type {variable_type}",
@@ -535,18 +694,21 @@ mod defined_errors_and_warnings {
 							Some(variable_site),
 						)],
 						kind,
-					},
+					}
+					.with_notes(mismatch.into_notes()),
 					AssignmentError::PropertyConstraint {
 						property_constraint: property_type,
 						value_type,
 						assignment_position,
+						mismatch,
 					} => Diagnostic::Position {
 						reason: format!(
 							"Type {value_type} does not meet property constraint {property_type}"
 						),
 						position: assignment_position,
 						kind,
-					},
+					}
+					.with_notes(mismatch.into_notes()),
 					AssignmentError::Constant(position) => Diagnostic::Position {
 						reason: "Cannot assign to constant".into(),
 						position,
@@ -568,16 +730,28 @@ mod defined_errors_and_warnings {
 					value_site,
 				} => Diagnostic::Position {
 					reason: format!(
-						"Type {attribute_name} is not assignable to {value_type} attribute of type {attribute_type}",
+						"Type {value_type} is not assignable to {attribute_name} attribute of type {attribute_type}",
 					),
 					position: value_site,
 					kind,
 				},
+				TypeCheckError::MissingJSXAttribute {
+					attribute_name,
+					attribute_type,
+					element_site,
+				} => Diagnostic::Position {
+					reason: format!(
+						"Missing {attribute_name} attribute of type {attribute_type}",
+					),
+					position: element_site,
+					kind,
+				},
 				TypeCheckError::ReturnedTypeDoesNotMatch {
 					annotation_position,
 					returned_position,
 					expected_return_type,
 					returned_type,
+					mismatch,
 				} => Diagnostic::PositionWithAdditionalLabels {
 					reason: format!(
 						"Cannot return {returned_type} because the function is expected to return {expected_return_type}",
@@ -588,7 +762,26 @@ mod defined_errors_and_warnings {
 					)],
 					position: returned_position,
 					kind,
-				},
+				}
+				.with_notes(mismatch.into_notes()),
+				TypeCheckError::YieldedTypeDoesNotMatch {
+					annotation_position,
+					yielded_position,
+					expected_yield_type,
+					yielded_type,
+					mismatch,
+				} => Diagnostic::PositionWithAdditionalLabels {
+					reason: format!(
+						"Cannot yield {yielded_type} because the generator is expected to yield {expected_yield_type}",
+					),
+					labels: vec![(
+						format!("Generator annotated to yield {expected_yield_type} here"),
+						Some(annotation_position),
+					)],
+					position: yielded_position,
+					kind,
+				}
+				.with_notes(mismatch.into_notes()),
 				TypeCheckError::TypeHasNoGenericParameters(name, position) => {
 					Diagnostic::Position {
 						reason: format!("Type '{name}' has no generic parameters",),
@@ -647,11 +840,14 @@ mod defined_errors_and_warnings {
 					position: between,
 					kind,
 				},
-				TypeCheckError::NotSatisfied { at, expected, found } => Diagnostic::Position {
-					reason: format!("Expected {expected}, found {found}"),
-					position: at,
-					kind,
-				},
+				TypeCheckError::NotSatisfied { at, expected, found, mismatch } => {
+					Diagnostic::Position {
+						reason: format!("Expected {expected}, found {found}"),
+						position: at,
+						kind,
+					}
+					.with_notes(mismatch.into_notes())
+				}
 				TypeCheckError::CannotRedeclareVariable { name, position } => {
 					Diagnostic::Position {
 						reason: format!("Cannot redeclare variable {name}"),
@@ -720,6 +916,11 @@ mod defined_errors_and_warnings {
 					position,
 					kind,
 				},
+				TypeCheckError::EnumMemberNeedsInitializer(position) => Diagnostic::Position {
+					reason: "Enum member must have initializer".to_owned(),
+					position,
+					kind,
+				},
 				TypeCheckError::TDZ(TDZ { position, variable_name }) => Diagnostic::Position {
 					reason: format!("Variable {variable_name} used before declaration"),
 					position,
@@ -731,7 +932,72 @@ mod defined_errors_and_warnings {
 					position,
 					kind,
 				},
-				TypeCheckError::NotInLoopOrCouldNotFindLabel(_) => todo!(),
+				TypeCheckError::NotInLoopOrCouldNotFindLabel(NotInLoopOrCouldNotFindLabel {
+					label,
+					position,
+				}) => Diagnostic::Position {
+					reason: if let Some(label) = label {
+						format!("Cannot find label {label}")
+					} else {
+						"Not in loop or switch".to_owned()
+					},
+					position,
+					kind,
+				},
+				TypeCheckError::PrivatePropertyOutsideClass { property, is_set, position } => Diagnostic::Position {
+					reason: format!(
+						"Cannot {} private property \"#{property}\" outside of its class",
+						if is_set { "set" } else { "get" }
+					),
+					position,
+					kind,
+				},
+				TypeCheckError::InvalidSuperUsage { is_call, position } => Diagnostic::Position {
+					reason: if is_call {
+						"super can only be called in the constructor of a class which extends another".to_owned()
+					} else {
+						"super properties can only be used in classes".to_owned()
+					},
+					position,
+					kind,
+				},
+				TypeCheckError::IncompatibleOverride { name, overridden, overriding, position } => Diagnostic::Position {
+					reason: format!("{name} of type {overriding} does not match overridden {overridden}"),
+					position,
+					kind,
+				},
+				TypeCheckError::NotDisposable { value_type, is_await, position } => Diagnostic::Position {
+					reason: if is_await {
+						format!("Type {value_type} does not have a [Symbol.asyncDispose] or [Symbol.dispose] method")
+					} else {
+						format!("Type {value_type} does not have a [Symbol.dispose] method")
+					},
+					position,
+					kind,
+				},
+				TypeCheckError::UnsupportedImportAttribute { key, value, position } => Diagnostic::Position {
+					reason: if key == "type" {
+						format!("Cannot import module with type \"{value}\", only \"json\" is supported")
+					} else {
+						format!("Unsupported import attribute \"{key}\"")
+					},
+					position,
+					kind,
+				},
+				TypeCheckError::JSONImportMismatch { path, is_json_import, position } => Diagnostic::Position {
+					reason: if is_json_import {
+						format!("\"{path}\" is not a JSON module and cannot be imported with {{ type: \"json\" }}")
+					} else {
+						format!("\"{path}\" is a JSON module and must be imported with {{ type: \"json\" }}")
+					},
+					position,
+					kind,
+				},
+				TypeCheckError::InvalidJSON(position) => Diagnostic::Position {
+					reason: "Invalid JSON, only strings, numbers, booleans, null, arrays and objects are allowed".to_owned(),
+					position,
+					kind,
+				},
 			}
 		}
 	}
@@ -744,6 +1010,12 @@ mod defined_errors_and_warnings {
 			expression_value: bool,
 		},
 		IgnoringAsExpression(SpanWithSource),
+		/// Neither type is assignable to the other
+		InvalidCast {
+			position: SpanWithSource,
+			from: TypeStringRepresentation,
+			to: TypeStringRepresentation,
+		},
 		Unimplemented {
 			thing: &'static str,
 			at: SpanWithSource,
@@ -779,6 +1051,11 @@ mod defined_errors_and_warnings {
 					position,
 					kind,
 				},
+				TypeCheckWarning::InvalidCast { position, from, to } => Diagnostic::Position {
+					reason: format!("Cannot cast {from} to {to}"),
+					position,
+					kind,
+				},
 				TypeCheckWarning::Unimplemented { thing, at } => Diagnostic::Position {
 					reason: format!("Unsupported: {thing}"),
 					position: at,
@@ -12,7 +12,7 @@ use crate::{
 	},
 	types::{
 		functions::SynthesisedArgument,
-		is_type_truthy_falsy,
+		get_constraint, is_type_truthy_falsy,
 		poly_types::FunctionTypeArguments,
 		properties::{get_property, set_property, PropertyValue},
 		substitute, Constructor, StructureGenerics, TypeId, TypeStore,
@@ -42,7 +42,7 @@ pub(crate) fn apply_event(
 				let value = match reference {
 					RootReference::Variable(id) => {
 						let value = get_value_of_variable(
-							environment.facts_chain(),
+							target.facts_chain().chain(environment.facts_chain()),
 							id,
 							Some(&*type_arguments),
 						);
@@ -83,8 +83,8 @@ pub(crate) fn apply_event(
 			facts.events.push(Event::SetsVariable(variable, new_value, position));
 			facts.variable_current_value.insert(variable, new_value);
 		}
-		Event::Getter { on, under, reflects_dependency, publicity, position } => {
-			let on = substitute(on, type_arguments, environment, types);
+		Event::Getter { on: was, under, reflects_dependency, publicity, position } => {
+			let on = substitute(was, type_arguments, environment, types);
 			let under = match under {
 				crate::types::properties::PropertyKey::Type(under) => {
 					let ty = substitute(under, type_arguments, environment, types);
@@ -93,11 +93,25 @@ pub(crate) fn apply_event(
 				under @ crate::types::properties::PropertyKey::String(_) => under,
 			};
 
-			let (_, value) =
-				get_property(on, publicity, under, None, environment, target, types, position)
-					.expect(
-						"Inferred or checking failed, could not get property when getting property",
-					);
+			let value = get_property(
+				on,
+				publicity,
+				under.clone(),
+				None,
+				environment,
+				target,
+				types,
+				position,
+			);
+			let value = if let Some((_, value)) = value {
+				value
+			} else {
+				// Optional properties (of the parameter's annotation) may not exist on the argument
+				let constraint = get_constraint(was, types).unwrap_or(was);
+				let is_optional = environment.get_property_modifiers(constraint, &under).optional;
+				assert!(is_optional, "Inferred or checking failed, could not get property");
+				TypeId::UNDEFINED_TYPE
+			};
 
 			if let Some(id) = reflects_dependency {
 				type_arguments.set_id_from_reference(id, value);
@@ -172,10 +186,8 @@ pub(crate) fn apply_event(
 				);
 
 				if let Err(err) = result {
-					if let SetPropertyError::DoesNotMeetConstraint {
-						property_constraint,
-						reason: _,
-					} = err
+					if let SetPropertyError::DoesNotMeetConstraint { property_constraint, reason } =
+						err
 					{
 						let value_type = if let PropertyValue::Value(id) = new {
 							TypeStringRepresentation::from_type_id(
@@ -194,10 +206,17 @@ pub(crate) fn apply_event(
 								value_type,
 								assignment_position: position.unwrap(),
 								call_site: None,
+								mismatch: reason
+									.into_representation(&environment.as_general_context(), types),
 							},
 						);
 					} else {
-						unreachable!()
+						errors.errors.push(
+							crate::types::calling::FunctionCallingError::SetPropertyNotWriteable {
+								assignment_position: position.unwrap(),
+								call_site: None,
+							},
+						);
 					}
 				}
 			}
@@ -406,6 +425,13 @@ pub(crate) fn apply_event(
 			position: _,
 			is_function_this,
 		} => {
+			// `super(...)` calls pass the `this` of the derived class
+			if is_function_this
+				&& type_arguments.local_arguments.contains_key(&referenced_in_scope_as)
+			{
+				return None;
+			}
+
 			// TODO
 			let is_under_dyn = true;
 
@@ -442,6 +468,42 @@ pub(crate) fn apply_event(
 
 			type_arguments.set_id_from_reference(referenced_in_scope_as, new_object_id);
 		}
+		Event::Deferred { events, position: _ } => {
+			// Run for errors and the returned value, but facts are discarded as the caller
+			// continues before these happen
+			let (_facts, early_return) =
+				target.new_conditional_target(|target: &mut InvocationContext| {
+					for event in events.into_vec() {
+						if let Some(early) = apply_event(
+							event,
+							this_value,
+							type_arguments,
+							environment,
+							target,
+							types,
+							errors,
+						) {
+							return Some(early);
+						}
+					}
+					None
+				});
+			return early_return;
+		}
+		Event::Yield { yielded, delegated, position } => {
+			let yielded = substitute(yielded, type_arguments, environment, types);
+			let values = if delegated {
+				iteration::get_iterated_values(yielded, environment, types)
+			} else {
+				Some(vec![yielded])
+			};
+			target.add_yielded(values);
+			target.get_latest_facts(environment).events.push(Event::Yield {
+				yielded,
+				delegated,
+				position,
+			});
+		}
 		Event::Iterate { kind, iterate_over, initial } => {
 			// TODO this might clash
 			let initial = initial
@@ -457,9 +519,10 @@ pub(crate) fn apply_event(
 				IterationKind::Properties(on) => {
 					IterationKind::Properties(substitute(on, type_arguments, environment, types))
 				}
-				IterationKind::Iterator(on) => {
-					IterationKind::Iterator(substitute(on, type_arguments, environment, types))
-				}
+				IterationKind::Iterator { on, variable } => IterationKind::Iterator {
+					on: substitute(on, type_arguments, environment, types),
+					variable,
+				},
 			};
 
 			let early_result = iteration::run_iteration_block(
@@ -1,3 +1,5 @@
+use source_map::SpanWithSource;
+
 use super::{Event, FinalEvent};
 use crate::{types::new_logical_or_type, CheckingData, Environment, TypeId};
 
@@ -9,6 +11,17 @@ pub(crate) enum ReturnedTypeFromBlock {
 	Returned(TypeId),
 }
 
+impl ReturnedTypeFromBlock {
+	/// Condition under which the block returns and the returned type (if it can return)
+	fn into_condition_and_returns(self) -> (TypeId, Option<TypeId>) {
+		match self {
+			ReturnedTypeFromBlock::ContinuedExecution => (TypeId::FALSE, None),
+			ReturnedTypeFromBlock::ReturnedIf { when, returns } => (when, Some(returns)),
+			ReturnedTypeFromBlock::Returned(returns) => (TypeId::TRUE, Some(returns)),
+		}
+	}
+}
+
 /// TODO will cover move, like yield events and stuff
 pub(crate) fn get_return_from_events<'a, T: crate::ReadFromFS, A: crate::ASTImplementation>(
 	iter: &mut (impl Iterator<Item = &'a Event> + ExactSizeIterator),
@@ -33,7 +46,7 @@ pub(crate) fn get_return_from_events<'a, T: crate::ReadFromFS, A: crate::ASTImpl
 						&checking_data.types,
 					);
 
-					if let crate::subtyping::SubTypeResult::IsNotSubType(_) = result {
+					if let crate::subtyping::SubTypeResult::IsNotSubType(mismatch) = result {
 						checking_data.diagnostics_container.add_error(
 							crate::diagnostics::TypeCheckError::ReturnedTypeDoesNotMatch {
 								expected_return_type:
@@ -52,6 +65,10 @@ pub(crate) fn get_return_from_events<'a, T: crate::ReadFromFS, A: crate::ASTImpl
 									),
 								annotation_position: annotation_span,
 								returned_position: *returned_position,
+								mismatch: mismatch.into_representation(
+									&environment.as_general_context(),
+									&checking_data.types,
+								),
 							},
 						);
 					}
@@ -73,7 +90,7 @@ pub(crate) fn get_return_from_events<'a, T: crate::ReadFromFS, A: crate::ASTImpl
 					expected_return_type,
 				);
 
-				return match (return_if_truthy, else_return) {
+				let (when, returns) = match (return_if_truthy, else_return) {
 					(
 						ReturnedTypeFromBlock::ContinuedExecution,
 						ReturnedTypeFromBlock::ContinuedExecution,
@@ -81,90 +98,70 @@ pub(crate) fn get_return_from_events<'a, T: crate::ReadFromFS, A: crate::ASTImpl
 						continue;
 					}
 					(
-						ReturnedTypeFromBlock::ContinuedExecution,
-						ReturnedTypeFromBlock::ReturnedIf { when: _, returns: _ },
+						ReturnedTypeFromBlock::Returned(true_returns),
+						ReturnedTypeFromBlock::Returned(false_returns),
 					) => {
-						todo!("Expend rest of iterator")
-						// ReturnedTypeFromBlock::ReturnedIf {
-						// 	when: types.new_logical_negation_type(when),
-						// 	returns,
-						// }
+						return ReturnedTypeFromBlock::Returned(
+							checking_data.types.new_conditional_type(
+								*on,
+								true_returns,
+								false_returns,
+							),
+						);
 					}
 					(
+						ReturnedTypeFromBlock::Returned(returns),
 						ReturnedTypeFromBlock::ContinuedExecution,
-						ReturnedTypeFromBlock::Returned(_),
-					) => {
-						todo!()
-					}
+					) => (*on, returns),
 					(
-						_v @ ReturnedTypeFromBlock::ReturnedIf { .. },
 						ReturnedTypeFromBlock::ContinuedExecution,
-					) => {
-						todo!()
-						// return v
+						ReturnedTypeFromBlock::Returned(returns),
+					) => (checking_data.types.new_logical_negation_type(*on), returns),
+					(true_branch, else_branch) => {
+						// At least one branch returns conditionally
+						let (true_when, true_returns) = true_branch.into_condition_and_returns();
+						let (else_when, else_returns) = else_branch.into_condition_and_returns();
+						let types = &mut checking_data.types;
+						let returns = match (true_returns, else_returns) {
+							(Some(true_returns), Some(else_returns)) => {
+								types.new_conditional_type(*on, true_returns, else_returns)
+							}
+							(Some(returns), None) | (None, Some(returns)) => returns,
+							(None, None) => unreachable!(),
+						};
+						(types.new_conditional_type(*on, true_when, else_when), returns)
 					}
-					(
-						ReturnedTypeFromBlock::ReturnedIf { when: _, returns: _ },
-						ReturnedTypeFromBlock::ReturnedIf { .. },
-					) => todo!(),
-					(
-						ReturnedTypeFromBlock::ReturnedIf { when: _, returns: _ },
-						ReturnedTypeFromBlock::Returned(_),
-					) => todo!(),
-					(
-						ReturnedTypeFromBlock::Returned(_true_returned),
-						ReturnedTypeFromBlock::ReturnedIf { when: _, returns: _ },
-					) => {
-						todo!("expend iterator")
-						// TODO could be simpler, aka a chain
-						// ReturnedTypeFromBlock::ReturnedIf {
-						// 	when: types.new_logical_or_type(lhs, rhs),
-						// 	returns: types.new_conditional_type(when, returns, true_returned),
-						// }
+				};
+
+				// if (a) { return 2 }
+				// else { if (b) { return 3 } }
+				// return 4
+				return match get_return_from_events(
+					iter,
+					checking_data,
+					environment,
+					expected_return_type,
+				) {
+					ReturnedTypeFromBlock::ContinuedExecution => {
+						ReturnedTypeFromBlock::ReturnedIf { when, returns }
 					}
-					(ReturnedTypeFromBlock::Returned(true_returns), right) => {
-						// if (a) { return 2 }
-						// else { if (b) { return 3 } }
-						// return 4
-						let right = if iter.len() == 0
-							&& !matches!(right, ReturnedTypeFromBlock::Returned(..))
-						{
-							get_return_from_events(
-								iter,
-								checking_data,
-								environment,
-								expected_return_type,
-							)
-						} else {
-							right
-						};
-						match right {
-							ReturnedTypeFromBlock::ContinuedExecution => {
-								ReturnedTypeFromBlock::ReturnedIf {
-									when: *on,
-									returns: true_returns,
-								}
-							}
-							ReturnedTypeFromBlock::ReturnedIf { when, returns: false_returns } => {
-								ReturnedTypeFromBlock::ReturnedIf {
-									when: new_logical_or_type(*on, when, &mut checking_data.types),
-									returns: checking_data.types.new_conditional_type(
-										when,
-										false_returns,
-										true_returns,
-									),
-								}
-							}
-							ReturnedTypeFromBlock::Returned(false_returns) => {
-								ReturnedTypeFromBlock::Returned(
-									checking_data.types.new_conditional_type(
-										*on,
-										true_returns,
-										false_returns,
-									),
-								)
-							}
-						}
+					ReturnedTypeFromBlock::ReturnedIf {
+						when: rest_when,
+						returns: rest_returns,
+					} => ReturnedTypeFromBlock::ReturnedIf {
+						when: new_logical_or_type(when, rest_when, &mut checking_data.types),
+						returns: checking_data.types.new_conditional_type(
+							when,
+							returns,
+							rest_returns,
+						),
+					},
+					ReturnedTypeFromBlock::Returned(rest_returns) => {
+						ReturnedTypeFromBlock::Returned(checking_data.types.new_conditional_type(
+							when,
+							returns,
+							rest_returns,
+						))
 					}
 				};
 			}
@@ -197,3 +194,133 @@ pub(crate) fn extract_throw_events(events: Vec<Event>, thrown: &mut TypeId) -> V
 	}
 	new_events
 }
+
+/// Removes `break`s targeting this structure (with `carry == 0`). Events after a
+/// conditional `break` are moved into the branches so they only run if the `break` did not.
+/// `break`s and `continue`s targeting outer structures have their carry decremented
+pub(crate) fn consume_break_events(events: Vec<Event>) -> Vec<Event> {
+	fn contains_break_or_continue(events: &[Event]) -> bool {
+		events.iter().any(|event| match event {
+			Event::FinalEvent(FinalEvent::Break { .. } | FinalEvent::Continue { .. }) => true,
+			Event::Conditionally { true_events, else_events, .. } => {
+				contains_break_or_continue(true_events) || contains_break_or_continue(else_events)
+			}
+			_ => false,
+		})
+	}
+
+	let mut new_events = Vec::new();
+	let mut iter = events.into_iter();
+	while let Some(event) = iter.next() {
+		match event {
+			Event::FinalEvent(FinalEvent::Break { carry: 0, .. }) => break,
+			Event::FinalEvent(FinalEvent::Break { carry, position }) => {
+				new_events.push(FinalEvent::Break { carry: carry - 1, position }.into());
+				break;
+			}
+			Event::FinalEvent(FinalEvent::Continue { carry, position }) => {
+				new_events
+					.push(FinalEvent::Continue { carry: carry.saturating_sub(1), position }.into());
+				break;
+			}
+			Event::Conditionally { condition, true_events, else_events, position }
+				if contains_break_or_continue(&true_events)
+					|| contains_break_or_continue(&else_events) =>
+			{
+				let rest: Vec<Event> = iter.collect();
+				let true_events =
+					true_events.into_vec().into_iter().chain(rest.iter().cloned()).collect();
+				let else_events = else_events.into_vec().into_iter().chain(rest).collect();
+				new_events.push(Event::Conditionally {
+					condition,
+					true_events: consume_break_events(true_events).into_boxed_slice(),
+					else_events: consume_break_events(else_events).into_boxed_slice(),
+					position,
+				});
+				break;
+			}
+			event => new_events.push(event),
+		}
+	}
+	new_events
+}
+
+/// Moves the events after the first `await` into its [`Event::Deferred`]. Events after a
+/// conditional `await` are moved into the branches so they are only deferred if the `await` ran.
+/// Events after a loop containing an `await` are deferred
+pub(crate) fn defer_events_after_await(events: Vec<Event>) -> Vec<Event> {
+	let mut new_events = Vec::new();
+	let mut iter = events.into_iter();
+	while let Some(event) = iter.next() {
+		match event {
+			Event::Deferred { position, .. } => {
+				let rest = defer_events_after_await(iter.collect());
+				new_events.push(Event::Deferred { events: rest.into_boxed_slice(), position });
+				break;
+			}
+			Event::Conditionally { condition, true_events, else_events, position }
+				if get_await_position(&true_events).is_some()
+					|| get_await_position(&else_events).is_some() =>
+			{
+				let rest: Vec<Event> = iter.collect();
+				let true_events =
+					true_events.into_vec().into_iter().chain(rest.iter().cloned()).collect();
+				let else_events = else_events.into_vec().into_iter().chain(rest).collect();
+				new_events.push(Event::Conditionally {
+					condition,
+					true_events: defer_events_after_await(true_events).into_boxed_slice(),
+					else_events: defer_events_after_await(else_events).into_boxed_slice(),
+					position,
+				});
+				break;
+			}
+			Event::Iterate { kind, iterate_over, initial } => {
+				let Some(position) = get_await_position(&iterate_over) else {
+					new_events.push(Event::Iterate { kind, iterate_over, initial });
+					continue;
+				};
+				let iterate_over = defer_events_after_await(iterate_over.into_vec());
+				new_events.push(Event::Iterate {
+					kind,
+					iterate_over: iterate_over.into_boxed_slice(),
+					initial,
+				});
+				let rest = defer_events_after_await(iter.collect());
+				if !rest.is_empty() {
+					new_events.push(Event::Deferred { events: rest.into_boxed_slice(), position });
+				}
+				break;
+			}
+			event => new_events.push(event),
+		}
+	}
+	new_events
+}
+
+fn get_await_position(events: &[Event]) -> Option<SpanWithSource> {
+	events.iter().find_map(|event| match event {
+		Event::Deferred { position, .. } => Some(*position),
+		Event::Conditionally { true_events, else_events, .. } => {
+			get_await_position(true_events).or_else(|| get_await_position(else_events))
+		}
+		Event::Iterate { iterate_over, .. } => get_await_position(iterate_over),
+		_ => None,
+	})
+}
+
+/// Positions of `throw`s in the events (not including those in called functions)
+pub(crate) fn get_throw_positions(events: &[Event], positions: &mut Vec<SpanWithSource>) {
+	for event in events {
+		match event {
+			Event::FinalEvent(FinalEvent::Throw { position, .. }) => positions.push(*position),
+			Event::Conditionally { true_events: events, else_events, .. } => {
+				get_throw_positions(events, positions);
+				get_throw_positions(else_events, positions);
+			}
+			Event::Iterate { iterate_over: events, .. } | Event::Deferred { events, .. } => {
+				get_throw_positions(events, positions);
+			}
+			_ => {}
+		}
+	}
+}
@@ -101,6 +101,21 @@ pub enum Event {
 		/// Contains initial values that the iteration runs over. Without, initial iterations can't access anything...?
 		initial: InitialVariables,
 	},
+	/// Events that happen after an `await` in an `async` function. These run after the caller
+	/// has continued, so are not applied synchronously
+	///
+	/// Pushed (empty) at the `await`. Following events are moved in when the function is registered
+	Deferred {
+		events: Box<[Event]>,
+		position: SpanWithSource,
+	},
+	/// A `yield` in a generator function. Values are collected when the generator is called
+	Yield {
+		yielded: TypeId,
+		/// For `yield*`. `yielded` is then the iterable that is delegated to
+		delegated: bool,
+		position: SpanWithSource,
+	},
 
 	/// *lil bit magic*, handles:
 	/// - Creating objects `{}`
@@ -2,7 +2,7 @@ use source_map::SpanWithSource;
 
 use crate::{
 	context::get_on_ctx,
-	subtyping::check_satisfies,
+	subtyping::{check_satisfies, SubTypeResult},
 	types::{functions::SynthesisedArgument, printing::debug_effects},
 	types::{printing::print_type, Type, TypeStore},
 	Constant, Environment, TypeId,
@@ -223,7 +223,7 @@ pub(crate) fn call_constant_function(
 				.next()
 				.ok_or(ConstantFunctionError::BadCall)?
 				.0;
-			if check_satisfies(arg, ty, types, environment) {
+			if let SubTypeResult::IsSubType = check_satisfies(arg, ty, types, environment) {
 				Ok(ConstantOutput::Value(ty))
 			} else {
 				let output = format!(
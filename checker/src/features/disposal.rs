@@ -0,0 +1,194 @@
+//! `using` and `await using` declarations. Values are disposed (in reverse order of declaration)
+//! when the scope they are declared in exits
+
+use std::borrow::Cow;
+
+use source_map::SpanWithSource;
+
+use crate::{
+	context::{facts::Publicity, invocation::CheckThings, Logical},
+	diagnostics::{TypeCheckError, TypeStringRepresentation},
+	events::{Event, FinalEvent},
+	features::functions::ThisValue,
+	types::{
+		calling::{call_type, CalledWithNew, CallingInput},
+		get_constraint,
+		properties::{PropertyKey, PropertyValue},
+		TypeStore,
+	},
+	CheckingData, Environment, TypeId,
+};
+
+/// A value from a `using` declaration
+pub struct Disposable {
+	/// The `[Symbol.dispose]` (or `[Symbol.asyncDispose]`) method
+	method: TypeId,
+	value: TypeId,
+	position: SpanWithSource,
+}
+
+/// Finds the dispose method on the value of a `using` declaration. `await using` falls back to
+/// `[Symbol.dispose]`. `null` and `undefined` are skipped, as are `any` values (for now)
+pub fn get_disposable<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	value: TypeId,
+	is_await: bool,
+	position: SpanWithSource,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, A>,
+) -> Option<Disposable> {
+	// Constraints can be nested, for example on the variable of a `for of` over a free variable
+	let mut constraint = value;
+	while let Some(next) = get_constraint(constraint, &checking_data.types) {
+		if next == constraint {
+			break;
+		}
+		constraint = next;
+	}
+	if let TypeId::NULL_TYPE | TypeId::UNDEFINED_TYPE | TypeId::ANY_TYPE = constraint {
+		return None;
+	}
+
+	let symbols: &[&str] = if is_await { &["asyncDispose", "dispose"] } else { &["dispose"] };
+	let method = symbols.iter().find_map(|symbol| {
+		let key = get_symbol_key(symbol, environment, &checking_data.types)?;
+		environment
+			.get_property(
+				value,
+				Publicity::Public,
+				key,
+				&mut checking_data.types,
+				None,
+				position.without_source(),
+			)
+			.map(|(_, method)| method)
+	});
+
+	if let Some(method) = method {
+		Some(Disposable { method, value, position })
+	} else {
+		checking_data.diagnostics_container.add_error(TypeCheckError::NotDisposable {
+			value_type: TypeStringRepresentation::from_type_id(
+				value,
+				&environment.as_general_context(),
+				&checking_data.types,
+				checking_data.options.debug_types,
+			),
+			is_await,
+			position,
+		});
+		None
+	}
+}
+
+/// Calls the dispose methods at the end of the scope. The events from calling them are also added
+/// before any `return`, `throw`, `break` or `continue` after `events_start`, as these also exit
+/// the scope
+pub fn dispose_at_scope_exit<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	disposables: Vec<Disposable>,
+	events_start: usize,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, A>,
+) {
+	if disposables.is_empty() {
+		return;
+	}
+
+	let scope_events_end = environment.facts.events.len();
+
+	for Disposable { method, value, position } in disposables.into_iter().rev() {
+		// TODO `await` the result of `[Symbol.asyncDispose]`
+		let result = call_type(
+			method,
+			Vec::new(),
+			CallingInput {
+				called_with_new: CalledWithNew::None,
+				this_value: ThisValue::Passed(value),
+				call_site_type_arguments: None,
+				call_site: position,
+			},
+			environment,
+			&mut CheckThings,
+			&mut checking_data.types,
+		);
+
+		if let Err(errors) = result {
+			for error in errors {
+				checking_data
+					.diagnostics_container
+					.add_error(TypeCheckError::FunctionCallingError(error));
+			}
+		}
+	}
+
+	let dispose_events = environment.facts.events.split_off(scope_events_end);
+	let scope_events = environment.facts.events.split_off(events_start);
+	let exits = matches!(scope_events.last(), Some(Event::FinalEvent(_)));
+
+	environment.facts.events.extend(add_before_exits(scope_events, &dispose_events, false));
+	if !exits {
+		environment.facts.events.extend(dispose_events);
+	}
+}
+
+/// `in_loop` is for events of a nested iteration, where `break` and `continue` only exit the loop
+fn add_before_exits(events: Vec<Event>, dispose_events: &[Event], in_loop: bool) -> Vec<Event> {
+	let mut new_events = Vec::with_capacity(events.len());
+	for event in events {
+		match event {
+			Event::FinalEvent(FinalEvent::Break { .. } | FinalEvent::Continue { .. })
+				if in_loop =>
+			{
+				new_events.push(event);
+			}
+			Event::FinalEvent(_) => {
+				new_events.extend_from_slice(dispose_events);
+				new_events.push(event);
+			}
+			Event::Conditionally { condition, true_events, else_events, position } => {
+				new_events.push(Event::Conditionally {
+					condition,
+					true_events: add_before_exits(true_events.into_vec(), dispose_events, in_loop)
+						.into_boxed_slice(),
+					else_events: add_before_exits(else_events.into_vec(), dispose_events, in_loop)
+						.into_boxed_slice(),
+					position,
+				});
+			}
+			Event::Iterate { kind, iterate_over, initial } => {
+				new_events.push(Event::Iterate {
+					kind,
+					iterate_over: add_before_exits(iterate_over.into_vec(), dispose_events, true)
+						.into_boxed_slice(),
+					initial,
+				});
+			}
+			// After an `await`
+			Event::Deferred { events, position } => {
+				new_events.push(Event::Deferred {
+					events: add_before_exits(events.into_vec(), dispose_events, in_loop)
+						.into_boxed_slice(),
+					position,
+				});
+			}
+			event => new_events.push(event),
+		}
+	}
+	new_events
+}
+
+/// TODO temp, symbols are currently number constants on the `Symbols` interface
+fn get_symbol_key(
+	name: &str,
+	environment: &Environment,
+	types: &TypeStore,
+) -> Option<PropertyKey<'static>> {
+	let symbols = environment.get_type_from_name("Symbols")?;
+	let key = PropertyKey::String(Cow::Borrowed(name));
+	if let Logical::Pure(PropertyValue::Value(symbol)) =
+		environment.get_property_unbound(symbols, Publicity::Public, key, types)?
+	{
+		Some(PropertyKey::from_type(symbol, types))
+	} else {
+		None
+	}
+}
@@ -12,6 +12,7 @@ use crate::{
 		CanReferenceThis, ContextType, Syntax,
 	},
 	events::RootReference,
+	features::{generators, promises},
 	types::{
 		self,
 		classes::ClassValue,
@@ -205,12 +206,35 @@ pub enum FunctionBehavior {
 		/// The id of the generic that needs to be pulled out
 		this_object_type: TypeId,
 	},
+	/// Construct signatures (`new (a: T): U` members and `new (a: T) => U` annotations). Must be
+	/// called with new, but there is no `this` object so the return type is the instance
+	ConstructorSignature,
 }
 
 impl FunctionBehavior {
 	pub(crate) fn can_be_bound(&self) -> bool {
 		matches!(self, Self::Method { .. } | Self::Function { .. })
 	}
+
+	pub(crate) fn is_async(&self) -> bool {
+		match self {
+			Self::ArrowFunction { is_async }
+			| Self::Method { is_async, .. }
+			| Self::Function { is_async, .. } => *is_async,
+			Self::Constructor { .. } | Self::ConstructorSignature => false,
+		}
+	}
+
+	pub(crate) fn is_generator(&self) -> bool {
+		match self {
+			Self::Method { is_generator, .. } | Self::Function { is_generator, .. } => {
+				*is_generator
+			}
+			Self::ArrowFunction { .. } | Self::Constructor { .. } | Self::ConstructorSignature => {
+				false
+			}
+		}
+	}
 }
 
 /// Covers both actual functions and
@@ -297,6 +321,7 @@ pub enum FunctionRegisterBehavior<'a, A: crate::ASTImplementation> {
 		is_async: bool,
 		is_generator: bool,
 		super_type: Option<TypeId>,
+		class_prototype: TypeId,
 		// location: Option<String>,
 	},
 	Constructor {
@@ -358,8 +383,8 @@ where
 	A: crate::ASTImplementation,
 	F: SynthesisableFunction<A>,
 {
-	let _is_async = behavior.is_async();
-	let _is_generator = behavior.is_generator();
+	let is_async = behavior.is_async();
+	let is_generator = behavior.is_generator();
 
 	let (mut behavior, scope, constructor, location, expected_parameters, _expected_return) =
 		match behavior {
@@ -372,6 +397,7 @@ where
 					extends: super_type.is_some(),
 					type_of_super: super_type,
 					this_object_type: TypeId::ERROR_TYPE,
+					class_prototype: prototype,
 				},
 				Some((prototype, properties)),
 				None,
@@ -445,18 +471,41 @@ where
 				None,
 				None,
 			),
-			FunctionRegisterBehavior::ClassMethod { is_async, is_generator, super_type: _ }
-			| FunctionRegisterBehavior::ObjectMethod { is_async, is_generator } => (
+			FunctionRegisterBehavior::ClassMethod {
+				is_async,
+				is_generator,
+				super_type,
+				class_prototype,
+			} => (
+				FunctionBehavior::Method {
+					is_async,
+					is_generator,
+					free_this_id: TypeId::ERROR_TYPE,
+				},
+				FunctionScope::MethodFunction {
+					free_this_type: TypeId::ERROR_TYPE,
+					is_async,
+					is_generator,
+					type_of_super: super_type,
+					class_prototype: Some(class_prototype),
+				},
+				None,
+				None,
+				None,
+				None,
+			),
+			FunctionRegisterBehavior::ObjectMethod { is_async, is_generator } => (
 				FunctionBehavior::Method {
 					is_async,
 					is_generator,
 					free_this_id: TypeId::ERROR_TYPE,
 				},
-				// TODO eager super
 				FunctionScope::MethodFunction {
 					free_this_type: TypeId::ERROR_TYPE,
 					is_async,
 					is_generator,
+					type_of_super: None,
+					class_prototype: None,
 				},
 				None,
 				None,
@@ -547,11 +596,7 @@ where
 				// TODO what is the union, shouldn't it be the this_constraint?
 				*this_type = new_conditional_type;
 			}
-			FunctionScope::Constructor {
-				extends: _,
-				type_of_super: _,
-				ref mut this_object_type,
-			} => {
+			FunctionScope::Constructor { ref mut this_object_type, .. } => {
 				crate::utils::notify!("Setting 'this' type here");
 				if let Some((prototype, properties)) = constructor {
 					let new_this_object_type = types::create_this_before_function_synthesis(
@@ -601,16 +646,34 @@ where
 		// Temporary move events to satisfy borrow checker
 		let events = mem::take(&mut function_environment.facts.events);
 
+		// Returns in `async` functions are checked against what the promise resolves to and in
+		// generator functions against the `R` of `Generator<Y, R, N>`
+		let expected_return = if is_async {
+			return_type_annotation.map(|(annotation, position)| {
+				let resolves_to =
+					promises::get_promise_resolved_type(annotation, &checking_data.types);
+				(resolves_to.unwrap_or(annotation), position)
+			})
+		} else if is_generator {
+			return_type_annotation.map(|(annotation, position)| {
+				let arguments =
+					generators::get_generator_arguments(annotation, &checking_data.types);
+				(arguments.map_or(annotation, |(_, returned)| returned), position)
+			})
+		} else {
+			return_type_annotation
+		};
+
 		let returned = crate::events::helpers::get_return_from_events(
 			&mut events.iter(),
 			checking_data,
 			// TODO environment should be good enough, but needs environment not context
 			&mut function_environment,
-			return_type_annotation,
+			expected_return,
 		);
 		function_environment.facts.events = events;
 
-		match returned {
+		let returned = match returned {
 			crate::events::helpers::ReturnedTypeFromBlock::ContinuedExecution => {
 				TypeId::UNDEFINED_TYPE
 			}
@@ -618,6 +681,26 @@ where
 				checking_data.types.new_conditional_type(when, returns, TypeId::UNDEFINED_TYPE)
 			}
 			crate::events::helpers::ReturnedTypeFromBlock::Returned(ty) => ty,
+		};
+
+		if is_async {
+			promises::new_promise_type(returned, &mut checking_data.types)
+		} else if is_generator {
+			let expected_yield = return_type_annotation.and_then(|(annotation, position)| {
+				generators::get_generator_arguments(annotation, &checking_data.types)
+					.map(|(yielded, _)| (yielded, position))
+			});
+			let events = mem::take(&mut function_environment.facts.events);
+			let yielded = generators::get_yielded_type_from_events(
+				&events,
+				expected_yield,
+				&mut function_environment,
+				checking_data,
+			);
+			function_environment.facts.events = events;
+			generators::new_generator_type(yielded, returned, &mut checking_data.types)
+		} else {
+			returned
 		}
 	} else {
 		return_type_annotation.map_or(TypeId::UNDEFINED_TYPE, |(left, _)| left)
@@ -673,6 +756,11 @@ where
 		}
 	}
 
+	// For object type annotations in parameters etc
+	base_environment.facts.property_modifiers.extend(facts.property_modifiers);
+	base_environment.facts.call_signatures.extend(facts.call_signatures);
+	base_environment.facts.construct_signatures.extend(facts.construct_signatures);
+
 	for (on, properties) in facts.closure_current_values {
 		match base_environment.facts.closure_current_values.entry(on) {
 			Entry::Occupied(_occupied) => {}
@@ -721,6 +809,18 @@ where
 	// TODO why
 	base_environment.variable_names.extend(variable_names);
 
+	let effects = if is_async {
+		// TODO thrown values should reject the returned promise. Currently they are lost
+		let mut throw_positions = Vec::new();
+		crate::events::helpers::get_throw_positions(&facts.events, &mut throw_positions);
+		if let Some(position) = throw_positions.first() {
+			checking_data.raise_unimplemented_error("throw in async function", *position);
+		}
+		crate::events::helpers::defer_events_after_await(facts.events)
+	} else {
+		facts.events
+	};
+
 	FunctionType {
 		id,
 		constant_function: None,
@@ -728,7 +828,7 @@ where
 		type_parameters,
 		parameters: synthesised_parameters,
 		return_type: returned,
-		effects: facts.events,
+		effects,
 		free_variables,
 		closed_over_variables: closes_over,
 	}
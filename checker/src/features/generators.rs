@@ -0,0 +1,186 @@
+//! Generator functions (`function*`) and `yield`
+
+use source_map::SpanWithSource;
+
+use crate::{
+	diagnostics::{TypeCheckError, TypeStringRepresentation},
+	events::Event,
+	features::{
+		iteration::{get_iterated_type, get_iterated_values},
+		objects::SpecialObjects,
+	},
+	subtyping::{type_is_subtype, BasicEquality, SubTypeResult},
+	types::{
+		get_constraint, poly_types::generic_type_arguments::StructureGenericArguments, Constructor,
+		PolyNature, StructureGenerics, TypeStore,
+	},
+	CheckingData, Environment, Type, TypeId,
+};
+
+/// `Generator<yielded, returned, any>`
+pub(crate) fn new_generator_type(
+	yielded: TypeId,
+	returned: TypeId,
+	types: &mut TypeStore,
+) -> TypeId {
+	types.register_type(Type::Constructor(Constructor::StructureGenerics(StructureGenerics {
+		on: TypeId::GENERATOR_TYPE,
+		arguments: StructureGenericArguments {
+			type_arguments: FromIterator::from_iter([
+				(TypeId::Y_TYPE, (yielded, source_map::Nullable::NULL)),
+				(TypeId::R_TYPE, (returned, source_map::Nullable::NULL)),
+				(TypeId::N_TYPE, (TypeId::ANY_TYPE, source_map::Nullable::NULL)),
+			]),
+			closures: Default::default(),
+		},
+	})))
+}
+
+/// For `Generator<Y, R, N>` (and the result of calling a generator function) returns `Y` and `R`
+pub(crate) fn get_generator_arguments(ty: TypeId, types: &TypeStore) -> Option<(TypeId, TypeId)> {
+	let ty = if let Type::SpecialObject(SpecialObjects::Generator { of, .. }) =
+		types.get_type_by_id(ty)
+	{
+		*of
+	} else {
+		ty
+	};
+
+	if let Type::Constructor(Constructor::StructureGenerics(StructureGenerics {
+		on: TypeId::GENERATOR_TYPE,
+		arguments,
+	})) = types.get_type_by_id(ty)
+	{
+		// Unset arguments (for example `Generator<number>`) default to `any`
+		let get_argument = |on| {
+			arguments.type_arguments.get(&on).map_or(TypeId::ANY_TYPE, |(argument, _)| *argument)
+		};
+		Some((get_argument(TypeId::Y_TYPE), get_argument(TypeId::R_TYPE)))
+	} else {
+		None
+	}
+}
+
+/// The value of `yield operand` or `yield* operand`. Records the yield for the function
+pub fn yield_expression<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	operand: TypeId,
+	delegated: bool,
+	position: SpanWithSource,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, A>,
+) -> TypeId {
+	// Known values (for example from array literals) are yielded individually, as
+	// objects created in the generator are not available when it is called
+	let known_values = if delegated {
+		get_iterated_values(operand, environment, &checking_data.types)
+	} else {
+		None
+	};
+
+	if let Some(values) = known_values {
+		environment.facts.events.extend(values.into_iter().map(|yielded| Event::Yield {
+			yielded,
+			delegated: false,
+			position,
+		}));
+	} else {
+		environment.facts.events.push(Event::Yield { yielded: operand, delegated, position });
+	}
+
+	if delegated {
+		// `yield*` evaluates to what the delegated to generator returns
+		get_generator_arguments(operand, &checking_data.types)
+			.map_or(TypeId::ANY_TYPE, |(_, returned)| returned)
+	} else {
+		// TODO the `N` type, what is passed to `next`
+		TypeId::ANY_TYPE
+	}
+}
+
+/// Union of what is yielded in `events`, for the `Y` of a generator function's return type.
+/// Checks each against the `expected` (from the return type annotation)
+pub(crate) fn get_yielded_type_from_events<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	events: &[Event],
+	expected: Option<(TypeId, SpanWithSource)>,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, A>,
+) -> TypeId {
+	fn collect_yields(events: &[Event], yields: &mut Vec<(TypeId, bool, SpanWithSource)>) {
+		for event in events {
+			match event {
+				Event::Yield { yielded, delegated, position } => {
+					yields.push((*yielded, *delegated, *position));
+				}
+				Event::Conditionally { true_events, else_events, .. } => {
+					collect_yields(true_events, yields);
+					collect_yields(else_events, yields);
+				}
+				Event::Iterate { iterate_over: events, .. } | Event::Deferred { events, .. } => {
+					collect_yields(events, yields);
+				}
+				_ => {}
+			}
+		}
+	}
+
+	let mut yields = Vec::new();
+	collect_yields(events, &mut yields);
+
+	let mut yielded_type = None::<TypeId>;
+	for (yielded, delegated, position) in yields {
+		let yielded = if delegated {
+			get_iterated_type(yielded, environment, &mut checking_data.types)
+		} else {
+			yielded
+		};
+		// Values that change between iterations are generalised
+		let yielded = if let Type::RootPolyType(PolyNature::FreeVariable { .. }) =
+			checking_data.types.get_type_by_id(yielded)
+		{
+			get_constraint(yielded, &checking_data.types).unwrap_or(yielded)
+		} else {
+			yielded
+		};
+
+		if let Some((expected, annotation_position)) = expected {
+			let mut behavior =
+				BasicEquality { add_property_restrictions: true, position: annotation_position };
+			let result = type_is_subtype(
+				expected,
+				yielded,
+				&mut behavior,
+				environment,
+				&checking_data.types,
+			);
+			if let SubTypeResult::IsNotSubType(mismatch) = result {
+				let ctx = &environment.as_general_context();
+				checking_data.diagnostics_container.add_error(
+					TypeCheckError::YieldedTypeDoesNotMatch {
+						expected_yield_type: TypeStringRepresentation::from_type_id(
+							expected,
+							ctx,
+							&checking_data.types,
+							false,
+						),
+						yielded_type: TypeStringRepresentation::from_type_id(
+							yielded,
+							ctx,
+							&checking_data.types,
+							false,
+						),
+						annotation_position,
+						yielded_position: position,
+						mismatch: mismatch.into_representation(ctx, &checking_data.types),
+					},
+				);
+			}
+		}
+
+		yielded_type = Some(match yielded_type {
+			Some(existing) => checking_data.types.new_or_type(existing, yielded),
+			None => yielded,
+		});
+	}
+
+	yielded_type.unwrap_or(TypeId::NEVER_TYPE)
+}
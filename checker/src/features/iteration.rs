@@ -2,16 +2,18 @@ use std::collections::HashMap;
 
 use crate::{
 	context::{
-		environment::Label, get_value_of_variable, invocation::InvocationContext,
-		CallCheckingBehavior,
+		environment::Label, facts::Publicity, get_on_ctx, get_value_of_variable,
+		invocation::InvocationContext, CallCheckingBehavior, Logical, VariableRegisterArguments,
 	},
 	events::{
 		application::ErrorsAndInfo, apply_event, Event, FinalEvent, InitialVariables, RootReference,
 	},
-	features::operations::CanonicalEqualityAndInequality,
+	features::{objects::SpecialObjects, operations::CanonicalEqualityAndInequality},
 	types::{
+		get_constraint,
 		poly_types::{generic_type_arguments::TypeArgumentStore, FunctionTypeArguments},
-		substitute, Constructor, ObjectNature, PolyNature, TypeStore,
+		properties::{PropertyKey, PropertyValue},
+		substitute, Constructor, ObjectNature, PolyNature, StructureGenerics, TypeStore,
 	},
 	CheckingData, Constant, Environment, Facts, Scope, Type, TypeId, VariableId,
 };
@@ -183,14 +185,15 @@ pub fn synthesise_iteration<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 		}
 		IterationBehavior::For { initialiser, condition, afterthought } => {
 			// 99% of the time need to do this, so doing here anyway
-			let ((condition, result, dependent_variables), ..) = environment
+			let ((condition, result, dependent_variables, disposables), ..) = environment
 				.new_lexical_environment_fold_into_parent(
 					Scope::Block {},
 					checking_data,
 					|environment, checking_data| {
+						let mut disposables = Vec::new();
 						let dependent_variables_initial_values: HashMap<VariableId, TypeId> =
 							if let Some(initialiser) = initialiser {
-								A::synthesise_for_loop_initialiser(
+								disposables = A::synthesise_for_loop_initialiser(
 									initialiser,
 									environment,
 									checking_data,
@@ -273,7 +276,7 @@ pub fn synthesise_iteration<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 
 						// TODO copy value of variables between things, or however it works
 
-						(condition, events, dependent_variables)
+						(condition, events, dependent_variables, disposables)
 					},
 				);
 
@@ -310,6 +313,14 @@ pub fn synthesise_iteration<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 			) {
 				todo!("{early_return:?}")
 			}
+
+			let events_end = environment.facts.events.len();
+			crate::features::disposal::dispose_at_scope_exit(
+				disposables,
+				events_end,
+				environment,
+				checking_data,
+			);
 		}
 		IterationBehavior::ForIn { lhs: _, rhs } => {
 			// TODO for of Object.keys ???
@@ -344,7 +355,51 @@ pub fn synthesise_iteration<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 				todo!("{early_return:?}")
 			}
 		}
-		IterationBehavior::ForOf { lhs: _, rhs: _ } => todo!(),
+		IterationBehavior::ForOf { lhs, rhs } => {
+			let on = A::synthesise_expression(rhs, TypeId::ANY_TYPE, environment, checking_data);
+
+			let element = get_iterated_type(on, environment, &mut checking_data.types);
+			// Stands in for each value. Set to each value if the iteration is unrolled
+			let variable = checking_data
+				.types
+				.register_type(Type::RootPolyType(PolyNature::Parameter { fixed_to: element }));
+
+			let ((), result, ..) = environment.new_lexical_environment_fold_into_parent(
+				Scope::Iteration { label },
+				checking_data,
+				|environment, checking_data| {
+					A::declare_and_assign_to_fields(
+						lhs,
+						environment,
+						checking_data,
+						VariableRegisterArguments {
+							constant: false,
+							space: None,
+							initial_value: Some(variable),
+						},
+					);
+
+					loop_body(environment, checking_data);
+				},
+			);
+
+			let events = result.unwrap().0.events;
+
+			if let Some(early_return) = run_iteration_block(
+				IterationKind::Iterator { on, variable },
+				events,
+				InitialVariablesInput::Compute,
+				&mut FunctionTypeArguments::new(),
+				environment,
+				&mut InvocationContext::new_empty(),
+				// TODO shouldn't be needed
+				&mut Default::default(),
+				&mut checking_data.types,
+			) {
+				crate::utils::notify!("Loop returned {:?}", early_return);
+				environment.facts.events.push(Event::FinalEvent(early_return));
+			}
+		}
 	}
 }
 
@@ -362,7 +417,12 @@ pub enum IterationKind {
 		postfix_condition: bool,
 	},
 	Properties(TypeId),
-	Iterator(TypeId),
+	/// `for of`
+	Iterator {
+		on: TypeId,
+		/// The type the element is referenced by in the events
+		variable: TypeId,
+	},
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -418,6 +478,7 @@ pub(crate) fn run_iteration_block(
 
 				evaluate_iterations(
 					iterations,
+					None,
 					&events,
 					type_arguments,
 					top_environment,
@@ -426,58 +487,13 @@ pub(crate) fn run_iteration_block(
 					types,
 				)
 			} else {
-				let initial = match initial {
-					InitialVariablesInput::Calculated(initial) => {
-						for (id, value) in &initial {
-							invocation_context
-								.get_latest_facts(top_environment)
-								.variable_current_value
-								.insert(*id, *value);
-						}
-
-						initial
-					}
-					InitialVariablesInput::Compute => {
-						// TODO maybe treat the same way as closures
-						let mut initial = map_vec::Map::new();
-
-						for event in &events {
-							// TODO also nested events right?
-							if let Event::ReadsReference {
-								reference: RootReference::Variable(variable_id),
-								reflects_dependency: Some(free_variable_id),
-								position: _,
-							} = event
-							{
-								let value_before_iterations = get_value_of_variable(
-									top_environment.facts_chain(),
-									*variable_id,
-									None::<&crate::types::poly_types::FunctionTypeArguments>,
-								)
-								.unwrap();
-
-								crate::utils::notify!(
-									"setting '{}' to have initial type {}",
-									top_environment.get_variable_name(*variable_id),
-									crate::types::printing::print_type(
-										value_before_iterations,
-										types,
-										&top_environment.as_general_context(),
-										true
-									)
-								);
-
-								initial.insert(*variable_id, value_before_iterations);
-
-								top_environment
-									.facts
-									.variable_current_value
-									.insert(*variable_id, *free_variable_id);
-							}
-						}
-						initial
-					}
-				};
+				let initial = set_initial_variables(
+					initial,
+					&events,
+					top_environment,
+					invocation_context,
+					types,
+				);
 
 				crate::utils::notify!("Saving events");
 
@@ -512,13 +528,204 @@ pub(crate) fn run_iteration_block(
 				todo!("dependent in")
 			}
 		}
-		IterationKind::Iterator(_) => todo!(),
+		IterationKind::Iterator { on, variable } => {
+			let values = get_iterated_values(on, top_environment, types)
+				.filter(|values| values.len() < MAX_ITERATIONS);
+
+			if let Some(values) = values {
+				crate::utils::notify!("Evaluating a constant amount of values {:?}", values.len());
+
+				if let InitialVariablesInput::Calculated(initial) = initial {
+					for (variable_id, initial_value) in &initial {
+						invocation_context
+							.get_latest_facts(top_environment)
+							.variable_current_value
+							.insert(*variable_id, *initial_value);
+					}
+				}
+
+				evaluate_iterations(
+					values.len(),
+					Some((variable, &values)),
+					&events,
+					type_arguments,
+					top_environment,
+					invocation_context,
+					errors,
+					types,
+				)
+			} else {
+				let initial = set_initial_variables(
+					initial,
+					&events,
+					top_environment,
+					invocation_context,
+					types,
+				);
+
+				invocation_context.get_latest_facts(top_environment).events.push(Event::Iterate {
+					kind: IterationKind::Iterator { on, variable },
+					initial,
+					iterate_over: events.into_boxed_slice(),
+				});
+
+				None
+			}
+		}
+	}
+}
+
+/// Values of variables before the loop, when not unrolled. Variables changed in the loop
+/// are set to the free variable they are read as
+fn set_initial_variables(
+	initial: InitialVariablesInput,
+	events: &[Event],
+	top_environment: &mut Environment,
+	invocation_context: &mut InvocationContext,
+	types: &TypeStore,
+) -> InitialVariables {
+	match initial {
+		InitialVariablesInput::Calculated(initial) => {
+			for (id, value) in &initial {
+				invocation_context
+					.get_latest_facts(top_environment)
+					.variable_current_value
+					.insert(*id, *value);
+			}
+
+			initial
+		}
+		InitialVariablesInput::Compute => {
+			// TODO maybe treat the same way as closures
+			let mut initial = map_vec::Map::new();
+
+			for event in events {
+				// TODO also nested events right?
+				if let Event::ReadsReference {
+					reference: RootReference::Variable(variable_id),
+					reflects_dependency: Some(free_variable_id),
+					position: _,
+				} = event
+				{
+					let value_before_iterations = get_value_of_variable(
+						top_environment.facts_chain(),
+						*variable_id,
+						None::<&crate::types::poly_types::FunctionTypeArguments>,
+					)
+					.unwrap();
+
+					crate::utils::notify!(
+						"setting '{}' to have initial type {}",
+						top_environment.get_variable_name(*variable_id),
+						crate::types::printing::print_type(
+							value_before_iterations,
+							types,
+							&top_environment.as_general_context(),
+							true
+						)
+					);
+
+					initial.insert(*variable_id, value_before_iterations);
+
+					top_environment
+						.facts
+						.variable_current_value
+						.insert(*variable_id, *free_variable_id);
+				}
+			}
+			initial
+		}
+	}
+}
+
+/// The values that iterating over `on` gives, if they are known. For arrays and the results
+/// of calling generator functions
+pub(crate) fn get_iterated_values(
+	on: TypeId,
+	environment: &Environment,
+	types: &TypeStore,
+) -> Option<Vec<TypeId>> {
+	match types.get_type_by_id(on) {
+		Type::SpecialObject(SpecialObjects::Generator { yielded, .. }) => yielded.clone(),
+		// Objects with arguments from being substituted
+		Type::Constructor(Constructor::StructureGenerics(StructureGenerics { on, .. }))
+			if matches!(types.get_type_by_id(*on), Type::Object(ObjectNature::RealDeal)) =>
+		{
+			get_iterated_values(*on, environment, types)
+		}
+		Type::Object(ObjectNature::RealDeal) => {
+			let is_array = environment
+				.parents_iter()
+				.find_map(|ctx| get_on_ctx!(ctx.facts.prototypes.get(&on)).copied())
+				== Some(TypeId::ARRAY_TYPE);
+
+			if !is_array {
+				return None;
+			}
+
+			let get_value = |key: PropertyKey| {
+				if let Some(Logical::Pure(PropertyValue::Value(value))) =
+					environment.get_property_unbound(on, Publicity::Public, key, types)
+				{
+					Some(value)
+				} else {
+					None
+				}
+			};
+
+			let length = get_value(PropertyKey::String("length".into()))?;
+			let Type::Constant(Constant::Number(length)) = types.get_type_by_id(length) else {
+				return None;
+			};
+
+			(0u32..)
+				.take_while(|idx| f64::from(*idx) < length.into_inner())
+				.map(|idx| get_value(PropertyKey::from_usize(idx as usize)))
+				.collect()
+		}
+		_ => None,
+	}
+}
+
+/// The type of the values that iterating over `on` gives
+pub(crate) fn get_iterated_type(
+	on: TypeId,
+	environment: &Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	if let Some(values) = get_iterated_values(on, environment, types) {
+		return values
+			.into_iter()
+			.reduce(|acc, value| types.new_or_type(acc, value))
+			.unwrap_or(TypeId::NEVER_TYPE);
+	}
+
+	if let Some((yielded, _)) = super::generators::get_generator_arguments(on, types) {
+		return yielded;
+	}
+
+	match types.get_type_by_id(on) {
+		Type::Constructor(Constructor::StructureGenerics(StructureGenerics {
+			on: TypeId::ARRAY_TYPE,
+			arguments,
+		})) => arguments.get_argument(TypeId::T_TYPE).unwrap_or(TypeId::ANY_TYPE),
+		Type::RootPolyType(_) | Type::Constructor(_) => {
+			if let Some(constraint) = get_constraint(on, types) {
+				get_iterated_type(constraint, environment, types)
+			} else {
+				TypeId::ANY_TYPE
+			}
+		}
+		_ => TypeId::ANY_TYPE,
 	}
 }
 
 #[must_use]
+#[allow(clippy::too_many_arguments)]
 fn evaluate_iterations(
 	iterations: usize,
+	// For `for of`, the type that is set to each value
+	loop_variable: Option<(TypeId, &[TypeId])>,
 	events: &[Event],
 	arguments: &mut FunctionTypeArguments,
 	top_environment: &mut Environment,
@@ -531,7 +738,11 @@ fn evaluate_iterations(
 		return None;
 	}
 
-	'main_iterations: for _ in 0..iterations {
+	'main_iterations: for iteration in 0..iterations {
+		if let Some((variable, values)) = loop_variable {
+			arguments.set_id_from_reference(variable, values[iteration]);
+		}
+
 		'inner_loop: for event in events {
 			let result = invocation_context.new_loop_iteration(|invocation_context| {
 				apply_event(
@@ -714,8 +925,13 @@ fn calculate_result_of_loop(
 						.and_then(|vs| vs.get(possible_changing_variable_id).copied())
 					{
 						end
+					} else if let Some(value) =
+						inside_loop.variable_values.get(possible_changing_variable_id)
+					{
+						*value
 					} else {
-						*inside_loop.variable_values.get(possible_changing_variable_id).unwrap()
+						crate::utils::notify!("Variable not updated directly in loop");
+						return Err(());
 					},
 				);
 
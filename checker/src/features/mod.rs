@@ -7,10 +7,15 @@
 /// - Internal structures
 pub mod assignments;
 pub mod constant_functions;
+pub mod disposal;
 pub mod functions;
+pub mod generators;
 pub mod iteration;
+pub mod module_resolution;
 pub mod modules;
+pub(crate) mod narrowing;
 pub mod objects;
 pub mod operations;
+pub mod promises;
 pub mod template_literal;
 pub mod variables;
@@ -0,0 +1,391 @@
+//! Resolution of non-relative (package) import specifiers. Follows the parts of the
+//! [Node](https://nodejs.org/api/packages.html) and TypeScript algorithms that are required
+//! for finding declaration files
+//!
+//! All file system access goes through [`ReadFromFS`] so the same logic works with in-memory files
+
+use std::{
+	collections::HashMap,
+	path::{Path, PathBuf},
+};
+
+use crate::ReadFromFS;
+
+/// Conditions in `package.json` `exports` that are considered, in order of preference
+const EXPORT_CONDITIONS: &[&str] = &["types", "typings", "import", "node", "require", "default"];
+
+/// Extensions tried when a specifier has no extension (or an extension that does not hold types)
+const EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts"];
+
+/// Aliases for imports. Equivalent to `compilerOptions.baseUrl` and `compilerOptions.paths` in a `tsconfig.json`
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Deserialize), serde(default))]
+pub struct ImportAliases {
+	/// Directory that non-relative specifiers and `paths` are resolved from
+	pub base_url: Option<PathBuf>,
+	/// Patterns (with at most one `*`) to a list of locations to try
+	pub paths: HashMap<String, Vec<String>>,
+}
+
+/// Resolves `specifier` imported from the file at `from`
+///
+/// 1. Using `paths` and `baseUrl` aliases
+/// 2. Walking up from `from` looking in `node_modules` folders for the package, falling back to
+///    `node_modules/@types`
+///
+/// Returns the path to a **declaration file** or source file
+pub(crate) fn resolve_package_import<T: ReadFromFS>(
+	from: &Path,
+	specifier: &str,
+	aliases: &ImportAliases,
+	file_reader: &T,
+) -> Option<PathBuf> {
+	if let Some(resolved) = resolve_alias(specifier, aliases, file_reader) {
+		return Some(resolved);
+	}
+
+	let (package_name, subpath) = split_specifier(specifier)?;
+
+	// `@scope/name` becomes `@types/scope__name`
+	let types_package_name = match package_name.strip_prefix('@') {
+		Some(scoped) => scoped.replace('/', "__"),
+		None => package_name.to_owned(),
+	};
+
+	for directory in from.parent().unwrap_or(Path::new("")).ancestors() {
+		let node_modules = directory.join("node_modules");
+
+		let package = node_modules.join(package_name);
+		if let Some(resolved) = resolve_in_package(&package, subpath, file_reader) {
+			return Some(resolved);
+		}
+
+		let types_package = node_modules.join("@types").join(&types_package_name);
+		if let Some(resolved) = resolve_in_package(&types_package, subpath, file_reader) {
+			return Some(resolved);
+		}
+	}
+
+	None
+}
+
+fn resolve_alias<T: ReadFromFS>(
+	specifier: &str,
+	aliases: &ImportAliases,
+	file_reader: &T,
+) -> Option<PathBuf> {
+	let base = aliases.base_url.as_deref().unwrap_or(Path::new(""));
+
+	// Exact patterns take precedence, then longest prefix
+	let mut matches: Vec<(&str, &str)> = aliases
+		.paths
+		.keys()
+		.filter_map(|pattern| Some((pattern.as_str(), match_pattern(pattern, specifier)?)))
+		.collect();
+	matches.sort_by_key(|(pattern, _)| {
+		(pattern.contains('*'), std::cmp::Reverse(pattern_prefix_length(pattern)))
+	});
+
+	for (pattern, matched) in matches {
+		for location in &aliases.paths[pattern] {
+			let location = location.replacen('*', matched, 1);
+			if let Some(resolved) = resolve_as_file_or_directory(&base.join(location), file_reader)
+			{
+				return Some(resolved);
+			}
+		}
+	}
+
+	if aliases.base_url.is_some() {
+		resolve_as_file_or_directory(&base.join(specifier), file_reader)
+	} else {
+		None
+	}
+}
+
+/// Length before the `*`. Patterns with longer prefixes are more specific
+fn pattern_prefix_length(pattern: &str) -> usize {
+	pattern.find('*').unwrap_or(pattern.len())
+}
+
+/// Returns the part matched by `*` (or empty if no wildcard)
+fn match_pattern<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+	if let Some((prefix, suffix)) = pattern.split_once('*') {
+		specifier.strip_prefix(prefix).and_then(|rest| rest.strip_suffix(suffix))
+	} else {
+		(pattern == specifier).then_some("")
+	}
+}
+
+/// Splits `@scope/name/sub/path` into `@scope/name` and `sub/path`
+fn split_specifier(specifier: &str) -> Option<(&str, &str)> {
+	let mut slashes = specifier.match_indices('/').map(|(idx, _)| idx);
+	let end = if specifier.starts_with('@') {
+		// Skip the scope
+		slashes.next()?;
+		slashes.next()
+	} else {
+		slashes.next()
+	};
+
+	Some(match end {
+		Some(end) => (&specifier[..end], &specifier[end + 1..]),
+		None => (specifier, ""),
+	})
+}
+
+fn resolve_in_package<T: ReadFromFS>(
+	package: &Path,
+	subpath: &str,
+	file_reader: &T,
+) -> Option<PathBuf> {
+	let package_json = file_reader(&package.join("package.json"))
+		.and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+
+	if let Some(package_json) = package_json {
+		if let Some(exports) = package_json.get("exports") {
+			// If `exports` exists, only its entries are accessible
+			let key = if subpath.is_empty() { ".".to_owned() } else { format!("./{subpath}") };
+			let target = resolve_exports(exports, &key)?;
+			return resolve_target(&package.join(target), file_reader);
+		}
+
+		if subpath.is_empty() {
+			for field in ["types", "typings"] {
+				if let Some(serde_json::Value::String(path)) = package_json.get(field) {
+					if let Some(resolved) = resolve_target(&package.join(path), file_reader) {
+						return Some(resolved);
+					}
+				}
+			}
+		}
+	}
+
+	if subpath.is_empty() {
+		resolve_as_file_or_directory(package, file_reader)
+	} else {
+		resolve_as_file_or_directory(&package.join(subpath), file_reader)
+	}
+}
+
+/// Finds the target for `key` (`.` or `./subpath`). Handles the shorthand form, conditions and
+/// subpath patterns
+fn resolve_exports(exports: &serde_json::Value, key: &str) -> Option<String> {
+	match exports {
+		serde_json::Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => {
+			if let Some(value) = map.get(key) {
+				return resolve_conditions(value, None);
+			}
+			// Like Node, the pattern with the longest prefix is used (regardless of order)
+			let (_, matched, value) = map
+				.iter()
+				.filter_map(|(pattern, value)| {
+					let matched = match_pattern(pattern, key).filter(|_| pattern.contains('*'))?;
+					Some((pattern, matched, value))
+				})
+				.max_by_key(|(pattern, _, _)| (pattern_prefix_length(pattern), pattern.len()))?;
+			resolve_conditions(value, Some(matched))
+		}
+		// Shorthand for `{ ".": ... }`
+		exports => (key == ".").then(|| resolve_conditions(exports, None)).flatten(),
+	}
+}
+
+fn resolve_conditions(value: &serde_json::Value, wildcard: Option<&str>) -> Option<String> {
+	match value {
+		serde_json::Value::String(target) => Some(match wildcard {
+			Some(wildcard) => target.replace('*', wildcard),
+			None => target.clone(),
+		}),
+		serde_json::Value::Object(conditions) => EXPORT_CONDITIONS.iter().find_map(|condition| {
+			conditions.get(*condition).and_then(|value| resolve_conditions(value, wildcard))
+		}),
+		serde_json::Value::Array(alternatives) => {
+			alternatives.iter().find_map(|value| resolve_conditions(value, wildcard))
+		}
+		_ => None,
+	}
+}
+
+/// For a target that may be a JavaScript file, finds the declaration file alongside it
+fn resolve_target<T: ReadFromFS>(target: &Path, file_reader: &T) -> Option<PathBuf> {
+	let path = target.to_str()?;
+	for (js_extension, declaration_extension) in
+		[(".js", ".d.ts"), (".mjs", ".d.mts"), (".cjs", ".d.cts")]
+	{
+		if let Some(stem) = path.strip_suffix(js_extension) {
+			let declaration = PathBuf::from(format!("{stem}{declaration_extension}"));
+			return file_reader(&declaration).is_some().then_some(declaration);
+		}
+	}
+	resolve_as_file_or_directory(target, file_reader)
+}
+
+fn resolve_as_file_or_directory<T: ReadFromFS>(path: &Path, file_reader: &T) -> Option<PathBuf> {
+	let has_types_extension = path
+		.file_name()
+		.and_then(|name| name.to_str())
+		.is_some_and(|name| EXTENSIONS.iter().any(|ext| name.ends_with(&format!(".{ext}"))));
+
+	if has_types_extension && file_reader(path).is_some() {
+		return Some(path.to_owned());
+	}
+
+	let path = path.to_str()?;
+	let candidates = EXTENSIONS
+		.iter()
+		.map(|ext| format!("{path}.{ext}"))
+		.chain(EXTENSIONS.iter().map(|ext| format!("{path}/index.{ext}")));
+
+	for candidate in candidates {
+		let candidate = PathBuf::from(candidate);
+		if file_reader(&candidate).is_some() {
+			return Some(candidate);
+		}
+	}
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+	};
+
+	use super::{resolve_package_import, ImportAliases};
+
+	fn resolve(files: &[(&str, &str)], specifier: &str) -> Option<PathBuf> {
+		resolve_with_aliases(files, specifier, &ImportAliases::default())
+	}
+
+	fn resolve_with_aliases(
+		files: &[(&str, &str)],
+		specifier: &str,
+		aliases: &ImportAliases,
+	) -> Option<PathBuf> {
+		let files: HashMap<PathBuf, String> = files
+			.iter()
+			.map(|(path, content)| (PathBuf::from(path), (*content).to_owned()))
+			.collect();
+		let file_reader = |path: &Path| files.get(path).cloned();
+		resolve_package_import(Path::new("project/src/main.ts"), specifier, aliases, &file_reader)
+	}
+
+	#[test]
+	fn exports_conditions() {
+		let files = [
+			(
+				"project/node_modules/pkg/package.json",
+				r#"{ "exports": { ".": { "import": "./esm/index.js", "types": "./types/index.d.ts" } } }"#,
+			),
+			("project/node_modules/pkg/esm/index.d.ts", ""),
+			("project/node_modules/pkg/types/index.d.ts", ""),
+		];
+		assert_eq!(
+			resolve(&files, "pkg"),
+			Some(PathBuf::from("project/node_modules/pkg/types/index.d.ts"))
+		);
+
+		// Nested conditions and the shorthand form
+		let files = [
+			(
+				"project/node_modules/pkg/package.json",
+				r#"{ "exports": { "node": { "import": "./node.mjs" }, "default": "./index.js" } }"#,
+			),
+			("project/node_modules/pkg/node.d.mts", ""),
+			("project/node_modules/pkg/index.d.ts", ""),
+		];
+		assert_eq!(
+			resolve(&files, "pkg"),
+			Some(PathBuf::from("project/node_modules/pkg/node.d.mts"))
+		);
+	}
+
+	#[test]
+	fn exports_restrict_subpaths() {
+		let files = [
+			("project/node_modules/pkg/package.json", r#"{ "exports": { ".": "./index.js" } }"#),
+			("project/node_modules/pkg/index.d.ts", ""),
+			("project/node_modules/pkg/internal.d.ts", ""),
+		];
+		assert_eq!(resolve(&files, "pkg/internal"), None);
+	}
+
+	#[test]
+	fn subpath_patterns_use_longest_prefix() {
+		let files = [
+			(
+				"project/node_modules/pkg/package.json",
+				r#"{ "exports": { "./*": "./dist/*.js", "./features/*": "./dist/features/*/index.js" } }"#,
+			),
+			("project/node_modules/pkg/dist/utils.d.ts", ""),
+			("project/node_modules/pkg/dist/features/a.d.ts", ""),
+			("project/node_modules/pkg/dist/features/a/index.d.ts", ""),
+		];
+		assert_eq!(
+			resolve(&files, "pkg/utils"),
+			Some(PathBuf::from("project/node_modules/pkg/dist/utils.d.ts"))
+		);
+		assert_eq!(
+			resolve(&files, "pkg/features/a"),
+			Some(PathBuf::from("project/node_modules/pkg/dist/features/a/index.d.ts"))
+		);
+	}
+
+	#[test]
+	fn types_and_typings_fields() {
+		let files = [
+			("project/node_modules/a/package.json", r#"{ "types": "./lib/a.d.ts" }"#),
+			("project/node_modules/a/lib/a.d.ts", ""),
+			(
+				"project/node_modules/b/package.json",
+				r#"{ "typings": "./b.d.ts", "main": "./b.js" }"#,
+			),
+			("project/node_modules/b/b.d.ts", ""),
+			("project/node_modules/c/package.json", r#"{ "main": "./c.js" }"#),
+			("project/node_modules/c/index.d.ts", ""),
+		];
+		assert_eq!(resolve(&files, "a"), Some(PathBuf::from("project/node_modules/a/lib/a.d.ts")));
+		assert_eq!(resolve(&files, "b"), Some(PathBuf::from("project/node_modules/b/b.d.ts")));
+		// Falls back to `index.d.ts`
+		assert_eq!(resolve(&files, "c"), Some(PathBuf::from("project/node_modules/c/index.d.ts")));
+	}
+
+	#[test]
+	fn types_packages() {
+		let files = [
+			("project/node_modules/lodash/package.json", r#"{ "main": "./lodash.js" }"#),
+			("project/node_modules/@types/lodash/index.d.ts", ""),
+			("node_modules/@types/scope__name/index.d.ts", ""),
+		];
+		assert_eq!(
+			resolve(&files, "lodash"),
+			Some(PathBuf::from("project/node_modules/@types/lodash/index.d.ts"))
+		);
+		// Scoped packages, found in an ancestor directory
+		assert_eq!(
+			resolve(&files, "@scope/name"),
+			Some(PathBuf::from("node_modules/@types/scope__name/index.d.ts"))
+		);
+		assert_eq!(resolve(&files, "missing"), None);
+	}
+
+	#[test]
+	fn path_aliases_use_longest_prefix() {
+		let aliases = ImportAliases {
+			base_url: Some(PathBuf::from("project")),
+			paths: [
+				("@/*".to_owned(), vec!["src/*".to_owned()]),
+				("@/components/*".to_owned(), vec!["ui/*".to_owned()]),
+			]
+			.into_iter()
+			.collect(),
+		};
+		let files = [("project/src/components/button.ts", ""), ("project/ui/button.ts", "")];
+		assert_eq!(
+			resolve_with_aliases(&files, "@/components/button", &aliases),
+			Some(PathBuf::from("project/ui/button.ts"))
+		);
+	}
+}
@@ -4,7 +4,8 @@ use crate::{
 	CheckingData, Environment, Scope, Type, TypeId, VariableId,
 };
 
-use source_map::Span;
+use source_map::{SourceId, Span};
+use std::collections::HashMap;
 
 #[derive(Debug)]
 pub struct NamePair<'a> {
@@ -26,6 +27,10 @@ pub enum ImportKind<'a, T: Iterator<Item = NamePair<'a>>> {
 pub struct SynthesisedModule<M> {
 	pub content: M,
 	pub exported: Exported,
+	/// Import specifiers to the (non definition) modules they resolved to. Used for bundling
+	pub imports: HashMap<String, SourceId>,
+	/// Variables declared at the top level of the module (so not including imports). Used for bundling
+	pub declared_variables: HashMap<String, VariableId>,
 	/// TODO ...
 	pub facts: Facts,
 }
@@ -81,6 +86,7 @@ pub fn import_items<
 >(
 	environment: &mut Environment,
 	partial_import_path: &str,
+	is_json_import: bool,
 	import_position: Span,
 	default_import: Option<(&str, Span)>,
 	kind: ImportKind<'b, P>,
@@ -98,7 +104,13 @@ pub fn import_items<
 		return;
 	}
 
-	let exports = checking_data.import_file(current_source, partial_import_path, environment);
+	let exports = checking_data.import_file(
+		current_source,
+		partial_import_path,
+		is_json_import,
+		import_position.with_source(current_source),
+		environment,
+	);
 
 	if let Err(ref err) = exports {
 		checking_data.diagnostics_container.add_error(
@@ -0,0 +1,343 @@
+use std::collections::HashSet;
+
+use crate::{
+	context::{facts::Publicity, get_on_ctx, Logical},
+	features::{
+		functions::FunctionBehavior,
+		operations::{type_of_name, CanonicalEqualityAndInequality, PureUnary},
+	},
+	types::{
+		get_constraint, is_type_truthy_falsy, properties::PropertyKey, Constructor, PolyNature,
+		TypeOperator, TypeRelationOperator, TypeStore,
+	},
+	Constant, Decidable, Environment, PropertyValue, Type, TypeId,
+};
+
+/// Refines values in the environment for a branch where `condition` is truthy (or falsy if
+/// `negate`). Variables are re-pointed to the narrowed value and properties of dependent objects
+/// are set on the facts of `environment`, so the refinements end with the branch
+pub(crate) fn narrow_based_on_condition(
+	condition: TypeId,
+	negate: bool,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) {
+	let Type::Constructor(constructor) = types.get_type_by_id(condition) else {
+		narrow_truthiness(condition, negate, environment, types);
+		return;
+	};
+
+	match constructor.clone() {
+		Constructor::UnaryOperator { operator: PureUnary::LogicalNot, operand } => {
+			narrow_based_on_condition(operand, !negate, environment, types);
+		}
+		// `lhs && rhs` is truthy only if both are and `lhs || rhs` is falsy only if both are
+		Constructor::ConditionalResult { condition: lhs, truthy_result, else_result, .. } => {
+			if !negate && else_result == lhs {
+				narrow_based_on_condition(lhs, false, environment, types);
+				narrow_based_on_condition(truthy_result, false, environment, types);
+			} else if negate && truthy_result == lhs {
+				narrow_based_on_condition(lhs, true, environment, types);
+				narrow_based_on_condition(else_result, true, environment, types);
+			}
+		}
+		Constructor::CanonicalRelationOperator {
+			lhs,
+			operator: CanonicalEqualityAndInequality::StrictEqual,
+			rhs,
+		} => narrow_equality(lhs, rhs, negate, environment, types),
+		Constructor::TypeRelationOperator(TypeRelationOperator::InstanceOf { ty, rhs }) => {
+			if !negate {
+				narrow_instance_of(ty, rhs, environment, types);
+			}
+		}
+		Constructor::TypeRelationOperator(TypeRelationOperator::HasProperty { ty, property }) => {
+			let narrowed = filter_members(ty, types, |member, types| {
+				if member == TypeId::ANY_TYPE || types.get_type_by_id(member).is_dependent() {
+					return Some(member);
+				}
+				let has_property = environment
+					.get_property_unbound(member, Publicity::Public, property.clone(), types)
+					.is_some();
+				(has_property != negate).then_some(member)
+			});
+			if let Some(narrowed) = narrowed {
+				narrow_reference(ty, narrowed, environment, types);
+			}
+		}
+		Constructor::CanonicalRelationOperator { .. }
+		| Constructor::UnaryOperator { .. }
+		| Constructor::TypeRelationOperator(TypeRelationOperator::Extends { .. }) => {}
+		Constructor::BinaryOperator { .. }
+		| Constructor::TypeOperator(_)
+		| Constructor::Image { .. }
+		| Constructor::Property { .. }
+		| Constructor::StructureGenerics(_)
+		| Constructor::Mapped(_) => narrow_truthiness(condition, negate, environment, types),
+	}
+}
+
+/// `lhs === rhs`. Either side can be the reference, the other side must not be dependent
+fn narrow_equality(
+	lhs: TypeId,
+	rhs: TypeId,
+	negate: bool,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) {
+	let (reference, value) =
+		if types.get_type_by_id(rhs).is_dependent() { (rhs, lhs) } else { (lhs, rhs) };
+
+	if types.get_type_by_id(value).is_dependent() {
+		return;
+	}
+
+	match types.get_type_by_id(reference) {
+		// `typeof x === "string"`
+		Type::Constructor(Constructor::TypeOperator(TypeOperator::PrimitiveTypeName(on))) => {
+			let Type::Constant(Constant::String(name)) = types.get_type_by_id(value) else {
+				return;
+			};
+			let (on, name) = (*on, name.clone());
+			let narrowed =
+				filter_members(on, types, |member, types| match type_of_name(member, types) {
+					Some(member_name) => ((member_name == name) != negate).then_some(member),
+					None if member == TypeId::ANY_TYPE && !negate => {
+						Some(type_from_type_of_name(&name).unwrap_or(member))
+					}
+					None => Some(member),
+				});
+			if let Some(narrowed) = narrowed {
+				narrow_reference(on, narrowed, environment, types);
+			}
+		}
+		// Discriminant on a union `x.kind === "a"`. The object is narrowed if members can be
+		// removed, otherwise just the property
+		Type::Constructor(Constructor::Property { on, under, .. }) => {
+			let (on, under) = (*on, under.clone());
+			let narrowed = filter_members(on, types, |member, types| {
+				let property = environment.get_property_unbound(
+					member,
+					Publicity::Public,
+					under.clone(),
+					types,
+				);
+				let Some(Logical::Pure(PropertyValue::Value(property))) = property else {
+					return Some(member);
+				};
+				// Removed if the property cannot be equal (or in the falsy case, is always equal)
+				let is_equal = constants_equal(property, value, types);
+				(is_equal != Some(negate)).then_some(member)
+			});
+
+			if let Some(narrowed) = narrowed {
+				narrow_reference(on, narrowed, environment, types);
+			} else if !negate {
+				narrow_reference(reference, value, environment, types);
+			} else if let Some(narrowed) = filter_not_equal(reference, value, types) {
+				narrow_reference(reference, narrowed, environment, types);
+			}
+		}
+		_ => {
+			if !negate {
+				narrow_reference(reference, value, environment, types);
+			} else if let Some(narrowed) = filter_not_equal(reference, value, types) {
+				narrow_reference(reference, narrowed, environment, types);
+			}
+		}
+	}
+}
+
+/// Removes members that are equal to `value`. `boolean` is treated as `true | false`
+fn filter_not_equal(reference: TypeId, value: TypeId, types: &mut TypeStore) -> Option<TypeId> {
+	filter_members(reference, types, |member, types| match (member, value) {
+		(TypeId::BOOLEAN_TYPE, TypeId::TRUE) => Some(TypeId::FALSE),
+		(TypeId::BOOLEAN_TYPE, TypeId::FALSE) => Some(TypeId::TRUE),
+		_ => (constants_equal(member, value, types) != Some(true)).then_some(member),
+	})
+}
+
+/// Truthy removes `null`, `undefined` and falsy constants. Falsy keeps only the members that can
+/// be falsy
+fn narrow_truthiness(
+	reference: TypeId,
+	negate: bool,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) {
+	if !types.get_type_by_id(reference).is_dependent() {
+		return;
+	}
+
+	let narrowed = filter_members(reference, types, |member, types| {
+		match (member, negate) {
+			(TypeId::BOOLEAN_TYPE, false) => return Some(TypeId::TRUE),
+			(TypeId::BOOLEAN_TYPE, true) => return Some(TypeId::FALSE),
+			(TypeId::STRING_TYPE | TypeId::NUMBER_TYPE | TypeId::ANY_TYPE, _) => {
+				return Some(member)
+			}
+			_ => {}
+		}
+		match is_type_truthy_falsy(member, types) {
+			Decidable::Known(truthy) => (truthy != negate).then_some(member),
+			// Objects are always truthy
+			Decidable::Unknown(_) => {
+				let is_object = matches!(types.get_type_by_id(member), Type::Interface { .. })
+					|| type_of_name(member, types).is_some_and(|name| name == "object");
+				(!(negate && is_object)).then_some(member)
+			}
+		}
+	});
+
+	if let Some(narrowed) = narrowed {
+		narrow_reference(reference, narrowed, environment, types);
+	}
+}
+
+/// Narrow to the type of `this` in the constructor (the instance)
+fn narrow_instance_of(
+	ty: TypeId,
+	rhs: TypeId,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) {
+	let Type::Function(function_id, _) = types.get_type_by_id(rhs) else { return };
+	let FunctionBehavior::Constructor { this_object_type, .. } =
+		types.get_function_from_id(*function_id).behavior
+	else {
+		return;
+	};
+
+	let prototype = environment.get_property(
+		rhs,
+		Publicity::Public,
+		PropertyKey::String(std::borrow::Cow::Borrowed("prototype")),
+		types,
+		None,
+		source_map::Nullable::NULL,
+	);
+	if let Some((_, prototype)) = prototype {
+		environment.facts.prototypes.insert(this_object_type, prototype);
+	}
+
+	narrow_value(ty, this_object_type, environment);
+}
+
+/// The members of a union, through aliases
+pub(crate) fn get_union_members(on: TypeId, types: &TypeStore) -> Vec<TypeId> {
+	match types.get_type_by_id(on) {
+		Type::Or(left, right) => {
+			let mut members = get_union_members(*left, types);
+			members.extend(get_union_members(*right, types));
+			members
+		}
+		Type::AliasTo { to, parameters: None, .. } => get_union_members(*to, types),
+		_ => vec![on],
+	}
+}
+
+/// Maps the members of the constraint of `reference`. Returns the value to narrow to if any members
+/// were removed or replaced
+fn filter_members(
+	reference: TypeId,
+	types: &mut TypeStore,
+	mut filter: impl FnMut(TypeId, &TypeStore) -> Option<TypeId>,
+) -> Option<TypeId> {
+	let on = get_constraint(reference, types).unwrap_or(reference);
+	let members = get_union_members(on, types);
+	let kept = members.iter().filter_map(|member| filter(*member, types)).collect::<Vec<_>>();
+
+	if kept == members {
+		return None;
+	}
+
+	if let Some(existing) = types.narrowed_values.get(&(reference, kept.clone())) {
+		return Some(*existing);
+	}
+
+	let narrowed = kept
+		.iter()
+		.copied()
+		.reduce(|lhs, rhs| types.new_or_type(lhs, rhs))
+		.unwrap_or(TypeId::NEVER_TYPE);
+
+	// Narrowed values stay dependent so that operations on them are not evaluated as constants
+	let narrowed = if let Type::Constant(_) = types.get_type_by_id(narrowed) {
+		narrowed
+	} else {
+		types.register_type(Type::RootPolyType(PolyNature::Open(narrowed)))
+	};
+	types.narrowed_values.insert((reference, kept), narrowed);
+	Some(narrowed)
+}
+
+/// `Some` if both are constants (or the same type)
+fn constants_equal(lhs: TypeId, rhs: TypeId, types: &TypeStore) -> Option<bool> {
+	if lhs == rhs {
+		return Some(true);
+	}
+	let is_constant = |ty| {
+		matches!(ty, TypeId::NULL_TYPE | TypeId::UNDEFINED_TYPE)
+			|| matches!(types.get_type_by_id(ty), Type::Constant(_))
+	};
+	if let (Type::Constant(lhs), Type::Constant(rhs)) =
+		(types.get_type_by_id(lhs), types.get_type_by_id(rhs))
+	{
+		Some(lhs == rhs)
+	} else if is_constant(lhs) && is_constant(rhs) {
+		Some(false)
+	} else {
+		None
+	}
+}
+
+fn type_from_type_of_name(name: &str) -> Option<TypeId> {
+	match name {
+		"string" => Some(TypeId::STRING_TYPE),
+		"number" => Some(TypeId::NUMBER_TYPE),
+		"boolean" => Some(TypeId::BOOLEAN_TYPE),
+		"undefined" => Some(TypeId::UNDEFINED_TYPE),
+		"function" => Some(TypeId::FUNCTION_TYPE),
+		"object" => Some(TypeId::OBJECT_TYPE),
+		_ => None,
+	}
+}
+
+/// Variables with the value `reference` or, if it is a property of a dependent object, the property
+fn narrow_reference(
+	reference: TypeId,
+	to: TypeId,
+	environment: &mut Environment,
+	types: &TypeStore,
+) {
+	if let Type::Constructor(Constructor::Property { on, under, .. }) =
+		types.get_type_by_id(reference)
+	{
+		environment.facts.current_properties.entry(*on).or_default().push((
+			Publicity::Public,
+			under.clone(),
+			PropertyValue::Value(to),
+		));
+	} else {
+		narrow_value(reference, to, environment);
+	}
+}
+
+/// Sets the value of variables that currently have the value `from` to `to`
+fn narrow_value(from: TypeId, to: TypeId, environment: &mut Environment) {
+	let mut visited = HashSet::new();
+	let mut to_narrow = Vec::new();
+
+	for ctx in environment.parents_iter() {
+		for (variable, value) in get_on_ctx!(&ctx.facts.variable_current_value) {
+			// Inner contexts take precedence
+			if visited.insert(*variable) && *value == from {
+				to_narrow.push(*variable);
+			}
+		}
+	}
+
+	for variable in to_narrow {
+		environment.facts.variable_current_value.insert(variable, to);
+	}
+}
@@ -6,8 +6,8 @@ use crate::{
 		Environment,
 	},
 	types::{
-		properties::{PropertyKey, PropertyValue},
-		TypeStore,
+		properties::{PropertyKey, PropertyModifiers, PropertyValue},
+		Type, TypeStore,
 	},
 	TypeId,
 };
@@ -41,13 +41,38 @@ impl ObjectBuilder {
 	}
 }
 
+/// Freezes `object` and any objects under its properties, marking the properties as `readonly`.
+/// Used for `as const`
+pub(crate) fn freeze_object_deep(object: TypeId, environment: &mut Environment, types: &TypeStore) {
+	if !matches!(types.get_type_by_id(object), Type::Object(_))
+		|| environment.is_frozen(object).is_some()
+	{
+		return;
+	}
+
+	environment.facts.frozen.insert(object, TypeId::TRUE);
+
+	for (_, key, value) in environment.get_properties_on_type(object) {
+		let modifiers = PropertyModifiers {
+			readonly: true,
+			..environment.get_property_modifiers(object, &key)
+		};
+		environment.facts.set_property_modifiers(object, key, modifiers);
+		freeze_object_deep(value, environment, types);
+	}
+}
+
 #[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
 pub enum SpecialObjects {
 	Promise {
 		events: (),
 	},
+	/// The result of calling a generator function
 	Generator {
-		position: (),
+		/// `Generator<Y, R, N>`
+		of: TypeId,
+		/// The values yielded in order. `None` if they depend on something unknown
+		yielded: Option<Vec<TypeId>>,
 	},
 	Proxy {
 		over: TypeId,
@@ -2,10 +2,12 @@ use derive_enum_from_into::EnumFrom;
 use source_map::SpanWithSource;
 
 use crate::{
+	context::{facts::Publicity, get_on_ctx},
 	diagnostics::{TypeCheckError, TypeStringRepresentation},
+	features::narrowing::get_union_members,
 	types::{
-		cast_as_number, cast_as_string, is_type_truthy_falsy, new_logical_or_type, Constructor,
-		StructureGenerics, TypeStore,
+		cast_as_number, cast_as_string, get_constraint, is_type_truthy_falsy, new_logical_or_type,
+		properties::PropertyKey, Constructor, StructureGenerics, TypeStore,
 	},
 	CheckingData, Constant, Decidable, Environment, Type, TypeId,
 };
@@ -468,6 +470,177 @@ pub fn evaluate_pure_unary_operator(
 	}
 }
 
+/// `lhs instanceof rhs`. Uses the `prototype` property of `rhs` and the prototype chain of `lhs`
+///
+/// Returns a dependent boolean if either side is dependent
+pub fn evaluate_instance_of_operation(
+	lhs: TypeId,
+	rhs: TypeId,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	if types.get_type_by_id(lhs).is_dependent() || types.get_type_by_id(rhs).is_dependent() {
+		let constructor =
+			Constructor::TypeRelationOperator(crate::types::TypeRelationOperator::InstanceOf {
+				ty: lhs,
+				rhs,
+			});
+		return types.register_type(Type::Constructor(constructor));
+	}
+
+	// Objects and functions can have arguments curried onto them
+	let without_arguments = |ty: TypeId, types: &TypeStore| {
+		if let Type::Constructor(Constructor::StructureGenerics(StructureGenerics { on, .. })) =
+			types.get_type_by_id(ty)
+		{
+			*on
+		} else {
+			ty
+		}
+	};
+	let (lhs, rhs) = (without_arguments(lhs, types), without_arguments(rhs, types));
+
+	match types.get_type_by_id(lhs) {
+		// Primitives are not instances of anything
+		Type::Constant(_) => return TypeId::FALSE,
+		Type::Or(left, right) => {
+			let (left, right) = (*left, *right);
+			let left = evaluate_instance_of_operation(left, rhs, environment, types);
+			let right = evaluate_instance_of_operation(right, rhs, environment, types);
+			return if left == right { left } else { TypeId::BOOLEAN_TYPE };
+		}
+		Type::Object(_)
+		| Type::Function(..)
+		| Type::FunctionReference(..)
+		| Type::SpecialObject(_) => {}
+		Type::AliasTo { .. }
+		| Type::And(..)
+		| Type::Interface { .. }
+		| Type::RootPolyType(_)
+		| Type::Constructor(_) => return TypeId::BOOLEAN_TYPE,
+	}
+
+	let prototype = environment.get_property(
+		rhs,
+		crate::context::facts::Publicity::Public,
+		crate::types::properties::PropertyKey::String(std::borrow::Cow::Borrowed("prototype")),
+		types,
+		None,
+		source_map::Nullable::NULL,
+	);
+
+	let Some((_, prototype)) = prototype else {
+		crate::utils::notify!("No prototype on RHS of instanceof");
+		return TypeId::BOOLEAN_TYPE;
+	};
+
+	// Walk up the prototype chain
+	let mut on = lhs;
+	while let Some(next) = environment
+		.parents_iter()
+		.find_map(|ctx| get_on_ctx!(ctx.facts.prototypes.get(&on)).copied())
+	{
+		let next = without_arguments(next, types);
+
+		if next == prototype {
+			return TypeId::TRUE;
+		} else if next == on {
+			break;
+		}
+		on = next;
+	}
+
+	TypeId::FALSE
+}
+
+/// `typeof operand`. Returns a dependent string if the name is not the same for every member of
+/// the constraint of `operand`
+pub fn evaluate_type_of_operation(operand: TypeId, types: &mut TypeStore) -> TypeId {
+	let on = get_constraint(operand, types).unwrap_or(operand);
+	let mut names =
+		get_union_members(on, types).into_iter().map(|member| type_of_name(member, types));
+
+	let first = names.next().flatten();
+	if let Some(name) = first.filter(|name| names.all(|other| other == Some(name))) {
+		types.new_constant_type(Constant::String(name.to_owned()))
+	} else if types.get_type_by_id(operand).is_dependent() {
+		types.register_type(Type::Constructor(Constructor::TypeOperator(
+			crate::types::TypeOperator::PrimitiveTypeName(operand),
+		)))
+	} else {
+		TypeId::STRING_TYPE
+	}
+}
+
+/// The result of `typeof` for values of `ty`, if it is known
+pub(crate) fn type_of_name(ty: TypeId, types: &TypeStore) -> Option<&'static str> {
+	match ty {
+		TypeId::STRING_TYPE => return Some("string"),
+		TypeId::NUMBER_TYPE => return Some("number"),
+		TypeId::BOOLEAN_TYPE => return Some("boolean"),
+		TypeId::UNDEFINED_TYPE => return Some("undefined"),
+		TypeId::NULL_TYPE => return Some("object"),
+		TypeId::FUNCTION_TYPE => return Some("function"),
+		TypeId::ANY_TYPE | TypeId::ERROR_TYPE | TypeId::NEVER_TYPE => return None,
+		_ => {}
+	}
+
+	match types.get_type_by_id(ty) {
+		Type::Constant(constant) => Some(match constant {
+			Constant::Number(_) | Constant::NaN => "number",
+			Constant::String(_) => "string",
+			Constant::Boolean(_) => "boolean",
+			Constant::Symbol { .. } => "symbol",
+			Constant::Undefined => "undefined",
+			Constant::Null => "object",
+		}),
+		Type::Function(..) | Type::FunctionReference(..) => Some("function"),
+		Type::Object(_) | Type::SpecialObject(_) | Type::Interface { .. } => Some("object"),
+		Type::Constructor(Constructor::StructureGenerics(StructureGenerics { on, .. })) => {
+			type_of_name(*on, types)
+		}
+		Type::AliasTo { to, parameters: None, .. } => type_of_name(*to, types),
+		Type::AliasTo { .. }
+		| Type::And(..)
+		| Type::Or(..)
+		| Type::RootPolyType(_)
+		| Type::Constructor(_) => None,
+	}
+}
+
+/// `property in on`. Returns a dependent boolean if only some members of the constraint of `on`
+/// have the property
+pub fn evaluate_in_operation(
+	property: PropertyKey<'static>,
+	on: TypeId,
+	environment: &Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	if environment.property_in(on, &property) {
+		return TypeId::TRUE;
+	}
+
+	let Some(constraint) = get_constraint(on, types) else {
+		return TypeId::FALSE;
+	};
+
+	let has_property = |member: TypeId| {
+		member != TypeId::ANY_TYPE
+			&& !types.get_type_by_id(member).is_dependent()
+			&& environment
+				.get_property_unbound(member, Publicity::Public, property.clone(), types)
+				.is_some()
+	};
+
+	if get_union_members(constraint, types).into_iter().all(has_property) {
+		TypeId::TRUE
+	} else {
+		types.register_type(Type::Constructor(Constructor::TypeRelationOperator(
+			crate::types::TypeRelationOperator::HasProperty { ty: on, property },
+		)))
+	}
+}
+
 fn attempt_constant_equality(
 	lhs: TypeId,
 	rhs: TypeId,
@@ -0,0 +1,135 @@
+//! `async` functions and `await`
+
+use source_map::SpanWithSource;
+
+use crate::{
+	context::{facts::Publicity, Logical},
+	diagnostics::TypeCheckWarning,
+	events::Event,
+	types::{
+		get_constraint,
+		poly_types::generic_type_arguments::StructureGenericArguments,
+		properties::{PropertyKey, PropertyValue},
+		Constructor, StructureGenerics, TypeStore,
+	},
+	CheckingData, Environment, Type, TypeId,
+};
+
+/// `Promise<resolves_to>`
+pub(crate) fn new_promise_type(resolves_to: TypeId, types: &mut TypeStore) -> TypeId {
+	types.register_type(Type::Constructor(Constructor::StructureGenerics(StructureGenerics {
+		on: TypeId::PROMISE_TYPE,
+		arguments: StructureGenericArguments {
+			type_arguments: FromIterator::from_iter([(
+				TypeId::T_TYPE,
+				(resolves_to, source_map::Nullable::NULL),
+			)]),
+			closures: Default::default(),
+		},
+	})))
+}
+
+/// For `Promise<T>` returns `T`
+pub(crate) fn get_promise_resolved_type(ty: TypeId, types: &TypeStore) -> Option<TypeId> {
+	if let Type::Constructor(Constructor::StructureGenerics(StructureGenerics {
+		on: TypeId::PROMISE_TYPE,
+		arguments,
+	})) = types.get_type_by_id(ty)
+	{
+		arguments.type_arguments.get(&TypeId::T_TYPE).map(|(resolves_to, _)| *resolves_to)
+	} else {
+		None
+	}
+}
+
+/// The value of `await operand`. Unwraps promises and thenables (objects with a `then` method).
+/// Warns if the operand cannot be a promise
+///
+/// Also marks the point in the events, after which events are deferred (see [`Event::Deferred`])
+pub fn await_expression<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	operand: TypeId,
+	position: SpanWithSource,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, A>,
+) -> TypeId {
+	environment.facts.events.push(Event::Deferred { events: Box::default(), position });
+
+	if let Some(resolved) = get_awaited_type(operand, environment, &mut checking_data.types) {
+		resolved
+	} else {
+		checking_data
+			.diagnostics_container
+			.add_warning(TypeCheckWarning::AwaitUsedOnNonPromise(position));
+		operand
+	}
+}
+
+fn get_awaited_type(
+	on: TypeId,
+	environment: &Environment,
+	types: &mut TypeStore,
+) -> Option<TypeId> {
+	if let TypeId::ANY_TYPE | TypeId::ERROR_TYPE = on {
+		return Some(on);
+	}
+
+	if let Some(resolved) = get_promise_resolved_type(on, types) {
+		return Some(resolved);
+	}
+
+	match types.get_type_by_id(on) {
+		Type::Or(left, right) => {
+			let (left, right) = (*left, *right);
+			// Non-promise members are passed through
+			let left_resolved = get_awaited_type(left, environment, types);
+			let right_resolved = get_awaited_type(right, environment, types);
+			if left_resolved.is_none() && right_resolved.is_none() {
+				None
+			} else {
+				Some(
+					types.new_or_type(
+						left_resolved.unwrap_or(left),
+						right_resolved.unwrap_or(right),
+					),
+				)
+			}
+		}
+		Type::RootPolyType(_) | Type::Constructor(_) => {
+			let constraint = get_constraint(on, types)?;
+			get_awaited_type(constraint, environment, types)
+		}
+		Type::Object(_) | Type::Interface { .. } | Type::AliasTo { .. } => {
+			get_thenable_resolved_type(on, environment, types)
+		}
+		_ => None,
+	}
+}
+
+/// For `{ then(onfulfilled: (value: T) => any) }` returns `T`
+fn get_thenable_resolved_type(
+	on: TypeId,
+	environment: &Environment,
+	types: &TypeStore,
+) -> Option<TypeId> {
+	let then = environment.get_property_unbound(
+		on,
+		Publicity::Public,
+		PropertyKey::String("then".into()),
+		types,
+	)?;
+	let Logical::Pure(PropertyValue::Value(then)) = then else {
+		return None;
+	};
+	let on_fulfilled = get_first_parameter_type(then, types)?;
+	get_first_parameter_type(on_fulfilled, types)
+}
+
+fn get_first_parameter_type(function: TypeId, types: &TypeStore) -> Option<TypeId> {
+	let function = get_constraint(function, types).unwrap_or(function);
+	if let Type::Function(id, _) | Type::FunctionReference(id) = types.get_type_by_id(function) {
+		let parameter = types.get_function_from_id(*id).parameters.parameters.first()?;
+		Some(get_constraint(parameter.ty, types).unwrap_or(parameter.ty))
+	} else {
+		None
+	}
+}
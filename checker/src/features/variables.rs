@@ -98,7 +98,7 @@ pub fn check_variable_initialization<T: crate::ReadFromFS, A: crate::ASTImplemen
 		&checking_data.types,
 	);
 
-	if let SubTypeResult::IsNotSubType(_matches) = type_is_subtype {
+	if let SubTypeResult::IsNotSubType(mismatch) = type_is_subtype {
 		let error = crate::diagnostics::TypeCheckError::AssignmentError(
 			AssignmentError::DoesNotMeetConstraint {
 				variable_type: crate::diagnostics::TypeStringRepresentation::from_type_id(
@@ -115,6 +115,8 @@ pub fn check_variable_initialization<T: crate::ReadFromFS, A: crate::ASTImplemen
 					checking_data.options.debug_types,
 				),
 				value_site: expression_declared_pos,
+				mismatch: mismatch
+					.into_representation(&environment.as_general_context(), &checking_data.types),
 			},
 		);
 
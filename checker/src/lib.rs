@@ -20,11 +20,12 @@ pub mod synthesis;
 
 use context::Names;
 use diagnostics::{TypeCheckError, TypeCheckWarning};
-pub(crate) use serialization::BinarySerializable;
+pub(crate) use serialization::{BinarySerializable, DeserializeError};
 
 use features::{
 	functions::SynthesisableFunction,
 	modules::{Exported, InvalidModule, SynthesisedModule},
+	variables::VariableOrImport,
 };
 
 use source_map::{FileSystem, MapFileStore, SpanWithSource, WithPathMap};
@@ -33,7 +34,10 @@ use std::{
 	path::{Path, PathBuf},
 };
 
-use types::{subtyping::check_satisfies, TypeStore};
+use types::{
+	subtyping::{check_satisfies, SubTypeResult},
+	TypeStore,
+};
 
 pub use context::{GeneralContext, RootContext};
 pub use diagnostics::{Diagnostic, DiagnosticKind, DiagnosticsContainer};
@@ -64,6 +68,10 @@ pub struct ModuleData<'a, FileReader, AST: ASTImplementation> {
 	pub(crate) _currently_checking_modules: HashSet<PathBuf>,
 	/// The result of checking. Includes exported variables and facts
 	pub(crate) synthesised_modules: HashMap<SourceId, SynthesisedModule<AST::OwnedModule>>,
+	/// Declaration files found through imports. (not those added to the root)
+	pub(crate) synthesised_definition_modules: HashMap<SourceId, SynthesisedModule<()>>,
+	/// From `declare module "name" { ... }` in definition files
+	pub(crate) declared_modules: HashMap<String, Exported>,
 }
 
 pub trait ASTImplementation: Sized {
@@ -90,12 +98,18 @@ pub trait ASTImplementation: Sized {
 
 	type ClassMethod<'a>: SynthesisableFunction<Self>;
 
+	/// The errors alongside the module are syntax errors that parsing recovered from. The module
+	/// may then be partial
+	#[allow(clippy::type_complexity)]
 	fn module_from_string(
 		source_id: SourceId,
 		string: String,
 		options: Self::ParseOptions,
 		parser_requirements: &mut Self::ParserRequirements,
-	) -> Result<Self::Module<'static>, Self::ParseError>;
+	) -> Result<
+		(Self::Module<'static>, Vec<Self::ParseError>),
+		(Self::ParseError, Vec<Self::ParseError>),
+	>;
 
 	fn definition_module_from_string(
 		source_id: SourceId,
@@ -114,10 +128,19 @@ pub trait ASTImplementation: Sized {
 	#[allow(clippy::needless_lifetimes)]
 	fn synthesise_definition_file<'a, T: crate::ReadFromFS>(
 		file: Self::DefinitionFile<'a>,
+		source: SourceId,
 		root: &RootContext,
 		checking_data: &mut CheckingData<T, Self>,
 	) -> (Names, Facts);
 
+	/// JSON modules are a single constant value, which is their default export
+	fn synthesise_json_module<T: crate::ReadFromFS>(
+		source: SourceId,
+		content: String,
+		root: &RootContext,
+		checking_data: &mut CheckingData<T, Self>,
+	) -> Result<(TypeId, Facts), Self::ParseError>;
+
 	/// Expected is used for eagerly setting function parameters
 	fn synthesise_expression<'a, T: crate::ReadFromFS>(
 		expression: &'a Self::Expression<'a>,
@@ -140,17 +163,34 @@ pub trait ASTImplementation: Sized {
 		checking_data: &mut crate::CheckingData<T, Self>,
 	) -> TypeId;
 
-	/// Don't need to return anything. All information recorded via changed to `environment`
+	/// Other than `using` declarations (which are disposed after the loop), all information is
+	/// recorded via changes to `environment`
 	fn synthesise_for_loop_initialiser<'a, T: crate::ReadFromFS>(
 		for_loop_initialiser: &'a Self::ForStatementInitiliser<'a>,
 		environment: &mut Environment,
 		checking_data: &mut crate::CheckingData<T, Self>,
+	) -> Vec<features::disposal::Disposable>;
+
+	/// Declares the variables in `field` (for example in `for (const [a, b] of ...)`), with values
+	/// destructured from `arguments.initial_value`
+	fn declare_and_assign_to_fields<'a, T: crate::ReadFromFS>(
+		field: &'a Self::VariableField<'a>,
+		environment: &mut Environment,
+		checking_data: &mut crate::CheckingData<T, Self>,
+		arguments: context::VariableRegisterArguments,
 	);
 
 	fn expression_position<'a>(expression: &'a Self::Expression<'a>) -> Span;
 
 	fn type_parameter_name<'a>(parameter: &'a Self::TypeParameter<'a>) -> &'a str;
 
+	/// The `extends` constraint of a type parameter, `any` if it has none
+	fn synthesise_type_parameter_extends<'a, T: crate::ReadFromFS>(
+		parameter: &'a Self::TypeParameter<'a>,
+		environment: &mut Environment,
+		checking_data: &mut crate::CheckingData<T, Self>,
+	) -> TypeId;
+
 	fn parse_options(is_js: bool, parse_comments: bool, lsp_mode: bool) -> Self::ParseOptions;
 
 	fn owned_module_from_module(m: Self::Module<'static>) -> Self::OwnedModule;
@@ -170,6 +210,8 @@ where
 		Self {
 			files: files.unwrap_or_default(),
 			synthesised_modules: Default::default(),
+			synthesised_definition_modules: Default::default(),
+			declared_modules: Default::default(),
 			_currently_checking_modules: Default::default(),
 			// custom_module_resolvers,
 			file_reader: file_resolver,
@@ -196,10 +238,6 @@ pub struct VariableId(pub SourceId, pub u32);
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, binary_serialize_derive::BinarySerializable)]
 pub struct FunctionId(pub SourceId, pub u32);
 
-impl FunctionId {
-	pub const AUTO_CONSTRUCTOR: Self = FunctionId(source_map::Nullable::NULL, 0);
-}
-
 #[derive(Debug)]
 pub enum Decidable<T> {
 	Known(T),
@@ -272,6 +310,8 @@ where
 		&mut self,
 		from: SourceId,
 		importing_path: &str,
+		is_json_import: bool,
+		position: SpanWithSource,
 		environment: &mut Environment,
 	) -> Result<Result<Exported, InvalidModule>, CouldNotOpenFile> {
 		fn get_module<'a, T: crate::ReadFromFS, A: crate::ASTImplementation>(
@@ -308,47 +348,215 @@ where
 			}
 		}
 
-		if importing_path.starts_with('.') {
-			let from_path = self.modules.files.get_file_path(from);
+		/// Declaration files do not have exports, so everything declared in them is exported
+		fn get_definition_module<'a, T: crate::ReadFromFS, A: crate::ASTImplementation>(
+			path: &Path,
+			environment: &mut Environment,
+			checking_data: &'a mut CheckingData<T, A>,
+		) -> Option<Result<(&'a Exported, &'a Facts), A::ParseError>> {
+			let source = if let Some(existing) =
+				checking_data.modules.files.get_source_at_path(path)
+			{
+				existing
+			} else {
+				let (source, content) = checking_data.modules.get_file(path)?;
+				let definition_file = match A::definition_module_from_string(
+					source,
+					content,
+					&mut checking_data.modules.parser_requirements,
+				) {
+					Ok(definition_file) => definition_file,
+					Err(err) => return Some(Err(err)),
+				};
+
+				let (names, facts) = A::synthesise_definition_file(
+					definition_file,
+					source,
+					environment.get_root(),
+					checking_data,
+				);
+
+				let exported_variables: Vec<_> = names
+					.variables
+					.into_iter()
+					.filter_map(|(name, variable)| {
+						if let VariableOrImport::Variable { mutability, declared_at, .. } = variable
+						{
+							let id = VariableId(declared_at.source, declared_at.start);
+							Some((name, (id, mutability)))
+						} else {
+							None
+						}
+					})
+					.collect();
+				let declared_variables =
+					exported_variables.iter().map(|(name, (id, _))| (name.clone(), *id)).collect();
+				let exported = Exported {
+					default: names.default_export,
+					named: exported_variables,
+					named_types: names.named_types.into_iter().collect(),
+				};
+
+				checking_data.modules.synthesised_definition_modules.insert(
+					source,
+					SynthesisedModule {
+						content: (),
+						exported,
+						imports: Default::default(),
+						declared_variables,
+						facts,
+					},
+				);
+				source
+			};
+
+			checking_data
+				.modules
+				.synthesised_definition_modules
+				.get(&source)
+				.map(|module| Ok((&module.exported, &module.facts)))
+		}
+
+		fn get_json_module<'a, T: crate::ReadFromFS, A: crate::ASTImplementation>(
+			path: &Path,
+			environment: &mut Environment,
+			checking_data: &'a mut CheckingData<T, A>,
+		) -> Option<Result<(&'a Exported, &'a Facts), A::ParseError>> {
+			let source =
+				if let Some(existing) = checking_data.modules.files.get_source_at_path(path) {
+					existing
+				} else {
+					let (source, content) = checking_data.modules.get_file(path)?;
+					let (value, facts) = match A::synthesise_json_module(
+						source,
+						content,
+						environment.get_root(),
+						checking_data,
+					) {
+						Ok(result) => result,
+						Err(err) => return Some(Err(err)),
+					};
+
+					checking_data.modules.synthesised_definition_modules.insert(
+						source,
+						SynthesisedModule {
+							content: (),
+							exported: Exported {
+								default: Some(value),
+								named: Vec::new(),
+								named_types: Vec::new(),
+							},
+							imports: Default::default(),
+							declared_variables: Default::default(),
+							facts,
+						},
+					);
+					source
+				};
+
+			checking_data
+				.modules
+				.synthesised_definition_modules
+				.get(&source)
+				.map(|module| Ok((&module.exported, &module.facts)))
+		}
+
+		if let Some(exported) = self.modules.declared_modules.get(importing_path) {
+			return Ok(Ok(exported.clone()));
+		}
+
+		let from_path = self.modules.files.get_file_path(from);
+
+		let full_importer = if importing_path.starts_with('.') {
 			let from = PathBuf::from(importing_path);
 			let mut full_importer =
 				path_absolutize::Absolutize::absolutize_from(&from, from_path.parent().unwrap())
 					.unwrap()
 					.to_path_buf();
 
-			let result = if full_importer.extension().is_some() {
-				get_module(&full_importer, environment, self)
-			} else {
-				let mut result = None;
-				for ext in ["ts", "tsx", "js"] {
+			if full_importer.extension().is_none() {
+				for ext in ["ts", "tsx", "d.ts", "js"] {
 					full_importer.set_extension(ext);
 					// TODO change parse options based on extension
-					result = get_module(&full_importer, environment, self);
-					if result.is_some() {
+					if self.modules.files.get_source_at_path(&full_importer).is_some()
+						|| (self.modules.file_reader)(&full_importer).is_some()
+					{
 						break;
 					}
 				}
-				result
-			};
+			}
+			full_importer
+		} else {
+			let resolved = features::module_resolution::resolve_package_import(
+				&from_path,
+				importing_path,
+				&self.options.import_aliases,
+				self.modules.file_reader,
+			);
+			match resolved {
+				Some(path) => path,
+				None => return Err(CouldNotOpenFile(PathBuf::from(importing_path))),
+			}
+		};
 
-			match result {
-				Some(Ok(synthesised_module)) => {
-					environment.facts.extend_ref(&synthesised_module.facts);
-					Ok(Ok(synthesised_module.exported.clone()))
-				}
-				Some(Err(error)) => {
-					self.diagnostics_container.add_error(error);
-					Ok(Err(InvalidModule))
+		let is_definition_file = full_importer
+			.file_name()
+			.and_then(|name| name.to_str())
+			.is_some_and(|name| name.contains(".d."));
+
+		// The attribute decides how the module is read, the extension is only used to check it
+		let is_json_module = is_json_import;
+		if is_json_module != full_importer.extension().is_some_and(|ext| ext == "json") {
+			self.diagnostics_container.add_error(TypeCheckError::JSONImportMismatch {
+				path: importing_path,
+				is_json_import,
+				position,
+			});
+			return Ok(Err(InvalidModule));
+		}
+
+		// A module that is still being synthesised is part of a cycle and its exports are not known yet
+		if !(is_definition_file || is_json_module) {
+			let existing = self.modules.files.get_source_at_path(&full_importer);
+			if let Some(source) =
+				existing.filter(|source| !self.modules.synthesised_modules.contains_key(source))
+			{
+				if let Scope::Module { imports, .. } = &mut environment.context_type.scope {
+					imports.insert(importing_path.to_owned(), source);
 				}
-				None => Err(CouldNotOpenFile(full_importer)),
+				self.raise_unimplemented_error("cyclic imports", position);
+				return Ok(Err(InvalidModule));
 			}
+		}
+
+		let result = if is_definition_file {
+			get_definition_module(&full_importer, environment, self)
+		} else if is_json_module {
+			get_json_module(&full_importer, environment, self)
 		} else {
-			// TODO temp + bad position
-			self.raise_unimplemented_error(
-				"non relative import (aka npm)",
-				SpanWithSource { source: from, start: 0, end: 1 },
-			);
-			Ok(Err(InvalidModule))
+			get_module(&full_importer, environment, self)
+				.map(|result| result.map(|module| (&module.exported, &module.facts)))
+		};
+
+		match result {
+			Some(Ok((exported, facts))) => {
+				environment.facts.extend_ref(facts);
+				let exported = exported.clone();
+				if !(is_definition_file || is_json_module) {
+					if let (Some(source), Scope::Module { imports, .. }) = (
+						self.modules.files.get_source_at_path(&full_importer),
+						&mut environment.context_type.scope,
+					) {
+						imports.insert(importing_path.to_owned(), source);
+					}
+				}
+				Ok(Ok(exported))
+			}
+			Some(Err(error)) => {
+				self.diagnostics_container.add_error(error);
+				Ok(Err(InvalidModule))
+			}
+			None => Err(CouldNotOpenFile(full_importer)),
 		}
 	}
 
@@ -369,7 +577,11 @@ where
 	}
 
 	pub fn add_expression_mapping(&mut self, span: SpanWithSource, instance: Instance) {
-		self.type_mappings.expressions_to_instances.push(span, instance);
+		self.type_mappings
+			.expressions_to_instances
+			.entry(span.source)
+			.or_default()
+			.push(span, instance);
 	}
 
 	pub fn check_satisfies(
@@ -379,7 +591,9 @@ where
 		at: SpanWithSource,
 		environment: &mut Environment,
 	) {
-		if !check_satisfies(expr_ty, to_satisfy, &self.types, environment) {
+		if let SubTypeResult::IsNotSubType(mismatch) =
+			check_satisfies(expr_ty, to_satisfy, &self.types, environment)
+		{
 			let expected = diagnostics::TypeStringRepresentation::from_type_id(
 				to_satisfy,
 				&environment.as_general_context(),
@@ -392,10 +606,13 @@ where
 				&self.types,
 				false,
 			);
+			let mismatch =
+				mismatch.into_representation(&environment.as_general_context(), &self.types);
 			self.diagnostics_container.add_error(TypeCheckError::NotSatisfied {
 				at,
 				expected,
 				found,
+				mismatch,
 			});
 		}
 	}
@@ -408,6 +625,52 @@ pub struct CheckOutput<A: crate::ASTImplementation> {
 	pub module_contents: MapFileStore<WithPathMap>,
 	pub modules: HashMap<SourceId, SynthesisedModule<A::OwnedModule>>,
 	pub diagnostics: crate::DiagnosticsContainer,
+	/// For printing types after checking
+	pub root: RootContext,
+}
+
+impl<A: crate::ASTImplementation> CheckOutput<A> {
+	/// Prints the type of the innermost expression at `position` in the file at `path`.
+	/// Requires `store_expression_type_mappings`
+	#[must_use]
+	pub fn get_type_at_position(&self, path: &Path, position: u32, debug: bool) -> Option<String> {
+		let source = self.module_contents.get_source_at_path(path)?;
+		let instance = self.type_mappings.expressions_to_instances.get(&source)?.get(position)?;
+		let module = self.modules.get(&source)?;
+
+		let mut environment = self.root.new_lexical_environment(Scope::Module {
+			source,
+			exported: Exported::default(),
+			imports: Default::default(),
+		});
+		// TODO facts of the nearest context rather than the module
+		environment.facts = module.facts.clone();
+
+		Some(types::printing::print_type(
+			instance.get_value_on_ref(),
+			&self.types,
+			&environment.as_general_context(),
+			debug,
+		))
+	}
+
+	/// The variable referenced at `position` in the file at `path`. [`VariableId`]s are the position
+	/// of the declaration. Requires `store_expression_type_mappings`
+	#[must_use]
+	pub fn get_variable_declaration_at_position(
+		&self,
+		path: &Path,
+		position: u32,
+	) -> Option<VariableId> {
+		let source = self.module_contents.get_source_at_path(path)?;
+		self.type_mappings
+			.expressions_to_instances
+			.get(&source)
+			.and_then(|instances| instances.get(position))
+			.and_then(Instance::get_variable_id)
+			// Assignment targets are not expressions
+			.or_else(|| self.type_mappings.variable_references.get(&(source, position)).copied())
+	}
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -436,10 +699,90 @@ pub fn check_project<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 			module_contents: checking_data.modules.files,
 			modules: Default::default(),
 			diagnostics: checking_data.diagnostics_container,
+			root,
 		};
 	}
 
-	for point in &entry_points {
+	check_entry_points(&entry_points, root, checking_data)
+}
+
+/// Same as [`check_project`] but with the root loaded from a buffer created by
+/// [`definition_file_to_buffer`], rather than from parsing and synthesising definition files
+#[allow(clippy::needless_pass_by_value)]
+pub fn check_project_with_definition_cache<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	entry_points: Vec<PathBuf>,
+	definition_cache: &[u8],
+	resolver: T,
+	options: Option<TypeCheckOptions>,
+	parser_requirements: A::ParserRequirements,
+) -> CheckOutput<A> {
+	let mut checking_data = CheckingData::<T, A>::new(
+		options.unwrap_or_default(),
+		&resolver,
+		None,
+		parser_requirements,
+	);
+
+	match RootContext::deserialize(definition_cache, &mut checking_data.modules.files) {
+		Ok((root, types, declared_modules)) => {
+			checking_data.types = types;
+			checking_data.modules.declared_modules = declared_modules;
+			check_entry_points(&entry_points, root, checking_data)
+		}
+		Err(reason) => {
+			checking_data.diagnostics_container.add_error(Diagnostic::Global {
+				reason: format!("could not load definition cache: {reason}"),
+				kind: crate::DiagnosticKind::Error,
+			});
+			CheckOutput {
+				type_mappings: checking_data.type_mappings,
+				types: checking_data.types,
+				module_contents: checking_data.modules.files,
+				modules: Default::default(),
+				diagnostics: checking_data.diagnostics_container,
+				root: RootContext::new_with_primitive_references(),
+			}
+		}
+	}
+}
+
+/// Synthesises a definition file and writes the resulting root and types to a buffer, so later
+/// checks can skip parsing it. Returns the diagnostics (and files for printing them) if it has errors
+#[allow(clippy::needless_pass_by_value)]
+pub fn definition_file_to_buffer<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	definition_file: &Path,
+	resolver: T,
+	parser_requirements: A::ParserRequirements,
+) -> Result<Vec<u8>, (DiagnosticsContainer, MapFileStore<WithPathMap>)> {
+	let mut checking_data = CheckingData::<T, A>::new(
+		TypeCheckOptions::default(),
+		&resolver,
+		None,
+		parser_requirements,
+	);
+
+	let mut root = RootContext::new_with_primitive_references();
+
+	let definition_files = std::iter::once(definition_file.to_path_buf()).collect();
+	add_definition_files_to_root(definition_files, &mut root, &mut checking_data);
+
+	if checking_data.diagnostics_container.has_error() {
+		return Err((checking_data.diagnostics_container, checking_data.modules.files));
+	}
+
+	let source = checking_data.modules.files.get_source_at_path(definition_file).unwrap();
+	let content = checking_data.modules.files.get_file_content(source);
+	let definition_file = (definition_file.display().to_string(), content);
+
+	Ok(root.serialize(checking_data.types, definition_file, checking_data.modules.declared_modules))
+}
+
+fn check_entry_points<T: crate::ReadFromFS, A: crate::ASTImplementation>(
+	entry_points: &[PathBuf],
+	root: RootContext,
+	mut checking_data: CheckingData<T, A>,
+) -> CheckOutput<A> {
+	for point in entry_points {
 		let entry_content = (checking_data.modules.file_reader)(point);
 		if let Some(content) = entry_content {
 			let (source, module) = get_source(&mut checking_data, point, content);
@@ -475,6 +818,7 @@ pub fn check_project<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 		module_contents: modules.files,
 		modules: modules.synthesised_modules,
 		diagnostics: diagnostics_container,
+		root,
 	}
 }
 
@@ -497,12 +841,18 @@ fn get_source<T: crate::ReadFromFS, A: crate::ASTImplementation>(
 		checking_data.options.lsp_mode,
 	);
 
-	let module = A::module_from_string(
+	let (module, recovered_errors) = match A::module_from_string(
 		source,
 		content,
 		parse_options,
 		&mut checking_data.modules.parser_requirements,
-	);
+	) {
+		Ok((module, recovered_errors)) => (Ok(module), recovered_errors),
+		Err((error, recovered_errors)) => (Err(error), recovered_errors),
+	};
+	for error in recovered_errors {
+		checking_data.diagnostics_container.add_error(error);
+	}
 
 	(source, module)
 }
@@ -531,7 +881,8 @@ pub(crate) fn add_definition_files_to_root<T: crate::ReadFromFS, A: crate::ASTIm
 
 		match result {
 			Ok(tdm) => {
-				let (names, facts) = A::synthesise_definition_file(tdm, root, checking_data);
+				let (names, facts) =
+					A::synthesise_definition_file(tdm, source_id, root, checking_data);
 				root.variables.extend(names.variables);
 				root.named_types.extend(names.named_types);
 				root.variable_names.extend(names.variable_names);
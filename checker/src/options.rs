@@ -1,5 +1,6 @@
 /// Options for type checking
 /// TODO figure out compat with tsc
+#[derive(Clone)]
 #[cfg_attr(feature = "serde-serialize", derive(serde::Deserialize), serde(default))]
 #[allow(clippy::struct_excessive_bools)]
 pub struct TypeCheckOptions {
@@ -19,6 +20,10 @@ pub struct TypeCheckOptions {
 	/// TODO maybe levels
 	pub strict_casts: bool,
 
+	/// `as` expressions produce the type annotation (and `as const` produces a readonly value)
+	/// rather than being ignored
+	pub allow_type_casts: bool,
+
 	/// Any types displayed will be in debug view
 	pub debug_types: bool,
 
@@ -30,6 +35,17 @@ pub struct TypeCheckOptions {
 
 	/// Allows partial syntax and collects other information for using in editor
 	pub lsp_mode: bool,
+
+	/// `baseUrl` and `paths` for resolving non-relative imports
+	pub import_aliases: crate::features::module_resolution::ImportAliases,
+
+	/// Function called for JSX elements with intrinsic (lowercase) tag names. Called with the tag
+	/// name and the attributes (which include `children`)
+	pub jsx_factory: String,
+
+	/// Interface mapping intrinsic tag names to the types of their attributes. Intrinsic elements
+	/// are not checked if it is not declared
+	pub jsx_intrinsic_elements: String,
 }
 
 impl Default for TypeCheckOptions {
@@ -42,8 +58,12 @@ impl Default for TypeCheckOptions {
 			debug_types: false,
 			parse_comments: true,
 			strict_casts: false,
+			allow_type_casts: false,
 			store_expression_type_mappings: false,
 			lsp_mode: false,
+			import_aliases: Default::default(),
+			jsx_factory: "JSXH".to_owned(),
+			jsx_intrinsic_elements: "JSX.IntrinsicElements".to_owned(),
 		}
 	}
 }
@@ -7,25 +7,66 @@ use std::{
 	convert::TryInto,
 };
 
-use source_map::{SourceId, SpanWithSource};
+use source_map::{Nullable, SourceId, SpanWithSource};
 
 /// TODO unsure about iterator
 /// This is automated by the derive macro TODO link
-pub(crate) trait BinarySerializable {
+pub(crate) trait BinarySerializable: Sized {
 	fn serialize(self, buf: &mut Vec<u8>);
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self;
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError>;
+}
+
+/// Why a buffer could not be deserialized (it was truncated or is not from [`BinarySerializable::serialize`])
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DeserializeError {
+	UnexpectedEnd,
+	InvalidDiscriminant,
+	InvalidString,
+	InvalidNumber,
+}
+
+impl std::fmt::Display for DeserializeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			DeserializeError::UnexpectedEnd => "unexpected end of buffer",
+			DeserializeError::InvalidDiscriminant => "invalid discriminant",
+			DeserializeError::InvalidString => "invalid UTF-8 string",
+			DeserializeError::InvalidNumber => "invalid number",
+		})
+	}
+}
+
+/// Reads the next `N` bytes
+pub(crate) fn next_bytes<const N: usize, I: Iterator<Item = u8>>(
+	iter: &mut I,
+) -> Result<[u8; N], DeserializeError> {
+	let mut bytes = [0; N];
+	for byte in &mut bytes {
+		*byte = iter.next().ok_or(DeserializeError::UnexpectedEnd)?;
+	}
+	Ok(bytes)
 }
 
 impl BinarySerializable for String {
 	fn serialize(self, buf: &mut Vec<u8>) {
-		buf.push(u8::try_from(self.len()).unwrap());
+		buf.extend_from_slice(&u32::try_from(self.len()).unwrap().to_le_bytes());
 		buf.extend_from_slice(self.as_bytes());
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, _source: SourceId) -> Self {
-		let len = iter.next().unwrap();
-		iter.by_ref().take(len as usize).map(|v| v as char).collect::<String>()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let len = u32::deserialize(iter, source)? as usize;
+		let bytes: Vec<u8> = iter.by_ref().take(len).collect();
+		if bytes.len() != len {
+			return Err(DeserializeError::UnexpectedEnd);
+		}
+		String::from_utf8(bytes).map_err(|_| DeserializeError::InvalidString)
 	}
 }
 
@@ -33,7 +74,12 @@ impl BinarySerializable for String {
 impl BinarySerializable for () {
 	fn serialize(self, _buf: &mut Vec<u8>) {}
 
-	fn deserialize<I: Iterator<Item = u8>>(_iter: &mut I, _source: SourceId) -> Self {}
+	fn deserialize<I: Iterator<Item = u8>>(
+		_iter: &mut I,
+		_source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		Ok(())
+	}
 }
 
 impl BinarySerializable for u8 {
@@ -41,8 +87,11 @@ impl BinarySerializable for u8 {
 		buf.push(self);
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, _source: SourceId) -> Self {
-		iter.next().unwrap()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		_source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		iter.next().ok_or(DeserializeError::UnexpectedEnd)
 	}
 }
 
@@ -56,11 +105,14 @@ impl<T: BinarySerializable> BinarySerializable for Option<T> {
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		if iter.next().unwrap() == 0 {
-			None
-		} else {
-			Some(T::deserialize(iter, source))
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		match u8::deserialize(iter, source)? {
+			0 => Ok(None),
+			1 => T::deserialize(iter, source).map(Some),
+			_ => Err(DeserializeError::InvalidDiscriminant),
 		}
 	}
 }
@@ -73,8 +125,11 @@ impl<T: BinarySerializable> BinarySerializable for Vec<T> {
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
 		(0..size).map(|_| T::deserialize(iter, source)).collect()
 	}
 }
@@ -84,8 +139,11 @@ impl<T: BinarySerializable> BinarySerializable for Box<T> {
 		BinarySerializable::serialize(*self, buf);
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		Box::new(T::deserialize(iter, source))
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		T::deserialize(iter, source).map(Box::new)
 	}
 }
 
@@ -97,8 +155,11 @@ impl<T: BinarySerializable> BinarySerializable for Box<[T]> {
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
 		(0..size).map(|_| T::deserialize(iter, source)).collect()
 	}
 }
@@ -113,8 +174,35 @@ where
 		self.1.serialize(buf);
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		(T::deserialize(iter, source), U::deserialize(iter, source))
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		Ok((T::deserialize(iter, source)?, U::deserialize(iter, source)?))
+	}
+}
+
+impl<T, U, V> BinarySerializable for (T, U, V)
+where
+	T: BinarySerializable,
+	U: BinarySerializable,
+	V: BinarySerializable,
+{
+	fn serialize(self, buf: &mut Vec<u8>) {
+		self.0.serialize(buf);
+		self.1.serialize(buf);
+		self.2.serialize(buf);
+	}
+
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		Ok((
+			T::deserialize(iter, source)?,
+			U::deserialize(iter, source)?,
+			V::deserialize(iter, source)?,
+		))
 	}
 }
 
@@ -132,9 +220,14 @@ where
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
-		(0..size).map(|_| (K::deserialize(iter, source), V::deserialize(iter, source))).collect()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
+		(0..size)
+			.map(|_| Ok((K::deserialize(iter, source)?, V::deserialize(iter, source)?)))
+			.collect()
 	}
 }
 
@@ -150,8 +243,11 @@ where
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
 		(0..size).map(|_| V::deserialize(iter, source)).collect()
 	}
 }
@@ -169,9 +265,14 @@ where
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
-		(0..size).map(|_| (K::deserialize(iter, source), V::deserialize(iter, source))).collect()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
+		(0..size)
+			.map(|_| Ok((K::deserialize(iter, source)?, V::deserialize(iter, source)?)))
+			.collect()
 	}
 }
 
@@ -187,53 +288,62 @@ where
 		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let size = u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]);
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let size = u16::from_le_bytes(next_bytes(iter)?);
 		(0..size).map(|_| V::deserialize(iter, source)).collect()
 	}
 }
 
 impl BinarySerializable for SpanWithSource {
 	fn serialize(self, buf: &mut Vec<u8>) {
+		self.source.serialize(buf);
 		buf.extend_from_slice(&TryInto::<u32>::try_into(self.start).unwrap().to_le_bytes());
 		buf.extend_from_slice(&TryInto::<u32>::try_into(self.end).unwrap().to_le_bytes());
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, source: SourceId) -> Self {
-		let start: u32 = u32::from_le_bytes([
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-		]);
-		let end: u32 = u32::from_le_bytes([
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-		]);
-
-		SpanWithSource { start, end, source }
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		let source = SourceId::deserialize(iter, source)?;
+		let start = u32::deserialize(iter, source)?;
+		let end = u32::deserialize(iter, source)?;
+		Ok(SpanWithSource { start, end, source })
 	}
 }
 
+/// Sources are not stored. Everything (apart from null positions) is deserialized as being from the
+/// backing source
 impl BinarySerializable for SourceId {
-	fn serialize(self, _buf: &mut Vec<u8>) {
-		todo!()
+	fn serialize(self, buf: &mut Vec<u8>) {
+		buf.push(u8::from(self != SourceId::NULL));
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(_iter: &mut I, _source: SourceId) -> Self {
-		todo!()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		match u8::deserialize(iter, source)? {
+			0 => Ok(SourceId::NULL),
+			1 => Ok(source),
+			_ => Err(DeserializeError::InvalidDiscriminant),
+		}
 	}
 }
 
 impl BinarySerializable for u32 {
-	fn serialize(self, _buf: &mut Vec<u8>) {
-		todo!()
+	fn serialize(self, buf: &mut Vec<u8>) {
+		buf.extend_from_slice(&self.to_le_bytes());
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(_iter: &mut I, _source: SourceId) -> Self {
-		todo!()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		_source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		next_bytes(iter).map(u32::from_le_bytes)
 	}
 }
 
@@ -242,8 +352,11 @@ impl BinarySerializable for crate::TypeId {
 		buf.extend_from_slice(&self.0.to_le_bytes());
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, _source: SourceId) -> Self {
-		Self(u16::from_le_bytes([iter.next().unwrap(), iter.next().unwrap()]))
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		_source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		next_bytes(iter).map(|bytes| Self(u16::from_le_bytes(bytes)))
 	}
 }
 
@@ -252,18 +365,12 @@ impl BinarySerializable for ordered_float::NotNan<f64> {
 		buf.extend_from_slice(&self.into_inner().to_le_bytes());
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, _source: SourceId) -> Self {
-		Self::new(f64::from_le_bytes([
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-			iter.next().unwrap(),
-		]))
-		.unwrap()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		_source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		Self::new(f64::from_le_bytes(next_bytes(iter)?))
+			.map_err(|_| DeserializeError::InvalidNumber)
 	}
 }
 
@@ -272,7 +379,97 @@ impl BinarySerializable for bool {
 		buf.push(u8::from(self));
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(iter: &mut I, _source: SourceId) -> Self {
-		iter.next().unwrap() == 1
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, DeserializeError> {
+		match u8::deserialize(iter, source)? {
+			0 => Ok(false),
+			1 => Ok(true),
+			_ => Err(DeserializeError::InvalidDiscriminant),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use crate::{
+		check_project, check_project_with_definition_cache, definition_file_to_buffer,
+		synthesis::EznoParser, CheckOutput, INTERNAL_DEFINITION_FILE,
+		INTERNAL_DEFINITION_FILE_PATH,
+	};
+
+	fn read_file(path: &Path) -> Option<String> {
+		if path == Path::new(INTERNAL_DEFINITION_FILE_PATH) {
+			Some(INTERNAL_DEFINITION_FILE.to_owned())
+		} else if path == Path::new("main.ts") {
+			Some(
+				"const a: Array<number> = [1];\nconst b: string = a.length;\na.push(\"hi\");"
+					.into(),
+			)
+		} else {
+			None
+		}
+	}
+
+	fn definition_cache() -> Vec<u8> {
+		let definition_file = Path::new(INTERNAL_DEFINITION_FILE_PATH);
+		let Ok(cache) = definition_file_to_buffer::<_, EznoParser>(definition_file, read_file, ())
+		else {
+			panic!("could not create definition cache");
+		};
+		cache
+	}
+
+	fn diagnostic_reasons(output: CheckOutput<EznoParser>) -> Vec<String> {
+		output
+			.diagnostics
+			.into_iter()
+			.map(|diagnostic| diagnostic.reason_and_position().0)
+			.collect()
+	}
+
+	#[test]
+	fn definition_cache_round_trip() {
+		let cache = definition_cache();
+
+		let with_cache = check_project_with_definition_cache::<_, EznoParser>(
+			vec!["main.ts".into()],
+			&cache,
+			read_file,
+			None,
+			(),
+		);
+		let without_cache = check_project::<_, EznoParser>(
+			vec!["main.ts".into()],
+			std::iter::once(INTERNAL_DEFINITION_FILE_PATH.into()).collect(),
+			read_file,
+			None,
+			(),
+		);
+
+		let reasons = diagnostic_reasons(with_cache);
+		assert_eq!(reasons.len(), 2, "{reasons:?}");
+		assert_eq!(reasons, diagnostic_reasons(without_cache));
+	}
+
+	#[test]
+	fn truncated_definition_cache() {
+		let cache = definition_cache();
+
+		for length in [0, 10, 20, cache.len() / 2, cache.len() - 1] {
+			let output = check_project_with_definition_cache::<_, EznoParser>(
+				vec!["main.ts".into()],
+				&cache[..length],
+				read_file,
+				None,
+				(),
+			);
+			let reasons = diagnostic_reasons(output);
+			assert_eq!(reasons.len(), 1, "{reasons:?}");
+			assert!(reasons[0].starts_with("could not load definition cache"), "{reasons:?}");
+		}
 	}
 }
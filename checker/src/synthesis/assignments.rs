@@ -6,6 +6,7 @@ use parser::{
 
 use crate::{
 	context::{facts::Publicity, Environment},
+	diagnostics::TypeCheckError,
 	features::assignments::{Assignable, Reference},
 	synthesis::expressions::synthesise_expression,
 	types::properties::PropertyKey,
@@ -125,8 +126,23 @@ pub(crate) fn synthesise_access_to_reference<T: crate::ReadFromFS>(
 				parser::PropertyReference::Standard { property, is_private } => {
 					let publicity =
 						if *is_private { Publicity::Private } else { Publicity::Public };
+					let on = if *is_private
+						&& !environment
+							.can_use_private_properties_of(parent_ty, &checking_data.types)
+					{
+						checking_data.diagnostics_container.add_error(
+							TypeCheckError::PrivatePropertyOutsideClass {
+								property: property.clone(),
+								is_set: true,
+								position: position.with_source(environment.get_source()),
+							},
+						);
+						TypeId::ERROR_TYPE
+					} else {
+						parent_ty
+					};
 					Reference::Property {
-						on: parent_ty,
+						on,
 						with: crate::types::properties::PropertyKey::String(Cow::Owned(
 							property.clone(),
 						)),
@@ -1,9 +1,12 @@
 use parser::{
-	Declaration, ExpressionOrStatementPosition, Statement, StatementOrDeclaration,
-	VariableIdentifier,
+	declarations::VariableDeclaration, Declaration, ExpressionOrStatementPosition, Statement,
+	StatementOrDeclaration, VariableIdentifier,
 };
 
-use crate::{context::Environment, diagnostics::TypeCheckError, CheckingData, Scope, TypeId};
+use crate::{
+	context::Environment, diagnostics::TypeCheckError, features::disposal::dispose_at_scope_exit,
+	CheckingData, Scope, TypeId,
+};
 
 use super::{
 	classes::synthesise_class_declaration, declarations::synthesise_variable_declaration,
@@ -22,6 +25,10 @@ pub(super) fn synthesise_block<T: crate::ReadFromFS>(
 	// Run through accessible statements
 	let mut unreachable_at_idx = None::<usize>;
 
+	// From `using` declarations. Events after the first one can exit before disposing
+	let mut disposables = Vec::new();
+	let mut disposables_events_start = None::<usize>;
+
 	for (idx, statement) in statements.iter().enumerate() {
 		match statement {
 			StatementOrDeclaration::Statement(statement) => {
@@ -35,6 +42,17 @@ pub(super) fn synthesise_block<T: crate::ReadFromFS>(
 					break;
 				}
 			}
+			StatementOrDeclaration::Declaration(Declaration::Variable(
+				declaration @ VariableDeclaration::UsingDeclaration { .. },
+			)) => {
+				disposables.extend(synthesise_variable_declaration(
+					declaration,
+					environment,
+					checking_data,
+					false,
+				));
+				disposables_events_start.get_or_insert(environment.facts.events.len());
+			}
 			StatementOrDeclaration::Declaration(declaration) => {
 				synthesise_declaration(declaration, environment, checking_data);
 			}
@@ -44,6 +62,10 @@ pub(super) fn synthesise_block<T: crate::ReadFromFS>(
 		}
 	}
 
+	if let Some(events_start) = disposables_events_start {
+		dispose_at_scope_exit(disposables, events_start, environment, checking_data);
+	}
+
 	if let Some(idx) = unreachable_at_idx {
 		let statements_not_run = &statements[idx..];
 		if !statements_not_run.is_empty() {
@@ -90,6 +112,7 @@ pub(crate) fn synthesise_declaration<T: crate::ReadFromFS>(
 		}
 		Declaration::DeclareVariable(_)
 		| Declaration::DeclareFunction(_)
+		| Declaration::DeclareNamespace(_)
 		| Declaration::DeclareInterface(_)
 		| Declaration::Function(_)
 		| Declaration::Enum(_)
@@ -134,7 +157,8 @@ pub(crate) fn synthesise_declaration<T: crate::ReadFromFS>(
 					| parser::declarations::export::Exportable::ImportParts { .. }
 					| parser::declarations::export::Exportable::Function(_)
 					| parser::declarations::export::Exportable::Interface(_)
-					| parser::declarations::export::Exportable::TypeAlias(_) => {}
+					| parser::declarations::export::Exportable::TypeAlias(_)
+					| parser::declarations::export::Exportable::Enum(_) => {}
 				}
 			}
 			parser::declarations::ExportDeclaration::Default { expression, position } => {
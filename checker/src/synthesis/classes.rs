@@ -4,13 +4,20 @@ use parser::{
 	PropertyKey as ParserPropertyKey,
 };
 
+use source_map::Span;
+
 use crate::{
 	context::{facts::Publicity, Environment},
+	diagnostics::{TypeCheckError, TypeStringRepresentation},
 	features::functions::{
 		function_to_property, ClassPropertiesToRegister, FunctionRegisterBehavior, GetterSetter,
 	},
+	subtyping::{type_is_subtype, BasicEquality, SubTypeResult},
 	synthesis::parser_property_key_to_checker_property_key,
-	types::{classes::ClassValue, properties::PropertyKey, FunctionType},
+	types::{
+		classes::ClassValue, get_larger_type, printing::print_type, properties::PropertyKey,
+		FunctionType,
+	},
 	CheckingData, PropertyValue, Scope, Type, TypeId,
 };
 
@@ -43,10 +50,26 @@ pub(super) fn synthesise_class_declaration<
 		synthesise_expression(extends, environment, checking_data, TypeId::ANY_TYPE)
 	});
 
-	// TODO prototype of extends
 	let class_prototype =
 		checking_data.types.register_type(Type::Object(crate::types::ObjectNature::RealDeal));
 
+	// Instances inherit through the prototype of the class being extended
+	let parent_prototype = extends.and_then(|extends| {
+		environment
+			.get_property(
+				extends,
+				Publicity::Public,
+				PropertyKey::String(std::borrow::Cow::Borrowed("prototype")),
+				&mut checking_data.types,
+				None,
+				class.position,
+			)
+			.map(|(_, prototype)| prototype)
+	});
+	if let Some(parent_prototype) = parent_prototype {
+		environment.facts.prototypes.insert(class_prototype, parent_prototype);
+	}
+
 	let class_constructor = class.members.iter().find_map(|member| {
 		if let ClassMember::Constructor(c) = &member.on {
 			Some(c)
@@ -88,8 +111,8 @@ pub(super) fn synthesise_class_declaration<
 					FunctionRegisterBehavior::ClassMethod {
 						is_async,
 						is_generator,
-						// TODO
-						super_type: None,
+						super_type: Some(parent_prototype.unwrap_or(TypeId::OBJECT_TYPE)),
+						class_prototype,
 					},
 				);
 
@@ -97,6 +120,19 @@ pub(super) fn synthesise_class_declaration<
 					function_to_property(&getter_setter, method_ty, &mut checking_data.types);
 
 				let position = Some(method.position.with_source(environment.get_source()));
+
+				if let (Some(parent_prototype), PropertyValue::Value(overriding)) =
+					(parent_prototype, &property)
+				{
+					check_override(
+						parent_prototype,
+						&property_key,
+						*overriding,
+						method.position,
+						environment,
+						checking_data,
+					);
+				}
 				environment.facts.register_property(
 					class_prototype,
 					publicity,
@@ -128,6 +164,14 @@ pub(super) fn synthesise_class_declaration<
 				);
 				static_property_keys.push(value);
 			}
+			ClassMember::Method(true, method) => {
+				let value = parser_property_key_to_checker_property_key(
+					method.name.get_ast_ref(),
+					environment,
+					checking_data,
+				);
+				static_property_keys.push(value);
+			}
 			_ => {}
 		}
 	}
@@ -141,8 +185,12 @@ pub(super) fn synthesise_class_declaration<
 		};
 		environment.new_function(checking_data, constructor, behavior)
 	} else {
+		// Uses the position of the class as there is no constructor
+		let id = crate::FunctionId(environment.get_source(), class.position.start);
 		FunctionType::new_auto_constructor(
+			id,
 			class_prototype,
+			extends,
 			ClassPropertiesToRegister(properties),
 			environment,
 			checking_data,
@@ -151,6 +199,20 @@ pub(super) fn synthesise_class_declaration<
 
 	let class_type = checking_data.types.new_function_type(function);
 
+	// Static members are inherited through the class being extended
+	if let Some(extends) = extends {
+		environment.facts.prototypes.insert(class_type, extends);
+	}
+
+	environment.facts.register_property(
+		class_type,
+		Publicity::Public,
+		PropertyKey::String(std::borrow::Cow::Borrowed("prototype")),
+		PropertyValue::Value(class_prototype),
+		true,
+		None,
+	);
+
 	// Static items and blocks
 
 	// TODO ...
@@ -166,8 +228,8 @@ pub(super) fn synthesise_class_declaration<
 				let behavior = FunctionRegisterBehavior::ClassMethod {
 					is_async: method.header.is_async(),
 					is_generator: method.header.is_generator(),
-					// TODO
-					super_type: None,
+					super_type: Some(extends.unwrap_or(TypeId::FUNCTION_TYPE)),
+					class_prototype,
 				};
 				let function = environment.new_function(checking_data, method, behavior);
 
@@ -215,7 +277,7 @@ pub(super) fn synthesise_class_declaration<
 			}
 			ClassMember::StaticBlock(block) => {
 				environment.new_lexical_environment_fold_into_parent(
-					Scope::StaticBlock {},
+					Scope::StaticBlock { class_prototype },
 					checking_data,
 					|environment, checking_data| {
 						synthesise_block(&block.0, environment, checking_data);
@@ -228,3 +290,87 @@ pub(super) fn synthesise_class_declaration<
 
 	class_type
 }
+
+/// Checks a method is compatible with the method it overrides on the prototype of the class being extended
+fn check_override<T: crate::ReadFromFS>(
+	parent_prototype: TypeId,
+	key: &PropertyKey<'static>,
+	overriding: TypeId,
+	position: Span,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) {
+	let Some((_, overridden)) = environment.get_property(
+		parent_prototype,
+		Publicity::Public,
+		key.clone(),
+		&mut checking_data.types,
+		None,
+		position,
+	) else {
+		return;
+	};
+
+	let (
+		Type::Function(overridden_id, _) | Type::FunctionReference(overridden_id),
+		Type::Function(overriding_id, _) | Type::FunctionReference(overriding_id),
+	) = (
+		checking_data.types.get_type_by_id(overridden),
+		checking_data.types.get_type_by_id(overriding),
+	)
+	else {
+		return;
+	};
+	let overridden_function = checking_data.types.get_function_from_id(*overridden_id).clone();
+	let overriding_function = checking_data.types.get_function_from_id(*overriding_id).clone();
+
+	let position = position.with_source(environment.get_source());
+	let mut behavior = BasicEquality { add_property_restrictions: false, position };
+
+	// Methods are compared on their larger types, as the return type is the inferred one.
+	// Parameters are compared contravariantly
+	let mut is_subtype = |base: TypeId, ty: TypeId, environment: &mut Environment| {
+		let types = &checking_data.types;
+		let result = type_is_subtype(
+			get_larger_type(base, types),
+			get_larger_type(ty, types),
+			&mut behavior,
+			environment,
+			types,
+		);
+		matches!(result, SubTypeResult::IsSubType)
+	};
+	let parameters_match = overriding_function
+		.parameters
+		.parameters
+		.iter()
+		.zip(overridden_function.parameters.parameters.iter())
+		.all(|(overriding, overridden)| is_subtype(overriding.ty, overridden.ty, environment));
+	let return_matches =
+		is_subtype(overridden_function.return_type, overriding_function.return_type, environment);
+
+	if !(parameters_match && return_matches) {
+		let name = match key {
+			PropertyKey::String(name) => name.to_string(),
+			PropertyKey::Type(ty) => {
+				print_type(*ty, &checking_data.types, &environment.as_general_context(), false)
+			}
+		};
+		checking_data.diagnostics_container.add_error(TypeCheckError::IncompatibleOverride {
+			name,
+			overridden: TypeStringRepresentation::from_type_id(
+				overridden,
+				&environment.as_general_context(),
+				&checking_data.types,
+				false,
+			),
+			overriding: TypeStringRepresentation::from_type_id(
+				overriding,
+				&environment.as_general_context(),
+				&checking_data.types,
+				false,
+			),
+			position,
+		});
+	}
+}
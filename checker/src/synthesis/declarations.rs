@@ -1,15 +1,23 @@
 use parser::declarations::VariableDeclaration;
 
-use crate::{context::Environment, features::variables::VariableMutability, CheckingData};
+use crate::{
+	context::Environment,
+	features::{
+		disposal::{get_disposable, Disposable},
+		variables::VariableMutability,
+	},
+	CheckingData,
+};
 
 use super::variables::synthesise_variable_declaration_item;
 
+/// Returns the values from `using` declarations, which should be disposed at the end of the scope
 pub(super) fn synthesise_variable_declaration<T: crate::ReadFromFS>(
 	declaration: &VariableDeclaration,
 	environment: &mut Environment,
 	checking_data: &mut CheckingData<T, super::EznoParser>,
 	exported: bool,
-) {
+) -> Vec<Disposable> {
 	match declaration {
 		VariableDeclaration::ConstDeclaration { declarations, .. } => {
 			for variable_declaration in declarations {
@@ -21,6 +29,7 @@ pub(super) fn synthesise_variable_declaration<T: crate::ReadFromFS>(
 					exported.then_some(VariableMutability::Constant),
 				);
 			}
+			Vec::new()
 		}
 		VariableDeclaration::LetDeclaration { declarations, .. } => {
 			for variable_declaration in declarations {
@@ -40,6 +49,21 @@ pub(super) fn synthesise_variable_declaration<T: crate::ReadFromFS>(
 					exported,
 				);
 			}
+			Vec::new()
 		}
+		VariableDeclaration::UsingDeclaration { declarations, is_await, .. } => declarations
+			.iter()
+			.filter_map(|variable_declaration| {
+				let value = synthesise_variable_declaration_item(
+					variable_declaration,
+					environment,
+					true,
+					checking_data,
+					None,
+				);
+				let position = variable_declaration.position.with_source(environment.get_source());
+				get_disposable(value, *is_await, position, environment, checking_data)
+			})
+			.collect(),
 	}
 }
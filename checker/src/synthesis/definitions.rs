@@ -1,35 +1,84 @@
-use parser::ASTNode;
+use std::collections::HashMap;
+
+use parser::{
+	declarations::{export::Exportable, DeclareVariableDeclaration, ExportDeclaration},
+	types::namespace::Namespace,
+	ASTNode, TypeDefinitionModuleDeclaration,
+};
 
 use crate::{
-	context::{Names, RootContext, VariableRegisterArguments},
+	context::{get_value_of_variable, Names, RootContext, VariableRegisterArguments},
+	diagnostics::TypeCheckError,
+	features::{modules::NamePair, variables::VariableOrImport},
 	synthesis::{
-		functions::synthesise_function_annotation, type_annotations::synthesise_type_annotation,
+		functions::synthesise_function_annotation,
+		hoisting::{check_import_attributes, export_part_to_name_pair},
+		type_annotations::synthesise_type_annotation,
+	},
+	types::{
+		poly_types::FunctionTypeArguments,
+		properties::{PropertyKey, PropertyValue},
 	},
-	Environment, Facts, TypeId,
+	Environment, Facts, Scope, TypeId, VariableId,
 };
 
 /// Interprets a definition module (.d.ts) and produces a [Environment]. Consumes the [`TypeDefinitionModule`]
 /// TODO remove unwraps here and add to the existing error handler
+#[allow(clippy::needless_pass_by_value)]
 pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 	definition: parser::TypeDefinitionModule,
+	source: source_map::SourceId,
 	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
 	root: &RootContext,
 ) -> (Names, Facts) {
-	use std::collections::HashMap;
+	let mut hoisted = HoistedTypes::default();
+	let mut env = root.new_lexical_environment(Scope::DefinitionModule { source });
 
-	use parser::{
-		declarations::{DeclareVariableDeclaration, TypeAlias},
-		TypeDeclaration, TypeDefinitionModuleDeclaration,
-	};
+	hoist_declarations(&definition.declarations, &mut hoisted, &mut env, checking_data);
+	synthesise_declarations(&definition.declarations, &mut hoisted, &mut env, checking_data);
 
-	let mut idx_to_types = HashMap::new();
-	// TODO NULL
-	let source = source_map::Nullable::NULL;
-	let mut env = root.new_lexical_environment(crate::Scope::DefinitionModule { source });
+	let default_export = definition
+		.declarations
+		.iter()
+		.find_map(|declaration| {
+			if let TypeDefinitionModuleDeclaration::ExportDefault { name, .. } = declaration {
+				Some(name.as_str())
+			} else {
+				None
+			}
+		})
+		.and_then(|name| {
+			let (_, _, variable) = env.get_variable_unbound(name)?;
+			get_value_of_variable(
+				env.facts_chain(),
+				variable.get_id(),
+				None::<&FunctionTypeArguments>,
+			)
+		});
 
-	// Hoisting names of interfaces, namespaces and types
-	// At some point with binaries could remove this pass
-	for statement in &definition.declarations {
+	let Environment { named_types, facts, variable_names, variables, .. } = env;
+	(Names { variables, named_types, variable_names, default_export }, facts)
+}
+
+/// Types registered in the hoisting pass, for the second pass. Keyed by the start of the declaration
+#[derive(Default)]
+pub(super) struct HoistedTypes {
+	interfaces: HashMap<u32, TypeId>,
+	/// Types declared in a namespace (without the namespace prefix)
+	namespaces: HashMap<u32, HashMap<String, TypeId>>,
+	/// Objects of namespaces in this scope. Later declarations of the same namespace add to them
+	namespace_objects: HashMap<String, TypeId>,
+}
+
+/// Hoisting names of interfaces, namespaces and types
+/// At some point with binaries could remove this pass
+fn hoist_declarations<T: crate::ReadFromFS>(
+	declarations: &[TypeDefinitionModuleDeclaration],
+	hoisted: &mut HoistedTypes,
+	env: &mut Environment,
+	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
+) {
+	for statement in declarations {
 		match statement {
 			TypeDefinitionModuleDeclaration::Interface(interface) => {
 				let ty = env.new_interface(
@@ -37,10 +86,10 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 					interface.on.is_nominal,
 					interface.on.type_parameters.as_deref(),
 					interface.on.extends.as_deref(),
-					interface.on.position.with_source(source),
+					interface.on.position.with_source(env.get_source()),
 					checking_data,
 				);
-				idx_to_types.insert(interface.on.position.start, ty);
+				hoisted.interfaces.insert(interface.on.position.start, ty);
 			}
 			TypeDefinitionModuleDeclaration::Class(_class) => {
 				todo!();
@@ -50,7 +99,8 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 				// 	env.register_type(Type::NamedRooted { name class.name.clone())),
 				// ),
 			}
-			TypeDefinitionModuleDeclaration::TypeAlias(alias) => {
+			TypeDefinitionModuleDeclaration::TypeAlias(alias)
+			| TypeDefinitionModuleDeclaration::LocalTypeAlias(alias) => {
 				env.new_alias(
 					&alias.type_name.name,
 					alias.type_name.type_parameters.as_deref(),
@@ -59,11 +109,33 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 					checking_data,
 				);
 			}
+			TypeDefinitionModuleDeclaration::Namespace(namespace) => {
+				hoist_namespace(namespace, hoisted, env, checking_data);
+			}
+			TypeDefinitionModuleDeclaration::ExportDefault {
+				declaration: Some(declaration),
+				..
+			} => {
+				hoist_declarations(
+					std::slice::from_ref(&**declaration),
+					hoisted,
+					env,
+					checking_data,
+				);
+			}
 			_ => {}
 		}
 	}
+}
 
-	for declaration in definition.declarations {
+fn synthesise_declarations<T: crate::ReadFromFS>(
+	declarations: &[TypeDefinitionModuleDeclaration],
+	hoisted: &mut HoistedTypes,
+	env: &mut Environment,
+	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
+) {
+	let source = env.get_source();
+	for declaration in declarations {
 		match declaration {
 			TypeDefinitionModuleDeclaration::Function(func) => {
 				// TODO abstract
@@ -72,7 +144,7 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 					&func.type_parameters,
 					&func.parameters,
 					func.return_type.as_ref(),
-					&mut env,
+					env,
 					checking_data,
 					func.performs.as_ref().into(),
 					&declared_at,
@@ -88,6 +160,7 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 					&declared_at,
 					base.effects,
 					base.constant_function,
+					base.behavior,
 				);
 
 				let _context = decorators_to_context(&func.decorators);
@@ -108,82 +181,61 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 				declarations,
 				position: _,
 				decorators: _,
-			}) => {
-				for declaration in &declarations {
+			})
+			| TypeDefinitionModuleDeclaration::LocalVariableDeclaration(
+				DeclareVariableDeclaration { keyword: _, declarations, position: _, decorators: _ },
+			) => {
+				for declaration in declarations {
 					// TODO is it ever `None`...?
-					let constraint = declaration.type_annotation.as_ref().map_or(
-						TypeId::ANY_TYPE,
-						|annotation| {
-							synthesise_type_annotation(annotation, &mut env, checking_data)
-						},
-					);
+					let constraint = declaration
+						.type_annotation
+						.as_ref()
+						.map_or(TypeId::ANY_TYPE, |annotation| {
+							synthesise_type_annotation(annotation, env, checking_data)
+						});
 
 					let initial_value = Some(checking_data.types.register_type(
 						crate::Type::RootPolyType(crate::types::PolyNature::Open(constraint)),
 					));
 					crate::synthesis::variables::register_variable(
 						declaration.name.get_ast_ref(),
-						&mut env,
+						env,
 						checking_data,
 						VariableRegisterArguments { constant: true, space: None, initial_value },
 					);
 				}
 			}
 			TypeDefinitionModuleDeclaration::Interface(interface) => {
-				let ty = idx_to_types.remove(&interface.on.position.start).unwrap();
+				let ty = hoisted.interfaces.remove(&interface.on.position.start).unwrap();
 				super::interfaces::synthesise_signatures(
 					interface.on.type_parameters.as_deref(),
 					&interface.on.members,
 					super::interfaces::OnToType(ty),
-					&mut env,
+					env,
 					checking_data,
 				);
 			}
 			// TODO handle locals differently, (maybe squash ast as well)
-			TypeDefinitionModuleDeclaration::LocalTypeAlias(TypeAlias {
-				type_name,
-				type_expression: _,
-				..
-			})
-			| TypeDefinitionModuleDeclaration::TypeAlias(TypeAlias {
-				type_name,
-				type_expression: _,
-				..
-			}) => {
-				let TypeDeclaration { type_parameters, .. } = &type_name;
-
-				// To remove when implementing
-				#[allow(clippy::redundant_pattern_matching)]
-				if let Some(_) = type_parameters {
-					todo!()
-				// let ty = if let Some(type_parameters) = type_parameters {
-				//     let mut root = env.new_lexical_root();
-				//     let type_parameters = generic_type_parameters_from_generic_type_constraints(
-				//         type_parameters,
-				//         &mut env,
-				//         error_handler,
-				//         type_mappings,
-				//     );
-				//     let borrow = type_parameters.0.borrow();
-				//     for parameter in borrow.iter().cloned() {
-				//         env.declare_generic_type_parameter(parameter);
-				//     }
-				//     env.get_type(&type_expression, error_handler, type_mappings).unwrap()
-				// } else {
-				//     env.get_type(&type_expression, error_handler, type_mappings).unwrap()
-				// };
-				// todo!("This should have two passes with a empty type");
-				} else {
-					// todo!("Modify alias")
-					// let ty = env.get_type_handle_errors(&type_expression, checking_data);
-					// env.register_type(ty);
+			// Aliases are synthesised in the hoisting pass above
+			TypeDefinitionModuleDeclaration::LocalTypeAlias(_)
+			| TypeDefinitionModuleDeclaration::TypeAlias(_) => {}
+			TypeDefinitionModuleDeclaration::Namespace(namespace) => {
+				synthesise_namespace(namespace, hoisted, env, checking_data);
+			}
+			TypeDefinitionModuleDeclaration::Comment(_comment) => {}
+			TypeDefinitionModuleDeclaration::ExportDefault { declaration, .. } => {
+				if let Some(declaration) = declaration {
+					synthesise_declarations(
+						std::slice::from_ref(&**declaration),
+						hoisted,
+						env,
+						checking_data,
+					);
 				}
 			}
-			TypeDefinitionModuleDeclaration::Namespace(_) => unimplemented!(),
-			TypeDefinitionModuleDeclaration::LocalVariableDeclaration(_) => {
-				unimplemented!()
+			TypeDefinitionModuleDeclaration::Export(export) => {
+				synthesise_definition_export(export, env, checking_data);
 			}
-			TypeDefinitionModuleDeclaration::Comment(_comment) => {}
 			TypeDefinitionModuleDeclaration::Class(_class) => {
 				todo!();
 				// let existing_type =
@@ -212,63 +264,212 @@ pub(super) fn type_definition_file<T: crate::ReadFromFS>(
 			}
 		}
 	}
-
-	let Environment { named_types, facts, variable_names, variables, .. } = env;
-	(Names { variables, named_types, variable_names }, facts)
-}
-
-pub(crate) fn decorators_to_context(decorators: &[parser::Decorator]) -> Option<String> {
-	decorators.iter().find_map(|dec| {
-		if dec.name.first() == Some(&"server".to_owned()) {
-			Some("server".to_owned())
-		} else if dec.name.first() == Some(&"client".to_owned()) {
-			Some("client".to_owned())
-		} else {
-			None
-		}
-	})
 }
 
-#[cfg(feature = "declaration-synthesis")]
-pub fn definition_file_to_buffer<T: crate::ReadFromFS>(
-	handler: &T,
-	cwd: &std::path::Path,
-	file: &std::path::Path,
-) -> Result<Vec<u8>, String> {
-	// 	ModuleData::new_with_custom_module_resolvers(Default::default(), , cwd.to_owned());
+/// Everything in a definition file is exported, so `export { a as b }` only adds an alias and
+/// `export ... from "..."` adds the items of the other module
+fn synthesise_definition_export<T: crate::ReadFromFS>(
+	export: &ExportDeclaration,
+	env: &mut Environment,
+	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
+) {
+	let ExportDeclaration::Variable { exported, position } = export else {
+		return;
+	};
+	let source = env.get_source();
 
-	let mut checking_data = crate::CheckingData::new(Default::default(), handler);
+	let (from, attributes, parts) = match exported {
+		Exportable::Parts(parts) => {
+			for NamePair { value, r#as, .. } in parts.iter().filter_map(export_part_to_name_pair) {
+				if value != r#as {
+					if let Some(variable) = env.variables.get(value).cloned() {
+						env.variables.insert(r#as.to_owned(), variable);
+					}
+					if let Some(ty) = env.named_types.get(value).copied() {
+						env.named_types.insert(r#as.to_owned(), ty);
+					}
+				}
+			}
+			return;
+		}
+		Exportable::ImportAll { r#as: None, from, attributes } => (from, attributes, None),
+		Exportable::ImportParts { parts, from, attributes, .. } => (
+			from,
+			attributes,
+			Some(parts.iter().filter_map(export_part_to_name_pair).collect::<Vec<_>>()),
+		),
+		Exportable::ImportAll { r#as: Some(_), .. } => {
+			checking_data.raise_unimplemented_error(
+				"export * as in definition file",
+				position.with_source(source),
+			);
+			return;
+		}
+		_ => return,
+	};
 
-	let definition_file = if let Some(source) = handler(file) {
-		let now = std::time::Instant::now();
-		let from_string = parser::TypeDefinitionModule::from_string(source, Default::default());
+	let Some(path) = from.get_path() else { return };
+	let is_json_import = check_import_attributes(attributes.as_ref(), source, checking_data);
+	let exported = match checking_data.import_file(
+		source,
+		path,
+		is_json_import,
+		position.with_source(source),
+		env,
+	) {
+		Ok(Ok(exported)) => exported,
+		Ok(Err(_)) => return,
+		Err(file) => {
+			checking_data.diagnostics_container.add_error(TypeCheckError::CannotOpenFile {
+				file,
+				position: Some(position.with_source(source)),
+			});
+			return;
+		}
+	};
 
-		println!("Parsing {:?}", now.elapsed());
-		match from_string {
-			Ok((definition_file, _)) => definition_file,
-			Err(err) => {
-				return Err(format!("Error parsing {}, {:?}", file.display(), err));
+	let exported_as = |name: &str| -> Option<String> {
+		match &parts {
+			Some(parts) => {
+				parts.iter().find(|part| part.value == name).map(|part| part.r#as.to_owned())
 			}
+			None => Some(name.to_owned()),
 		}
-	} else {
-		return Err(format!("Could not find file {}", file.display()));
 	};
 
-	let now = std::time::Instant::now();
-	let env = type_definition_file(definition_file, &mut checking_data);
-	println!("Synthesis {:?}", now.elapsed());
+	for (name, (VariableId(variable_source, start), mutability)) in exported.named {
+		if let Some(name) = exported_as(&name) {
+			// The location of the variable identifies it, so this refers to the same variable
+			let declared_at =
+				source_map::SpanWithSource { start, end: start, source: variable_source };
+			env.variables.insert(
+				name,
+				VariableOrImport::Variable { mutability, declared_at, context: None },
+			);
+		}
+	}
+	for (name, ty) in exported.named_types {
+		if let Some(name) = exported_as(&name) {
+			env.named_types.insert(name, ty);
+		}
+	}
+}
 
-	if checking_data.diagnostics_container.has_error() {
-		todo!();
+/// Types in a namespace are available outside of it under `Namespace.Name`. Types in a
+/// `module "name"` are only available by importing `name`
+pub(super) fn hoist_namespace<T: crate::ReadFromFS>(
+	namespace: &Namespace,
+	hoisted: &mut HoistedTypes,
+	environment: &mut Environment,
+	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
+) {
+	let mut namespace_environment = environment
+		.new_lexical_environment(Scope::DefinitionModule { source: environment.get_source() });
+	if !namespace.is_module {
+		// Types from previous declarations of the namespace are in scope
+		let prefix = format!("{}.", namespace.name);
+		for (name, ty) in &environment.named_types {
+			if let Some(name) = name.strip_prefix(&prefix) {
+				namespace_environment.named_types.insert(name.to_owned(), *ty);
+			}
+		}
 	}
+	hoist_declarations(&namespace.declarations, hoisted, &mut namespace_environment, checking_data);
+	let Environment { named_types, facts, .. } = namespace_environment;
+	environment.facts.extend(facts, None);
 
-	Ok(env.serialize())
+	if !namespace.is_module {
+		for (name, ty) in &named_types {
+			environment.named_types.insert(format!("{}.{name}", namespace.name), *ty);
+		}
+	}
+	hoisted.namespaces.insert(namespace.position.start, named_types);
 }
 
-// TODO temp
-// pub fn root_context_from_bytes(file: Vec<u8>) -> RootContext {
-// 	let now = std::time::Instant::now();
-// 	let ctx = RootContext::deserialize(file, source_map::source_map::Nullable::NULL).unwrap();
-// 	println!("From binary {:?}", now.elapsed());
-// 	ctx
-// }
+/// Variables in a namespace become properties of an object under the namespace name. For
+/// `module "name"` they are registered as the exports of `name`
+pub(super) fn synthesise_namespace<T: crate::ReadFromFS>(
+	namespace: &Namespace,
+	hoisted: &mut HoistedTypes,
+	environment: &mut Environment,
+	checking_data: &mut crate::CheckingData<T, super::EznoParser>,
+) {
+	let source = environment.get_source();
+	let mut namespace_environment =
+		environment.new_lexical_environment(Scope::DefinitionModule { source });
+	namespace_environment.named_types =
+		hoisted.namespaces.remove(&namespace.position.start).unwrap_or_default();
+	synthesise_declarations(
+		&namespace.declarations,
+		hoisted,
+		&mut namespace_environment,
+		checking_data,
+	);
+	let Environment { named_types, facts, variables, .. } = namespace_environment;
+	environment.facts.extend(facts, None);
+
+	let variables = variables.into_iter().filter_map(|(name, variable)| {
+		if let crate::features::variables::VariableOrImport::Variable {
+			mutability,
+			declared_at,
+			..
+		} = variable
+		{
+			Some((name, (VariableId(declared_at.source, declared_at.start), mutability)))
+		} else {
+			None
+		}
+	});
+
+	if namespace.is_module {
+		let exported =
+			checking_data.modules.declared_modules.entry(namespace.name.clone()).or_default();
+		exported.named.extend(variables);
+		exported.named_types.extend(named_types);
+	} else {
+		let existing = hoisted.namespace_objects.get(&namespace.name).copied();
+		let object = existing.unwrap_or_else(|| {
+			checking_data.types.register_type(crate::Type::Object(
+				crate::types::ObjectNature::AnonymousTypeAnnotation,
+			))
+		});
+		for (name, (id, _)) in variables {
+			let Some(value) = environment.facts.variable_current_value.get(&id).copied() else {
+				continue;
+			};
+			environment.facts.register_property(
+				object,
+				crate::context::facts::Publicity::Public,
+				PropertyKey::String(std::borrow::Cow::Owned(name)),
+				PropertyValue::Value(value),
+				false,
+				None,
+			);
+		}
+		if existing.is_none() {
+			environment.register_variable_handle_error(
+				&namespace.name,
+				VariableRegisterArguments {
+					constant: true,
+					space: None,
+					initial_value: Some(object),
+				},
+				namespace.position.with_source(source),
+				&mut checking_data.diagnostics_container,
+			);
+			hoisted.namespace_objects.insert(namespace.name.clone(), object);
+		}
+	}
+}
+
+pub(crate) fn decorators_to_context(decorators: &[parser::Decorator]) -> Option<String> {
+	decorators.iter().find_map(|dec| {
+		if dec.name.first() == Some(&"server".to_owned()) {
+			Some("server".to_owned())
+		} else if dec.name.first() == Some(&"client".to_owned()) {
+			Some("client".to_owned())
+		} else {
+			None
+		}
+	})
+}
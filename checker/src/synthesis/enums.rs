@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+
+use parser::{
+	types::enum_declaration::{EnumDeclaration, EnumMember},
+	ASTNode,
+};
+
+use crate::{
+	context::{facts::Publicity, Environment, VariableRegisterArguments},
+	diagnostics::TypeCheckError,
+	features::objects::ObjectBuilder,
+	types::{
+		properties::{PropertyKey, PropertyValue},
+		Constant, Type,
+	},
+	CheckingData, TypeId,
+};
+
+use super::expressions::synthesise_expression;
+
+/// Registers `enum`s as
+/// - a value: an object with the constant members (with a reverse mapping for numeric members)
+/// - a type: a union of the member constants
+///
+/// Returns the type
+pub(super) fn synthesise_enum_declaration<T: crate::ReadFromFS>(
+	r#enum: &EnumDeclaration,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> TypeId {
+	let mut object = ObjectBuilder::new(None, &mut checking_data.types, &mut environment.facts);
+
+	// The value of the next member without an initialiser. `None` if it cannot be computed
+	let mut next_value = Some(0f64);
+	let mut union = TypeId::NEVER_TYPE;
+
+	for member in &r#enum.members {
+		let EnumMember::Variant { name, value, position } = member;
+		let position = position.with_source(environment.get_source());
+
+		let value = if let Some(value) = value {
+			let value = synthesise_expression(value, environment, checking_data, TypeId::ANY_TYPE);
+
+			next_value = if let Type::Constant(Constant::Number(number)) =
+				checking_data.types.get_type_by_id(value)
+			{
+				Some(number.into_inner() + 1f64)
+			} else {
+				None
+			};
+			value
+		} else if let Some(value) = next_value {
+			next_value = Some(value + 1f64);
+			checking_data.types.new_constant_type(Constant::Number(value.try_into().unwrap()))
+		} else {
+			checking_data
+				.diagnostics_container
+				.add_error(TypeCheckError::EnumMemberNeedsInitializer(position));
+			TypeId::NUMBER_TYPE
+		};
+
+		object.append(
+			environment,
+			Publicity::Public,
+			PropertyKey::String(Cow::Owned(name.clone())),
+			PropertyValue::Value(value),
+			Some(position),
+		);
+
+		// Reverse mapping
+		if let Type::Constant(Constant::Number(number)) = checking_data.types.get_type_by_id(value)
+		{
+			let key = PropertyKey::String(Cow::Owned(number.to_string()));
+			let name = checking_data.types.new_constant_type(Constant::String(name.clone()));
+			object.append(
+				environment,
+				Publicity::Public,
+				key,
+				PropertyValue::Value(name),
+				Some(position),
+			);
+		}
+
+		union = if union == TypeId::NEVER_TYPE {
+			value
+		} else {
+			checking_data.types.new_or_type(union, value)
+		};
+	}
+
+	let object = object.build_object();
+	// Members cannot be reassigned
+	environment.facts.frozen.insert(object, TypeId::TRUE);
+
+	if r#enum.is_constant {
+		checking_data.type_mappings.const_enums.insert(object);
+	}
+
+	let position = r#enum.get_position().with_source(environment.get_source());
+
+	let ty = checking_data.types.register_type(Type::AliasTo {
+		to: union,
+		name: r#enum.name.clone(),
+		parameters: None,
+	});
+	if environment.named_types.insert(r#enum.name.clone(), ty).is_some() {
+		checking_data
+			.diagnostics_container
+			.add_error(TypeCheckError::TypeAlreadyDeclared { name: r#enum.name.clone(), position });
+	}
+
+	environment.register_variable_handle_error(
+		&r#enum.name,
+		VariableRegisterArguments { constant: true, space: None, initial_value: Some(object) },
+		position,
+		&mut checking_data.diagnostics_container,
+	);
+
+	ty
+}
@@ -8,7 +8,7 @@ use parser::{
 	},
 	functions::MethodHeader,
 	operators::{BinaryOperator, UnaryOperator, UnaryPrefixAssignmentOperator},
-	ASTNode, Expression,
+	ASTNode, Expression, TypeAnnotation,
 };
 
 use crate::{
@@ -25,21 +25,26 @@ use crate::{
 };
 
 use crate::{
-	context::facts::Publicity,
-	diagnostics::TypeCheckWarning,
+	context::{environment::FunctionScope, facts::Publicity},
+	diagnostics::{TypeCheckError, TypeCheckWarning, TypeStringRepresentation},
 	features::{
 		assignments::Assignable,
+		functions::ThisValue,
 		objects::ObjectBuilder,
 		operations::{
+			evaluate_in_operation, evaluate_instance_of_operation,
 			evaluate_logical_operation_with_expression,
 			evaluate_pure_binary_operation_handle_errors, evaluate_pure_unary_operator,
-			EqualityAndInequality, MathematicalAndBitwise, PureUnary,
+			evaluate_type_of_operation, EqualityAndInequality, MathematicalAndBitwise, PureUnary,
 		},
 		template_literal::synthesise_template_literal,
 	},
-	types::calling::CalledWithNew,
+	types::{
+		calling::CalledWithNew,
+		subtyping::{check_satisfies, SubTypeResult},
+	},
 	types::{Constant, TypeId},
-	CheckingData, Environment, Instance, SpecialExpressions,
+	CheckingData, Environment, Instance, SpecialExpressions, Type,
 };
 
 use super::{
@@ -309,18 +314,30 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 					)
 				}
 				UnaryOperator::Await => {
-					checking_data.raise_unimplemented_error(
-						"await",
-						position.with_source(environment.get_source()),
+					let operand_type = synthesise_expression(
+						operand,
+						environment,
+						checking_data,
+						TypeId::ANY_TYPE,
 					);
-					return TypeId::ERROR_TYPE;
+					Instance::RValue(crate::features::promises::await_expression(
+						operand_type,
+						position.with_source(environment.get_source()),
+						environment,
+						checking_data,
+					))
 				}
 				UnaryOperator::TypeOf => {
-					checking_data.raise_unimplemented_error(
-						"TypeOf",
-						position.with_source(environment.get_source()),
+					let operand_type = synthesise_expression(
+						operand,
+						environment,
+						checking_data,
+						TypeId::ANY_TYPE,
 					);
-					return TypeId::ERROR_TYPE;
+					Instance::RValue(evaluate_type_of_operation(
+						operand_type,
+						&mut checking_data.types,
+					))
 				}
 				UnaryOperator::Void => {
 					let _operand_type = synthesise_expression(
@@ -395,11 +412,19 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 					}
 				}
 				UnaryOperator::Yield | UnaryOperator::DelegatedYield => {
-					checking_data.raise_unimplemented_error(
-						"yield expression",
-						position.with_source(environment.get_source()),
+					let operand_type = synthesise_expression(
+						operand,
+						environment,
+						checking_data,
+						TypeId::ANY_TYPE,
 					);
-					return TypeId::ERROR_TYPE;
+					Instance::RValue(crate::features::generators::yield_expression(
+						operand_type,
+						matches!(operator, UnaryOperator::DelegatedYield),
+						position.with_source(environment.get_source()),
+						environment,
+						checking_data,
+					))
 				}
 			}
 		}
@@ -506,9 +531,22 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 		}
 		Expression::PropertyAccess { parent, position, property, .. } => {
 			let on = synthesise_expression(parent, environment, checking_data, TypeId::ANY_TYPE);
-			let property = match property {
-				parser::PropertyReference::Standard { property, is_private: _ } => {
-					PropertyKey::String(Cow::Borrowed(property.as_str()))
+			let (property, publicity) = match property {
+				parser::PropertyReference::Standard { property, is_private: false } => {
+					(PropertyKey::String(Cow::Borrowed(property.as_str())), Publicity::Public)
+				}
+				parser::PropertyReference::Standard { property, is_private: true } => {
+					if !environment.can_use_private_properties_of(on, &checking_data.types) {
+						checking_data.diagnostics_container.add_error(
+							TypeCheckError::PrivatePropertyOutsideClass {
+								property: property.clone(),
+								is_set: false,
+								position: position.with_source(environment.get_source()),
+							},
+						);
+						return TypeId::ERROR_TYPE;
+					}
+					(PropertyKey::String(Cow::Borrowed(property.as_str())), Publicity::Private)
 				}
 				parser::PropertyReference::Marker(_) => {
 					crate::utils::notify!("Property marker found. TODO union of properties");
@@ -516,9 +554,6 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 				}
 			};
 
-			// TODO
-			let publicity = Publicity::Public;
-
 			let result = environment.get_property_handle_errors(
 				on,
 				publicity,
@@ -560,60 +595,51 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 			let position = pos.with_source(environment.get_source());
 			Instance::RValue(environment.get_value_of_this(&checking_data.types, &position))
 		}
-		Expression::SuperExpression(reference, _position) => match reference {
-			SuperReference::Call { arguments: _ } => {
-				todo!();
-
-				// let constructor =
-				// 	environment.get_current_constructor().expect("not in constructor");
-				// let crate::ConstructorInformation {
-				// 	fields,
-				// 	class_constructor_ty,
-				// 	class_instance_ty,
-				// 	..
-				// } = checking_data
-				// 	.functions
-				// 	.constructor_information
-				// 	.get(&constructor_pointer)
-				// 	.unwrap()
-				// 	.clone();
-
-				// let super_ty = if let Type::AliasTo { to, .. } =
-				// 	environment.get_type_by_id(class_instance_ty)
-				// {
-				// 	*to
-				// } else {
-				// 	panic!("No super")
-				// };
-
-				// let this_constructor =
-				// 	environment.get_constructor_for_prototype(class_instance_ty).unwrap();
-				// let super_constructor =
-				// 	environment.get_constructor_for_prototype(super_ty).unwrap();
-
-				// let this = environment.create_this(class_instance_ty);
-
-				// let mut chain = Chain::new();
-				// let mut chain = Annex::new(&chain);
-
-				// let ty = call_function(
-				// 	super_constructor,
-				// 	&None,
-				// 	Some(arguments),
-				// 	environment,
-				// 	checking_data,
-				// 	&
-				// 	position,
-				// 	CalledWithNew::SpecialSuperCall { on: this_constructor },
-				// );
-
-				// TODO explain
-				// synthesise_class_fields(fields, environment, checking_data, &chain);
-
-				// Instance::RValue(ty)
+		Expression::SuperExpression(reference, position) => match reference {
+			SuperReference::Call { arguments } => {
+				let Some(FunctionScope::Constructor {
+					type_of_super: Some(type_of_super),
+					this_object_type,
+					..
+				}) = environment.get_super_scope()
+				else {
+					checking_data.diagnostics_container.add_error(
+						TypeCheckError::InvalidSuperUsage {
+							is_call: true,
+							position: position.with_source(environment.get_source()),
+						},
+					);
+					return TypeId::ERROR_TYPE;
+				};
+
+				// Calls the constructor of the class being extended, which sets properties on `this`
+				call_function(
+					type_of_super,
+					CalledWithNew::SpecialSuperCall { this_type: this_object_type },
+					&None,
+					Some(arguments),
+					environment,
+					checking_data,
+					*position,
+				);
+				Instance::RValue(this_object_type)
+			}
+			SuperReference::PropertyAccess { property } => {
+				let key = PropertyKey::String(Cow::Owned(property.clone()));
+				match get_super_property(key, *position, environment, checking_data) {
+					Ok(instance) => instance,
+					Err(()) => return TypeId::ERROR_TYPE,
+				}
+			}
+			SuperReference::Index { indexer } => {
+				let indexer =
+					synthesise_expression(indexer, environment, checking_data, TypeId::ANY_TYPE);
+				let key = PropertyKey::from_type(indexer, &checking_data.types);
+				match get_super_property(key, *position, environment, checking_data) {
+					Ok(instance) => instance,
+					Err(()) => return TypeId::ERROR_TYPE,
+				}
 			}
-			SuperReference::PropertyAccess { property: _ } => todo!(),
-			SuperReference::Index { indexer: _ } => todo!(),
 		},
 		Expression::NewTarget(..) => todo!(),
 		Expression::FunctionCall { function, type_arguments, arguments, position, .. } => {
@@ -707,14 +733,64 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 			return TypeId::ERROR_TYPE;
 		}
 		Expression::SpecialOperators(operator, position) => match operator {
-			SpecialOperators::AsExpression { value, .. } => {
-				checking_data.diagnostics_container.add_warning(
-					TypeCheckWarning::IgnoringAsExpression(
-						position.with_source(environment.get_source()),
-					),
+			SpecialOperators::AsExpression { value, type_annotation } => {
+				if !checking_data.options.allow_type_casts {
+					checking_data.diagnostics_container.add_warning(
+						TypeCheckWarning::IgnoringAsExpression(
+							position.with_source(environment.get_source()),
+						),
+					);
+
+					return synthesise_expression(value, environment, checking_data, expecting);
+				}
+
+				if let TypeAnnotation::Name(name, _) = &**type_annotation {
+					if name == "const" {
+						let value =
+							synthesise_expression(value, environment, checking_data, expecting);
+						crate::features::objects::freeze_object_deep(
+							value,
+							environment,
+							&checking_data.types,
+						);
+						return value;
+					}
+				}
+
+				let value = synthesise_expression(value, environment, checking_data, expecting);
+				let cast_to =
+					synthesise_type_annotation(type_annotation, environment, checking_data);
+
+				let is_valid = matches!(
+					check_satisfies(value, cast_to, &checking_data.types, environment),
+					SubTypeResult::IsSubType
+				) || matches!(
+					check_satisfies(cast_to, value, &checking_data.types, environment),
+					SubTypeResult::IsSubType
 				);
 
-				return synthesise_expression(value, environment, checking_data, expecting);
+				if !is_valid {
+					let ctx = &environment.as_general_context();
+					checking_data.diagnostics_container.add_warning(
+						TypeCheckWarning::InvalidCast {
+							position: position.with_source(environment.get_source()),
+							from: TypeStringRepresentation::from_type_id(
+								value,
+								ctx,
+								&checking_data.types,
+								false,
+							),
+							to: TypeStringRepresentation::from_type_id(
+								cast_to,
+								ctx,
+								&checking_data.types,
+								false,
+							),
+						},
+					);
+				}
+
+				return cast_to;
 			}
 			SpecialOperators::IsExpression { value: _, type_annotation: _ } => {
 				todo!()
@@ -747,17 +823,24 @@ pub(super) fn synthesise_expression<T: crate::ReadFromFS>(
 					}
 				};
 				let rhs = synthesise_expression(rhs, environment, checking_data, TypeId::ANY_TYPE);
-				let result = environment
-					.property_in(rhs, &PropertyKey::from_type(lhs, &checking_data.types));
 
-				Instance::RValue(if result { TypeId::TRUE } else { TypeId::FALSE })
+				Instance::RValue(evaluate_in_operation(
+					PropertyKey::from_type(lhs, &checking_data.types),
+					rhs,
+					environment,
+					&mut checking_data.types,
+				))
 			}
-			SpecialOperators::InstanceOfExpression { .. } => {
-				checking_data.raise_unimplemented_error(
-					"instanceof expression",
-					position.with_source(environment.get_source()),
-				);
-				return TypeId::ERROR_TYPE;
+			SpecialOperators::InstanceOfExpression { lhs, rhs } => {
+				let lhs = synthesise_expression(lhs, environment, checking_data, TypeId::ANY_TYPE);
+				let rhs = synthesise_expression(rhs, environment, checking_data, TypeId::ANY_TYPE);
+
+				Instance::RValue(evaluate_instance_of_operation(
+					lhs,
+					rhs,
+					environment,
+					&mut checking_data.types,
+				))
 			}
 		},
 		Expression::DynamicImport { position, .. } => {
@@ -888,6 +971,65 @@ fn call_function<T: crate::ReadFromFS>(
 	)
 }
 
+/// `super.property` in class methods. Looks up the property on the prototype (or class for static methods) being
+/// extended, but binds `this` to the current `this`
+fn get_super_property<T: crate::ReadFromFS>(
+	key: PropertyKey<'static>,
+	position: parser::Span,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> Result<Instance, ()> {
+	let on = match environment.get_super_scope() {
+		Some(FunctionScope::MethodFunction { type_of_super: Some(type_of_super), .. }) => {
+			type_of_super
+		}
+		Some(FunctionScope::Constructor { type_of_super: Some(type_of_super), .. }) => environment
+			.get_property(
+				type_of_super,
+				Publicity::Public,
+				PropertyKey::String(Cow::Borrowed("prototype")),
+				&mut checking_data.types,
+				None,
+				position,
+			)
+			.map_or(TypeId::ERROR_TYPE, |(_, prototype)| prototype),
+		Some(FunctionScope::Constructor { type_of_super: None, .. }) => TypeId::OBJECT_TYPE,
+		_ => {
+			checking_data.diagnostics_container.add_error(TypeCheckError::InvalidSuperUsage {
+				is_call: false,
+				position: position.with_source(environment.get_source()),
+			});
+			return Err(());
+		}
+	};
+
+	let instance = environment.get_property_handle_errors(
+		on,
+		Publicity::Public,
+		key,
+		checking_data,
+		position,
+	)?;
+
+	let value_of_this = environment
+		.get_value_of_this(&checking_data.types, &position.with_source(environment.get_source()));
+	Ok(match instance {
+		Instance::RValue(value) => {
+			if let Type::Function(function, _) = checking_data.types.get_type_by_id(value) {
+				let function = *function;
+				Instance::RValue(
+					checking_data
+						.types
+						.register_type(Type::Function(function, ThisValue::Passed(value_of_this))),
+				)
+			} else {
+				Instance::RValue(value)
+			}
+		}
+		instance => instance,
+	})
+}
+
 pub(super) fn synthesise_object_literal<T: crate::ReadFromFS>(
 	ObjectLiteral { members, .. }: &ObjectLiteral,
 	checking_data: &mut CheckingData<T, super::EznoParser>,
@@ -1,19 +1,28 @@
+//! JSX elements. Intrinsic (lowercase) tags are checked against the
+//! [`crate::TypeCheckOptions::jsx_intrinsic_elements`] interface and created by calling
+//! [`crate::TypeCheckOptions::jsx_factory`]. Other tags are components, which are called with the
+//! attributes
+//!
 //! TODO need to call interface things rather going with own implementation to generate actual DOM operations
 
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashSet};
 
 use parser::{ASTNode, Expression, JSXAttribute, JSXElement, JSXNode, JSXRoot};
+use source_map::SpanWithSource;
 
 use crate::{
-	context::invocation::CheckThings,
+	context::{facts::Publicity, invocation::CheckThings, Logical},
+	diagnostics::{PropertyRepresentation, TypeCheckError, TypeStringRepresentation},
 	features::objects::ObjectBuilder,
+	subtyping::{type_is_subtype, BasicEquality, SubTypeResult},
 	synthesis::expressions::synthesise_expression,
 	types::{
-		calling::{call_type, CallingInput},
+		calling::{call_type, CalledWithNew, CallingInput},
+		get_constraint,
 		properties::PropertyKey,
-		SynthesisedArgument,
+		substitute, SynthesisedArgument, TypeStore,
 	},
-	CheckingData, Constant, Environment, TypeId,
+	CheckingData, Constant, Environment, PropertyValue, Type, TypeId,
 };
 
 pub(crate) fn synthesise_jsx_root<T: crate::ReadFromFS>(
@@ -23,7 +32,11 @@ pub(crate) fn synthesise_jsx_root<T: crate::ReadFromFS>(
 ) -> TypeId {
 	match jsx_root {
 		JSXRoot::Element(element) => synthesise_jsx_element(element, environment, checking_data),
-		JSXRoot::Fragment(_) => todo!(),
+		// Fragments are the array of their children
+		JSXRoot::Fragment(fragment) => {
+			synthesise_jsx_children(&fragment.children, true, environment, checking_data)
+				.unwrap_or(TypeId::ERROR_TYPE)
+		}
 	}
 }
 
@@ -32,146 +45,168 @@ pub(crate) fn synthesise_jsx_element<T: crate::ReadFromFS>(
 	environment: &mut Environment,
 	checking_data: &mut CheckingData<T, crate::synthesis::EznoParser>,
 ) -> TypeId {
-	// TODO cache or something?
-	// TODO temp, to be worked out
-	const JSX_NAME: &str = "JSXH";
-
-	let _tag_name = element.tag_name.as_str();
+	let position = element.get_position().with_source(environment.get_source());
+	let tag_name = element.tag_name.as_str();
+	let is_intrinsic =
+		tag_name.starts_with(|c: char| c.is_ascii_lowercase()) || tag_name.contains('-');
+
+	// The element (or component) and the type its attributes should satisfy
+	let (callee, attributes_type) = if is_intrinsic {
+		let intrinsic_elements =
+			environment.get_type_from_name(&checking_data.options.jsx_intrinsic_elements);
+
+		let attributes_type = intrinsic_elements.and_then(|intrinsic_elements| {
+			let attributes_type = get_attribute_type(
+				intrinsic_elements,
+				tag_name,
+				environment,
+				&mut checking_data.types,
+			);
+			if attributes_type.is_none() {
+				checking_data.diagnostics_container.add_error(
+					TypeCheckError::PropertyDoesNotExist {
+						on: TypeStringRepresentation::from_type_id(
+							intrinsic_elements,
+							&environment.as_general_context(),
+							&checking_data.types,
+							checking_data.options.debug_types,
+						),
+						property: PropertyRepresentation::StringKey(tag_name.to_owned()),
+						site: position,
+					},
+				);
+			}
+			attributes_type
+		});
 
-	let tag_name_as_cst_ty =
-		checking_data.types.new_constant_type(Constant::String(element.tag_name.clone()));
+		let factory = checking_data.options.jsx_factory.clone();
+		match environment.get_variable_handle_error(&factory, position, checking_data) {
+			Ok(factory) => (factory.1, attributes_type),
+			Err(_) => return TypeId::ERROR_TYPE,
+		}
+	} else {
+		// TODO `<Namespace.Component>`
+		match environment.get_variable_handle_error(tag_name, position, checking_data) {
+			Ok(component) => {
+				let attributes_type =
+					get_component_attributes_type(component.1, environment, &checking_data.types);
+				(component.1, attributes_type)
+			}
+			Err(_) => return TypeId::ERROR_TYPE,
+		}
+	};
 
 	let mut attributes_object =
 		ObjectBuilder::new(None, &mut checking_data.types, &mut environment.facts);
 
+	// Intrinsic elements are checked for attributes that are required but not given
+	let mut given_attributes = HashSet::new();
+
 	for attribute in &element.attributes {
-		let (name, attribute_value) = synthesise_attribute(attribute, environment, checking_data);
 		let attribute_position = attribute.get_position().with_source(environment.get_source());
+		if let JSXAttribute::Spread(expression, _) = attribute {
+			let spread =
+				synthesise_expression(expression, environment, checking_data, TypeId::ANY_TYPE);
+			// TODO getters, enumerable...
+			for (_, key, value) in environment.get_properties_on_type(spread) {
+				if let (true, Some(attributes_type), PropertyKey::String(name)) =
+					(is_intrinsic, attributes_type, &key)
+				{
+					check_attribute(
+						name,
+						value,
+						attributes_type,
+						attribute_position,
+						environment,
+						checking_data,
+					);
+					given_attributes.insert(name.to_string());
+				}
+				attributes_object.append(
+					environment,
+					Publicity::Public,
+					key,
+					PropertyValue::Value(value),
+					Some(attribute_position),
+				);
+			}
+			continue;
+		}
+
+		let Some((name, value)) =
+			synthesise_attribute(attribute, attributes_type, environment, checking_data)
+		else {
+			continue;
+		};
+
+		// Components are checked when calling
+		if let (true, Some(attributes_type)) = (is_intrinsic, attributes_type) {
+			check_attribute(
+				&name,
+				value,
+				attributes_type,
+				attribute_position,
+				environment,
+				checking_data,
+			);
+			given_attributes.insert(name.clone());
+		}
+
 		attributes_object.append(
 			environment,
-			crate::context::facts::Publicity::Public,
-			name,
-			crate::PropertyValue::Value(attribute_value),
+			Publicity::Public,
+			PropertyKey::String(Cow::Owned(name)),
+			PropertyValue::Value(value),
 			Some(attribute_position),
 		);
-
-		// let constraint = environment
-		// 	.get_property_unbound(element_type, name, &checking_data.types)
-		// 	.map(Logical::prop_to_type);
-		// 	match constraint {
-		// 		Some(attr_restriction) => {
-		// 			let mut basic_subtyping = BasicEquality {
-		// 				add_property_restrictions: false,
-		// 				position: attribute
-		// 					.get_position()
-		// 					.clone()
-		// 					.with_source(environment.get_source()),
-		// 			};
-		// 			let check = type_is_subtype(
-		// 				attr_restriction,
-		// 				attr_value,
-		// 				None,
-		// 				&mut basic_subtyping,
-		// 				environment,
-		// 				&checking_data.types,
-		// 			);
-		// 			match check {
-		// 				SubTypeResult::IsSubType => {
-		// 					new_element_object.append(
-		// 						environment,
-		// 						name,
-		// 						crate::types::properties::Property::Value(attr_value),
-		// 					);
-		// 				}
-		// 				SubTypeResult::IsNotSubType(_) => {
-		// 					checking_data.diagnostics_container.add_error(
-		// 						TypeCheckError::InvalidJSXAttribute {
-		// 							attribute_name: match attribute {
-		// 								JSXAttribute::Static(name, _, _)
-		// 								| JSXAttribute::Dynamic(name, _, _)
-		// 								| JSXAttribute::BooleanAttribute(name, _) => name.clone(),
-		// 								JSXAttribute::Spread(_, _) => todo!(),
-		// 								JSXAttribute::Shorthand(_) => todo!(),
-		// 							},
-		// 							attribute_type: TypeStringRepresentation::from_type_id(
-		// 								attr_restriction,
-		// 								&environment.as_general_context(),
-		// 								&checking_data.types,
-		// 								checking_data.options.debug_types,
-		// 							),
-		// 							value_type: TypeStringRepresentation::from_type_id(
-		// 								attr_value,
-		// 								&environment.as_general_context(),
-		// 								&checking_data.types,
-		// 								checking_data.options.debug_types,
-		// 							),
-		// 							attribute_type_site: (),
-		// 							value_site: parser::ASTNode::get_position(attribute)
-		// 								.clone()
-		// 								.with_source(environment.get_source()),
-		// 						},
-		// 					);
-		// 				}
-		// 			}
-		// 		}
-		// 		None => {
-		// 			todo!("Property does not exist on element");
-		// 		}
-		// 	}
 	}
 
-	// 	new_element_object.append(
-	// 		environment,
-	// 		name,
-	// 		crate::types::properties::Property::Value(attr_value),
-	// 	);
-	// }
-
-	let child_nodes = if let parser::JSXElementChildren::Children(ref children) = element.children {
-		let mut synthesised_child_nodes = ObjectBuilder::new(
-			Some(TypeId::ARRAY_TYPE),
-			&mut checking_data.types,
-			&mut environment.facts,
-		);
-
-		let children_iterator =
-			children.iter().filter(|p| !matches!(p, JSXNode::LineBreak)).enumerate();
-
-		for (idx, child) in children_iterator {
-			// TODO idx bad! and should override item
-			let property = PropertyKey::from_usize(idx);
+	if let parser::JSXElementChildren::Children(ref children) = element.children {
+		if let Some(children) = synthesise_jsx_children(children, false, environment, checking_data)
+		{
+			if let (true, Some(attributes_type)) = (is_intrinsic, attributes_type) {
+				check_attribute(
+					"children",
+					children,
+					attributes_type,
+					position,
+					environment,
+					checking_data,
+				);
+				given_attributes.insert("children".to_owned());
+			}
 
-			let child_position = child.get_position().with_source(environment.get_source());
-			let child = synthesise_jsx_child(child, environment, checking_data);
-			synthesised_child_nodes.append(
+			attributes_object.append(
 				environment,
-				crate::context::facts::Publicity::Public,
-				property,
-				crate::PropertyValue::Value(child),
-				Some(child_position),
+				Publicity::Public,
+				PropertyKey::String(Cow::Borrowed("children")),
+				PropertyValue::Value(children),
+				Some(position),
 			);
 		}
+	}
 
-		Some(synthesised_child_nodes.build_object())
-	} else {
-		None
-	};
-
-	let position = element.get_position().with_source(environment.get_source());
-	let jsx_function =
-		match environment.get_variable_handle_error(JSX_NAME, position, checking_data) {
-			Ok(ty) => ty.1,
-			Err(_) => {
-				todo!()
+	if let (true, Some(attributes_type)) = (is_intrinsic, attributes_type) {
+		for (_, key, ty) in environment.get_properties_on_type(attributes_type) {
+			let PropertyKey::String(ref name) = key else { continue };
+			if given_attributes.contains(name.as_ref())
+				|| environment.get_property_modifiers(attributes_type, &key).optional
+			{
+				continue;
 			}
-		};
+			checking_data.diagnostics_container.add_error(TypeCheckError::MissingJSXAttribute {
+				attribute_name: name.to_string(),
+				attribute_type: TypeStringRepresentation::from_type_id(
+					ty,
+					&environment.as_general_context(),
+					&checking_data.types,
+					checking_data.options.debug_types,
+				),
+				element_site: position,
+			});
+		}
+	}
 
-	let tag_name_argument = SynthesisedArgument {
-		value: tag_name_as_cst_ty,
-		spread: false,
-		// TODO use tag name position
-		position,
-	};
 	let attributes_argument = SynthesisedArgument {
 		value: attributes_object.build_object(),
 		spread: false,
@@ -179,17 +214,23 @@ pub(crate) fn synthesise_jsx_element<T: crate::ReadFromFS>(
 		position,
 	};
 
-	let mut args = vec![tag_name_argument, attributes_argument];
-	if let Some(child_nodes) = child_nodes {
-		// TODO position here
-		args.push(SynthesisedArgument { value: child_nodes, position, spread: false });
-	}
+	let arguments = if is_intrinsic {
+		let tag_name_argument = SynthesisedArgument {
+			value: checking_data.types.new_constant_type(Constant::String(tag_name.to_owned())),
+			spread: false,
+			// TODO use tag name position
+			position,
+		};
+		vec![tag_name_argument, attributes_argument]
+	} else {
+		vec![attributes_argument]
+	};
 
-	match call_type(
-		jsx_function,
-		args,
+	let result = call_type(
+		callee,
+		arguments,
 		CallingInput {
-			called_with_new: crate::types::calling::CalledWithNew::None,
+			called_with_new: CalledWithNew::None,
 			this_value: environment.facts.value_of_this,
 			call_site: position,
 			call_site_type_arguments: None,
@@ -197,169 +238,76 @@ pub(crate) fn synthesise_jsx_element<T: crate::ReadFromFS>(
 		environment,
 		&mut CheckThings,
 		&mut checking_data.types,
-	) {
-		Ok(res) => res.returned_type,
-		Err(_) => {
-			todo!("JSX Calling error")
+	);
+
+	match result {
+		Ok(result) => result.returned_type,
+		Err(errors) => {
+			for error in errors {
+				checking_data
+					.diagnostics_container
+					.add_error(TypeCheckError::FunctionCallingError(error));
+			}
+			TypeId::ERROR_TYPE
 		}
 	}
+}
+
+/// A single child is passed as is. Otherwise (or if `always_array`) they are collected into an
+/// array. [None] if there are no children
+#[allow(clippy::cast_precision_loss)]
+fn synthesise_jsx_children<T: crate::ReadFromFS>(
+	children: &[JSXNode],
+	always_array: bool,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, crate::synthesis::EznoParser>,
+) -> Option<TypeId> {
+	let children: Vec<_> = children
+		.iter()
+		.filter(|node| {
+			!matches!(node, JSXNode::LineBreak)
+				&& !matches!(node, JSXNode::InterpolatedExpression(expression, _) if matches!(&**expression, Expression::Comment { .. }))
+		})
+		.collect();
+
+	if let ([child], false) = (children.as_slice(), always_array) {
+		return Some(synthesise_jsx_child(child, environment, checking_data));
+	}
 
-	// else {
-	// 	match environment.get_variable_or_error(tag_name, todo!(), checking_data) {
-	// 		Ok(element_variable) => {
-	// 			let variable_type = element_variable.1.clone();
-
-	// 			todo!();
-
-	// 			// if let Some(Constant::FunctionReference(function)) =
-	// 			// 	checking_data.types.get_constant_type(variable_type)
-	// 			// {
-	// 			// 	match function {
-	// 			// 		FunctionPointer::Function(function_id) => {
-	// 			// 			todo!()
-	// 			// 			// checking_data
-	// 			// 			// 	.synthesise_hoisted_function(
-	// 			// 			// 		variable_type,
-	// 			// 			// 		*function_id,
-	// 			// 			// 		environment,
-	// 			// 			// 	)
-	// 			// 			// 	.expect("TODO recursive components");
-	// 			// 		}
-	// 			// 		FunctionPointer::Internal(..) | FunctionPointer::AutoConstructor(..) => {
-	// 			// 			unreachable!()
-	// 			// 		}
-	// 			// 	}
-	// 			// } else {
-	// 			// 	todo!()
-	// 			// };
-
-	// 			// let function = environment.get_function(variable_type).unwrap().clone();
-
-	// 			// let props_parameter = function.parameters.get_type_constraint_at_index(0).unwrap();
-
-	// 			// let mut expected_props = environment.get_properties_on_type(props_parameter);
-
-	// 			// let mut found_error = false;
-
-	// 			// let mut synthesised_attributes = ObjectBuilder::new(None, environment);
-	// 			// let mut synthesised_child_nodes =
-	// 			// 	ObjectBuilder::new(Some(TypeId::NODE_LIST_TYPE), environment);
-
-	// 			// for attribute in element.attributes.iter() {
-	// 			// 	let (name, attr_value) =
-	// 			// 		synthesise_attribute(attribute, environment, checking_data);
-
-	// 			// 	let expected = expected_props.contains(&name);
-	// 			// 	if expected {
-	// 			// 		let attr_restriction = environment
-	// 			// 			.get_property_constraint(props_parameter, name, &checking_data.types)
-	// 			// 			.unwrap();
-
-	// 			// 		let mut basic_subtyping = BasicEquality {
-	// 			// 			add_property_restrictions: false,
-	// 			// 			position: attribute.get_position().into_owned(),
-	// 			// 		};
-	// 			// 		let check = type_is_subtype(
-	// 			// 			attr_restriction,
-	// 			// 			attr_value,
-	// 			// 			None,
-	// 			// 			&mut basic_subtyping,
-	// 			// 			environment,
-	// 			// 			&checking_data.types,
-	// 			// 		);
-	// 			// 		match check {
-	// 			// 			SubTypeResult::IsSubType => {
-	// 			// 				synthesised_attributes.append(environment, name, attr_value);
-	// 			// 			}
-	// 			// 			SubTypeResult::IsNotSubType(_) => {
-	// 			// 				found_error = true;
-	// 			// 				checking_data.diagnostics_container.add_error(
-	// 			// 					TypeCheckError::InvalidJSXAttribute {
-	// 			// 						attribute_name: match attribute {
-	// 			// 							JSXAttribute::Static(name, _, _)
-	// 			// 							| JSXAttribute::Dynamic(name, _, _)
-	// 			// 							| JSXAttribute::BooleanAttribute(name, _) => name.clone(),
-	// 			// 							JSXAttribute::Spread(_, _) => todo!(),
-	// 			// 							JSXAttribute::Shorthand(_) => todo!(),
-	// 			// 						},
-	// 			// 						attribute_type: TypeStringRepresentation::from_type_id(
-	// 			// 							attr_restriction,
-	// 			// 							&environment.into_general_context(),
-	// 			// 							checking_data.options.debug_types,
-	// 			// 						),
-	// 			// 						value_type: TypeStringRepresentation::from_type_id(
-	// 			// 							attr_value,
-	// 			// 							&environment.into_general_context(),
-	// 			// 							checking_data.options.debug_types,
-	// 			// 						),
-	// 			// 						attribute_type_site: (),
-	// 			// 						value_site: parser::ASTNode::get_position(attribute)
-	// 			// 							.into_owned(),
-	// 			// 					},
-	// 			// 				);
-	// 			// 			}
-	// 			// 		}
-	// 			// 	} else {
-	// 			// 		crate::utils::notify!("TODO additional property");
-	// 			// 	}
-	// 			// }
-
-	// 			// if let JSXElementChildren::Children(ref mut children) = element.children {
-	// 			// 	for (idx, child) in
-	// 			// 		children.iter().filter(|p| !matches!(p, JSXNode::LineBreak)).enumerate()
-	// 			// 	{
-	// 			// 		let child = synthesise_jsx_child(child, environment, checking_data);
-
-	// 			// 		// TODO idx bad
-	// 			// 		// TODO should be done by item rather than idx
-	// 			// 		let number = checking_data
-	// 			// 			.types
-	// 			// 			.new_constant_type(Constant::Number((idx as f64).try_into().unwrap()));
-
-	// 			// 		synthesised_child_nodes.append(environment, number, child);
-	// 			// 	}
-	// 			// }
-
-	// 			// if expected_props.contains(&TypeId::CHILD_NODES_AS_STRING) {
-	// 			// 	let synthesised_child_nodes = synthesised_child_nodes.build_object();
-	// 			// 	synthesised_attributes.append(
-	// 			// 		environment,
-	// 			// 		TypeId::CHILD_NODES_AS_STRING,
-	// 			// 		synthesised_child_nodes,
-	// 			// 	);
-	// 			// }
-
-	// 			// if !found_error {
-	// 			// 	let props = synthesised_attributes.build_object();
-
-	// 			// 	function
-	// 			// 		.call(
-	// 			// 			&[synthesisedArgument::NonSpread {
-	// 			// 				ty: props,
-	// 			// 				position: Span { start: 0, end: 0, source_id: source_map::Nullable::NULL },
-	// 			// 			}],
-	// 			// 			None,
-	// 			// 			None,
-	// 			// 			&None,
-	// 			// 			checking_data,
-	// 			// 			environment,
-	// 			// 			// TODO unsure, this won't work for classes right...
-	// 			// 			crate::events::CalledWithNew::None,
-	// 			// 		)
-	// 			// 		.unwrap()
-	// 			// 		.returned_type
-	// 			// } else {
-	// 			// 	TypeId::ERROR_TYPE
-	// 			// }
-	// 		}
-	// 		Err(_) => {
-	// 			// TODO deferred custom elements...?
-	// 			crate::utils::notify!("add error");
-	// 			// checking_data.add_error(TypeCheckErrors::from(err), element.get_position().into());
-	// 			// return Ok(Instance::new_error_instance());
-	// 			TypeId::ERROR_TYPE
-	// 		}
-	// 	}
+	if children.is_empty() && !always_array {
+		return None;
+	}
+
+	let mut synthesised_child_nodes = ObjectBuilder::new(
+		Some(TypeId::ARRAY_TYPE),
+		&mut checking_data.types,
+		&mut environment.facts,
+	);
+
+	for (idx, child) in children.iter().enumerate() {
+		let child_position = child.get_position().with_source(environment.get_source());
+		let child = synthesise_jsx_child(child, environment, checking_data);
+		synthesised_child_nodes.append(
+			environment,
+			Publicity::Public,
+			PropertyKey::from_usize(idx),
+			PropertyValue::Value(child),
+			Some(child_position),
+		);
+	}
+
+	let length = checking_data
+		.types
+		.new_constant_type(Constant::Number((children.len() as f64).try_into().unwrap()));
+	synthesised_child_nodes.append(
+		environment,
+		Publicity::Public,
+		PropertyKey::String("length".into()),
+		PropertyValue::Value(length),
+		None,
+	);
+
+	Some(synthesised_child_nodes.build_object())
 }
 
 fn synthesise_jsx_child<T: crate::ReadFromFS>(
@@ -370,45 +318,8 @@ fn synthesise_jsx_child<T: crate::ReadFromFS>(
 	match child {
 		JSXNode::Element(element) => synthesise_jsx_element(element, environment, checking_data),
 		JSXNode::InterpolatedExpression(expression, _expression_position) => {
-			if matches!(&**expression, Expression::Comment { .. }) {
-				return TypeId::UNDEFINED_TYPE;
-			}
-
-			crate::utils::notify!("Cast to node!");
-			// TODO expecting
+			// TODO check against `string | number | Element | Array<...>`
 			synthesise_expression(expression, environment, checking_data, TypeId::ANY_TYPE)
-
-			// function intoNode(data) {
-			// 	if typeof data === "string" || typeof data === "number" {
-			// 		new Text(data)
-			// 	} else {
-			// 		return data;
-			// 	}
-			// }
-
-			// let expected_type = checking_data.memory.get_jsx_interpretable_values();
-			// Checking expression is `string | number | HTMLElement | Array<string | number | HTMLElement>`
-			// if let SubTypeResult::IsNotSubType(_mismatch) = type_is_subtype(
-			//     &expected_type,
-			//     &expression.as_type(),
-			//     &mut GeneralEquality(&mut checking_data.memory),
-			// ) {
-			//     checking_data.diagnostics_container.add_error(
-			//         TypeCheckError::InvalidJSXInterpolatedValue {
-			//             interpolation_site: expression_position.clone().unwrap(),
-			//             expected: TypeStringRepresentation::from_type(
-			//                 &expected_type,
-			//                 &checking_data.memory,
-			//                 checking_data.options.debug_types,
-			//             ),
-			//             found: TypeStringRepresentation::from_type(
-			//                 &expression.as_type(),
-			//                 &checking_data.memory,
-			//                 checking_data.options.debug_types,
-			//             ),
-			//         },
-			//     );
-			// }
 		}
 		JSXNode::TextNode(text, _) => {
 			checking_data.types.new_constant_type(Constant::String(text.clone()))
@@ -419,29 +330,143 @@ fn synthesise_jsx_child<T: crate::ReadFromFS>(
 	}
 }
 
+/// Returns [None] for shorthand attributes that are not a variable reference
 fn synthesise_attribute<T: crate::ReadFromFS>(
 	attribute: &JSXAttribute,
+	attributes_type: Option<TypeId>,
 	environment: &mut Environment,
 	checking_data: &mut CheckingData<T, crate::synthesis::EznoParser>,
-) -> (PropertyKey<'static>, TypeId) {
-	let (key, value) = match attribute {
-		// TODO check property exists ...?
-		JSXAttribute::Static(name, value, _attribute_id) => {
-			(name, checking_data.types.new_constant_type(crate::Constant::String(value.clone())))
-		}
+) -> Option<(String, TypeId)> {
+	match attribute {
+		JSXAttribute::Static(name, value, _attribute_id) => Some((
+			name.clone(),
+			checking_data.types.new_constant_type(crate::Constant::String(value.clone())),
+		)),
 		JSXAttribute::Dynamic(name, expression, _attribute_id) => {
 			if let Expression::ExpressionFunction(_) = &**expression {
 				// TODO temp context
 				environment.context_type.location = Some("client".to_owned());
 			}
-			// Do not care about the returned value at this point, just for synthesising the type into the map
-			// TODO expecting
-			(name, synthesise_expression(expression, environment, checking_data, TypeId::ANY_TYPE))
+			let expected = attributes_type
+				.and_then(|on| get_attribute_type(on, name, environment, &mut checking_data.types))
+				.unwrap_or(TypeId::ANY_TYPE);
+			let value = synthesise_expression(expression, environment, checking_data, expected);
+			Some((name.clone(), value))
 		}
-		JSXAttribute::BooleanAttribute(name, _) => (name, TypeId::TRUE),
-		JSXAttribute::Spread(_, _) => todo!(),
-		JSXAttribute::Shorthand(_) => todo!(),
+		JSXAttribute::BooleanAttribute(name, _) => Some((name.clone(), TypeId::TRUE)),
+		JSXAttribute::Shorthand(expression) => {
+			if let Expression::VariableReference(name, _) = expression {
+				let value =
+					synthesise_expression(expression, environment, checking_data, TypeId::ANY_TYPE);
+				Some((name.clone(), value))
+			} else {
+				checking_data.raise_unimplemented_error(
+					"non variable shorthand JSX attribute",
+					expression.get_position().with_source(environment.get_source()),
+				);
+				None
+			}
+		}
+		JSXAttribute::Spread(..) => unreachable!("spread attributes are handled above"),
+	}
+}
+
+/// Checks the value of an attribute on an intrinsic element. Attributes with a `-` (`data-*` etc)
+/// are allowed without being declared
+fn check_attribute<T: crate::ReadFromFS>(
+	name: &str,
+	value: TypeId,
+	attributes_type: TypeId,
+	position: SpanWithSource,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, crate::synthesis::EznoParser>,
+) {
+	let Some(expected) =
+		get_attribute_type(attributes_type, name, environment, &mut checking_data.types)
+	else {
+		if !name.contains('-') {
+			checking_data.diagnostics_container.add_error(TypeCheckError::PropertyDoesNotExist {
+				on: TypeStringRepresentation::from_type_id(
+					attributes_type,
+					&environment.as_general_context(),
+					&checking_data.types,
+					checking_data.options.debug_types,
+				),
+				property: PropertyRepresentation::StringKey(name.to_owned()),
+				site: position,
+			});
+		}
+		return;
 	};
 
-	(PropertyKey::String(Cow::Owned(key.clone())), value)
+	let mut behavior = BasicEquality { add_property_restrictions: false, position };
+	let result = type_is_subtype(expected, value, &mut behavior, environment, &checking_data.types);
+
+	if let SubTypeResult::IsNotSubType(_) = result {
+		checking_data.diagnostics_container.add_error(TypeCheckError::InvalidJSXAttribute {
+			attribute_name: name.to_owned(),
+			attribute_type: TypeStringRepresentation::from_type_id(
+				expected,
+				&environment.as_general_context(),
+				&checking_data.types,
+				checking_data.options.debug_types,
+			),
+			value_type: TypeStringRepresentation::from_type_id(
+				value,
+				&environment.as_general_context(),
+				&checking_data.types,
+				checking_data.options.debug_types,
+			),
+			attribute_type_site: (),
+			value_site: position,
+		});
+	}
+}
+
+fn get_attribute_type(
+	on: TypeId,
+	name: &str,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) -> Option<TypeId> {
+	/// Attributes of a union are either side. Attributes of generic types are specialised
+	fn resolve_attribute_type(
+		property: Logical<PropertyValue>,
+		environment: &mut Environment,
+		types: &mut TypeStore,
+	) -> TypeId {
+		match property {
+			Logical::Pure(property) => property.as_get_type(),
+			Logical::Or { left, right } => {
+				let left = resolve_attribute_type(*left, environment, types);
+				let right = resolve_attribute_type(*right, environment, types);
+				types.new_or_type(left, right)
+			}
+			Logical::Implies { on, mut antecedent } => {
+				let on = resolve_attribute_type(*on, environment, types);
+				substitute(on, &mut antecedent, environment, types)
+			}
+		}
+	}
+
+	let key = PropertyKey::String(Cow::Owned(name.to_owned()));
+	let property = environment.get_property_unbound(on, Publicity::Public, key, types)?;
+	Some(resolve_attribute_type(property, environment, types))
+}
+
+/// The type of the first parameter of a component function
+fn get_component_attributes_type(
+	component: TypeId,
+	environment: &Environment,
+	types: &TypeStore,
+) -> Option<TypeId> {
+	let component = get_constraint(component, types).unwrap_or(component);
+	let function = match types.get_type_by_id(component) {
+		Type::Function(function, _) | Type::FunctionReference(function) => *function,
+		Type::Interface { .. } | Type::Object(_) => {
+			*environment.get_signatures(component, false).first()?
+		}
+		_ => return None,
+	};
+	types.functions.get(&function)?.parameters.parameters.first().map(|parameter| parameter.ty)
 }
@@ -16,6 +16,7 @@ use crate::{
 	types::{
 		functions::{SynthesisedParameter, SynthesisedParameters, SynthesisedRestParameter},
 		poly_types::generic_type_arguments::TypeArgumentStore,
+		properties::new_key_of_type,
 		FunctionType, StructureGenerics,
 	},
 	types::{Constructor, Type, TypeId},
@@ -187,7 +188,16 @@ pub(crate) fn synthesise_type_parameters<T: crate::ReadFromFS>(
 					&mut checking_data.types,
 				)
 			}
-			GenericTypeConstraint::ExtendsKeyOf(_, _) => todo!(),
+			GenericTypeConstraint::ExtendsKeyOf(name, on) => {
+				let on = synthesise_type_annotation(on, environment, checking_data);
+				let key_of = new_key_of_type(on, environment, &mut checking_data.types);
+				environment.new_explicit_type_parameter(
+					name.as_str(),
+					Some(key_of),
+					None,
+					&mut checking_data.types,
+				)
+			}
 			GenericTypeConstraint::Spread { name: _, default: _ } => todo!(),
 		})
 		.collect()
@@ -586,6 +596,8 @@ pub(super) fn synthesise_function_annotation<T: crate::ReadFromFS, S: ContextTyp
 								free_this_type,
 								is_async: true,
 								is_generator: true,
+								type_of_super: None,
+								class_prototype: None,
 							}
 						} else {
 							crate::context::environment::FunctionScope::ArrowFunction {
@@ -8,6 +8,7 @@ use parser::{
 
 use crate::{
 	context::{Environment, VariableRegisterArguments},
+	diagnostics::TypeCheckError,
 	features::{
 		functions::synthesise_hoisted_statement_function,
 		modules::{import_items, ImportKind, NamePair},
@@ -17,6 +18,8 @@ use crate::{
 };
 
 use super::{
+	definitions::{hoist_namespace, synthesise_namespace, HoistedTypes},
+	enums::synthesise_enum_declaration,
 	functions::synthesise_function_annotation,
 	type_annotations::{comment_as_type_annotation, synthesise_type_annotation},
 	variables::register_variable,
@@ -29,6 +32,7 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 	checking_data: &mut CheckingData<T, super::EznoParser>,
 ) {
 	let mut idx_to_types = HashMap::new();
+	let mut hoisted_definitions = HoistedTypes::default();
 
 	// First stage
 	for item in items {
@@ -39,10 +43,17 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 				| parser::Declaration::Class(_)
 				| parser::Declaration::Variable(_)
 				| parser::Declaration::Function(_) => {}
-				parser::Declaration::Enum(r#enum) => checking_data.raise_unimplemented_error(
-					"enum",
-					r#enum.on.position.with_source(environment.get_source()),
-				),
+				parser::Declaration::Enum(r#enum) => {
+					synthesise_enum_declaration(&r#enum.on, environment, checking_data);
+				}
+				parser::Declaration::DeclareNamespace(namespace) => {
+					hoist_namespace(
+						namespace,
+						&mut hoisted_definitions,
+						environment,
+						checking_data,
+					);
+				}
 				parser::Declaration::DeclareInterface(interface) => {
 					// TODO any difference bc declare?
 					let ty = environment.new_interface(
@@ -100,9 +111,15 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 							VariableIdentifier::Marker(..) => None,
 						}
 					});
+					let is_json_import = check_import_attributes(
+						import.attributes.as_ref(),
+						environment.get_source(),
+						checking_data,
+					);
 					import_items(
 						environment,
 						import.from.get_path().unwrap(),
+						is_json_import,
 						import.position,
 						default_import,
 						items,
@@ -115,7 +132,7 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 					if let ExportDeclaration::Variable { exported, position } = &export.on {
 						// Imports & types
 						match exported {
-							Exportable::ImportAll { r#as, from } => {
+							Exportable::ImportAll { r#as, from, attributes } => {
 								let kind = match r#as {
 									Some(VariableIdentifier::Standard(name, position)) => {
 										ImportKind::All { under: name, position: *position }
@@ -123,10 +140,16 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 									Some(VariableIdentifier::Marker(_, _)) => todo!(),
 									None => ImportKind::Everything,
 								};
+								let is_json_import = check_import_attributes(
+									attributes.as_ref(),
+									environment.get_source(),
+									checking_data,
+								);
 
 								import_items::<iter::Empty<_>, _, _>(
 									environment,
 									from.get_path().unwrap(),
+									is_json_import,
 									*position,
 									None,
 									kind,
@@ -137,13 +160,22 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 								);
 							}
 							Exportable::ImportParts {
-								parts, from, type_definitions_only, ..
+								parts,
+								from,
+								attributes,
+								type_definitions_only,
 							} => {
 								let parts = parts.iter().filter_map(export_part_to_name_pair);
+								let is_json_import = check_import_attributes(
+									attributes.as_ref(),
+									environment.get_source(),
+									checking_data,
+								);
 
 								import_items(
 									environment,
 									from.get_path().unwrap(),
+									is_json_import,
 									*position,
 									None,
 									crate::features::modules::ImportKind::Parts(parts),
@@ -152,6 +184,24 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 									*type_definitions_only,
 								);
 							}
+							Exportable::Enum(r#enum) => {
+								let ty =
+									synthesise_enum_declaration(r#enum, environment, checking_data);
+								let variable_id = crate::VariableId(
+									environment.get_source(),
+									r#enum.position.start,
+								);
+
+								if let crate::Scope::Module { ref mut exported, .. } =
+									environment.context_type.scope
+								{
+									exported.named.push((
+										r#enum.name.clone(),
+										(variable_id, VariableMutability::Constant),
+									));
+									exported.named_types.push((r#enum.name.clone(), ty));
+								}
+							}
 							Exportable::TypeAlias(alias) => {
 								let export = environment.new_alias::<_, EznoParser>(
 									&alias.type_name.name,
@@ -234,6 +284,7 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 						&declared_at,
 						base.effects,
 						base.constant_function,
+						base.behavior,
 					);
 
 					environment.register_variable_handle_error(
@@ -248,12 +299,6 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 						&mut checking_data.diagnostics_container,
 					);
 				}
-				parser::Declaration::Enum(r#enum) => {
-					checking_data.raise_unimplemented_error(
-						"enum",
-						r#enum.position.with_source(environment.get_source()),
-					);
-				}
 				parser::Declaration::Interface(interface) => {
 					let ty = idx_to_types.remove(&interface.on.position.start).unwrap();
 					super::interfaces::synthesise_signatures(
@@ -264,6 +309,14 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 						checking_data,
 					);
 				}
+				parser::Declaration::DeclareNamespace(namespace) => {
+					synthesise_namespace(
+						namespace,
+						&mut hoisted_definitions,
+						environment,
+						checking_data,
+					);
+				}
 				parser::Declaration::DeclareVariable(DeclareVariableDeclaration {
 					keyword: _,
 					declarations,
@@ -305,8 +358,9 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 					parser::declarations::ExportDeclaration::Variable { exported, position: _ } => {
 						match exported {
 							Exportable::Function(func) => {
+								// Same position as the variable id used in the third stage
 								let declared_at =
-									func.get_position().with_source(environment.get_source());
+									item.get_position().with_source(environment.get_source());
 
 								if let Some(VariableIdentifier::Standard(name, ..)) =
 									func.name.as_option_variable_identifier()
@@ -339,6 +393,7 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 								);
 							}
 							Exportable::TypeAlias(_)
+							| Exportable::Enum(_)
 							| Exportable::Parts(..)
 							| Exportable::ImportAll { .. }
 							| Exportable::ImportParts { .. }
@@ -348,6 +403,7 @@ pub(crate) fn hoist_statements<T: crate::ReadFromFS>(
 					parser::declarations::ExportDeclaration::Default { .. } => {}
 				},
 				parser::Declaration::Class(_)
+				| parser::Declaration::Enum(_)
 				| parser::Declaration::TypeAlias(_)
 				| parser::Declaration::DeclareInterface(_)
 				| parser::Declaration::Import(_) => {}
@@ -485,6 +541,27 @@ pub(super) fn export_part_to_name_pair(
 	}
 }
 
+/// Reports unsupported attributes and returns whether the module is imported as JSON
+pub(super) fn check_import_attributes<T: ReadFromFS>(
+	attributes: Option<&parser::declarations::ImportAttributes>,
+	source: source_map::SourceId,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> bool {
+	let Some(attributes) = attributes else { return false };
+	for attribute in &attributes.attributes {
+		if !(attribute.key == "type" && attribute.value == "json") {
+			checking_data.diagnostics_container.add_error(
+				TypeCheckError::UnsupportedImportAttribute {
+					key: &attribute.key,
+					value: &attribute.value,
+					position: attribute.key_position.with_source(source),
+				},
+			);
+		}
+	}
+	attributes.get_type() == Some("json")
+}
+
 pub(super) fn hoist_variable_declaration<T: ReadFromFS>(
 	declaration: &parser::declarations::VariableDeclaration,
 	environment: &mut crate::context::Context<crate::context::environment::Syntax<'_>>,
@@ -494,7 +571,8 @@ pub(super) fn hoist_variable_declaration<T: ReadFromFS>(
 		parser::declarations::VariableDeclaration::ConstDeclaration {
 			declarations,
 			position: _,
-		} => {
+		}
+		| parser::declarations::VariableDeclaration::UsingDeclaration { declarations, .. } => {
 			for declaration in declarations {
 				crate::utils::notify!("TODO constraint needed to be set for free variable!!!");
 				let constraint =
@@ -1,6 +1,6 @@
 use parser::{
-	types::interface::{InterfaceDeclaration, InterfaceMember},
-	Decorated, PropertyKey as ParserPropertyKey, WithComment,
+	types::interface::{InterfaceDeclaration, InterfaceMember, Optionality, TypeRule},
+	Decorated, PropertyKey as ParserPropertyKey, TypeAnnotation, WithComment,
 };
 
 use crate::{
@@ -8,10 +8,12 @@ use crate::{
 	features::functions::{self, GetterSetter},
 	synthesis::parser_property_key_to_checker_property_key,
 	types::{
-		properties::{PropertyKey, PropertyValue},
-		FunctionType, Type,
+		mapped_types::{substitute_mapped_type, MappedModifier, MappedType},
+		poly_types::generic_type_arguments::StructureGenericArguments,
+		properties::{new_key_of_type, PropertyKey, PropertyModifiers, PropertyValue},
+		Constructor, FunctionType, Type,
 	},
-	CheckingData, TypeId,
+	CheckingData, Scope, TypeId,
 };
 
 use super::{
@@ -40,6 +42,16 @@ pub(crate) trait SynthesiseInterfaceBehavior {
 		&mut self,
 		key: ParserPropertyKeyType,
 		value: InterfaceValue,
+		modifiers: PropertyModifiers,
+		checking_data: &mut CheckingData<T, super::EznoParser>,
+		environment: &mut Environment,
+	);
+
+	/// For `(a: T): U` and `new (a: T): U` members
+	fn register_signature<T: crate::ReadFromFS>(
+		&mut self,
+		function: FunctionType,
+		is_new: bool,
 		checking_data: &mut CheckingData<T, super::EznoParser>,
 		environment: &mut Environment,
 	);
@@ -65,6 +77,7 @@ impl SynthesiseInterfaceBehavior for OnToType {
 		&mut self,
 		key: ParserPropertyKeyType,
 		value: InterfaceValue,
+		modifiers: PropertyModifiers,
 		checking_data: &mut CheckingData<T, super::EznoParser>,
 		environment: &mut Environment,
 	) {
@@ -98,7 +111,25 @@ impl SynthesiseInterfaceBehavior for OnToType {
 		};
 
 		// TODO: `None` position passed
-		environment.facts.register_property(self.0, publicity, under, ty, false, None);
+		environment.facts.register_property(self.0, publicity, under.clone(), ty, false, None);
+		environment.facts.set_property_modifiers(self.0, under, modifiers);
+	}
+
+	fn register_signature<T: crate::ReadFromFS>(
+		&mut self,
+		function: FunctionType,
+		is_new: bool,
+		checking_data: &mut CheckingData<T, super::EznoParser>,
+		environment: &mut Environment,
+	) {
+		let function_id = function.id;
+		checking_data.types.functions.insert(function_id, function);
+		let signatures = if is_new {
+			&mut environment.facts.construct_signatures
+		} else {
+			&mut environment.facts.call_signatures
+		};
+		signatures.entry(self.0).or_default().push(function_id);
 	}
 
 	fn interface_type(&self) -> Option<TypeId> {
@@ -106,6 +137,67 @@ impl SynthesiseInterfaceBehavior for OnToType {
 	}
 }
 
+/// `{ [P in keyof T as N]?: V }`. Evaluated if the keys are known, otherwise on substitution
+#[allow(clippy::too_many_arguments)]
+pub(super) fn synthesise_mapped_type<T: crate::ReadFromFS>(
+	parameter: &str,
+	rule: &TypeRule,
+	matching_type: &TypeAnnotation,
+	as_type: Option<&TypeAnnotation>,
+	optionality: &Optionality,
+	is_readonly: bool,
+	output_type: &TypeAnnotation,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> TypeId {
+	let matching_type = synthesise_type_annotation(matching_type, environment, checking_data);
+	let keys = match rule {
+		TypeRule::In => matching_type,
+		TypeRule::InKeyOf => new_key_of_type(matching_type, environment, &mut checking_data.types),
+	};
+
+	// The parameter is only available in the mapped type
+	let ((parameter, as_type, value), ..) = environment.new_lexical_environment_fold_into_parent(
+		Scope::TypeAlias,
+		checking_data,
+		|environment, checking_data| {
+			let parameter = environment
+				.new_explicit_type_parameter(parameter, Some(keys), None, &mut checking_data.types)
+				.id;
+			let as_type = as_type
+				.map(|as_type| synthesise_type_annotation(as_type, environment, checking_data));
+			let value = synthesise_type_annotation(output_type, environment, checking_data);
+			(parameter, as_type, value)
+		},
+	);
+
+	let mapped = MappedType {
+		parameter,
+		as_type,
+		value,
+		optional: match optionality {
+			Optionality::Default => MappedModifier::Preserve,
+			Optionality::Optional => MappedModifier::Add,
+			Optionality::Required => MappedModifier::Remove,
+		},
+		readonly: if is_readonly { MappedModifier::Add } else { MappedModifier::Preserve },
+	};
+
+	if checking_data.types.get_type_by_id(keys).is_dependent() {
+		checking_data.types.register_type(Type::Constructor(Constructor::Mapped(mapped)))
+	} else {
+		substitute_mapped_type(
+			&mapped,
+			&mut StructureGenericArguments {
+				type_arguments: map_vec::Map::new(),
+				closures: Vec::new(),
+			},
+			environment,
+			&mut checking_data.types,
+		)
+	}
+}
+
 pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterfaceBehavior>(
 	type_parameters: Option<&[parser::GenericTypeConstraint]>,
 	signatures: &[WithComment<Decorated<InterfaceMember>>],
@@ -129,7 +221,7 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 					type_parameters,
 					parameters,
 					return_type,
-					is_optional: _,
+					is_optional,
 					performs,
 					position,
 				} => {
@@ -167,6 +259,7 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 					interface_register_behavior.register(
 						ParserPropertyKeyType::ClassProperty(name),
 						InterfaceValue::Function(function, getter),
+						PropertyModifiers { optional: *is_optional, readonly: false },
 						checking_data,
 						environment,
 					);
@@ -174,8 +267,8 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 				InterfaceMember::Property {
 					name,
 					type_annotation,
-					is_readonly: _,
-					is_optional: _,
+					is_readonly,
+					is_optional,
 					position: _,
 				} => {
 					let value =
@@ -183,6 +276,7 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 					interface_register_behavior.register(
 						ParserPropertyKeyType::ClassProperty(name),
 						InterfaceValue::Value(value),
+						PropertyModifiers { optional: *is_optional, readonly: *is_readonly },
 						checking_data,
 						environment,
 					);
@@ -191,7 +285,7 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 					name: _,
 					indexer_type,
 					return_type,
-					is_readonly: _,
+					is_readonly,
 					position: _,
 				} => {
 					// TODO think this is okay
@@ -200,41 +294,65 @@ pub(super) fn synthesise_signatures<T: crate::ReadFromFS, B: SynthesiseInterface
 					interface_register_behavior.register(
 						ParserPropertyKeyType::Type(key),
 						InterfaceValue::Value(value),
+						PropertyModifiers { optional: false, readonly: *is_readonly },
 						checking_data,
 						environment,
 					);
 				}
 				InterfaceMember::Constructor {
-					parameters: _,
-					type_parameters: _,
-					return_type: _,
+					parameters,
+					type_parameters,
+					return_type,
 					is_readonly: _,
 					position,
-					performs: _,
-				} => checking_data.raise_unimplemented_error(
-					"interface constructor",
-					position.with_source(environment.get_source()),
-				),
+					performs,
+				} => {
+					let function = synthesise_function_annotation(
+						type_parameters,
+						parameters,
+						return_type.as_ref(),
+						environment,
+						checking_data,
+						performs.as_ref().into(),
+						&position.with_source(environment.get_source()),
+						functions::FunctionBehavior::ConstructorSignature,
+						None,
+					);
+					interface_register_behavior.register_signature(
+						function,
+						true,
+						checking_data,
+						environment,
+					);
+				}
 				InterfaceMember::Caller {
-					parameters: _,
-					type_parameters: _,
-					return_type: _,
-					is_readonly: _,
-					position,
-				} => checking_data.raise_unimplemented_error(
-					"interface caller",
-					position.with_source(environment.get_source()),
-				),
-				InterfaceMember::Rule {
-					parameter: _,
-					rule: _,
-					matching_type: _,
-					optionality: _,
+					parameters,
+					type_parameters,
+					return_type,
 					is_readonly: _,
-					output_type: _,
 					position,
-				} => checking_data.raise_unimplemented_error(
-					"interface rule",
+				} => {
+					let function = synthesise_function_annotation(
+						type_parameters,
+						parameters,
+						return_type.as_ref(),
+						environment,
+						checking_data,
+						super::Performs::None,
+						&position.with_source(environment.get_source()),
+						functions::FunctionBehavior::ArrowFunction { is_async: false },
+						None,
+					);
+					interface_register_behavior.register_signature(
+						function,
+						false,
+						checking_data,
+						environment,
+					);
+				}
+				// Object literal types which are only a rule are mapped types. See `synthesise_mapped_type`
+				InterfaceMember::Rule { position, .. } => checking_data.raise_unimplemented_error(
+					"mapped type with other members",
 					position.with_source(environment.get_source()),
 				),
 				InterfaceMember::Comment { .. } => {}
@@ -8,6 +8,7 @@ pub mod block;
 pub mod classes;
 pub mod declarations;
 pub mod definitions;
+mod enums;
 pub mod expressions;
 mod extensions;
 pub mod functions;
@@ -22,8 +23,10 @@ use parser::{ASTNode, PropertyKey as ParserPropertyKey};
 use source_map::SourceId;
 
 use crate::{
-	context::Names, types::properties::PropertyKey, CheckingData, Diagnostic, Environment, Facts,
-	RootContext, TypeId,
+	context::Names,
+	diagnostics::TypeCheckError,
+	types::properties::{new_key_of_type, PropertyKey},
+	CheckingData, Diagnostic, Environment, Facts, RootContext, Scope, TypeId,
 };
 
 use self::{
@@ -35,6 +38,96 @@ use self::{
 
 pub struct EznoParser;
 
+/// Returns the position of the first part of `expression` that JSON does not allow
+fn find_non_json_part(expression: &parser::Expression, content: &str) -> Option<source_map::Span> {
+	use parser::{
+		expressions::{object_literal::ObjectLiteralMember, ArrayElement, SpreadExpression},
+		operators::UnaryOperator,
+		Expression, Quoted, WithComment,
+	};
+
+	// The parser skips over trailing commas, so look at the source between the last item and the end
+	let trailing_comma = |last_end: u32, position: &source_map::Span| {
+		content[last_end as usize..position.end as usize].contains(',').then_some(*position)
+	};
+
+	match expression {
+		Expression::StringLiteral(_, Quoted::Double, _)
+		| Expression::BooleanLiteral(..)
+		| Expression::Null(..) => None,
+		Expression::NumberLiteral(_, position) => {
+			let text = &content[position.start as usize..position.end as usize];
+			(!is_json_number(text)).then_some(*position)
+		}
+		Expression::UnaryOperation { operator: UnaryOperator::Negation, operand, position }
+			if matches!(&**operand, Expression::NumberLiteral(..)) =>
+		{
+			let text = &content[position.start as usize..position.end as usize];
+			(!is_json_number(text)).then_some(*position)
+		}
+		Expression::ArrayLiteral(elements, position) => {
+			let mut last_end = position.start + 1;
+			for ArrayElement(element) in elements {
+				let Some(SpreadExpression::NonSpread(element)) = element else {
+					return Some(*position);
+				};
+				if let Some(position) = find_non_json_part(element, content) {
+					return Some(position);
+				}
+				last_end = element.get_position().end;
+			}
+			trailing_comma(last_end, position)
+		}
+		Expression::ObjectLiteral(object) => {
+			let mut last_end = object.position.start + 1;
+			for member in &object.members {
+				let ObjectLiteralMember::Property(
+					WithComment::None(ParserPropertyKey::StringLiteral(_, Quoted::Double, _)),
+					value,
+					position,
+				) = member
+				else {
+					return Some(*member.get_position());
+				};
+				if let Some(position) = find_non_json_part(value, content) {
+					return Some(position);
+				}
+				last_end = position.end;
+			}
+			trailing_comma(last_end, &object.position)
+		}
+		expression => Some(*expression.get_position()),
+	}
+}
+
+/// JavaScript numbers such as `.5`, `0x10`, `1_000` and `Infinity` are not valid JSON
+fn is_json_number(text: &str) -> bool {
+	fn skip_digits(text: &str) -> (&str, &str) {
+		text.split_at(text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len()))
+	}
+
+	let (integer, rest) = skip_digits(text.strip_prefix('-').unwrap_or(text));
+	if integer.is_empty() || (integer.len() > 1 && integer.starts_with('0')) {
+		return false;
+	}
+	let rest = if let Some(fraction) = rest.strip_prefix('.') {
+		let (digits, rest) = skip_digits(fraction);
+		if digits.is_empty() {
+			return false;
+		}
+		rest
+	} else {
+		rest
+	};
+	if let Some(exponent) = rest.strip_prefix(|c| c == 'e' || c == 'E') {
+		let (digits, rest) =
+			skip_digits(exponent.strip_prefix(|c| c == '+' || c == '-').unwrap_or(exponent));
+		!digits.is_empty() && rest.is_empty()
+	} else {
+		rest.is_empty()
+	}
+}
+
 // Clippy suggests a fix that breaks the code
 #[allow(clippy::needless_lifetimes)]
 impl crate::ASTImplementation for EznoParser {
@@ -61,9 +154,17 @@ impl crate::ASTImplementation for EznoParser {
 		string: String,
 		options: Self::ParseOptions,
 		_parser_requirements: &mut Self::ParserRequirements,
-	) -> Result<Self::Module<'static>, Self::ParseError> {
-		<parser::Module as parser::ASTNode>::from_string(string, options)
-			.map_err(|err| (err, source_id))
+	) -> Result<
+		(Self::Module<'static>, Vec<Self::ParseError>),
+		(Self::ParseError, Vec<Self::ParseError>),
+	> {
+		let with_source = |errors: Vec<parser::ParseError>| {
+			errors.into_iter().map(|err| (err, source_id)).collect()
+		};
+		match parser::Module::from_string_with_recovery(string, options) {
+			Ok((module, errors)) => Ok((module, with_source(errors))),
+			Err((error, errors)) => Err(((error, source_id), with_source(errors))),
+		}
 	}
 
 	fn definition_module_from_string(
@@ -102,6 +203,24 @@ impl crate::ASTImplementation for EznoParser {
 		parameter.name()
 	}
 
+	fn synthesise_type_parameter_extends<'a, T: crate::ReadFromFS>(
+		parameter: &'a Self::TypeParameter<'a>,
+		environment: &mut Environment,
+		checking_data: &mut crate::CheckingData<T, Self>,
+	) -> TypeId {
+		match parameter {
+			parser::GenericTypeConstraint::Extends(_, extends) => {
+				synthesise_type_annotation(extends, environment, checking_data)
+			}
+			parser::GenericTypeConstraint::ExtendsKeyOf(_, on) => {
+				let on = synthesise_type_annotation(on, environment, checking_data);
+				new_key_of_type(on, environment, &mut checking_data.types)
+			}
+			parser::GenericTypeConstraint::Parameter { .. }
+			| parser::GenericTypeConstraint::Spread { .. } => TypeId::ANY_TYPE,
+		}
+	}
+
 	fn synthesise_type_annotation<'a, T: crate::ReadFromFS>(
 		annotation: &Self::TypeAnnotation<'a>,
 		environment: &mut Environment,
@@ -114,10 +233,34 @@ impl crate::ASTImplementation for EznoParser {
 
 	fn synthesise_definition_file<'a, T: crate::ReadFromFS>(
 		file: Self::DefinitionFile<'a>,
+		source: SourceId,
 		root: &RootContext,
 		checking_data: &mut CheckingData<T, Self>,
 	) -> (Names, Facts) {
-		definitions::type_definition_file(file, checking_data, root)
+		definitions::type_definition_file(file, source, checking_data, root)
+	}
+
+	fn synthesise_json_module<T: crate::ReadFromFS>(
+		source: SourceId,
+		content: String,
+		root: &RootContext,
+		checking_data: &mut CheckingData<T, Self>,
+	) -> Result<(TypeId, Facts), Self::ParseError> {
+		let options =
+			parser::ParseOptions { comments: parser::Comments::All, ..Default::default() };
+		let expression = parser::Expression::from_string(content.clone(), options)
+			.map_err(|err| (err, source))?;
+		let mut environment = root.new_lexical_environment(Scope::DefinitionModule { source });
+		let value = if let Some(position) = find_non_json_part(&expression, &content) {
+			checking_data
+				.diagnostics_container
+				.add_error(TypeCheckError::InvalidJSON(position.with_source(source)));
+			TypeId::ERROR_TYPE
+		} else {
+			synthesise_expression(&expression, &mut environment, checking_data, TypeId::ANY_TYPE)
+		};
+		let Environment { facts, .. } = environment;
+		Ok((value, facts))
 	}
 
 	fn parse_options(is_js: bool, parse_comments: bool, lsp_mode: bool) -> Self::ParseOptions {
@@ -129,6 +272,7 @@ impl crate::ASTImplementation for EznoParser {
 			},
 			type_annotations: !is_js,
 			partial_syntax: lsp_mode,
+			error_recovery: true,
 			..Default::default()
 		}
 	}
@@ -150,17 +294,26 @@ impl crate::ASTImplementation for EznoParser {
 		for_loop_initialiser: &'a Self::ForStatementInitiliser<'a>,
 		environment: &mut Environment,
 		checking_data: &mut crate::CheckingData<T, Self>,
-	) {
+	) -> Vec<crate::features::disposal::Disposable> {
 		match for_loop_initialiser {
 			parser::statements::ForLoopStatementInitializer::VariableDeclaration(declaration) => {
 				// TODO is this correct & the best
 				hoist_variable_declaration(declaration, environment, checking_data);
-				synthesise_variable_declaration(declaration, environment, checking_data, false);
+				synthesise_variable_declaration(declaration, environment, checking_data, false)
 			}
 			parser::statements::ForLoopStatementInitializer::VarStatement(_) => todo!(),
 			parser::statements::ForLoopStatementInitializer::Expression(_) => todo!(),
 		}
 	}
+
+	fn declare_and_assign_to_fields<'a, T: crate::ReadFromFS>(
+		field: &'a Self::VariableField<'a>,
+		environment: &mut Environment,
+		checking_data: &mut crate::CheckingData<T, Self>,
+		arguments: crate::context::VariableRegisterArguments,
+	) {
+		variables::register_variable(field, environment, checking_data, arguments);
+	}
 }
 
 pub(super) fn parser_property_key_to_checker_property_key<
@@ -1,16 +1,29 @@
 use super::{
-	expressions::synthesise_multiple_expression, synthesise_block,
-	type_annotations::synthesise_type_annotation, variables::register_variable,
+	expressions::{synthesise_expression, synthesise_multiple_expression},
+	synthesise_block,
+	type_annotations::synthesise_type_annotation,
+	variables::register_variable,
 };
 use crate::{
 	context::{Scope, VariableRegisterArguments},
 	diagnostics::TypeCheckError,
-	features::iteration::{synthesise_iteration, IterationBehavior},
+	features::{
+		disposal::{dispose_at_scope_exit, get_disposable},
+		iteration::{synthesise_iteration, IterationBehavior},
+		operations::{evaluate_equality_inequality_operation, EqualityAndInequality},
+		variables::VariableWithValue,
+	},
 	synthesis::EznoParser,
-	CheckingData, Environment, TypeId,
+	types::{is_type_truthy_falsy, new_logical_or_type},
+	CheckingData, Decidable, Environment, TypeId,
 };
 
-use parser::{expressions::MultipleExpression, ASTNode, BlockOrSingleStatement, Statement};
+use parser::{
+	expressions::MultipleExpression,
+	statements::{SwitchBranch, SwitchStatement},
+	ASTNode, BlockOrSingleStatement, Expression, Statement, StatementOrDeclaration, VariableField,
+	VariableFieldInSourceCode, VariableIdentifier, VariableKeyword,
+};
 use std::collections::HashMap;
 
 pub type ExportedItems = HashMap<String, crate::features::variables::VariableOrImport>;
@@ -85,6 +98,16 @@ pub(super) fn synthesise_statement<T: crate::ReadFromFS>(
 					},
 					checking_data,
 				);
+
+				// `if (x === null) { return }`: the rest of the block only runs when the condition is falsy
+				if others.is_empty() && last.is_none() && block_exits(current.1) {
+					crate::features::narrowing::narrow_based_on_condition(
+						condition,
+						true,
+						environment,
+						&mut checking_data.types,
+					);
+				}
 			}
 
 			let others = if_statement
@@ -104,10 +127,12 @@ pub(super) fn synthesise_statement<T: crate::ReadFromFS>(
 			);
 		}
 		Statement::Switch(stmt) => {
-			checking_data.diagnostics_container.add_error(TypeCheckError::Unsupported {
-				thing: "Switch statement",
-				at: stmt.get_position().with_source(environment.get_source()),
-			});
+			synthesise_switch_statement(
+				stmt,
+				information.and_then(|info| info.label),
+				environment,
+				checking_data,
+			);
 		}
 		Statement::WhileLoop(stmt) => synthesise_iteration(
 			IterationBehavior::While(&stmt.condition),
@@ -128,23 +153,32 @@ pub(super) fn synthesise_statement<T: crate::ReadFromFS>(
 			},
 		),
 		Statement::ForLoop(stmt) => match &stmt.condition {
-			parser::statements::ForLoopCondition::ForOf {
-				keyword: _,
-				variable,
-				of,
-				position: _,
-			} => {
+			parser::statements::ForLoopCondition::ForOf { keyword, variable, of, position: _ } => {
 				synthesise_iteration(
 					IterationBehavior::ForOf { lhs: variable.get_ast_ref(), rhs: of },
 					information.and_then(|info| info.label),
 					environment,
 					checking_data,
 					|environment, checking_data| {
+						let events_start = environment.facts.events.len();
 						synthesise_block_or_single_statement(
 							&stmt.inner,
 							environment,
 							checking_data,
 						);
+						// The value of each iteration is disposed at the end of the iteration
+						if let Some(
+							is_await @ (VariableKeyword::Using | VariableKeyword::AwaitUsing),
+						) = keyword
+						{
+							dispose_for_of_variable(
+								variable.get_ast_ref(),
+								matches!(is_await, VariableKeyword::AwaitUsing),
+								events_start,
+								environment,
+								checking_data,
+							);
+						}
 					},
 				);
 			}
@@ -280,6 +314,30 @@ pub(super) fn synthesise_statement<T: crate::ReadFromFS>(
 	}
 }
 
+fn dispose_for_of_variable<T: crate::ReadFromFS>(
+	variable: &VariableField<VariableFieldInSourceCode>,
+	is_await: bool,
+	events_start: usize,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) {
+	// Destructuring is a syntax error
+	if let VariableField::Name(VariableIdentifier::Standard(name, position)) = variable {
+		let position = position.with_source(environment.get_source());
+		if let Ok(VariableWithValue(_, value)) =
+			environment.get_variable_handle_error(name, position, checking_data)
+		{
+			let disposables = get_disposable(value, is_await, position, environment, checking_data);
+			dispose_at_scope_exit(
+				disposables.into_iter().collect(),
+				events_start,
+				environment,
+				checking_data,
+			);
+		}
+	}
+}
+
 /// Expects that this caller has already create a context for this to run in
 fn synthesise_block_or_single_statement<T: crate::ReadFromFS>(
 	block_or_single_statement: &BlockOrSingleStatement,
@@ -295,3 +353,210 @@ fn synthesise_block_or_single_statement<T: crate::ReadFromFS>(
 		}
 	}
 }
+
+/// Cases are run as conditionals, where the condition is falling through from the previous case
+/// or the discriminant equalling the case value. `default` runs if no case matches
+fn synthesise_switch_statement<T: crate::ReadFromFS>(
+	switch: &SwitchStatement,
+	label: Option<String>,
+	environment: &mut Environment,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) {
+	let discriminant =
+		synthesise_multiple_expression(&switch.case, environment, checking_data, TypeId::ANY_TYPE);
+
+	// For narrowing the discriminant in each case
+	let discriminant_variable = if let MultipleExpression::Single(expression) = &switch.case {
+		if let Expression::VariableReference(name, _) = expression.get_non_parenthesized() {
+			environment.get_variable_unbound(name).map(|(_, _, variable)| variable.get_id())
+		} else {
+			None
+		}
+	} else {
+		None
+	};
+
+	environment.new_lexical_environment_fold_into_parent(
+		Scope::Switch { label },
+		checking_data,
+		|environment, checking_data| {
+			// Case values are synthesised first so that it is known whether `default` is entered.
+			// Each test is only evaluated if no previous case matched
+			let mut no_case_matches = TypeId::TRUE;
+			let mut tests = Vec::with_capacity(switch.branches.len());
+			for branch in &switch.branches {
+				let SwitchBranch::Case(test, _) = branch else {
+					tests.push(None);
+					continue;
+				};
+				let mut value = TypeId::NEVER_TYPE;
+				let equal = environment.new_conditional_context(
+					no_case_matches,
+					|environment: &mut Environment,
+					 checking_data: &mut CheckingData<T, EznoParser>| {
+						value = synthesise_expression(
+							test,
+							environment,
+							checking_data,
+							TypeId::ANY_TYPE,
+						);
+						let equal = evaluate_equality_inequality_operation(
+							discriminant,
+							&EqualityAndInequality::StrictEqual,
+							value,
+							&mut checking_data.types,
+							checking_data.options.strict_casts,
+						)
+						.unwrap_or(TypeId::ERROR_TYPE);
+
+						if let Decidable::Known(result) =
+							is_type_truthy_falsy(equal, &checking_data.types)
+						{
+							checking_data.raise_decidable_result_error(
+								test.get_position().with_source(environment.get_source()),
+								result,
+							);
+						}
+						equal
+					},
+					Some(|_: &mut Environment, _: &mut CheckingData<T, EznoParser>| TypeId::FALSE),
+					checking_data,
+				);
+
+				no_case_matches = match is_type_truthy_falsy(equal, &checking_data.types) {
+					Decidable::Known(true) => TypeId::FALSE,
+					Decidable::Known(false) => no_case_matches,
+					Decidable::Unknown(_) => {
+						let not_equal = checking_data.types.new_logical_negation_type(equal);
+						logical_and(no_case_matches, not_equal, checking_data)
+					}
+				};
+				tests.push(Some((value, equal)));
+			}
+
+			// Condition and narrowed discriminant value of the previous case, if it can fall through
+			let mut fall_through: Option<(TypeId, Option<TypeId>)> = None;
+
+			for (branch, test) in switch.branches.iter().zip(tests) {
+				let (statements, case_condition, case_value) = match (branch, test) {
+					(SwitchBranch::Case(_, statements), Some((value, equal))) => {
+						(statements, equal, Some(value))
+					}
+					(SwitchBranch::Default(statements), _) => (statements, no_case_matches, None),
+					(SwitchBranch::Case(..), None) => unreachable!(),
+				};
+
+				let (condition, narrowed) = if let Some((previous_condition, previous_narrowed)) =
+					fall_through
+				{
+					let condition = logical_or(previous_condition, case_condition, checking_data);
+					let narrowed = previous_narrowed
+						.zip(case_value)
+						.map(|(lhs, rhs)| checking_data.types.new_or_type(lhs, rhs));
+					(condition, narrowed)
+				} else {
+					(case_condition, case_value)
+				};
+
+				let narrow_to = discriminant_variable.zip(narrowed).filter(|_| {
+					matches!(
+						is_type_truthy_falsy(condition, &checking_data.types),
+						Decidable::Unknown(_)
+					)
+				});
+
+				environment.new_conditional_context(
+					condition,
+					|environment: &mut Environment,
+					 checking_data: &mut CheckingData<T, EznoParser>| {
+						if let Some((variable, value)) = narrow_to {
+							environment.facts.variable_current_value.insert(variable, value);
+						}
+						synthesise_block(statements, environment, checking_data);
+					},
+					None::<fn(&mut Environment, &mut CheckingData<T, EznoParser>)>,
+					checking_data,
+				);
+
+				let exits = statements_exit(statements);
+
+				fall_through = if exits { None } else { Some((condition, narrowed)) };
+			}
+		},
+	);
+}
+
+/// Whether `items` leave the current block (unconditionally)
+fn statements_exit(items: &[StatementOrDeclaration]) -> bool {
+	items.iter().any(
+		|item| matches!(item, StatementOrDeclaration::Statement(statement) if statement_exits(statement)),
+	)
+}
+
+/// Does not look into loops as `break` and `continue` there are for the loop
+fn statement_exits(statement: &Statement) -> bool {
+	match statement {
+		Statement::Break(..)
+		| Statement::Continue(..)
+		| Statement::Return(..)
+		| Statement::Throw(..) => true,
+		Statement::Block(block) => statements_exit(&block.0),
+		Statement::If(if_statement) => {
+			block_exits(&if_statement.inner)
+				&& if_statement.else_conditions.iter().all(|branch| block_exits(&branch.inner))
+				&& if_statement
+					.trailing_else
+					.as_ref()
+					.is_some_and(|trailing_else| block_exits(&trailing_else.inner))
+		}
+		Statement::TryCatch(try_catch) => {
+			let catch_exits = if let Some(catch) = &try_catch.catch_inner {
+				statements_exit(&catch.0)
+			} else {
+				true
+			};
+			try_catch.finally_inner.as_ref().is_some_and(|finally| statements_exit(&finally.0))
+				|| (statements_exit(&try_catch.try_inner.0) && catch_exits)
+		}
+		_ => false,
+	}
+}
+
+fn block_exits(block: &BlockOrSingleStatement) -> bool {
+	match block {
+		BlockOrSingleStatement::Braced(block) => statements_exit(&block.0),
+		BlockOrSingleStatement::SingleStatement(statement) => statement_exits(statement),
+	}
+}
+
+fn logical_and<T: crate::ReadFromFS>(
+	lhs: TypeId,
+	rhs: TypeId,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> TypeId {
+	match (
+		is_type_truthy_falsy(lhs, &checking_data.types),
+		is_type_truthy_falsy(rhs, &checking_data.types),
+	) {
+		(Decidable::Known(false), _) | (_, Decidable::Known(false)) => TypeId::FALSE,
+		(Decidable::Known(true), _) => rhs,
+		(_, Decidable::Known(true)) => lhs,
+		_ => checking_data.types.new_conditional_type(lhs, rhs, lhs),
+	}
+}
+
+fn logical_or<T: crate::ReadFromFS>(
+	lhs: TypeId,
+	rhs: TypeId,
+	checking_data: &mut CheckingData<T, super::EznoParser>,
+) -> TypeId {
+	match (
+		is_type_truthy_falsy(lhs, &checking_data.types),
+		is_type_truthy_falsy(rhs, &checking_data.types),
+	) {
+		(Decidable::Known(true), _) | (_, Decidable::Known(true)) => TypeId::TRUE,
+		(Decidable::Known(false), _) => rhs,
+		(_, Decidable::Known(false)) => lhs,
+		_ => new_logical_or_type(lhs, rhs, &mut checking_data.types),
+	}
+}
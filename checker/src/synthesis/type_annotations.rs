@@ -18,25 +18,26 @@
 //! - To allow for compat it treats it as inferred generic **so it can get properties off of it**. Would be better
 //! to allow this as a condition in the future
 
-use std::{convert::TryInto, iter::FromIterator};
+use std::{borrow::Cow, convert::TryInto, iter::FromIterator};
 
 use parser::{
 	type_annotations::{
 		AnnotationWithBinder, CommonTypes, SpreadKind, TypeCondition, TypeConditionResult,
 	},
+	types::interface::InterfaceMember,
 	ASTNode, TypeAnnotation,
 };
 use source_map::SpanWithSource;
 
 use crate::{
-	context::facts::Publicity,
+	context::{facts::Publicity, Logical, Scope},
 	diagnostics::TypeCheckError,
-	features::objects::ObjectBuilder,
+	features::{functions::FunctionBehavior, objects::ObjectBuilder, variables::VariableWithValue},
 	subtyping::{type_is_subtype, BasicEquality, SubTypeResult},
 	synthesis::functions::synthesise_function_annotation,
 	types::{
 		poly_types::generic_type_arguments::StructureGenericArguments,
-		properties::{PropertyKey, PropertyValue},
+		properties::{new_key_of_type, PropertyKey, PropertyValue},
 		substitute, Constant, PolyNature, StructureGenerics, Type,
 	},
 	types::{Constructor, TypeId},
@@ -78,6 +79,7 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 		}
 		TypeAnnotation::Name(name, pos) => match name.as_str() {
 			"any" => TypeId::ANY_TYPE,
+			"never" => TypeId::NEVER_TYPE,
 			"this" => todo!(), // environment.get_value_of_this(&mut checking_data.types),
 			"self" => TypeId::ANY_INFERRED_FREE_THIS,
 			name => {
@@ -174,20 +176,32 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 						unreachable!()
 					};
 
-					// TODO it is a bit weird with the arguments, maybe should get their restriction directly here?
-					// Definition files don't necessary need to check ...
-					let result = type_is_subtype(
+					// Restrictions can reference earlier parameters (for example `K extends keyof T`)
+					let parameter_restriction = substitute(
 						*parameter_restriction,
-						argument,
-						&mut basic_equality,
+						&mut type_arguments,
 						environment,
-						&checking_data.types,
+						&mut checking_data.types,
 					);
 
+					// Definition files don't necessary need to check ...
+					// Dependent arguments are checked when the outer type is given arguments
+					let result = if checking_data.types.get_type_by_id(argument).is_dependent() {
+						SubTypeResult::IsSubType
+					} else {
+						type_is_subtype(
+							parameter_restriction,
+							argument,
+							&mut basic_equality,
+							environment,
+							&checking_data.types,
+						)
+					};
+
 					if let SubTypeResult::IsNotSubType(_matches) = result {
 						let error = crate::diagnostics::TypeCheckError::GenericArgumentDoesNotMeetRestriction {
 							parameter_restriction: crate::diagnostics::TypeStringRepresentation::from_type_id(
-								*parameter_restriction,
+								parameter_restriction,
 								&environment.as_general_context(),
 								&checking_data.types,
 								checking_data.options.debug_types,
@@ -263,6 +277,7 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 				&position,
 				function_type.effects,
 				None,
+				function_type.behavior,
 			)
 		}
 		TypeAnnotation::Readonly(type_annotation, _) => {
@@ -282,7 +297,32 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 
 			// ty_to_be_readonly)
 		}
-		TypeAnnotation::NamespacedName(_, _, _) => unimplemented!(),
+		// Types in namespaces are registered under `Namespace.Name`. Otherwise `enum` members
+		TypeAnnotation::NamespacedName(name, member, position) => {
+			if let Some(ty) = environment.get_type_from_name(&format!("{name}.{member}")) {
+				return ty;
+			}
+			let position = position.with_source(environment.get_source());
+			let Ok(VariableWithValue(_, on)) =
+				environment.get_variable_handle_error(name, position, checking_data)
+			else {
+				return TypeId::ERROR_TYPE;
+			};
+			let property = environment.get_property_unbound(
+				on,
+				Publicity::Public,
+				PropertyKey::String(Cow::Borrowed(member)),
+				&checking_data.types,
+			);
+			if let Some(Logical::Pure(PropertyValue::Value(value))) = property {
+				value
+			} else {
+				checking_data
+					.diagnostics_container
+					.add_error(TypeCheckError::CannotFindType(member, position));
+				TypeId::ERROR_TYPE
+			}
+		}
 		TypeAnnotation::ArrayLiteral(item_annotation, _) => {
 			let item_type = synthesise_type_annotation(item_annotation, environment, checking_data);
 			let with_source = item_annotation.get_position().with_source(environment.get_source());
@@ -299,14 +339,62 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 			checking_data.types.register_type(ty)
 		}
 		TypeAnnotation::ConstructorLiteral {
-			type_parameters: _,
-			parameters: _,
-			return_type: _,
-			position: _,
-		} => unimplemented!(),
+			type_parameters,
+			parameters,
+			return_type,
+			position,
+		} => {
+			let position = position.with_source(environment.get_source());
+			let function_type = synthesise_function_annotation(
+				type_parameters,
+				parameters,
+				Some(return_type),
+				environment,
+				checking_data,
+				super::Performs::None,
+				&position,
+				FunctionBehavior::ConstructorSignature,
+				None,
+			);
+			checking_data.types.new_function_type_annotation(
+				function_type.type_parameters,
+				function_type.parameters,
+				function_type.return_type,
+				&position,
+				function_type.effects,
+				None,
+				function_type.behavior,
+			)
+		}
 		// Object literals are first turned into types as if they were interface declarations and then
 		// returns reference to object literal
 		TypeAnnotation::ObjectLiteral(members, _) => {
+			if let [member] = members.as_slice() {
+				if let InterfaceMember::Rule {
+					parameter,
+					rule,
+					matching_type,
+					as_type,
+					optionality,
+					is_readonly,
+					output_type,
+					position: _,
+				} = &member.get_ast_ref().on
+				{
+					return super::interfaces::synthesise_mapped_type(
+						parameter,
+						rule,
+						matching_type,
+						as_type.as_deref(),
+						optionality,
+						*is_readonly,
+						output_type,
+						environment,
+						checking_data,
+					);
+				}
+			}
+
 			// TODO rather than onto, generate a new type...
 			let onto = checking_data
 				.types
@@ -382,36 +470,72 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 
 			checking_data.types.new_property_on_type_annotation(being_indexed, indexer, environment)
 		}
-		TypeAnnotation::KeyOf(_, _) => unimplemented!(),
+		TypeAnnotation::KeyOf(on, _) => {
+			let on = synthesise_type_annotation(on, environment, checking_data);
+			new_key_of_type(on, environment, &mut checking_data.types)
+		}
+		// Only valid in the extends type of a conditional type, which scopes it
+		TypeAnnotation::Infer(name, _) => {
+			environment.new_explicit_type_parameter(name, None, None, &mut checking_data.types).id
+		}
 		TypeAnnotation::Conditional { condition, resolve_true, resolve_false, position: _ } => {
 			fn synthesise_condition(result: &TypeConditionResult) -> &TypeAnnotation {
 				match result {
-					TypeConditionResult::Reference(reference) => reference,
-					TypeConditionResult::Infer(_infer, _) => todo!(),
+					TypeConditionResult::Reference(reference)
+					| TypeConditionResult::Infer(reference, _) => reference,
 				}
 			}
 
-			let condition = synthesise_type_condition(condition, environment, checking_data);
-
-			let truthy_result = synthesise_type_annotation(
-				synthesise_condition(resolve_true),
-				environment,
-				checking_data,
-			);
+			let TypeCondition::Extends { ty, extends, position: _ } = condition else {
+				// TODO requires a kind of strict instance of ???
+				todo!()
+			};
+
+			let check_type = synthesise_type_annotation(ty, environment, checking_data);
+
+			// `infer` types are declared in the extends type and can be used in the truthy result
+			let ((extends, truthy_result), ..) = environment
+				.new_lexical_environment_fold_into_parent(
+					Scope::TypeAlias,
+					checking_data,
+					|environment, checking_data| {
+						let extends =
+							synthesise_type_annotation(extends, environment, checking_data);
+						let truthy_result = synthesise_type_annotation(
+							synthesise_condition(resolve_true),
+							environment,
+							checking_data,
+						);
+						(extends, truthy_result)
+					},
+				);
 			let else_result = synthesise_type_annotation(
 				synthesise_condition(resolve_false),
 				environment,
 				checking_data,
 			);
 
-			let ty = Type::Constructor(Constructor::ConditionalResult {
-				condition,
+			let ty = checking_data.types.new_conditional_extends_type(
+				check_type,
+				extends,
 				truthy_result,
 				else_result,
-				result_union: checking_data.types.new_or_type(truthy_result, else_result),
-			});
+			);
 
-			checking_data.types.register_type(ty)
+			if checking_data.types.get_type_by_id(check_type).is_dependent() {
+				ty
+			} else {
+				// Can be evaluated now
+				substitute(
+					ty,
+					&mut StructureGenericArguments {
+						type_arguments: map_vec::Map::new(),
+						closures: Vec::new(),
+					},
+					environment,
+					&mut checking_data.types,
+				)
+			}
 		}
 		TypeAnnotation::Marker(_, _) => {
 			crate::utils::notify!("Dump available object types in environment to somewhere..?");
@@ -433,28 +557,6 @@ pub(super) fn synthesise_type_annotation<T: crate::ReadFromFS>(
 	ty
 }
 
-fn synthesise_type_condition<T: crate::ReadFromFS>(
-	condition: &TypeCondition,
-	environment: &mut Environment,
-	checking_data: &mut CheckingData<T, super::EznoParser>,
-) -> TypeId {
-	match condition {
-		TypeCondition::Extends { ty, extends, position: _ } => {
-			let _item = synthesise_type_annotation(ty, environment, checking_data);
-			let _extends = synthesise_type_annotation(extends, environment, checking_data);
-			todo!();
-			// let ty = Type::Constructor(Constructor::BinaryOperator {
-			// 	operator: crate::structures::operators::CanonicalBinaryOperator::InstanceOf,
-			// 	lhs: item,
-			// 	rhs: extends,
-			// });
-			// checking_data.types.register_type(ty)
-		}
-		// TODO requires a kind of strict instance of ???
-		TypeCondition::Is { ty: _, is: _, position: _ } => todo!(),
-	}
-}
-
 /// Comment as type annotation
 pub(crate) fn comment_as_type_annotation<T: crate::ReadFromFS>(
 	possible_declaration: &str,
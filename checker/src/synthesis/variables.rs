@@ -162,7 +162,7 @@ pub(super) fn synthesise_variable_declaration_item<
 	_is_constant: bool,
 	checking_data: &mut CheckingData<T, super::EznoParser>,
 	exported: Option<VariableMutability>,
-) {
+) -> TypeId {
 	// This is only added if there is an annotation, so can be None
 	let get_position = variable_declaration.get_position();
 	let var_ty_and_pos = checking_data
@@ -197,6 +197,7 @@ pub(super) fn synthesise_variable_declaration_item<
 
 	let item = variable_declaration.name.get_ast_ref();
 	assign_to_fields(item, environment, checking_data, value_ty, exported);
+	value_ty
 }
 
 fn assign_to_fields<T: crate::ReadFromFS>(
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+	collections::{HashMap, HashSet},
+	path::PathBuf,
+};
 
 use source_map::{SourceId, SpanWithSource};
 
@@ -15,8 +18,8 @@ use crate::{
 /// and module things on [`crate::ModuleData`]
 #[derive(Default, Debug)]
 pub struct TypeMappings {
-	/// Figures out the types of the expressions in the AST
-	pub expressions_to_instances: RangeMap<Instance>,
+	/// Figures out the types of the expressions in the AST. Per source as positions are not unique
+	pub expressions_to_instances: HashMap<SourceId, RangeMap<Instance>>,
 	/// [Variable] data to a AST mapping
 	pub variables_to_constraints: VariablesToTypes,
 	/// Property to type, TODO kind of temp
@@ -29,6 +32,11 @@ pub struct TypeMappings {
 	pub variable_restrictions: HashMap<(SourceId, u32), (TypeId, SpanWithSource)>,
 	/// Temp
 	pub special_expressions: RangeMap<SpecialExpressions>,
+	/// Objects of `const enum`s. Accesses on these can be inlined
+	pub const_enums: HashSet<TypeId>,
+	/// Variable references and assignment targets (by position) to the variable they resolved to.
+	/// For imports this is the import site. Only recorded if `store_expression_type_mappings`
+	pub variable_references: HashMap<(SourceId, u32), VariableId>,
 }
 
 #[derive(Debug)]
@@ -3,11 +3,12 @@ use std::vec;
 
 use crate::{
 	context::{invocation::CheckThings, CallCheckingBehavior, Environment, Logical},
-	diagnostics::{TypeCheckError, TypeStringRepresentation, TDZ},
+	diagnostics::{NonEqualityRepresentation, TypeCheckError, TypeStringRepresentation, TDZ},
 	events::{application::ErrorsAndInfo, apply_event, Event, FinalEvent, RootReference},
 	features::{
 		constant_functions::{call_constant_function, ConstantFunctionError, ConstantOutput},
 		functions::{FunctionBehavior, ThisValue},
+		objects::SpecialObjects,
 	},
 	subtyping::{type_is_subtype, BasicEquality, SubTypeResult},
 	types::{functions::SynthesisedArgument, substitute, ObjectNature},
@@ -54,18 +55,45 @@ pub fn call_type_handle_errors<T: crate::ReadFromFS, A: crate::ASTImplementation
 
 	// TODO fails for ors
 	// TODO not great, gets parameters here, then
-	let arguments = if let Type::Function(f, _) | Type::FunctionReference(f) =
-		checking_data.types.get_type_by_id(base_function)
-	{
-		// TODO clone here bad
-		let parameters = checking_data.types.get_function_from_id(*f).parameters.clone();
+	let functions = match checking_data.types.get_type_by_id(base_function) {
+		Type::Function(f, _) | Type::FunctionReference(f) => vec![*f],
+		Type::Interface { .. } | Type::Object(_) => {
+			let is_new = matches!(input.called_with_new, CalledWithNew::New { .. });
+			environment.get_signatures(base_function, is_new)
+		}
+		_ => Vec::new(),
+	};
+	let arguments = if functions.is_empty() {
+		arguments
+			.into_iter()
+			.map(|argument| SynthesisedArgument {
+				spread: argument.spread,
+				position: A::expression_position(argument.expression)
+					.with_source(environment.get_source()),
+				value: A::synthesise_expression(
+					argument.expression,
+					TypeId::ANY_TYPE,
+					environment,
+					checking_data,
+				),
+			})
+			.collect()
+	} else {
 		arguments
 			.into_iter()
 			.enumerate()
 			.map(|(idx, argument)| {
-				let expected = parameters.parameters.get(idx).map_or(TypeId::ANY_TYPE, |p| {
-					get_constraint(p.ty, &checking_data.types).unwrap_or(p.ty)
+				// With overloads, an argument is only expected to be a type if every overload
+				// agrees on it
+				let mut expected_types = functions.iter().map(|f| {
+					let parameters = &checking_data.types.get_function_from_id(*f).parameters;
+					parameters.parameters.get(idx).map_or(TypeId::ANY_TYPE, |p| {
+						get_constraint(p.ty, &checking_data.types).unwrap_or(p.ty)
+					})
 				});
+				let first = expected_types.next().unwrap_or(TypeId::ANY_TYPE);
+				let expected =
+					if expected_types.all(|ty| ty == first) { first } else { TypeId::ANY_TYPE };
 
 				SynthesisedArgument {
 					spread: argument.spread,
@@ -80,21 +108,6 @@ pub fn call_type_handle_errors<T: crate::ReadFromFS, A: crate::ASTImplementation
 				}
 			})
 			.collect()
-	} else {
-		arguments
-			.into_iter()
-			.map(|argument| SynthesisedArgument {
-				spread: argument.spread,
-				position: A::expression_position(argument.expression)
-					.with_source(environment.get_source()),
-				value: A::synthesise_expression(
-					argument.expression,
-					TypeId::ANY_TYPE,
-					environment,
-					checking_data,
-				),
-			})
-			.collect()
 	};
 
 	let call_site = input.call_site;
@@ -173,20 +186,10 @@ pub fn call_type<E: CallCheckingBehavior>(
 			types,
 		)
 	} else {
-		let callable = get_logical_callable_from_type(on, types);
+		let is_new = matches!(called_with_new, CalledWithNew::New { .. });
+		let callables = get_logical_callable_from_type(on, is_new, top_environment, types);
 
-		if let Some(logical) = callable {
-			let structure_generics = None;
-			call_logical(
-				logical,
-				types,
-				CallingInputWithoutThis { called_with_new, call_site_type_arguments, call_site },
-				structure_generics,
-				arguments,
-				top_environment,
-				behavior,
-			)
-		} else {
+		if callables.is_empty() {
 			Err(vec![FunctionCallingError::NotCallable {
 				calling: crate::diagnostics::TypeStringRepresentation::from_type_id(
 					on,
@@ -196,6 +199,40 @@ pub fn call_type<E: CallCheckingBehavior>(
 				),
 				call_site,
 			}])
+		} else {
+			// Overloads are tried in declaration order. If none match then the errors are
+			// from the first
+			let mut first_errors = None;
+			let mut arguments = arguments;
+			let mut callables = callables.into_iter().peekable();
+			while let Some(logical) = callables.next() {
+				let arguments = if callables.peek().is_some() {
+					arguments.clone()
+				} else {
+					std::mem::take(&mut arguments)
+				};
+				let structure_generics = None;
+				let result = call_logical(
+					logical,
+					types,
+					CallingInputWithoutThis {
+						called_with_new,
+						call_site_type_arguments: call_site_type_arguments.clone(),
+						call_site,
+					},
+					structure_generics,
+					arguments,
+					top_environment,
+					behavior,
+				);
+				match result {
+					Ok(result) => return Ok(result),
+					Err(errors) => {
+						first_errors.get_or_insert(errors);
+					}
+				}
+			}
+			Err(first_errors.unwrap())
 		}
 	}
 }
@@ -230,7 +267,42 @@ fn call_logical<E: CallCheckingBehavior>(
 				todo!("recursive function type")
 			}
 		}
-		Logical::Or { left: _, right: _ } => todo!(),
+		Logical::Or { left, right } => {
+			// Either could be the one called, so the arguments have to be valid for both
+			let left = call_logical(
+				*left,
+				types,
+				CallingInputWithoutThis {
+					called_with_new,
+					call_site_type_arguments: call_site_type_arguments.clone(),
+					call_site,
+				},
+				structure_generics.clone(),
+				arguments.clone(),
+				environment,
+				behavior,
+			)?;
+			let right = call_logical(
+				*right,
+				types,
+				CallingInputWithoutThis { called_with_new, call_site_type_arguments, call_site },
+				structure_generics,
+				arguments,
+				environment,
+				behavior,
+			)?;
+
+			let mut warnings = left.warnings;
+			warnings.extend(right.warnings);
+			Ok(FunctionCallResult {
+				called: None,
+				returned_type: types.new_or_type(left.returned_type, right.returned_type),
+				warnings,
+				special: None,
+				found_dependent_argument: left.found_dependent_argument
+					|| right.found_dependent_argument,
+			})
+		}
 		Logical::Implies { on, antecedent } => call_logical(
 			*on,
 			types,
@@ -243,27 +315,36 @@ fn call_logical<E: CallCheckingBehavior>(
 	}
 }
 
+/// Returns the overloads that could be called, in order. Empty if `on` is not callable
 fn get_logical_callable_from_type(
 	on: TypeId,
+	is_new: bool,
+	environment: &Environment,
 	types: &TypeStore,
-) -> Option<Logical<(FunctionId, ThisValue)>> {
+) -> Vec<Logical<(FunctionId, ThisValue)>> {
 	match types.get_type_by_id(on) {
 		Type::And(_, _) => todo!(),
 		Type::Or(left, right) => {
-			if let (Some(left), Some(right)) = (
-				get_logical_callable_from_type(*left, types),
-				get_logical_callable_from_type(*right, types),
-			) {
-				Some(Logical::Or { left: Box::new(left), right: Box::new(right) })
-			} else {
-				None
-			}
+			// Every pairing of the overloads on either side. Empty if either side is not callable
+			let left = get_logical_callable_from_type(*left, is_new, environment, types);
+			let right = get_logical_callable_from_type(*right, is_new, environment, types);
+			left.iter()
+				.flat_map(|left| {
+					right.iter().map(|right| Logical::Or {
+						left: Box::new(left.clone()),
+						right: Box::new(right.clone()),
+					})
+				})
+				.collect()
 		}
 		Type::Constructor(Constructor::StructureGenerics(generic)) => {
-			get_logical_callable_from_type(generic.on, types).map(|res| Logical::Implies {
-				on: Box::new(res),
-				antecedent: generic.arguments.clone(),
-			})
+			get_logical_callable_from_type(generic.on, is_new, environment, types)
+				.into_iter()
+				.map(|res| Logical::Implies {
+					on: Box::new(res),
+					antecedent: generic.arguments.clone(),
+				})
+				.collect()
 		}
 		Type::RootPolyType(_) | Type::Constructor(_) => todo!(),
 
@@ -271,12 +352,18 @@ fn get_logical_callable_from_type(
 			if parameters.is_some() {
 				todo!()
 			}
-			get_logical_callable_from_type(*to, types)
+			get_logical_callable_from_type(*to, is_new, environment, types)
 		}
-		Type::Interface { .. } | Type::Constant(_) | Type::Object(_) => None,
-		Type::Function(f, t) => Some(Logical::Pure((*f, *t))),
+		// Call and construct signatures
+		Type::Interface { .. } | Type::Object(_) => environment
+			.get_signatures(on, is_new)
+			.into_iter()
+			.map(|f| Logical::Pure((f, ThisValue::Passed(on))))
+			.collect(),
+		Type::Constant(_) => Vec::new(),
+		Type::Function(f, t) => vec![Logical::Pure((*f, *t))],
 		// TODO not sure about `on`... also #98
-		Type::FunctionReference(f) => Some(Logical::Pure((*f, ThisValue::Passed(on)))),
+		Type::FunctionReference(f) => vec![Logical::Pure((*f, ThisValue::Passed(on)))],
 		Type::SpecialObject(_) => todo!(),
 	}
 }
@@ -422,6 +509,7 @@ pub enum FunctionCallingError {
 		argument_position: SpanWithSource,
 		parameter_position: SpanWithSource,
 		restriction: Option<(SpanWithSource, TypeStringRepresentation)>,
+		mismatch: NonEqualityRepresentation,
 	},
 	MissingArgument {
 		parameter_position: SpanWithSource,
@@ -454,6 +542,12 @@ pub enum FunctionCallingError {
 		assignment_position: SpanWithSource,
 		/// Should be set
 		call_site: Option<SpanWithSource>,
+		mismatch: NonEqualityRepresentation,
+	},
+	SetPropertyNotWriteable {
+		assignment_position: SpanWithSource,
+		/// Should be set
+		call_site: Option<SpanWithSource>,
 	},
 }
 
@@ -687,7 +781,12 @@ impl FunctionType {
 							vec![(value_of_this, source_map::Nullable::NULL, 0)],
 						);
 					}
-					CalledWithNew::SpecialSuperCall { this_type: _ } => todo!(),
+					CalledWithNew::SpecialSuperCall { this_type } => {
+						seeding_context.type_arguments.insert(
+							free_this_id,
+							vec![(this_type, source_map::Nullable::NULL, 0)],
+						);
+					}
 					CalledWithNew::None => {
 						// TODO
 						let value_of_this = this_value.get(environment, types, &call_site);
@@ -699,11 +798,26 @@ impl FunctionType {
 					}
 				}
 			}
-			FunctionBehavior::Constructor { non_super_prototype: _, this_object_type: _ } => {
+			FunctionBehavior::Constructor { non_super_prototype: _, this_object_type } => {
+				match called_with_new {
+					CalledWithNew::None => {
+						errors
+							.errors
+							.push(FunctionCallingError::NeedsToBeCalledWithNewKeyword(call_site));
+					}
+					// Rather than creating an object, properties are set on the `this` of the derived class
+					CalledWithNew::SpecialSuperCall { this_type } => {
+						seeding_context.type_arguments.insert(
+							this_object_type,
+							vec![(this_type, source_map::Nullable::NULL, 0)],
+						);
+					}
+					CalledWithNew::New { .. } => {}
+				}
+			}
+			FunctionBehavior::ConstructorSignature => {
 				if let CalledWithNew::None = called_with_new {
-					errors
-						.errors
-						.push(FunctionCallingError::NeedsToBeCalledWithNewKeyword(call_site));
+					errors.errors.push(FunctionCallingError::NeedsToBeCalledWithNewKeyword(call_site));
 				}
 			}
 		}
@@ -711,18 +825,8 @@ impl FunctionType {
 		{
 			let import_new_argument = match called_with_new {
 				CalledWithNew::New { on } => on,
-				CalledWithNew::SpecialSuperCall { .. } => {
-					todo!()
-					// let ty = this_value.0;
-					// let on = crate::types::printing::print_type(
-					// 	ty,
-					// 	types,
-					// 	&environment.as_general_context(),
-					// 	true,
-					// );
-					// crate::utils::notify!("This argument {}", on);
-					// ty
-				}
+				// TODO should be the `new.target` of the derived constructor
+				CalledWithNew::SpecialSuperCall { this_type } => this_type,
 				// In spec == undefined
 				CalledWithNew::None => TypeId::UNDEFINED_TYPE,
 			};
@@ -768,7 +872,7 @@ impl FunctionType {
 					let result =
 						type_is_subtype(*restriction, value, &mut behavior, environment, types);
 
-					if let SubTypeResult::IsNotSubType(_err) = result {
+					if let SubTypeResult::IsNotSubType(mismatch) = result {
 						let argument_type = TypeStringRepresentation::from_type_id(
 							value,
 							&environment.as_general_context(),
@@ -797,6 +901,8 @@ impl FunctionType {
 							parameter_type,
 							parameter_position: synthesised_parameter.position,
 							restriction,
+							mismatch: mismatch
+								.into_representation(&environment.as_general_context(), types),
 						});
 					}
 				}
@@ -820,6 +926,9 @@ impl FunctionType {
 			return Err(errors.errors);
 		}
 
+		let is_generator = self.behavior.is_generator();
+		let mut generator_yielded = None;
+
 		// Evaluate effects directly into environment
 		let early_return = behavior.new_function_context(self.id, |target| {
 			type_arguments.closure_id = if self.closed_over_variables.is_empty() {
@@ -831,6 +940,11 @@ impl FunctionType {
 
 			let mut return_result = None;
 
+			// Generator bodies run as they are iterated. Here that is just to find what they yield
+			if is_generator {
+				target.start_generator();
+			}
+
 			// Apply events here
 			for event in self.effects.clone() {
 				let current_errors = errors.errors.len();
@@ -851,6 +965,10 @@ impl FunctionType {
 					} else if let FunctionCallingError::SetPropertyConstraint {
 						call_site: ref mut c,
 						..
+					}
+					| FunctionCallingError::SetPropertyNotWriteable {
+						call_site: ref mut c,
+						..
 					} = d
 					{
 						*c = Some(call_site);
@@ -863,6 +981,10 @@ impl FunctionType {
 				}
 			}
 
+			if is_generator {
+				generator_yielded = target.end_generator(environment);
+			}
+
 			if let Some(closure_id) = type_arguments.closure_id {
 				crate::utils::notify!("Setting closure variables");
 
@@ -922,22 +1044,39 @@ impl FunctionType {
 						found_dependent_argument: false,
 					});
 				}
+				// Returns the return type (below)
+				FunctionBehavior::ConstructorSignature => {}
 			}
 		}
 
-		let returned_type = if let Some(early_return) = early_return {
-			match early_return {
+		let is_async = self.behavior.is_async();
+		let returned_type = if is_generator {
+			let of = substitute(self.return_type, &mut type_arguments, environment, types);
+			types.register_type(Type::SpecialObject(SpecialObjects::Generator {
+				of,
+				yielded: generator_yielded,
+			}))
+		} else if let Some(early_return) = early_return {
+			let returned = match early_return {
 				FinalEvent::Break { .. } | FinalEvent::Continue { .. } => {
 					unreachable!("function ended on continue / break")
 				}
 				FinalEvent::Throw { thrown: value, position } => {
-					behavior.get_latest_facts(environment).throw_value_in_facts(value, position);
+					// In `async` functions this rejects the promise rather than throwing here
+					if !is_async {
+						behavior.get_latest_facts(environment).throw_value_in_facts(value, position);
+					}
 					TypeId::NEVER_TYPE
 				}
 				FinalEvent::Return { returned, returned_position: _ } => {
 					// set events should cover property specialisation here:
 					returned
 				}
+			};
+			if is_async {
+				crate::features::promises::new_promise_type(returned, types)
+			} else {
+				returned
 			}
 		} else {
 			crate::utils::notify!("Substituting return type (no return)");
@@ -992,7 +1131,7 @@ impl FunctionType {
 						types,
 					);
 
-					if let SubTypeResult::IsNotSubType(_reasons) = result {
+					if let SubTypeResult::IsNotSubType(mismatch) = result {
 						errors.errors.push(FunctionCallingError::InvalidArgumentType {
 							parameter_type: TypeStringRepresentation::from_type_id(
 								parameter.ty,
@@ -1009,6 +1148,8 @@ impl FunctionType {
 							parameter_position: parameter.position,
 							argument_position: *position,
 							restriction: None,
+							mismatch: mismatch
+								.into_representation(&environment.as_general_context(), types),
 						});
 					}
 				} else {
@@ -1064,7 +1205,7 @@ impl FunctionType {
 							types,
 						);
 
-						if let SubTypeResult::IsNotSubType(_reasons) = result {
+						if let SubTypeResult::IsNotSubType(mismatch) = result {
 							errors.errors.push(FunctionCallingError::InvalidArgumentType {
 								parameter_type: TypeStringRepresentation::from_type_id(
 									rest_parameter.item_type,
@@ -1081,6 +1222,8 @@ impl FunctionType {
 								argument_position: argument.position,
 								parameter_position: rest_parameter.position,
 								restriction: None,
+								mismatch: mismatch
+									.into_representation(&environment.as_general_context(), types),
 							});
 						}
 					}
@@ -54,6 +54,7 @@ fn _register_properties_into_store<T: crate::ReadFromFS, A: crate::ASTImplementa
 		type_of_super: Some(TypeId::ERROR_TYPE),
 		// TODO get from above
 		this_object_type: TypeId::ERROR_TYPE,
+		class_prototype,
 	});
 
 	let ((), result, _) = environment.new_lexical_environment_fold_into_parent(
@@ -3,13 +3,17 @@ use std::collections::HashMap;
 use source_map::SpanWithSource;
 
 use crate::{
-	context::{environment::FunctionScope, ContextType},
+	context::{environment::FunctionScope, invocation::CheckThings, ContextType},
 	events::{Event, RootReference},
-	features::functions::{ClassPropertiesToRegister, FunctionBehavior},
+	features::functions::{ClassPropertiesToRegister, FunctionBehavior, ThisValue},
 	CheckingData, Facts, FunctionId, GenericTypeParameters, Scope, Type, TypeId,
 };
 
-use super::{classes::register_properties_into_environment, TypeStore};
+use super::{
+	calling::{call_type, CalledWithNew, CallingInput},
+	classes::register_properties_into_environment,
+	TypeStore,
+};
 
 /// This is a mesh of annotation and actually defined functions
 #[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
@@ -47,18 +51,39 @@ impl FunctionType {
 		A: crate::ASTImplementation,
 		S: ContextType,
 	>(
+		id: FunctionId,
 		class_prototype: TypeId,
+		extends: Option<TypeId>,
 		properties: ClassPropertiesToRegister<A>,
 		// TODO S overkill
 		context: &mut crate::context::Context<S>,
 		checking_data: &mut CheckingData<T, A>,
 	) -> Self {
 		let scope = Scope::Function(FunctionScope::Constructor {
-			extends: false,
-			type_of_super: None,
+			extends: extends.is_some(),
+			type_of_super: extends,
 			this_object_type: TypeId::ERROR_TYPE,
+			class_prototype,
 		});
 
+		// Derived classes without a constructor take the parameters of the class they extend
+		let mut parameters = extends
+			.and_then(|extends| {
+				if let Type::Function(id, _) | Type::FunctionReference(id) =
+					checking_data.types.get_type_by_id(extends)
+				{
+					Some(checking_data.types.get_function_from_id(*id).parameters.clone())
+				} else {
+					None
+				}
+			})
+			.unwrap_or_default();
+		for parameter in &mut parameters.parameters {
+			let constraint =
+				super::get_constraint(parameter.ty, &checking_data.types).unwrap_or(parameter.ty);
+			parameter.ty = checking_data.types.new_function_parameter(constraint);
+		}
+
 		let (on, env_data, _) = context.new_lexical_environment_fold_into_parent(
 			scope,
 			checking_data,
@@ -76,6 +101,33 @@ impl FunctionType {
 					*this_object_type = on;
 				}
 
+				// Equivalent to `constructor(...args) { super(...args) }`
+				if let Some(extends) = extends {
+					let arguments = parameters
+						.parameters
+						.iter()
+						.map(|parameter| SynthesisedArgument {
+							spread: false,
+							value: parameter.ty,
+							position: parameter.position,
+						})
+						.collect();
+					let input = CallingInput {
+						called_with_new: CalledWithNew::SpecialSuperCall { this_type: on },
+						this_value: ThisValue::Passed(on),
+						call_site_type_arguments: None,
+						call_site: source_map::Nullable::NULL,
+					};
+					let _ = call_type(
+						extends,
+						arguments,
+						input,
+						environment,
+						&mut CheckThings,
+						&mut checking_data.types,
+					);
+				}
+
 				register_properties_into_environment(environment, on, checking_data, properties);
 				on
 			},
@@ -87,10 +139,10 @@ impl FunctionType {
 
 		let (facts, _free_variables) = env_data.unwrap();
 		Self {
-			id: crate::FunctionId::AUTO_CONSTRUCTOR,
+			id,
 			constant_function: None,
 			type_parameters: None,
-			parameters: SynthesisedParameters::default(),
+			parameters,
 			// Only needed for printing
 			return_type: on,
 			effects: facts.events,
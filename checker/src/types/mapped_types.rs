@@ -0,0 +1,178 @@
+//! Mapped types, `{ [P in keyof T]?: T[P] }`
+
+use source_map::Nullable;
+
+use crate::{
+	context::facts::Publicity,
+	types::{
+		get_constraint,
+		poly_types::generic_type_arguments::TypeArgumentStore,
+		properties::{get_property_keys, PropertyKey, PropertyModifiers, PropertyValue},
+		substitute, Constant, Constructor, ObjectNature, PolyNature, Type, TypeId, TypeOperator,
+		TypeStore,
+	},
+	Environment,
+};
+
+/// `{ [P in K as N]: V }` where the keys (`K`) are not yet known
+#[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
+pub struct MappedType {
+	/// `P`. Its constraint is the keys `K`
+	pub parameter: TypeId,
+	/// From `as N`
+	pub as_type: Option<TypeId>,
+	pub value: TypeId,
+	pub optional: MappedModifier,
+	pub readonly: MappedModifier,
+}
+
+/// How a mapped type changes a modifier of the properties it maps over
+#[derive(Clone, Copy, Debug, PartialEq, Eq, binary_serialize_derive::BinarySerializable)]
+pub enum MappedModifier {
+	/// Same as the property being mapped over (if `K` is `keyof T`)
+	Preserve,
+	/// `?` and `readonly`
+	Add,
+	/// `-?`
+	Remove,
+}
+
+impl MappedModifier {
+	#[must_use]
+	pub fn apply(self, existing: bool) -> bool {
+		match self {
+			MappedModifier::Preserve => existing,
+			MappedModifier::Add => true,
+			MappedModifier::Remove => false,
+		}
+	}
+}
+
+/// The keys to map over, [`None`] if not yet known
+pub(crate) fn get_mapped_keys(
+	keys: TypeId,
+	environment: &Environment,
+	types: &mut TypeStore,
+) -> Option<Vec<TypeId>> {
+	match types.get_type_by_id(keys) {
+		// Interfaces keep `keyof` deferred, but can be read here. If there are no properties, the
+		// interface body might not have been synthesised yet
+		Type::Constructor(Constructor::TypeOperator(TypeOperator::KeyOf(on))) => {
+			let on = *on;
+			if types.get_type_by_id(on).is_dependent() {
+				return None;
+			}
+			let keys = get_property_keys(on, environment, types).filter(|keys| !keys.is_empty())?;
+			Some(
+				keys.into_iter()
+					.map(|key| match key {
+						PropertyKey::String(key) => {
+							types.new_constant_type(Constant::String(key.into_owned()))
+						}
+						PropertyKey::Type(key) => key,
+					})
+					.collect(),
+			)
+		}
+		Type::Or(left, right) => {
+			let (left, right) = (*left, *right);
+			let mut keys = get_mapped_keys(left, environment, types)?;
+			keys.extend(get_mapped_keys(right, environment, types)?);
+			Some(keys)
+		}
+		ty if ty.is_dependent() => None,
+		_ if keys == TypeId::NEVER_TYPE => Some(Vec::new()),
+		_ => Some(vec![keys]),
+	}
+}
+
+/// For `keyof T` (or a parameter constrained to `keyof T`), `T`. Modifiers are kept from its properties
+fn get_mapped_source(keys: TypeId, types: &TypeStore) -> Option<TypeId> {
+	match types.get_type_by_id(keys) {
+		Type::Constructor(Constructor::TypeOperator(TypeOperator::KeyOf(on))) => Some(*on),
+		Type::RootPolyType(PolyNature::Generic { eager_fixed, .. }) => {
+			if let Type::Constructor(Constructor::TypeOperator(TypeOperator::KeyOf(on))) =
+				types.get_type_by_id(*eager_fixed)
+			{
+				Some(*on)
+			} else {
+				None
+			}
+		}
+		_ => None,
+	}
+}
+
+/// Creates an object type if the keys are known after substitution, otherwise a new mapped type
+pub(crate) fn substitute_mapped_type(
+	mapped: &MappedType,
+	arguments: &mut impl TypeArgumentStore,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	let keys = get_constraint(mapped.parameter, types).expect("mapped type without keys");
+	let source = get_mapped_source(keys, types);
+	let keys = substitute(keys, arguments, environment, types);
+
+	let Some(keys) = get_mapped_keys(keys, environment, types) else {
+		let Type::RootPolyType(PolyNature::Generic { name, .. }) =
+			types.get_type_by_id(mapped.parameter)
+		else {
+			unreachable!("mapped type parameter")
+		};
+		let parameter = types.register_type(Type::RootPolyType(PolyNature::Generic {
+			name: name.clone(),
+			eager_fixed: keys,
+		}));
+		let mut arguments = arguments.to_structural_generic_arguments();
+		arguments.type_arguments.insert(mapped.parameter, (parameter, Nullable::NULL));
+		let as_type =
+			mapped.as_type.map(|as_type| substitute(as_type, &mut arguments, environment, types));
+		let value = substitute(mapped.value, &mut arguments, environment, types);
+		return types.register_type(Type::Constructor(Constructor::Mapped(MappedType {
+			parameter,
+			as_type,
+			value,
+			..*mapped
+		})));
+	};
+
+	let source = source.map(|source| substitute(source, arguments, environment, types));
+	let object = types.register_type(Type::Object(ObjectNature::AnonymousTypeAnnotation));
+
+	for key in keys {
+		let mut arguments = arguments.to_structural_generic_arguments();
+		arguments.type_arguments.insert(mapped.parameter, (key, Nullable::NULL));
+
+		let name = match mapped.as_type {
+			Some(as_type) => substitute(as_type, &mut arguments, environment, types),
+			None => key,
+		};
+		// `as never` removes the property
+		if name == TypeId::NEVER_TYPE {
+			continue;
+		}
+
+		let value = substitute(mapped.value, &mut arguments, environment, types);
+		let existing = source.map_or_else(PropertyModifiers::default, |source| {
+			environment.get_property_modifiers(source, &PropertyKey::from_type(key, types))
+		});
+		let modifiers = PropertyModifiers {
+			optional: mapped.optional.apply(existing.optional),
+			readonly: mapped.readonly.apply(existing.readonly),
+		};
+
+		let under = PropertyKey::from_type(name, types);
+		environment.facts.register_property(
+			object,
+			Publicity::Public,
+			under.clone(),
+			PropertyValue::Value(value),
+			false,
+			None,
+		);
+		environment.facts.set_property_modifiers(object, under, modifiers);
+	}
+
+	object
+}
@@ -2,6 +2,7 @@ pub mod calling;
 pub mod casts;
 pub mod classes;
 pub mod functions;
+pub mod mapped_types;
 pub mod others;
 pub mod poly_types;
 pub mod printing;
@@ -88,7 +89,22 @@ impl TypeId {
 
 	pub const SYMBOL_TO_PRIMITIVE: Self = Self(21);
 
-	pub(crate) const INTERNAL_TYPE_COUNT: usize = 23;
+	/// Returned from `async` functions and unwrapped by `await`
+	pub const PROMISE_TYPE: Self = Self(23);
+
+	/// Returned from `function*` functions. `Generator<Y, R, N>`
+	pub const GENERATOR_TYPE: Self = Self(24);
+	/// Used for Generator. The yielded type
+	pub const Y_TYPE: Self = Self(25);
+	/// Used for Generator. The returned type
+	pub const R_TYPE: Self = Self(26);
+	/// Used for Generator. The type passed to `next`
+	pub const N_TYPE: Self = Self(27);
+
+	/// `string | number`. The constraint of `keyof T` where `T` is generic
+	pub const STRING_OR_NUMBER_TYPE: Self = Self(28);
+
+	pub(crate) const INTERNAL_TYPE_COUNT: usize = 29;
 }
 
 #[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
@@ -256,6 +272,7 @@ pub enum Constructor {
 	},
 	/// Might not be best place but okay.
 	StructureGenerics(StructureGenerics),
+	Mapped(mapped_types::MappedType),
 }
 
 impl Constructor {
@@ -269,7 +286,8 @@ impl Constructor {
 			| Constructor::TypeOperator(_)
 			| Constructor::TypeRelationOperator(_)
 			| Constructor::Property { .. }
-			| Constructor::StructureGenerics(_) => None,
+			| Constructor::StructureGenerics(_)
+			| Constructor::Mapped(_) => None,
 		}
 	}
 }
@@ -284,13 +302,28 @@ pub struct StructureGenerics {
 #[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
 pub enum TypeOperator {
 	PrototypeOf(TypeId),
+	/// `typeof x` where the name depends on `x`
 	PrimitiveTypeName(TypeId),
+	/// `keyof T` where `T` is dependent. Otherwise the union of keys is computed directly
+	KeyOf(TypeId),
 }
 
-/// TODO instance of?
 #[derive(Clone, Debug, binary_serialize_derive::BinarySerializable)]
 pub enum TypeRelationOperator {
-	Extends { ty: TypeId, extends: TypeId },
+	Extends {
+		ty: TypeId,
+		extends: TypeId,
+	},
+	/// `ty instanceof rhs`. `rhs` is the constructor (rather than its prototype)
+	InstanceOf {
+		ty: TypeId,
+		rhs: TypeId,
+	},
+	/// `property in ty`
+	HasProperty {
+		ty: TypeId,
+		property: PropertyKey<'static>,
+	},
 }
 
 pub(crate) fn new_logical_or_type(lhs: TypeId, rhs: TypeId, types: &mut TypeStore) -> TypeId {
@@ -346,6 +379,9 @@ pub trait SubtypeBehavior {
 		restriction_mode: bool,
 	) -> Result<(), NonEqualityReason>;
 
+	/// The value set for `parameter` so far, if any
+	fn get_type_argument(&self, parameter: TypeId) -> Option<TypeId>;
+
 	fn add_property_restrictions(&self) -> bool;
 
 	fn add_function_restriction(
@@ -377,6 +413,10 @@ impl SubtypeBehavior for SeedingContext {
 		Ok(())
 	}
 
+	fn get_type_argument(&self, parameter: TypeId) -> Option<TypeId> {
+		self.type_arguments.get(&parameter).and_then(|values| values.first()).map(|(ty, _, _)| *ty)
+	}
+
 	fn add_property_restrictions(&self) -> bool {
 		false
 	}
@@ -403,6 +443,10 @@ impl SubtypeBehavior for BasicEquality {
 		Ok(())
 	}
 
+	fn get_type_argument(&self, _parameter: TypeId) -> Option<TypeId> {
+		None
+	}
+
 	fn add_property_restrictions(&self) -> bool {
 		self.add_property_restrictions
 	}
@@ -645,15 +689,24 @@ pub(crate) fn get_constraint(on: TypeId, types: &TypeStore) -> Option<TypeId> {
 				// TODO dynamic and open poly
 				Some(result_union)
 			}
+			Constructor::TypeOperator(TypeOperator::KeyOf(_)) => {
+				Some(TypeId::STRING_OR_NUMBER_TYPE)
+			}
+			Constructor::TypeOperator(TypeOperator::PrimitiveTypeName(_)) => {
+				Some(TypeId::STRING_TYPE)
+			}
 			Constructor::TypeOperator(_) | Constructor::CanonicalRelationOperator { .. } => {
 				// TODO open poly
 				Some(TypeId::BOOLEAN_TYPE)
 			}
 			Constructor::TypeRelationOperator(op) => match op {
-				crate::types::TypeRelationOperator::Extends { .. } => Some(TypeId::BOOLEAN_TYPE),
+				crate::types::TypeRelationOperator::Extends { .. }
+				| crate::types::TypeRelationOperator::InstanceOf { .. }
+				| crate::types::TypeRelationOperator::HasProperty { .. } => Some(TypeId::BOOLEAN_TYPE),
 			},
 			// TODO sure?
 			Constructor::StructureGenerics { .. } => None,
+			Constructor::Mapped(_) => Some(TypeId::OBJECT_TYPE),
 		},
 		Type::Object(ObjectNature::RealDeal) => {
 			crate::utils::notify!("Might be missing some obj here");
@@ -663,7 +716,7 @@ pub(crate) fn get_constraint(on: TypeId, types: &TypeStore) -> Option<TypeId> {
 	}
 }
 
-fn get_larger_type(on: TypeId, types: &TypeStore) -> TypeId {
+pub(crate) fn get_larger_type(on: TypeId, types: &TypeStore) -> TypeId {
 	if let Some(poly_base) = get_constraint(on, types) {
 		poly_base
 	} else if let Type::Constant(cst) = types.get_type_by_id(on) {
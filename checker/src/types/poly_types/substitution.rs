@@ -1,16 +1,23 @@
 //! How type parameters are resolved
 
 use crate::{
+	context::{facts::Publicity, Logical},
 	features::{
 		functions::ThisValue,
+		objects::SpecialObjects,
 		operations::{
-			evaluate_equality_inequality_operation, evaluate_mathematical_operation,
-			evaluate_pure_unary_operator,
+			evaluate_equality_inequality_operation, evaluate_in_operation,
+			evaluate_instance_of_operation, evaluate_mathematical_operation,
+			evaluate_pure_unary_operator, evaluate_type_of_operation,
 		},
 	},
+	subtyping::type_extends,
 	types::{
-		get_constraint, get_larger_type, is_type_truthy_falsy, Constructor, PolyNature,
-		StructureGenerics, Type, TypeStore,
+		get_constraint, get_larger_type, is_type_truthy_falsy,
+		mapped_types::substitute_mapped_type,
+		properties::{new_key_of_type, PropertyKey},
+		Constructor, PolyNature, StructureGenerics, Type, TypeArguments, TypeOperator,
+		TypeRelationOperator, TypeStore,
 	},
 	Decidable, Environment, TypeId,
 };
@@ -59,7 +66,11 @@ pub(crate) fn substitute(
 			let rhs = substitute(rhs, arguments, environment, types);
 			types.register_type(Type::Or(lhs, rhs))
 		}
-		Type::Constant(_) | Type::AliasTo { .. } | Type::Interface { .. } => id,
+		// Generators are created with their values
+		Type::Constant(_)
+		| Type::AliasTo { .. }
+		| Type::Interface { .. }
+		| Type::SpecialObject(SpecialObjects::Generator { .. }) => id,
 		Type::RootPolyType(nature) => {
 			if let PolyNature::Open(_) = nature {
 				id
@@ -95,6 +106,7 @@ pub(crate) fn substitute(
 				}
 			}
 			Constructor::UnaryOperator { operand, operator, .. } => {
+				let operand = substitute(operand, arguments, environment, types);
 				match evaluate_pure_unary_operator(
 					operator, operand, types,
 					// Restrictions should have been made ahead of time
@@ -114,6 +126,37 @@ pub(crate) fn substitute(
 				else_result,
 				result_union: _,
 			} => {
+				if let Type::Constructor(Constructor::TypeRelationOperator(
+					TypeRelationOperator::Extends { ty, extends },
+				)) = types.get_type_by_id(condition)
+				{
+					let (ty, extends) = (*ty, *extends);
+					let check_type = substitute(ty, arguments, environment, types);
+					return if types.get_type_by_id(check_type).is_dependent() {
+						// Still deferred
+						let extends = substitute(extends, arguments, environment, types);
+						let truthy_result =
+							substitute(truthy_result, arguments, environment, types);
+						let else_result = substitute(else_result, arguments, environment, types);
+						types.new_conditional_extends_type(
+							check_type,
+							extends,
+							truthy_result,
+							else_result,
+						)
+					} else {
+						substitute_extends_conditional(
+							ty,
+							check_type,
+							extends,
+							(truthy_result, else_result),
+							arguments,
+							environment,
+							types,
+						)
+					};
+				}
+
 				let condition = substitute(condition, arguments, environment, types);
 
 				// crate::utils::notify!(
@@ -195,13 +238,54 @@ pub(crate) fn substitute(
 						}))
 					}
 				} else {
-					todo!(
-						"Constructor::Property ({:?}[{:?}]) should be covered by events",
-						on,
-						under
+					// From type annotations, e.g. `T[K]`. Other property reads are covered by events
+					let on = substitute(on, arguments, environment, types);
+					let under = match under {
+						PropertyKey::Type(under) => {
+							let under = substitute(under, arguments, environment, types);
+							PropertyKey::from_type(under, types)
+						}
+						under @ PropertyKey::String(_) => under,
+					};
+					let under_is_dependent = matches!(
+						under,
+						PropertyKey::Type(under) if types.get_type_by_id(under).is_dependent()
 					);
+
+					if types.get_type_by_id(on).is_dependent() || under_is_dependent {
+						let result = substitute(result, arguments, environment, types);
+						types.register_type(Type::Constructor(Constructor::Property {
+							on,
+							under,
+							result,
+							bind_this,
+						}))
+					} else {
+						match environment.get_property_unbound(on, Publicity::Public, under, types)
+						{
+							Some(Logical::Pure(value)) => value.as_get_type(),
+							Some(Logical::Implies { on: value, mut antecedent }) => {
+								if let Logical::Pure(value) = *value {
+									substitute(
+										value.as_get_type(),
+										&mut antecedent,
+										environment,
+										types,
+									)
+								} else {
+									substitute(result, arguments, environment, types)
+								}
+							}
+							Some(Logical::Or { .. }) | None => {
+								substitute(result, arguments, environment, types)
+							}
+						}
+					}
 				}
 			}
+			Constructor::Mapped(mapped) => {
+				substitute_mapped_type(&mapped, arguments, environment, types)
+			}
 			Constructor::Image { .. } => {
 				todo!("Constructor::Image {id:?} should be covered by events");
 				// id
@@ -288,6 +372,14 @@ pub(crate) fn substitute(
 					}
 				}
 			}
+			Constructor::TypeOperator(TypeOperator::KeyOf(on)) => {
+				let on = substitute(on, arguments, environment, types);
+				new_key_of_type(on, environment, types)
+			}
+			Constructor::TypeOperator(TypeOperator::PrimitiveTypeName(on)) => {
+				let on = substitute(on, arguments, environment, types);
+				evaluate_type_of_operation(on, types)
+			}
 			Constructor::TypeOperator(..) => todo!(),
 			Constructor::TypeRelationOperator(op) => match op {
 				crate::types::TypeRelationOperator::Extends { ty, extends } => {
@@ -332,12 +424,79 @@ pub(crate) fn substitute(
 					// 	TypeId::FALSE
 					// }
 				}
+				crate::types::TypeRelationOperator::InstanceOf { ty, rhs } => {
+					let ty = substitute(ty, arguments, environment, types);
+					let rhs = substitute(rhs, arguments, environment, types);
+					evaluate_instance_of_operation(ty, rhs, environment, types)
+				}
+				crate::types::TypeRelationOperator::HasProperty { ty, property } => {
+					let ty = substitute(ty, arguments, environment, types);
+					let property = match property {
+						PropertyKey::Type(key) => {
+							let key = substitute(key, arguments, environment, types);
+							PropertyKey::from_type(key, types)
+						}
+						property @ PropertyKey::String(_) => property,
+					};
+					evaluate_in_operation(property, ty, environment, types)
+				}
 			},
 		},
 		Type::SpecialObject(_) => todo!(),
 	}
 }
 
+/// `check_type extends extends ? truthy_result : else_result` where `check_type` is known. `ty` is
+/// the check type before substitution, if it is a type parameter the condition distributes over
+/// unions. `infer` types found in `extends` are available in the truthy result
+fn substitute_extends_conditional(
+	ty: TypeId,
+	check_type: TypeId,
+	extends: TypeId,
+	(truthy_result, else_result): (TypeId, TypeId),
+	arguments: &mut impl TypeArgumentStore,
+	environment: &mut Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	let is_type_parameter = matches!(types.get_type_by_id(ty), Type::RootPolyType(_));
+	if let (true, Type::Or(left, right)) = (is_type_parameter, types.get_type_by_id(check_type)) {
+		let (left, right) = (*left, *right);
+		let results = [left, right].map(|member| {
+			substitute_extends_conditional(
+				ty,
+				member,
+				extends,
+				(truthy_result, else_result),
+				arguments,
+				environment,
+				types,
+			)
+		});
+		return match results {
+			[TypeId::NEVER_TYPE, result] | [result, TypeId::NEVER_TYPE] => result,
+			[left, right] => types.new_or_type(left, right),
+		};
+	}
+
+	let extends = substitute(extends, arguments, environment, types);
+	let (result, inferred) = match type_extends(check_type, extends, environment, types) {
+		Some(inferred) => (truthy_result, inferred),
+		None => (else_result, TypeArguments::new()),
+	};
+
+	if inferred.is_empty() && !is_type_parameter {
+		substitute(result, arguments, environment, types)
+	} else {
+		// The check type is narrowed to the member
+		let mut arguments = arguments.to_structural_generic_arguments();
+		if is_type_parameter {
+			arguments.type_arguments.insert(ty, (check_type, source_map::Nullable::NULL));
+		}
+		arguments.type_arguments.extend(inferred);
+		substitute(result, &mut arguments, environment, types)
+	}
+}
+
 pub(crate) fn curry_arguments(
 	arguments: &impl TypeArgumentStore,
 	types: &mut TypeStore,
@@ -5,8 +5,13 @@ use super::{properties::PropertyKey, PolyNature, Type, TypeArguments, TypeId, Ty
 use crate::{
 	context::{facts::Publicity, get_on_ctx, Logical},
 	events::{Event, FinalEvent},
+	features::functions::FunctionBehavior,
 	features::objects::SpecialObjects,
-	types::{get_constraint, Constructor, StructureGenerics, TypeRelationOperator},
+	types::{
+		get_constraint,
+		mapped_types::{MappedModifier, MappedType},
+		Constructor, StructureGenerics, TypeOperator, TypeRelationOperator,
+	},
 	Constant, GeneralContext, PropertyValue,
 };
 
@@ -150,6 +155,32 @@ fn print_type_into_buf(
 					buf.push('>');
 				}
 			}
+			Constructor::TypeOperator(TypeOperator::KeyOf(on)) => {
+				buf.push_str("keyof ");
+				print_type_into_buf(*on, buf, cycles, args, types, ctx, debug);
+			}
+			Constructor::Mapped(MappedType { parameter, as_type, value, optional, readonly }) => {
+				buf.push_str("{ ");
+				if let MappedModifier::Add = readonly {
+					buf.push_str("readonly ");
+				}
+				buf.push('[');
+				print_type_into_buf(*parameter, buf, cycles, args, types, ctx, debug);
+				buf.push_str(" in ");
+				let keys = get_constraint(*parameter, types).unwrap();
+				print_type_into_buf(keys, buf, cycles, args, types, ctx, debug);
+				if let Some(as_type) = as_type {
+					buf.push_str(" as ");
+					print_type_into_buf(*as_type, buf, cycles, args, types, ctx, debug);
+				}
+				buf.push_str(match optional {
+					MappedModifier::Preserve => "]: ",
+					MappedModifier::Add => "]?: ",
+					MappedModifier::Remove => "]-?: ",
+				});
+				print_type_into_buf(*value, buf, cycles, args, types, ctx, debug);
+				buf.push_str(" }");
+			}
 			constructor if debug => match constructor {
 				Constructor::BinaryOperator { lhs, operator, rhs } => {
 					print_type_into_buf(*lhs, buf, cycles, args, types, ctx, debug);
@@ -183,6 +214,19 @@ fn print_type_into_buf(
 					buf.push_str(" extends ");
 					print_type_into_buf(*extends, buf, cycles, args, types, ctx, debug);
 				}
+				Constructor::TypeRelationOperator(TypeRelationOperator::InstanceOf { ty, rhs }) => {
+					print_type_into_buf(*ty, buf, cycles, args, types, ctx, debug);
+					buf.push_str(" instanceof ");
+					print_type_into_buf(*rhs, buf, cycles, args, types, ctx, debug);
+				}
+				Constructor::TypeRelationOperator(TypeRelationOperator::HasProperty {
+					ty,
+					property,
+				}) => {
+					print_property_key_into_buf(buf, property, cycles, args, types, ctx, debug);
+					buf.push_str(" in ");
+					print_type_into_buf(*ty, buf, cycles, args, types, ctx, debug);
+				}
 				Constructor::Image { on: _, with: _, result } => {
 					buf.write_fmt(format_args!("[func result {}] ", id.0)).unwrap();
 					// TODO arguments
@@ -194,7 +238,9 @@ fn print_type_into_buf(
 					print_property_key_into_buf(buf, under, cycles, args, types, ctx, debug);
 					buf.push(']');
 				}
-				Constructor::StructureGenerics { .. } | Constructor::ConditionalResult { .. } => {
+				Constructor::StructureGenerics { .. }
+				| Constructor::ConditionalResult { .. }
+				| Constructor::Mapped(_) => {
 					unreachable!()
 				}
 			},
@@ -258,6 +304,11 @@ fn print_type_into_buf(
 				)
 				.unwrap();
 			}
+			if let FunctionBehavior::Constructor { .. } | FunctionBehavior::ConstructorSignature =
+				func.behavior
+			{
+				buf.push_str("new ");
+			}
 			if let Some(ref parameters) = func.type_parameters {
 				buf.push('<');
 				for (not_at_end, param) in parameters.0.iter().nendiate() {
@@ -325,11 +376,15 @@ fn print_type_into_buf(
 			buf.push_str("{ ");
 			let properties = get_on_ctx!(ctx.get_properties_on_type(id));
 			for (not_at_end, (publicity, key, value)) in properties.into_iter().nendiate() {
+				let modifiers = get_on_ctx!(ctx.get_property_modifiers(id, &key));
+				if modifiers.readonly {
+					buf.push_str("readonly ");
+				}
 				if let Publicity::Private = publicity {
 					buf.push('#');
 				}
 				print_property_key_into_buf(buf, &key, cycles, args, types, ctx, debug);
-				buf.push_str(": ");
+				buf.push_str(if modifiers.optional { "?: " } else { ": " });
 				print_type_into_buf(value, buf, cycles, args, types, ctx, debug);
 				if not_at_end {
 					buf.push_str(", ");
@@ -339,7 +394,9 @@ fn print_type_into_buf(
 		}
 		Type::SpecialObject(special_object) => match special_object {
 			SpecialObjects::Promise { events: () } => todo!(),
-			SpecialObjects::Generator { position: () } => todo!(),
+			SpecialObjects::Generator { of, yielded: _ } => {
+				print_type_into_buf(*of, buf, cycles, args, types, ctx, debug);
+			}
 			SpecialObjects::Proxy { handler, over } => {
 				// Copies from node behavior
 				buf.push_str("Proxy [ ");
@@ -574,6 +631,15 @@ pub fn debug_effects(
 				debug_effects(buf, iterate_over, types, ctx, debug);
 				buf.push_str("end");
 			}
+			Event::Deferred { events, position: _ } => {
+				buf.push_str("after await\n");
+				debug_effects(buf, events, types, ctx, debug);
+				buf.push_str("end");
+			}
+			Event::Yield { yielded, delegated, position: _ } => {
+				buf.push_str(if *delegated { "yield* " } else { "yield " });
+				print_type_into_buf(*yielded, buf, &mut HashSet::new(), None, types, ctx, debug);
+			}
 			Event::FinalEvent(FinalEvent::Throw { thrown, .. }) => {
 				buf.push_str("throw ");
 				print_type_into_buf(*thrown, buf, &mut HashSet::new(), None, types, ctx, debug);
@@ -16,6 +16,8 @@ use crate::{
 
 use source_map::{SourceId, SpanWithSource};
 
+use crate::serialization::BinarySerializable;
+
 use super::{calling::CalledWithNew, Constructor, Type, TypeStore};
 
 pub enum PropertyKind {
@@ -71,13 +73,29 @@ impl<'a> PropertyKey<'a> {
 	}
 }
 
-impl crate::serialization::BinarySerializable for PropertyKey<'static> {
-	fn serialize(self, _buf: &mut Vec<u8>) {
-		todo!()
+impl BinarySerializable for PropertyKey<'static> {
+	fn serialize(self, buf: &mut Vec<u8>) {
+		match self {
+			PropertyKey::String(s) => {
+				buf.push(0);
+				s.into_owned().serialize(buf);
+			}
+			PropertyKey::Type(t) => {
+				buf.push(1);
+				t.serialize(buf);
+			}
+		}
 	}
 
-	fn deserialize<I: Iterator<Item = u8>>(_iter: &mut I, _source: SourceId) -> Self {
-		todo!()
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: SourceId,
+	) -> Result<Self, crate::DeserializeError> {
+		match u8::deserialize(iter, source)? {
+			0 => Ok(Self::String(Cow::Owned(String::deserialize(iter, source)?))),
+			1 => Ok(Self::Type(TypeId::deserialize(iter, source)?)),
+			_ => Err(crate::DeserializeError::InvalidDiscriminant),
+		}
 	}
 }
 
@@ -95,6 +113,15 @@ impl<'a> PropertyKey<'a> {
 	}
 }
 
+/// Modifiers of a property in a type annotation, `a?: T` and `readonly a: T`
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, binary_serialize_derive::BinarySerializable,
+)]
+pub struct PropertyModifiers {
+	pub optional: bool,
+	pub readonly: bool,
+}
+
 /// TODO type predicate based
 ///
 /// TODO getter, setting need a closure id
@@ -432,12 +459,25 @@ fn evaluate_get_on_poly<E: CallCheckingBehavior>(
 					environment,
 					types,
 				);
-				let right =
-					resolve_logical_with_poly(*right, on, under, arguments, environment, types);
+				let right = resolve_logical_with_poly(
+					*right,
+					on,
+					under.clone(),
+					arguments,
+					environment,
+					types,
+				);
 
 				if let (Some(lhs), Some(rhs)) = (left, right) {
 					crate::utils::notify!("TODO how does conditionality work");
-					Some(types.new_or_type(lhs, rhs))
+					let result = types.new_or_type(lhs, rhs);
+					// Which one depends on `on`
+					Some(types.register_type(Type::Constructor(Constructor::Property {
+						on,
+						under: under.into_owned(),
+						result,
+						bind_this: true,
+					})))
 				} else {
 					crate::utils::notify!("TODO emit some diagnostic about missing");
 					None
@@ -491,15 +531,19 @@ pub(crate) fn set_property<E: CallCheckingBehavior>(
 	types: &TypeStore,
 	setter_position: Option<SpanWithSource>,
 ) -> Result<Option<TypeId>, SetPropertyError> {
-	// TODO
-	// if environment.is_not_writeable(on, under) {
-	// 	return Err(SetPropertyError::NotWriteable);
-	// }
+	// TODO per property `writable`
+	if environment.is_frozen(on) == Some(TypeId::TRUE) {
+		return Err(SetPropertyError::NotWriteable);
+	}
 
 	// if E::CHECK_PARAMETERS {
 	let object_constraint = environment.get_object_constraints(on);
 
 	for constraint in object_constraint {
+		if environment.get_property_modifiers(constraint, under).readonly {
+			return Err(SetPropertyError::NotWriteable);
+		}
+
 		let property_constraint =
 			environment.get_property_unbound(constraint, publicity, under.clone(), types);
 
@@ -660,3 +704,79 @@ pub(crate) fn set_property<E: CallCheckingBehavior>(
 	}
 	Ok(None)
 }
+
+/// Keys of public properties on `on`. [`None`] if `on` is dependent
+pub(crate) fn get_property_keys(
+	on: TypeId,
+	environment: &Environment,
+	types: &TypeStore,
+) -> Option<Vec<PropertyKey<'static>>> {
+	match types.get_type_by_id(on) {
+		Type::AliasTo { to, parameters: None, .. } => get_property_keys(*to, environment, types),
+		Type::Constructor(Constructor::StructureGenerics(StructureGenerics { on, .. })) => {
+			get_property_keys(*on, environment, types)
+		}
+		// Keys of either
+		Type::And(left, right) => {
+			let mut keys = get_property_keys(*left, environment, types)?;
+			for key in get_property_keys(*right, environment, types)? {
+				if !keys.contains(&key) {
+					keys.push(key);
+				}
+			}
+			Some(keys)
+		}
+		// Keys on both
+		Type::Or(left, right) => {
+			let right = get_property_keys(*right, environment, types)?;
+			let mut keys = get_property_keys(*left, environment, types)?;
+			keys.retain(|key| right.contains(key));
+			Some(keys)
+		}
+		ty if ty.is_dependent() => None,
+		_ => Some(
+			environment
+				.get_properties_on_type(on)
+				.into_iter()
+				.filter_map(|(publicity, key, _)| {
+					matches!(publicity, Publicity::Public).then_some(key)
+				})
+				.collect(),
+		),
+	}
+}
+
+/// `keyof on`. The union of keys, or [`super::TypeOperator::KeyOf`] if `on` is dependent or an
+/// interface (which can have properties added after this, by hoisting or declaration merging)
+pub(crate) fn new_key_of_type(
+	on: TypeId,
+	environment: &Environment,
+	types: &mut TypeStore,
+) -> TypeId {
+	// Anything can be used as a key of `any`
+	if on == TypeId::ANY_TYPE {
+		return types.new_or_type(TypeId::STRING_OR_NUMBER_TYPE, TypeId::SYMBOL_TYPE);
+	}
+	let keys = if let Type::Interface { .. } = types.get_type_by_id(on) {
+		None
+	} else {
+		get_property_keys(on, environment, types)
+	};
+	let Some(keys) = keys else {
+		return types.register_type(Type::Constructor(Constructor::TypeOperator(
+			super::TypeOperator::KeyOf(on),
+		)));
+	};
+	let mut union = None::<TypeId>;
+	for key in keys {
+		let key = match key {
+			PropertyKey::String(key) => types.new_constant_type(Constant::String(key.into_owned())),
+			PropertyKey::Type(key) => key,
+		};
+		union = Some(match union {
+			Some(existing) => types.new_or_type(existing, key),
+			None => key,
+		});
+	}
+	union.unwrap_or(TypeId::NEVER_TYPE)
+}
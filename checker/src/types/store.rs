@@ -5,7 +5,7 @@ use crate::{
 	features::functions::{ClosureId, FunctionBehavior},
 	types::FunctionType,
 	types::{PolyNature, Type},
-	Environment, FunctionId, GeneralContext, TypeId,
+	BinarySerializable, Environment, FunctionId, GeneralContext, TypeId,
 };
 
 use super::{
@@ -29,6 +29,10 @@ pub struct TypeStore {
 
 	/// TODO not best place but is passed through everything so
 	pub(crate) closure_counter: u32,
+
+	/// Narrowing a value to the same members reuses the type, so that narrowing in a nested
+	/// condition (`x !== null && x`) refers to the same value as narrowing the outer one
+	pub(crate) narrowed_values: HashMap<(TypeId, Vec<TypeId>), TypeId>,
 }
 
 impl Default for TypeStore {
@@ -85,6 +89,30 @@ impl Default for TypeStore {
 			},
 			// `void` type. Does not block sometimes
 			Type::AliasTo { to: TypeId::UNDEFINED_TYPE, name: "void".into(), parameters: None },
+			Type::Interface {
+				name: "Promise".to_owned(),
+				parameters: Some(vec![TypeId::T_TYPE]),
+				nominal: true,
+			},
+			Type::Interface {
+				name: "Generator".to_owned(),
+				parameters: Some(vec![TypeId::Y_TYPE, TypeId::R_TYPE, TypeId::N_TYPE]),
+				nominal: true,
+			},
+			// Generator Y, R and N
+			Type::RootPolyType(PolyNature::Generic {
+				name: "Y".to_owned(),
+				eager_fixed: TypeId::ANY_TYPE,
+			}),
+			Type::RootPolyType(PolyNature::Generic {
+				name: "R".to_owned(),
+				eager_fixed: TypeId::ANY_TYPE,
+			}),
+			Type::RootPolyType(PolyNature::Generic {
+				name: "N".to_owned(),
+				eager_fixed: TypeId::ANY_TYPE,
+			}),
+			Type::Or(TypeId::STRING_TYPE, TypeId::NUMBER_TYPE),
 		];
 
 		// Check that above is correct, TODO eventually a macro
@@ -97,10 +125,35 @@ impl Default for TypeStore {
 			_specialisations: Default::default(),
 			called_functions: Default::default(),
 			closure_counter: 0,
+			narrowed_values: Default::default(),
 		}
 	}
 }
 
+/// Only the types, functions and closure counter are stored. Used for caching the root
+impl BinarySerializable for TypeStore {
+	fn serialize(self, buf: &mut Vec<u8>) {
+		self.types.serialize(buf);
+		self.functions.serialize(buf);
+		self.closure_counter.serialize(buf);
+	}
+
+	fn deserialize<I: Iterator<Item = u8>>(
+		iter: &mut I,
+		source: source_map::SourceId,
+	) -> Result<Self, crate::DeserializeError> {
+		Ok(Self {
+			types: BinarySerializable::deserialize(iter, source)?,
+			functions: BinarySerializable::deserialize(iter, source)?,
+			_dependent_dependencies: Default::default(),
+			_specialisations: Default::default(),
+			called_functions: Default::default(),
+			closure_counter: BinarySerializable::deserialize(iter, source)?,
+			narrowed_values: Default::default(),
+		})
+	}
+}
+
 impl TypeStore {
 	pub fn new_constant_type(&mut self, constant: crate::Constant) -> crate::TypeId {
 		// Reuse existing ids rather than creating new types sometimes
@@ -162,6 +215,7 @@ impl TypeStore {
 	}
 
 	/// From something like: let a: number => string. Rather than a actual function
+	#[allow(clippy::too_many_arguments)]
 	pub fn new_function_type_annotation(
 		&mut self,
 		type_parameters: Option<super::poly_types::GenericTypeParameters>,
@@ -170,6 +224,7 @@ impl TypeStore {
 		declared_at: &source_map::SpanWithSource,
 		effects: Vec<crate::events::Event>,
 		constant_function: Option<String>,
+		behavior: FunctionBehavior,
 	) -> TypeId {
 		let id = crate::FunctionId(declared_at.source, declared_at.start);
 		let function_type = FunctionType {
@@ -180,8 +235,7 @@ impl TypeStore {
 			// TODO
 			free_variables: Default::default(),
 			closed_over_variables: Default::default(),
-			// TODO
-			behavior: FunctionBehavior::ArrowFunction { is_async: false },
+			behavior,
 			constant_function,
 			id,
 		};
@@ -250,26 +304,20 @@ impl TypeStore {
 		data: TData,
 	) -> Option<Logical<TResult>> {
 		match self.get_type_by_id(on) {
-			Type::Function(..) => {
+			Type::Function(..) | Type::FunctionReference(_) => {
 				let on_function = ctx
 					.parents_iter()
 					.find_map(|env| resolver(&env, self, on, data))
 					.map(Logical::Pure);
 
+				// Classes that extend have the class they extend as a prototype (for static members)
 				// TODO undecided on this
 				on_function.or_else(|| {
-					self.get_fact_about_type(ctx, TypeId::FUNCTION_TYPE, resolver, data)
-				})
-			}
-			Type::FunctionReference(_) => {
-				let on_function = ctx
-					.parents_iter()
-					.find_map(|env| resolver(&env, self, on, data))
-					.map(Logical::Pure);
-
-				// TODO undecided on this
-				on_function.or_else(|| {
-					self.get_fact_about_type(ctx, TypeId::FUNCTION_TYPE, resolver, data)
+					let prototype = ctx
+						.parents_iter()
+						.find_map(|ctx| get_on_ctx!(ctx.facts.prototypes.get(&on)).copied())
+						.unwrap_or(TypeId::FUNCTION_TYPE);
+					self.get_fact_about_type(ctx, prototype, resolver, data)
 				})
 			}
 			Type::AliasTo { to, .. } => {
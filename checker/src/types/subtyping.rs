@@ -1,16 +1,24 @@
 //! Type subtyping / order / subtype checking.
 
+use std::borrow::Cow;
+
 use crate::{
-	context::{Environment, GeneralContext, Logical},
+	context::{facts::Publicity, Environment, GeneralContext, Logical},
+	diagnostics::{
+		NonEqualityRepresentation, PropertyErrorRepresentation, TypeStringRepresentation,
+	},
 	types::{
 		poly_types::generic_type_arguments::TypeArgumentStore, printing::print_type, TypeStore,
 	},
-	PropertyValue, TypeId,
+	Constant, FunctionId, PropertyValue, TypeId,
 };
 
 use super::{
-	get_constraint, properties::PropertyKey, Constructor, PolyNature, StructureGenerics, Type,
-	TypeArguments,
+	get_constraint, get_larger_type,
+	mapped_types::{MappedModifier, MappedType},
+	poly_types::SeedingContext,
+	properties::{get_property_keys, PropertyKey},
+	Constructor, PolyNature, StructureGenerics, Type, TypeArguments, TypeOperator,
 };
 
 pub use super::{BasicEquality, NonEqualityReason, PropertyError, SubTypeResult, SubtypeBehavior};
@@ -34,6 +42,44 @@ pub fn type_is_subtype<T: SubtypeBehavior>(
 	result
 }
 
+/// For conditional types, whether `ty extends extends`. Returns the values of any `infer` types in
+/// `extends` if so
+pub(crate) fn type_extends(
+	ty: TypeId,
+	extends: TypeId,
+	environment: &mut Environment,
+	types: &TypeStore,
+) -> Option<TypeArguments> {
+	if get_larger_type(ty, types) == extends {
+		return Some(TypeArguments::new());
+	}
+
+	// `infer` types are generic, so are found like function type arguments
+	let mut seeding_context = SeedingContext {
+		type_arguments: map_vec::Map::new(),
+		type_restrictions: map_vec::Map::new(),
+		locally_held_functions: map_vec::Map::new(),
+		argument_position_and_parameter_idx: (source_map::Nullable::NULL, 0),
+	};
+	let result =
+		type_is_subtype2(extends, ty, None, None, &mut seeding_context, environment, types, false);
+
+	if let SubTypeResult::IsSubType = result {
+		Some(
+			seeding_context
+				.type_arguments
+				.into_iter()
+				.filter_map(|(parameter, values)| {
+					let (value, position, _) = values.into_iter().next()?;
+					Some((parameter, (value, position)))
+				})
+				.collect(),
+		)
+	} else {
+		None
+	}
+}
+
 fn set_object_restriction(environment: &mut Environment, object: TypeId, restriction: TypeId) {
 	match environment.object_constraints.entry(object) {
 		std::collections::hash_map::Entry::Occupied(mut entry) => {
@@ -186,54 +232,16 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 				return SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch);
 			};
 
-			let left_func = types.functions.get(left_func).unwrap();
-			let right_func = types.functions.get(right_func).unwrap();
-
-			for (idx, lhs_param) in left_func.parameters.parameters.iter().enumerate() {
-				match right_func.parameters.get_type_constraint_at_index(idx) {
-					Some(right_param_ty) => {
-						let result = type_is_subtype2(
-							right_param_ty,
-							lhs_param.ty,
-							right_type_arguments,
-							base_type_arguments,
-							behavior,
-							environment,
-							types,
-							// !!!
-							true,
-						);
-
-						match result {
-							SubTypeResult::IsSubType => {}
-							err @ SubTypeResult::IsNotSubType(_) => {
-								// TODO don't short circuit
-								return err;
-							}
-						}
-					}
-					None => {
-						return SubTypeResult::IsNotSubType(NonEqualityReason::MissingParameter)
-					}
-				}
-			}
-			// TODO optional and rest parameters
-
-			// `void` return type means anything goes here
-			if TypeId::VOID_TYPE == left_func.return_type {
-				SubTypeResult::IsSubType
-			} else {
-				type_is_subtype2(
-					left_func.return_type,
-					right_func.return_type,
-					base_type_arguments,
-					right_type_arguments,
-					behavior,
-					environment,
-					types,
-					restriction_mode,
-				)
-			}
+			function_is_subtype(
+				*left_func,
+				*right_func,
+				base_type_arguments,
+				right_type_arguments,
+				behavior,
+				environment,
+				types,
+				restriction_mode,
+			)
 		}
 		Type::Constant(lhs) => {
 			if let Type::Constant(rhs) = right_ty {
@@ -439,8 +447,8 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 					StructureGenerics { on: TypeId::ARRAY_TYPE, arguments: right_arguments },
 				)) = right_ty
 				{
-					let left_arg = arguments.get_local_argument(TypeId::T_TYPE).unwrap();
-					let right_arg = right_arguments.get_local_argument(TypeId::T_TYPE).unwrap();
+					let left_arg = arguments.get_argument(TypeId::T_TYPE).unwrap();
+					let right_arg = right_arguments.get_argument(TypeId::T_TYPE).unwrap();
 					// TODO unsure about arguments here
 					type_is_subtype2(
 						left_arg,
@@ -456,6 +464,53 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 					crate::utils::notify!("Not array-ish");
 					SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
 				}
+			} else if let (TypeId::PROMISE_TYPE, Some(right_resolves_to)) =
+				(*on, crate::features::promises::get_promise_resolved_type(ty, types))
+			{
+				// Promises are compared by what they resolve to
+				let left_resolves_to =
+					arguments.get_argument(TypeId::T_TYPE).unwrap_or(TypeId::ANY_TYPE);
+				type_is_subtype2(
+					left_resolves_to,
+					right_resolves_to,
+					base_type_arguments,
+					right_type_arguments,
+					behavior,
+					environment,
+					types,
+					restriction_mode,
+				)
+			} else if let (TypeId::GENERATOR_TYPE, Some((right_yields, right_returns))) =
+				(*on, crate::features::generators::get_generator_arguments(ty, types))
+			{
+				// Generators are compared by what they yield and return
+				let (left_yields, left_returns) =
+					crate::features::generators::get_generator_arguments(base_type, types)
+						.unwrap_or((TypeId::ANY_TYPE, TypeId::ANY_TYPE));
+				let result = type_is_subtype2(
+					left_yields,
+					right_yields,
+					base_type_arguments,
+					right_type_arguments,
+					behavior,
+					environment,
+					types,
+					restriction_mode,
+				);
+				if let SubTypeResult::IsSubType = result {
+					type_is_subtype2(
+						left_returns,
+						right_returns,
+						base_type_arguments,
+						right_type_arguments,
+						behavior,
+						environment,
+						types,
+						restriction_mode,
+					)
+				} else {
+					result
+				}
 			} else {
 				if base_type_arguments.is_some() {
 					todo!("need chain to do nesting")
@@ -476,6 +531,60 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 			Constructor::BinaryOperator { .. }
 			| Constructor::CanonicalRelationOperator { .. }
 			| Constructor::UnaryOperator { .. } => unreachable!("invalid constructor on LHS"),
+			Constructor::TypeOperator(TypeOperator::KeyOf(on)) => {
+				if let Type::Constructor(Constructor::TypeOperator(TypeOperator::KeyOf(right_on))) =
+					right_ty
+				{
+					if on == right_on {
+						return SubTypeResult::IsSubType;
+					}
+				}
+
+				// `on` may have been inferred from a previous argument
+				let on = base_type_arguments
+					.and_then(|arguments| arguments.get(on))
+					.map(|(on, _)| *on)
+					.or_else(|| behavior.get_type_argument(*on))
+					.unwrap_or(*on);
+				let keys = get_property_keys(on, environment, types);
+
+				if let Some(keys) = keys {
+					let key = match right_ty {
+						Type::Constant(Constant::String(key)) => {
+							PropertyKey::String(Cow::Owned(key.clone()))
+						}
+						_ => PropertyKey::Type(ty),
+					};
+					if keys.contains(&key) {
+						SubTypeResult::IsSubType
+					} else {
+						SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
+					}
+				} else if T::INFER_GENERICS {
+					// Keys are not known until `on` is
+					type_is_subtype2(
+						TypeId::STRING_OR_NUMBER_TYPE,
+						ty,
+						base_type_arguments,
+						right_type_arguments,
+						behavior,
+						environment,
+						types,
+						restriction_mode,
+					)
+				} else {
+					SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
+				}
+			}
+			Constructor::Mapped(mapped) => check_mapped_type(
+				mapped,
+				ty,
+				right_type_arguments,
+				behavior,
+				environment,
+				types,
+				restriction_mode,
+			),
 			Constructor::TypeOperator(_) => todo!(),
 			Constructor::TypeRelationOperator(_) => todo!(),
 			Constructor::ConditionalResult {
@@ -561,9 +670,37 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 					environment,
 					restriction_mode,
 				),
-				Type::Function(..) => {
-					crate::utils::notify!("TODO implement function checking");
-					SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
+				Type::Function(right_func, _) | Type::FunctionReference(right_func) => {
+					// Must match every call signature (and any properties)
+					let signatures = environment.get_signatures(base_type, false);
+					if signatures.is_empty() {
+						return SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch);
+					}
+					for signature in signatures {
+						let result = function_is_subtype(
+							signature,
+							*right_func,
+							base_type_arguments,
+							right_type_arguments,
+							behavior,
+							environment,
+							types,
+							restriction_mode,
+						);
+						if let SubTypeResult::IsNotSubType(..) = result {
+							return result;
+						}
+					}
+					check_properties(
+						base_type,
+						ty,
+						types,
+						base_type_arguments,
+						right_type_arguments,
+						behavior,
+						environment,
+						restriction_mode,
+					)
 				}
 				Type::And(_, _) => todo!(),
 				Type::Or(_left, _right) => {
@@ -648,7 +785,6 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 						)
 					}
 				}
-				Type::FunctionReference(_) => todo!(),
 				Type::SpecialObject(_) => todo!(),
 			}
 		}
@@ -656,6 +792,66 @@ fn type_is_subtype2<T: SubtypeBehavior>(
 	}
 }
 
+/// Parameters are checked contravariantly and the return type covariantly
+#[allow(clippy::too_many_arguments)]
+fn function_is_subtype<T: SubtypeBehavior>(
+	left_func: FunctionId,
+	right_func: FunctionId,
+	base_type_arguments: Option<&TypeArguments>,
+	right_type_arguments: Option<&TypeArguments>,
+	behavior: &mut T,
+	environment: &mut Environment,
+	types: &TypeStore,
+	restriction_mode: bool,
+) -> SubTypeResult {
+	let left_func = types.functions.get(&left_func).unwrap();
+	let right_func = types.functions.get(&right_func).unwrap();
+
+	for (idx, lhs_param) in left_func.parameters.parameters.iter().enumerate() {
+		match right_func.parameters.get_type_constraint_at_index(idx) {
+			Some(right_param_ty) => {
+				let result = type_is_subtype2(
+					right_param_ty,
+					lhs_param.ty,
+					right_type_arguments,
+					base_type_arguments,
+					behavior,
+					environment,
+					types,
+					// !!!
+					true,
+				);
+
+				match result {
+					SubTypeResult::IsSubType => {}
+					err @ SubTypeResult::IsNotSubType(_) => {
+						// TODO don't short circuit
+						return err;
+					}
+				}
+			}
+			None => return SubTypeResult::IsNotSubType(NonEqualityReason::MissingParameter),
+		}
+	}
+	// TODO optional and rest parameters
+
+	// `void` return type means anything goes here
+	if TypeId::VOID_TYPE == left_func.return_type {
+		SubTypeResult::IsSubType
+	} else {
+		type_is_subtype2(
+			left_func.return_type,
+			right_func.return_type,
+			base_type_arguments,
+			right_type_arguments,
+			behavior,
+			environment,
+			types,
+			restriction_mode,
+		)
+	}
+}
+
 /// TODO temp
 
 #[allow(clippy::too_many_arguments)]
@@ -672,12 +868,16 @@ fn check_properties<T: SubtypeBehavior>(
 	let mut property_errors = Vec::new();
 	for (publicity, key, property) in environment.get_properties_on_type(base_type) {
 		let rhs_property = environment.get_property_unbound(ty, publicity, key.clone(), types);
+		let is_optional = environment.get_property_modifiers(base_type, &key).optional;
 
 		match rhs_property {
 			Some(rhs_property) => {
 				match rhs_property {
 					Logical::Pure(rhs_property) => {
 						let rhs_type = rhs_property.as_set_type();
+						if is_optional && rhs_type == TypeId::UNDEFINED_TYPE {
+							continue;
+						}
 						// crate::utils::notify!(
 						// 	"Checking {} with {}, against {}, left={:?}",
 						// 	print_type(key, types, &environment.as_general_context(), true),
@@ -704,10 +904,14 @@ fn check_properties<T: SubtypeBehavior>(
 								}
 							}
 							SubTypeResult::IsNotSubType(mismatch) => {
+								// Show the type argument rather than the parameter
+								let expected = base_type_arguments
+									.and_then(|arguments| arguments.get(&property))
+									.map_or(property, |(argument, _)| *argument);
 								property_errors.push((
 									key,
 									PropertyError::Invalid {
-										expected: property,
+										expected,
 										found: rhs_type,
 										mismatch,
 									},
@@ -719,6 +923,7 @@ fn check_properties<T: SubtypeBehavior>(
 					Logical::Implies { .. } => todo!(),
 				}
 			}
+			None if is_optional => {}
 			// TODO
 			None => {
 				property_errors.push((key, PropertyError::Missing));
@@ -734,16 +939,146 @@ fn check_properties<T: SubtypeBehavior>(
 	}
 }
 
+/// For mapped types that were not evaluated when created. This is for mapped types over interfaces
+/// whose properties had not been synthesised at the time (in hoisted type aliases)
+fn check_mapped_type<T: SubtypeBehavior>(
+	mapped: &MappedType,
+	ty: TypeId,
+	right_type_arguments: Option<&TypeArguments>,
+	behavior: &mut T,
+	environment: &mut Environment,
+	types: &TypeStore,
+	restriction_mode: bool,
+) -> SubTypeResult {
+	let keys = get_constraint(mapped.parameter, types).unwrap();
+	let source = match types.get_type_by_id(keys) {
+		Type::Constructor(Constructor::TypeOperator(TypeOperator::KeyOf(source)))
+			if mapped.as_type.is_none() =>
+		{
+			*source
+		}
+		// Depends on a type parameter
+		_ => {
+			return if T::INFER_GENERICS {
+				SubTypeResult::IsSubType
+			} else {
+				SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
+			};
+		}
+	};
+	// `T` is assignable to mappings of `T[P]` that do not make properties required
+	if ty == source && mapped.optional != MappedModifier::Remove {
+		if let Type::Constructor(Constructor::Property {
+			on,
+			under: PropertyKey::Type(under),
+			..
+		}) = types.get_type_by_id(mapped.value)
+		{
+			if *on == source && *under == mapped.parameter {
+				return SubTypeResult::IsSubType;
+			}
+		}
+	}
+
+	let Some(keys) = get_property_keys(source, environment, types) else {
+		return SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch);
+	};
+
+	let mut property_errors = Vec::new();
+	for key in keys {
+		let expected = match types.get_type_by_id(mapped.value) {
+			// `T[P]`
+			Type::Constructor(Constructor::Property {
+				on,
+				under: PropertyKey::Type(under),
+				..
+			}) if *under == mapped.parameter => {
+				match environment.get_property_unbound(*on, Publicity::Public, key.clone(), types) {
+					Some(Logical::Pure(value)) => value.as_get_type(),
+					_ => continue,
+				}
+			}
+			value if value.is_dependent() => continue,
+			_ => mapped.value,
+		};
+		let is_optional =
+			mapped.optional.apply(environment.get_property_modifiers(source, &key).optional);
+
+		match environment.get_property_unbound(ty, Publicity::Public, key.clone(), types) {
+			Some(Logical::Pure(rhs_property)) => {
+				let rhs_type = rhs_property.as_set_type();
+				if is_optional && rhs_type == TypeId::UNDEFINED_TYPE {
+					continue;
+				}
+				let result = type_is_subtype2(
+					expected,
+					rhs_type,
+					None,
+					right_type_arguments,
+					behavior,
+					environment,
+					types,
+					restriction_mode,
+				);
+				if let SubTypeResult::IsNotSubType(mismatch) = result {
+					property_errors
+						.push((key, PropertyError::Invalid { expected, found: rhs_type, mismatch }));
+				}
+			}
+			Some(_) => {}
+			None if is_optional => {}
+			None => property_errors.push((key, PropertyError::Missing)),
+		}
+	}
+
+	if property_errors.is_empty() {
+		SubTypeResult::IsSubType
+	} else {
+		SubTypeResult::IsNotSubType(NonEqualityReason::PropertiesInvalid { errors: property_errors })
+	}
+}
+
 impl NonEqualityReason {
-	pub(crate) fn _into_error_message(self, _environment: &GeneralContext) -> Vec<String> {
+	/// Prints the types, for diagnostics
+	pub(crate) fn into_representation(
+		self,
+		ctx: &GeneralContext,
+		types: &TypeStore,
+	) -> NonEqualityRepresentation {
 		match self {
 			NonEqualityReason::GenericParameterMismatch
 			| NonEqualityReason::MissingParameter
-			| NonEqualityReason::Mismatch => Vec::new(),
+			| NonEqualityReason::TooStrict
+			| NonEqualityReason::Mismatch => NonEqualityRepresentation::Mismatch,
 			NonEqualityReason::PropertiesInvalid { errors } => {
-				errors.into_iter().map(|error| format!("{error:?}")).collect()
+				let errors = errors
+					.into_iter()
+					.map(|(key, error)| {
+						let key = match key {
+							PropertyKey::String(key) => key.into_owned(),
+							PropertyKey::Type(key) => {
+								format!("[{}]", print_type(key, types, ctx, false))
+							}
+						};
+						let error = match error {
+							PropertyError::Missing => PropertyErrorRepresentation::Missing,
+							PropertyError::Invalid { expected, found, mismatch } => {
+								PropertyErrorRepresentation::Invalid {
+									expected: TypeStringRepresentation::from_type_id(
+										expected, ctx, types, false,
+									),
+									found: TypeStringRepresentation::from_type_id(
+										found, ctx, types, false,
+									),
+									mismatch: mismatch.into_representation(ctx, types),
+								}
+							}
+						};
+						(key, error)
+					})
+					.collect();
+				NonEqualityRepresentation::PropertiesInvalid(errors)
 			}
-			NonEqualityReason::TooStrict => todo!(),
 		}
 	}
 }
@@ -753,15 +1088,14 @@ pub(crate) fn check_satisfies(
 	to_satisfy: TypeId,
 	types: &TypeStore,
 	environment: &mut Environment,
-) -> bool {
+) -> SubTypeResult {
 	if expr_ty == TypeId::ERROR_TYPE {
-		false
+		SubTypeResult::IsNotSubType(NonEqualityReason::Mismatch)
 	} else {
 		let mut basic_equality = BasicEquality {
 			add_property_restrictions: false,
 			position: source_map::Nullable::NULL,
 		};
-		let result = type_is_subtype(to_satisfy, expr_ty, &mut basic_equality, environment, types);
-		matches!(result, SubTypeResult::IsSubType)
+		type_is_subtype(to_satisfy, expr_ty, &mut basic_equality, environment, types)
 	}
 }
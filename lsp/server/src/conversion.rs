@@ -0,0 +1,133 @@
+//! Between checker and LSP representations. LSP positions are zero based lines and UTF-16 columns,
+//! where the checker uses byte offsets
+
+use std::path::{Path, PathBuf};
+
+use checker::{
+	source_map::{FileSystem, MapFileStore, SpanWithSource, WithPathMap},
+	DiagnosticKind,
+};
+use lsp_types::{
+	Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range, Url,
+};
+
+pub(crate) fn offset_to_position(content: &str, offset: u32) -> Position {
+	let mut offset = (offset as usize).min(content.len());
+	while !content.is_char_boundary(offset) {
+		offset -= 1;
+	}
+	let before = &content[..offset];
+	let line = before.matches('\n').count();
+	let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+	let character = before[line_start..].encode_utf16().count();
+	Position {
+		line: u32::try_from(line).unwrap_or(u32::MAX),
+		character: u32::try_from(character).unwrap_or(u32::MAX),
+	}
+}
+
+/// Positions past the end of a line are clamped to the end of that line
+pub(crate) fn position_to_offset(content: &str, position: Position) -> usize {
+	let mut line_start = 0;
+	for _ in 0..position.line {
+		match content[line_start..].find('\n') {
+			Some(index) => line_start += index + 1,
+			None => return content.len(),
+		}
+	}
+
+	let line = content[line_start..].split('\n').next().unwrap_or_default();
+	let mut character = 0;
+	for (index, chr) in line.char_indices() {
+		if character >= position.character as usize {
+			return line_start + index;
+		}
+		character += chr.len_utf16();
+	}
+	line_start + line.len()
+}
+
+pub(crate) fn span_to_range(span: SpanWithSource, files: &MapFileStore<WithPathMap>) -> Range {
+	files.get_source_by_id(span.source, |source| Range {
+		start: offset_to_position(&source.content, span.start),
+		end: offset_to_position(&source.content, span.end),
+	})
+}
+
+pub(crate) fn path_to_uri(path: &Path) -> Option<Url> {
+	Url::from_file_path(path).ok()
+}
+
+/// Non file URIs (for example unsaved documents) use their path
+pub(crate) fn uri_to_path(uri: &Url) -> PathBuf {
+	uri.to_file_path().unwrap_or_else(|()| PathBuf::from(uri.path()))
+}
+
+fn kind_to_severity(kind: DiagnosticKind) -> DiagnosticSeverity {
+	match kind {
+		DiagnosticKind::Error => DiagnosticSeverity::ERROR,
+		DiagnosticKind::Warning => DiagnosticSeverity::WARNING,
+		DiagnosticKind::Info => DiagnosticSeverity::INFORMATION,
+	}
+}
+
+/// Returns the path of the file the diagnostic is in, [`None`] if global
+pub(crate) fn convert_diagnostic(
+	diagnostic: checker::Diagnostic,
+	files: &MapFileStore<WithPathMap>,
+) -> (Option<PathBuf>, Diagnostic) {
+	match diagnostic {
+		checker::Diagnostic::Global { reason, kind } => (
+			None,
+			Diagnostic {
+				severity: Some(kind_to_severity(kind)),
+				source: Some("ezno".to_owned()),
+				message: reason,
+				..Default::default()
+			},
+		),
+		checker::Diagnostic::Position { reason, position, kind } => (
+			Some(files.get_file_path_and_content(position.source).0),
+			Diagnostic {
+				range: span_to_range(position, files),
+				severity: Some(kind_to_severity(kind)),
+				source: Some("ezno".to_owned()),
+				message: reason,
+				..Default::default()
+			},
+		),
+		checker::Diagnostic::PositionWithAdditionalLabels { reason, position, labels, kind } => {
+			let mut message = reason;
+			let mut related_information = Vec::new();
+			for (label, label_position) in labels {
+				let location = label_position.and_then(|label_position| {
+					let (path, _) = files.get_file_path_and_content(label_position.source);
+					Some(Location {
+						uri: path_to_uri(&path)?,
+						range: span_to_range(label_position, files),
+					})
+				});
+				if let Some(location) = location {
+					related_information
+						.push(DiagnosticRelatedInformation { location, message: label });
+				} else {
+					message.push('\n');
+					message.push_str(&label);
+				}
+			}
+
+			(
+				Some(files.get_file_path_and_content(position.source).0),
+				Diagnostic {
+					range: span_to_range(position, files),
+					severity: Some(kind_to_severity(kind)),
+					source: Some("ezno".to_owned()),
+					message,
+					related_information: (!related_information.is_empty())
+						.then_some(related_information),
+					..Default::default()
+				},
+			)
+		}
+	}
+}
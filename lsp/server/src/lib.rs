@@ -0,0 +1,69 @@
+//! Language server for Ezno. Speaks JSON-RPC over stdio (or any [`Connection`]).
+//!
+//! Supports
+//! - diagnostics (published on opening and changing a document)
+//! - hover (the type of the expression under the cursor)
+//! - go to definition (of variables)
+
+mod conversion;
+mod server;
+
+use lsp_server::{Connection, Message};
+use lsp_types::{
+	HoverProviderCapability, OneOf, ServerCapabilities, TextDocumentSyncCapability,
+	TextDocumentSyncKind,
+};
+
+use server::Server;
+
+pub type LspError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Runs the server on stdin and stdout until the client asks it to exit
+pub fn run_stdio() -> Result<(), LspError> {
+	let (connection, io_threads) = Connection::stdio();
+	run_server(&connection)?;
+	// Required to close the writer thread
+	drop(connection);
+	io_threads.join()?;
+	Ok(())
+}
+
+/// Runs the server on `connection` until the client asks it to exit
+pub fn run_server(connection: &Connection) -> Result<(), LspError> {
+	let capabilities = serde_json::to_value(server_capabilities())?;
+	let _initialize_params = connection.initialize(capabilities)?;
+
+	let mut server = Server::default();
+
+	for message in &connection.receiver {
+		match message {
+			Message::Request(request) => {
+				if connection.handle_shutdown(&request)? {
+					return Ok(());
+				}
+				let response = server.handle_request(request);
+				connection.sender.send(Message::Response(response))?;
+			}
+			Message::Notification(notification) => {
+				for notification in server.handle_notification(notification) {
+					connection.sender.send(Message::Notification(notification))?;
+				}
+			}
+			Message::Response(_) => {}
+		}
+	}
+
+	Ok(())
+}
+
+#[must_use]
+pub fn server_capabilities() -> ServerCapabilities {
+	ServerCapabilities {
+		text_document_sync: Some(TextDocumentSyncCapability::Kind(
+			TextDocumentSyncKind::INCREMENTAL,
+		)),
+		hover_provider: Some(HoverProviderCapability::Simple(true)),
+		definition_provider: Some(OneOf::Left(true)),
+		..Default::default()
+	}
+}
@@ -0,0 +1,266 @@
+use std::{
+	collections::{HashMap, HashSet},
+	panic::{self, AssertUnwindSafe},
+	path::Path,
+};
+
+use checker::{
+	source_map::FileSystem, synthesis::EznoParser, CheckOutput, TypeCheckOptions, VariableId,
+};
+use lsp_server::{ErrorCode, Notification, Request, Response};
+use lsp_types::{
+	notification::{
+		DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+		Notification as NotificationTrait, PublishDiagnostics,
+	},
+	request::{GotoDefinition, HoverRequest, Request as RequestTrait},
+	Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+	DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+	HoverParams, Location, MarkupContent, MarkupKind, PublishDiagnosticsParams, Range,
+	TextDocumentPositionParams, Url,
+};
+
+use crate::conversion::{
+	convert_diagnostic, offset_to_position, path_to_uri, position_to_offset, uri_to_path,
+};
+
+/// Open documents and the results of checking them
+#[derive(Default)]
+pub(crate) struct Server {
+	/// Contents of open documents. Imports of these use this rather than the file system
+	documents: HashMap<Url, String>,
+	/// Result of the last check of an open document (as the entry point). Not present if the checker
+	/// panicked
+	checked: HashMap<Url, CheckOutput<EznoParser>>,
+	/// The internal definition file, synthesised on the first check (see
+	/// [`checker::definition_file_to_buffer`]). Later checks load the root from this rather than
+	/// parsing and synthesising the definition file again
+	definition_cache: Option<Vec<u8>>,
+}
+
+impl Server {
+	pub(crate) fn handle_request(&mut self, request: Request) -> Response {
+		match request.method.as_str() {
+			HoverRequest::METHOD => handle::<HoverRequest>(request, |params| self.hover(params)),
+			GotoDefinition::METHOD => {
+				handle::<GotoDefinition>(request, |params| self.definition(params))
+			}
+			method => Response::new_err(
+				request.id,
+				ErrorCode::MethodNotFound as i32,
+				format!("unsupported request {method}"),
+			),
+		}
+	}
+
+	/// Returns notifications to send back (diagnostics)
+	pub(crate) fn handle_notification(&mut self, notification: Notification) -> Vec<Notification> {
+		match notification.method.as_str() {
+			DidOpenTextDocument::METHOD => {
+				let Ok(DidOpenTextDocumentParams { text_document }) =
+					notification.extract(DidOpenTextDocument::METHOD)
+				else {
+					return Vec::new();
+				};
+				self.documents.insert(text_document.uri.clone(), text_document.text);
+				self.check(&text_document.uri)
+			}
+			DidChangeTextDocument::METHOD => {
+				let Ok(DidChangeTextDocumentParams { text_document, content_changes }) =
+					notification.extract(DidChangeTextDocument::METHOD)
+				else {
+					return Vec::new();
+				};
+				let Some(content) = self.documents.get_mut(&text_document.uri) else {
+					return Vec::new();
+				};
+				for change in content_changes {
+					if let Some(Range { start, end }) = change.range {
+						let start = position_to_offset(content, start);
+						let end = position_to_offset(content, end).max(start);
+						content.replace_range(start..end, &change.text);
+					} else {
+						*content = change.text;
+					}
+				}
+				self.check(&text_document.uri)
+			}
+			DidCloseTextDocument::METHOD => {
+				let Ok(DidCloseTextDocumentParams { text_document }) =
+					notification.extract(DidCloseTextDocument::METHOD)
+				else {
+					return Vec::new();
+				};
+				self.documents.remove(&text_document.uri);
+				self.checked.remove(&text_document.uri);
+				vec![publish_diagnostics(text_document.uri, Vec::new())]
+			}
+			_ => Vec::new(),
+		}
+	}
+
+	/// Checks the document as an entry point. Returns diagnostics for it and any files it imports.
+	/// Only the modules it imports are checked, other open documents keep their last result
+	fn check(&mut self, uri: &Url) -> Vec<Notification> {
+		let open_documents = self
+			.documents
+			.iter()
+			.map(|(uri, content)| (uri_to_path(uri), content.clone()))
+			.collect::<HashMap<_, _>>();
+
+		let read_from_fs = |path: &Path| {
+			if path == Path::new(checker::INTERNAL_DEFINITION_FILE_PATH) {
+				Some(checker::INTERNAL_DEFINITION_FILE.to_owned())
+			} else {
+				open_documents.get(path).cloned().or_else(|| std::fs::read_to_string(path).ok())
+			}
+		};
+
+		let options = TypeCheckOptions {
+			lsp_mode: true,
+			store_expression_type_mappings: true,
+			..Default::default()
+		};
+
+		// The checker can panic on unimplemented things, which should not stop the server
+		if self.definition_cache.is_none() {
+			let definition_cache = panic::catch_unwind(|| {
+				checker::definition_file_to_buffer::<_, EznoParser>(
+					Path::new(checker::INTERNAL_DEFINITION_FILE_PATH),
+					read_from_fs,
+					(),
+				)
+			});
+			self.definition_cache = definition_cache.ok().and_then(Result::ok);
+		}
+
+		let result = panic::catch_unwind(AssertUnwindSafe(|| {
+			if let Some(ref definition_cache) = self.definition_cache {
+				checker::check_project_with_definition_cache::<_, EznoParser>(
+					vec![uri_to_path(uri)],
+					definition_cache,
+					read_from_fs,
+					Some(options),
+					(),
+				)
+			} else {
+				checker::check_project::<_, EznoParser>(
+					vec![uri_to_path(uri)],
+					HashSet::from([checker::INTERNAL_DEFINITION_FILE_PATH.into()]),
+					read_from_fs,
+					Some(options),
+					(),
+				)
+			}
+		}));
+
+		// Always publish for this document so that previous diagnostics are cleared
+		let mut diagnostics = HashMap::from([(uri.clone(), Vec::new())]);
+
+		if let Ok(mut output) = result {
+			for diagnostic in std::mem::take(&mut output.diagnostics).get_diagnostics() {
+				let (path, diagnostic) = convert_diagnostic(diagnostic, &output.module_contents);
+				// Global diagnostics are shown on the document
+				let uri = match path {
+					Some(path) => self.uri_for_path(&path),
+					None => Some(uri.clone()),
+				};
+				if let Some(uri) = uri {
+					diagnostics.entry(uri).or_default().push(diagnostic);
+				}
+			}
+			self.checked.insert(uri.clone(), output);
+		} else {
+			self.checked.remove(uri);
+			diagnostics.entry(uri.clone()).or_default().push(Diagnostic {
+				severity: Some(DiagnosticSeverity::ERROR),
+				source: Some("ezno".to_owned()),
+				message: "Internal error while checking this file".to_owned(),
+				..Default::default()
+			});
+		}
+
+		diagnostics
+			.into_iter()
+			.map(|(uri, diagnostics)| publish_diagnostics(uri, diagnostics))
+			.collect()
+	}
+
+	fn hover(&self, params: HoverParams) -> Option<Hover> {
+		let TextDocumentPositionParams { text_document, position } =
+			params.text_document_position_params;
+		let (output, offset) = self.get_checked_at(&text_document.uri, position)?;
+
+		let ty = output.get_type_at_position(&uri_to_path(&text_document.uri), offset, false)?;
+
+		Some(Hover {
+			contents: HoverContents::Markup(MarkupContent {
+				kind: MarkupKind::Markdown,
+				value: format!("```ts\n{ty}\n```"),
+			}),
+			range: None,
+		})
+	}
+
+	fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+		let TextDocumentPositionParams { text_document, position } =
+			params.text_document_position_params;
+		let (output, offset) = self.get_checked_at(&text_document.uri, position)?;
+
+		let VariableId(source, start) = output
+			.get_variable_declaration_at_position(&uri_to_path(&text_document.uri), offset)?;
+
+		let (path, content) = output.module_contents.get_file_path_and_content(source);
+		// Only the start is known, so the range is to the end of the identifier
+		let end = content[start as usize..]
+			.find(|chr: char| !(chr.is_alphanumeric() || matches!(chr, '_' | '$')))
+			.map_or(content.len(), |length| start as usize + length);
+
+		Some(GotoDefinitionResponse::Scalar(Location {
+			uri: self.uri_for_path(&path)?,
+			range: Range {
+				start: offset_to_position(&content, start),
+				end: offset_to_position(&content, u32::try_from(end).ok()?),
+			},
+		}))
+	}
+
+	/// The check output for the document and the position as an offset into it
+	fn get_checked_at(
+		&self,
+		uri: &Url,
+		position: lsp_types::Position,
+	) -> Option<(&CheckOutput<EznoParser>, u32)> {
+		let content = self.documents.get(uri)?;
+		let output = self.checked.get(uri)?;
+		let offset = u32::try_from(position_to_offset(content, position)).ok()?;
+		Some((output, offset))
+	}
+
+	/// Prefers the URI of an open document
+	fn uri_for_path(&self, path: &Path) -> Option<Url> {
+		self.documents
+			.keys()
+			.find(|uri| uri_to_path(uri) == path)
+			.cloned()
+			.or_else(|| path_to_uri(path))
+	}
+}
+
+fn handle<R: RequestTrait>(
+	request: Request,
+	handler: impl FnOnce(R::Params) -> R::Result,
+) -> Response {
+	let id = request.id.clone();
+	match request.extract::<R::Params>(R::METHOD) {
+		Ok((id, params)) => Response::new_ok(id, handler(params)),
+		Err(err) => Response::new_err(id, ErrorCode::InvalidParams as i32, err.to_string()),
+	}
+}
+
+fn publish_diagnostics(uri: Url, diagnostics: Vec<Diagnostic>) -> Notification {
+	Notification::new(
+		PublishDiagnostics::METHOD.to_owned(),
+		PublishDiagnosticsParams { uri, diagnostics, version: None },
+	)
+}
@@ -0,0 +1,197 @@
+use std::thread::JoinHandle;
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+	notification::{
+		DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized,
+		Notification as NotificationTrait, PublishDiagnostics,
+	},
+	request::{GotoDefinition, HoverRequest, Initialize, Request as RequestTrait, Shutdown},
+	DidChangeTextDocumentParams, DidOpenTextDocumentParams, GotoDefinitionParams,
+	GotoDefinitionResponse, Hover, HoverContents, HoverParams, InitializeParams, Position,
+	PublishDiagnosticsParams, Range, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+	TextDocumentItem, TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+
+/// Drives the server over in-memory pipes
+struct Client {
+	connection: Connection,
+	server: JoinHandle<()>,
+	request_id: i32,
+}
+
+impl Client {
+	fn new() -> Self {
+		let (server_connection, connection) = Connection::memory();
+		let server = std::thread::spawn(move || {
+			ezno_lsp::run_server(&server_connection).unwrap();
+		});
+		let mut client = Self { connection, server, request_id: 0 };
+		let _ = client.request::<Initialize>(InitializeParams::default());
+		client.notify::<Initialized>(lsp_types::InitializedParams {});
+		client
+	}
+
+	fn request<R: RequestTrait>(&mut self, params: R::Params) -> serde_json::Value {
+		self.request_id += 1;
+		let id = RequestId::from(self.request_id);
+		let request = Request::new(id.clone(), R::METHOD.to_owned(), params);
+		self.connection.sender.send(Message::Request(request)).unwrap();
+		for message in &self.connection.receiver {
+			if let Message::Response(response) = message {
+				assert_eq!(response.id, id);
+				assert!(response.error.is_none(), "{:?}", response.error);
+				return response.result.unwrap_or_default();
+			}
+		}
+		panic!("server disconnected")
+	}
+
+	fn notify<N: NotificationTrait>(&self, params: N::Params) {
+		let notification = Notification::new(N::METHOD.to_owned(), params);
+		self.connection.sender.send(Message::Notification(notification)).unwrap();
+	}
+
+	/// Skips diagnostics for other documents
+	fn diagnostics_for(&self, uri: &Url) -> PublishDiagnosticsParams {
+		for message in &self.connection.receiver {
+			if let Message::Notification(notification) = message {
+				let params: PublishDiagnosticsParams =
+					notification.extract(PublishDiagnostics::METHOD).unwrap();
+				if &params.uri == uri {
+					return params;
+				}
+			}
+		}
+		panic!("server disconnected")
+	}
+
+	fn open(&self, uri: &Url, text: &str) {
+		self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+			text_document: TextDocumentItem {
+				uri: uri.clone(),
+				language_id: "typescript".to_owned(),
+				version: 1,
+				text: text.to_owned(),
+			},
+		});
+	}
+
+	fn shutdown(mut self) {
+		let _ = self.request::<Shutdown>(());
+		self.notify::<Exit>(());
+		self.server.join().unwrap();
+	}
+}
+
+fn position_params(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+	TextDocumentPositionParams {
+		text_document: TextDocumentIdentifier { uri: uri.clone() },
+		position: Position { line, character },
+	}
+}
+
+fn uri() -> Url {
+	Url::from_file_path(std::env::temp_dir().join("ezno_lsp_test.ts")).unwrap()
+}
+
+#[test]
+fn diagnostics_and_incremental_change() {
+	let uri = uri();
+	let client = Client::new();
+
+	client.open(&uri, "const a: string = 2;\nconst b = 3;");
+
+	let PublishDiagnosticsParams { diagnostics, .. } = client.diagnostics_for(&uri);
+	assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+	assert_eq!(diagnostics[0].message, "Type 2 is not assignable to type string");
+	assert_eq!(
+		diagnostics[0].range,
+		Range { start: Position::new(0, 18), end: Position::new(0, 19) }
+	);
+
+	// Replace `2` with `"hi"`
+	client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+		text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version: 2 },
+		content_changes: vec![TextDocumentContentChangeEvent {
+			range: Some(Range { start: Position::new(0, 18), end: Position::new(0, 19) }),
+			range_length: None,
+			text: "\"hi\"".to_owned(),
+		}],
+	});
+
+	let PublishDiagnosticsParams { diagnostics, .. } = client.diagnostics_for(&uri);
+	assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+	client.shutdown();
+}
+
+#[test]
+fn definition_file_types_after_changes() {
+	let uri = uri();
+	let client = Client::new();
+
+	client.open(&uri, "const a: Array<number> = [1];\na.push(\"hi\");");
+	let PublishDiagnosticsParams { diagnostics: first, .. } = client.diagnostics_for(&uri);
+	assert_eq!(first.len(), 1, "{first:?}");
+
+	// Later checks use the cached definition file and should give the same result
+	for version in 2..4 {
+		client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+			text_document: VersionedTextDocumentIdentifier { uri: uri.clone(), version },
+			content_changes: vec![TextDocumentContentChangeEvent {
+				range: None,
+				range_length: None,
+				text: "const a: Array<number> = [1];\na.push(\"hi\");".to_owned(),
+			}],
+		});
+		let PublishDiagnosticsParams { diagnostics, .. } = client.diagnostics_for(&uri);
+		assert_eq!(diagnostics, first);
+	}
+
+	client.shutdown();
+}
+
+#[test]
+fn hover() {
+	let uri = uri();
+	let mut client = Client::new();
+
+	client.open(&uri, "const a = { b: 2 };\nconst c = a.b;");
+	let _ = client.diagnostics_for(&uri);
+
+	let hover = client.request::<HoverRequest>(HoverParams {
+		text_document_position_params: position_params(&uri, 1, 12),
+		work_done_progress_params: Default::default(),
+	});
+	let hover: Hover = serde_json::from_value(hover).unwrap();
+	let HoverContents::Markup(content) = hover.contents else { panic!("expected markup") };
+	assert_eq!(content.value, "```ts\n2\n```");
+
+	client.shutdown();
+}
+
+#[test]
+fn go_to_definition() {
+	let uri = uri();
+	let mut client = Client::new();
+
+	client.open(&uri, "let value = 2;\nvalue = 4;\nconst other = value;");
+	let _ = client.diagnostics_for(&uri);
+
+	for position in [position_params(&uri, 2, 16), position_params(&uri, 1, 0)] {
+		let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+			text_document_position_params: position,
+			work_done_progress_params: Default::default(),
+			partial_result_params: Default::default(),
+		});
+		let GotoDefinitionResponse::Scalar(location) = serde_json::from_value(definition).unwrap()
+		else {
+			panic!("expected single location")
+		};
+		assert_eq!(location.uri, uri);
+		assert_eq!(location.range, Range { start: Position::new(0, 4), end: Position::new(0, 9) });
+	}
+
+	client.shutdown();
+}
@@ -8,8 +8,8 @@ use crate::{
 	declarations::{export::Exportable, ExportDeclaration},
 	expect_semi_colon,
 	marker::MARKER,
-	Declaration, Decorated, Marker, ParseOptions, ParseResult, Statement, TSXKeyword, VisitOptions,
-	Visitable,
+	Declaration, Decorated, Marker, ParseOptions, ParseResult, Statement, TSXKeyword, TokenStart,
+	VisitOptions, Visitable,
 };
 
 #[derive(Debug, Clone, PartialEq, Visitable, get_field_by_type::GetFieldByType, EnumFrom)]
@@ -41,6 +41,7 @@ impl StatementOrDeclaration {
 							},
 						..
 					}) | Declaration::Import(..)
+					| Declaration::TypeAlias(..)
 			),
 			Self::Marker(..) => false,
 		}
@@ -103,7 +104,7 @@ impl ASTNode for StatementOrDeclaration {
 				item.to_string_from_buffer(buf, options, local);
 			}
 			StatementOrDeclaration::Marker(_, _) => {
-				panic!()
+				assert!(options.expect_markers, "marker found");
 			}
 		}
 	}
@@ -361,11 +362,27 @@ pub(crate) fn parse_statements_and_declarations(
 	options: &ParseOptions,
 ) -> ParseResult<Vec<StatementOrDeclaration>> {
 	let mut items = Vec::new();
-	while let Some(Token(token_type, _)) = reader.peek() {
+	while let Some(Token(token_type, start)) = reader.peek() {
 		if let TSXToken::EOS | TSXToken::CloseBrace = token_type {
 			break;
 		}
 
+		if options.error_recovery {
+			let start = *start;
+			let result =
+				StatementOrDeclaration::from_reader(reader, state, options).and_then(|value| {
+					if value.requires_semi_colon() {
+						expect_semi_colon(reader, &state.line_starts, value.get_position().end)?;
+					}
+					Ok(value)
+				});
+			items.push(match result {
+				Ok(value) => value,
+				Err(error) => recover(reader, state, start, error),
+			});
+			continue;
+		}
+
 		let value = StatementOrDeclaration::from_reader(reader, state, options)?;
 		if value.requires_semi_colon() {
 			expect_semi_colon(reader, &state.line_starts, value.get_position().end)?;
@@ -378,6 +395,76 @@ pub(crate) fn parse_statements_and_declarations(
 	Ok(items)
 }
 
+/// Records `error` and skips tokens until the next likely statement or declaration boundary.
+/// Returns a [`StatementOrDeclaration::Marker`] in place of the broken region
+fn recover(
+	reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
+	state: &mut crate::ParsingState,
+	start: TokenStart,
+	error: crate::ParseError,
+) -> StatementOrDeclaration {
+	let mut end = error.position.end;
+	state.errors.push(error);
+
+	// Depth of brackets opened in the skipped region
+	let mut depth = 0u32;
+	// Whether the failed parse consumed any tokens
+	let mut skipped_any = !matches!(reader.peek(), Some(Token(_, next)) if next.0 <= start.0);
+	while let Some(Token(token, token_start)) = reader.peek() {
+		match token {
+			TSXToken::EOS => break,
+			TSXToken::CloseBrace if depth == 0 => break,
+			TSXToken::SemiColon if depth == 0 => {
+				end = reader.next().unwrap().get_end().0;
+				break;
+			}
+			token
+				if depth == 0
+					&& skipped_any && is_recovery_point(token)
+					&& state.line_starts.byte_indexes_on_different_lines(
+						end as usize,
+						token_start.0 as usize,
+					) =>
+			{
+				break;
+			}
+			_ => {}
+		}
+		let token = reader.next().unwrap();
+		match token.0 {
+			TSXToken::OpenBrace | TSXToken::OpenParentheses | TSXToken::OpenBracket => depth += 1,
+			TSXToken::CloseBrace | TSXToken::CloseParentheses | TSXToken::CloseBracket => {
+				depth = depth.saturating_sub(1);
+			}
+			_ => {}
+		}
+		end = token.get_end().0;
+		skipped_any = true;
+	}
+
+	let end = end.max(start.0);
+	StatementOrDeclaration::Marker(
+		state.new_partial_point_marker(start),
+		Span { start: start.0, end, source: () },
+	)
+}
+
+/// Tokens that (at the start of a line) begin a new statement or declaration
+fn is_recovery_point(token: &TSXToken) -> bool {
+	token.is_statement_or_declaration_start()
+		|| matches!(
+			token,
+			TSXToken::Keyword(
+				TSXKeyword::Var
+					| TSXKeyword::Class | TSXKeyword::Return
+					| TSXKeyword::Throw | TSXKeyword::Switch
+					| TSXKeyword::Try | TSXKeyword::Do
+					| TSXKeyword::Interface | TSXKeyword::Type
+					| TSXKeyword::Enum
+			)
+		)
+}
+
 pub fn statements_and_declarations_to_string<T: source_map::ToString>(
 	items: &[StatementOrDeclaration],
 	buf: &mut T,
@@ -395,6 +482,7 @@ pub fn statements_and_declarations_to_string<T: source_map::ToString>(
 		}
 
 		options.add_indent(local.depth, buf);
+		buf.add_mapping(&item.get_position().with_source(local.under));
 		item.to_string_from_buffer(buf, options, local);
 		if (!at_end || options.trailing_semicolon) && item.requires_semi_colon() {
 			buf.push(';');
@@ -5,8 +5,8 @@ use crate::{
 };
 
 use super::{
-	variable::VariableDeclaration, ClassDeclaration, ImportExportName, ImportLocation,
-	InterfaceDeclaration, StatementFunction, TypeAlias,
+	variable::VariableDeclaration, ClassDeclaration, EnumDeclaration, ImportAttributes,
+	ImportExportName, ImportLocation, InterfaceDeclaration, StatementFunction, TypeAlias,
 };
 
 use get_field_by_type::GetFieldByType;
@@ -39,9 +39,19 @@ pub enum Exportable {
 	Variable(VariableDeclaration),
 	Interface(InterfaceDeclaration),
 	TypeAlias(TypeAlias),
+	Enum(EnumDeclaration),
 	Parts(Vec<ExportPart>),
-	ImportAll { r#as: Option<VariableIdentifier>, from: ImportLocation },
-	ImportParts { parts: Vec<ExportPart>, from: ImportLocation, type_definitions_only: bool },
+	ImportAll {
+		r#as: Option<VariableIdentifier>,
+		from: ImportLocation,
+		attributes: Option<ImportAttributes>,
+	},
+	ImportParts {
+		parts: Vec<ExportPart>,
+		from: ImportLocation,
+		attributes: Option<ImportAttributes>,
+		type_definitions_only: bool,
+	},
 }
 
 impl ASTNode for ExportDeclaration {
@@ -77,10 +87,15 @@ impl ASTNode for ExportDeclaration {
 				};
 				let start = state.expect_keyword(reader, TSXKeyword::From)?;
 
-				let (from, end) = ImportLocation::from_reader(reader, state, options, Some(start))?;
+				let (from, mut end) =
+					ImportLocation::from_reader(reader, state, options, Some(start))?;
+				let attributes = ImportAttributes::optional_from_reader(reader, state, options)?;
+				if let Some(ref attributes) = attributes {
+					end = attributes.get_position().get_end();
+				}
 
 				Ok(ExportDeclaration::Variable {
-					exported: Exportable::ImportAll { r#as, from },
+					exported: Exportable::ImportAll { r#as, from, attributes },
 					position: start.union(end),
 				})
 			}
@@ -92,7 +107,21 @@ impl ASTNode for ExportDeclaration {
 				let position = start.union(class_declaration.get_position());
 				Ok(Self::Variable { exported: Exportable::Class(class_declaration), position })
 			}
+			Token(TSXToken::Keyword(TSXKeyword::Enum), _) => {
+				let enum_declaration = EnumDeclaration::from_reader(reader, state, options)?;
+				let position = start.union(enum_declaration.get_position());
+				Ok(Self::Variable { exported: Exportable::Enum(enum_declaration), position })
+			}
 			Token(TSXToken::Keyword(TSXKeyword::Const | TSXKeyword::Let), _) => {
+				// Const can be either variable declaration or const enum
+				if let Some(Token(TSXToken::Keyword(TSXKeyword::Enum), _)) = reader.peek_n(1) {
+					let enum_declaration = EnumDeclaration::from_reader(reader, state, options)?;
+					let position = start.union(enum_declaration.get_position());
+					return Ok(Self::Variable {
+						exported: Exportable::Enum(enum_declaration),
+						position,
+					});
+				}
 				let variable_declaration =
 					VariableDeclaration::from_reader(reader, state, options)?;
 				let position = start.union(variable_declaration.get_position());
@@ -127,13 +156,19 @@ impl ASTNode for ExportDeclaration {
 
 					let from_pos = state.expect_keyword(reader, TSXKeyword::From)?;
 
-					let (from, end) =
+					let (from, mut end) =
 						ImportLocation::from_reader(reader, state, options, Some(from_pos))?;
+					let attributes =
+						ImportAttributes::optional_from_reader(reader, state, options)?;
+					if let Some(ref attributes) = attributes {
+						end = attributes.get_position().get_end();
+					}
 
 					Ok(Self::Variable {
 						exported: Exportable::ImportParts {
 							parts,
 							from,
+							attributes,
 							type_definitions_only: true,
 						},
 						position: start.union(end),
@@ -170,12 +205,18 @@ impl ASTNode for ExportDeclaration {
 						let Token(_from_kw, start) = reader.next().unwrap();
 						state.append_keyword_at_pos(start.0, TSXKeyword::From);
 
-						let (from, end) =
+						let (from, mut end) =
 							ImportLocation::from_reader(reader, state, options, Some(start))?;
+						let attributes =
+							ImportAttributes::optional_from_reader(reader, state, options)?;
+						if let Some(ref attributes) = attributes {
+							end = attributes.get_position().get_end();
+						}
 						Ok(Self::Variable {
 							exported: Exportable::ImportParts {
 								parts,
 								from,
+								attributes,
 								type_definitions_only: false,
 							},
 							position: start.union(end),
@@ -217,6 +258,7 @@ impl ASTNode for ExportDeclaration {
 					TSXToken::Keyword(TSXKeyword::Let),
 					TSXToken::Keyword(TSXKeyword::Interface),
 					TSXToken::Keyword(TSXKeyword::Type),
+					TSXToken::Keyword(TSXKeyword::Enum),
 					TSXToken::OpenBrace,
 				],
 			),
@@ -231,6 +273,13 @@ impl ASTNode for ExportDeclaration {
 	) {
 		match self {
 			ExportDeclaration::Variable { exported, .. } => {
+				if let Exportable::Enum(EnumDeclaration { is_constant: true, .. }) = exported {
+					// `const enum`s are inlined so have no output without types
+					if !options.include_types {
+						return;
+					}
+				}
+
 				buf.push_str("export ");
 				match exported {
 					Exportable::Class(class_declaration) => {
@@ -248,6 +297,9 @@ impl ASTNode for ExportDeclaration {
 					Exportable::TypeAlias(type_alias) => {
 						type_alias.to_string_from_buffer(buf, options, local);
 					}
+					Exportable::Enum(r#enum) => {
+						r#enum.to_string_from_buffer(buf, options, local);
+					}
 					Exportable::Parts(parts) => {
 						buf.push('{');
 						options.push_gap_optionally(buf);
@@ -261,18 +313,21 @@ impl ASTNode for ExportDeclaration {
 						options.push_gap_optionally(buf);
 						buf.push('}');
 					}
-					Exportable::ImportAll { r#as, from } => {
+					Exportable::ImportAll { r#as, from, attributes } => {
 						buf.push_str("* ");
 						if let Some(r#as) = r#as {
 							buf.push_str("as ");
 							r#as.to_string_from_buffer(buf, options, local);
 							buf.push(' ');
 						}
-						buf.push_str("from \"");
+						buf.push_str("from ");
 						from.to_string_from_buffer(buf);
-						buf.push('"');
+						if let Some(attributes) = attributes {
+							buf.push(' ');
+							attributes.to_string_from_buffer(buf, options, local);
+						}
 					}
-					Exportable::ImportParts { parts, from, type_definitions_only } => {
+					Exportable::ImportParts { parts, from, attributes, type_definitions_only } => {
 						if *type_definitions_only {
 							buf.push_str("type ");
 						}
@@ -289,9 +344,12 @@ impl ASTNode for ExportDeclaration {
 						options.push_gap_optionally(buf);
 						buf.push('}');
 						options.push_gap_optionally(buf);
-						buf.push_str("from \"");
+						buf.push_str("from ");
 						from.to_string_from_buffer(buf);
-						buf.push('"');
+						if let Some(attributes) = attributes {
+							buf.push(' ');
+							attributes.to_string_from_buffer(buf, options, local);
+						}
 					}
 				}
 			}
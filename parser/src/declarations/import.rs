@@ -1,7 +1,10 @@
 use get_field_by_type::GetFieldByType;
 use iterator_endiate::EndiateIteratorExt;
 use source_map::Span;
-use tokenizer_lib::{sized_tokens::TokenStart, Token, TokenReader};
+use tokenizer_lib::{
+	sized_tokens::{TokenReaderWithTokenEnds, TokenStart},
+	Token, TokenReader,
+};
 
 use crate::{
 	errors::parse_lexing_error, parse_bracketed, throw_unexpected_token,
@@ -33,11 +36,131 @@ pub struct ImportDeclaration {
 	pub default: Option<VariableIdentifier>,
 	pub items: ImportedItems,
 	pub from: ImportLocation,
+	pub attributes: Option<ImportAttributes>,
 	pub position: Span,
 	#[cfg(feature = "extras")]
 	pub reversed: bool,
 }
 
+/// `with { type: "json" }` after the location of an import
+///
+/// <https://github.com/tc39/proposal-import-attributes>
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct ImportAttributes {
+	pub attributes: Vec<ImportAttribute>,
+	pub position: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct ImportAttribute {
+	pub key: String,
+	/// Keys can be identifiers or strings
+	pub key_quoted: Option<Quoted>,
+	pub value: String,
+	pub quoted: Quoted,
+	/// Of the key, for diagnostics about the attribute
+	pub key_position: Span,
+}
+
+impl ImportAttributes {
+	/// The value of the `type` attribute
+	#[must_use]
+	pub fn get_type(&self) -> Option<&str> {
+		self.attributes.iter().find(|attribute| attribute.key == "type").map(|a| a.value.as_str())
+	}
+
+	/// For after the location of `import` and `export ... from` declarations
+	pub(crate) fn optional_from_reader(
+		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
+		state: &mut crate::ParsingState,
+		options: &ParseOptions,
+	) -> ParseResult<Option<Self>> {
+		if let Some(Token(TSXToken::Keyword(TSXKeyword::With), _)) = reader.peek() {
+			Self::from_reader(reader, state, options).map(Some)
+		} else {
+			Ok(None)
+		}
+	}
+}
+
+impl ASTNode for ImportAttributes {
+	fn from_reader(
+		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
+		state: &mut crate::ParsingState,
+		_options: &ParseOptions,
+	) -> ParseResult<Self> {
+		let start = state.expect_keyword(reader, TSXKeyword::With)?;
+		reader.expect_next(TSXToken::OpenBrace)?;
+		let mut attributes = Vec::new();
+		let end = loop {
+			if let Some(Token(TSXToken::CloseBrace, _)) = reader.peek() {
+				break reader.expect_next_get_end(TSXToken::CloseBrace)?;
+			}
+			let token = reader.next().ok_or_else(parse_lexing_error)?;
+			let key_position = token.get_span();
+			let (key, key_quoted) = if let Token(TSXToken::StringLiteral(key, quoted), _) = token {
+				(key, Some(quoted))
+			} else {
+				(token_as_identifier(token, "import attribute key")?.0, None)
+			};
+			reader.expect_next(TSXToken::Colon)?;
+			let token = reader.next().ok_or_else(parse_lexing_error)?;
+			let Token(TSXToken::StringLiteral(value, quoted), _) = token else {
+				return Err(crate::ParseError::new(
+					crate::ParseErrors::ExpectedStringLiteral { found: token.0 },
+					token.1.with_length(0),
+				));
+			};
+			attributes.push(ImportAttribute { key, key_quoted, value, quoted, key_position });
+			if reader.conditional_next(|t| matches!(t, TSXToken::Comma)).is_none() {
+				break reader.expect_next_get_end(TSXToken::CloseBrace)?;
+			}
+		};
+		Ok(Self { attributes, position: start.union(end) })
+	}
+
+	fn to_string_from_buffer<T: source_map::ToString>(
+		&self,
+		buf: &mut T,
+		options: &crate::ToStringOptions,
+		_local: crate::LocalToStringInformation,
+	) {
+		buf.push_str("with");
+		options.push_gap_optionally(buf);
+		buf.push('{');
+		options.push_gap_optionally(buf);
+		for (at_end, attribute) in self.attributes.iter().endiate() {
+			let ImportAttribute { key, key_quoted, value, quoted, key_position: _ } = attribute;
+			if let Some(key_quoted) = key_quoted {
+				buf.push(key_quoted.as_char());
+				buf.push_str(key);
+				buf.push(key_quoted.as_char());
+			} else {
+				buf.push_str(key);
+			}
+			buf.push(':');
+			options.push_gap_optionally(buf);
+			buf.push(quoted.as_char());
+			buf.push_str(value);
+			buf.push(quoted.as_char());
+			if !at_end {
+				buf.push(',');
+				options.push_gap_optionally(buf);
+			}
+		}
+		options.push_gap_optionally(buf);
+		buf.push('}');
+	}
+
+	fn get_position(&self) -> &Span {
+		&self.position
+	}
+}
+
 /// TODO default
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
@@ -92,7 +215,12 @@ impl ASTNode for ImportDeclaration {
 			state.expect_keyword(reader, TSXKeyword::From)?
 		};
 
-		let (from, end) = ImportLocation::from_reader(reader, state, options, Some(start))?;
+		let (from, mut end) = ImportLocation::from_reader(reader, state, options, Some(start))?;
+
+		let attributes = ImportAttributes::optional_from_reader(reader, state, options)?;
+		if let Some(ref attributes) = attributes {
+			end = attributes.get_position().get_end();
+		}
 
 		Ok(ImportDeclaration {
 			default: out.default,
@@ -101,6 +229,7 @@ impl ASTNode for ImportDeclaration {
 			#[cfg(feature = "extras")]
 			is_deferred: out.is_deferred,
 			from,
+			attributes,
 			position: out.start.union(end),
 			#[cfg(feature = "extras")]
 			reversed: false,
@@ -164,6 +293,10 @@ impl ASTNode for ImportDeclaration {
 			options.push_gap_optionally(buf);
 		}
 		self.from.to_string_from_buffer(buf);
+		if let Some(ref attributes) = self.attributes {
+			buf.push(' ');
+			attributes.to_string_from_buffer(buf, options, local);
+		}
 	}
 
 	fn get_position(&self) -> &Span {
@@ -191,6 +324,7 @@ impl ImportDeclaration {
 			#[cfg(feature = "extras")]
 			is_deferred: out.is_deferred,
 			from,
+			attributes: None,
 			position: start.union(out.end),
 			reversed: true,
 		})
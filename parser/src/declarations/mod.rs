@@ -27,10 +27,13 @@ pub use super::types::{
 	declares::*,
 	enum_declaration::{EnumDeclaration, EnumMember},
 	interface::InterfaceDeclaration,
+	namespace::Namespace,
 	type_alias::TypeAlias,
 };
 pub use classes::ClassDeclaration;
-pub use import::{ImportDeclaration, ImportExportName, ImportPart};
+pub use import::{
+	ImportAttribute, ImportAttributes, ImportDeclaration, ImportExportName, ImportPart,
+};
 
 #[derive(
 	Debug, Clone, Visitable, EnumFrom, EnumTryInto, PartialEq, get_field_by_type::GetFieldByType,
@@ -49,6 +52,7 @@ pub enum Declaration {
 	// Special TS only
 	DeclareVariable(DeclareVariableDeclaration),
 	DeclareFunction(DeclareFunctionDeclaration),
+	DeclareNamespace(Namespace),
 	#[from_ignore]
 	DeclareInterface(InterfaceDeclaration),
 	// Top level only
@@ -63,8 +67,9 @@ impl Declaration {
 		reader: &mut impl tokenizer_lib::TokenReader<crate::TSXToken, crate::TokenStart>,
 		options: &ParseOptions,
 	) -> bool {
+		let is_using_declaration = Self::is_using_declaration_start(reader);
 		let Some(Token(token, _)) = reader.peek() else { return false };
-		let result = matches!(
+		let result = is_using_declaration || matches!(
 			token,
 			TSXToken::Keyword(
 				TSXKeyword::Let
@@ -98,6 +103,17 @@ impl Declaration {
 		#[cfg(not(feature = "extras"))]
 		return result;
 	}
+
+	/// `using` (and `await using`) is only a declaration if followed by a name
+	fn is_using_declaration_start(
+		reader: &mut impl tokenizer_lib::TokenReader<crate::TSXToken, crate::TokenStart>,
+	) -> bool {
+		let is_await =
+			matches!(reader.peek(), Some(Token(TSXToken::Keyword(TSXKeyword::Await), _)));
+		let offset = usize::from(is_await);
+		matches!(reader.peek_n(offset), Some(Token(TSXToken::Keyword(TSXKeyword::Using), _)))
+			&& matches!(reader.peek_n(offset + 1), Some(Token(TSXToken::Identifier(_), _)))
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -183,7 +199,7 @@ impl crate::ASTNode for Declaration {
 		match reader.peek().ok_or_else(parse_lexing_error)?.0 {
 			// Const can be either variable declaration or const enum
 			TSXToken::Keyword(TSXKeyword::Const) => {
-				let after_const = reader.peek_n(2);
+				let after_const = reader.peek_n(1);
 				if let Some(Token(TSXToken::Keyword(TSXKeyword::Enum), _)) = after_const {
 					EnumDeclaration::from_reader(reader, state, options)
 						.map(|on| Declaration::Enum(Decorated::new(decorators, on)))
@@ -192,7 +208,7 @@ impl crate::ASTNode for Declaration {
 					Ok(Declaration::Variable(declaration))
 				}
 			}
-			TSXToken::Keyword(TSXKeyword::Let) => {
+			TSXToken::Keyword(TSXKeyword::Let | TSXKeyword::Using | TSXKeyword::Await) => {
 				let declaration = VariableDeclaration::from_reader(reader, state, options)?;
 				Ok(Declaration::Variable(declaration))
 			}
@@ -255,13 +271,14 @@ impl crate::ASTNode for Declaration {
 							ParseErrors::InvalidDeclareItem("type alias"),
 							*item.get_position(),
 						)),
-						TypeDefinitionModuleDeclaration::Namespace(item) => Err(ParseError::new(
-							ParseErrors::InvalidDeclareItem("namespace"),
-							*item.get_position(),
-						)),
+						TypeDefinitionModuleDeclaration::Namespace(item) => {
+							Ok(Declaration::DeclareNamespace(item))
+						}
 						TypeDefinitionModuleDeclaration::LocalTypeAlias(_)
 						| TypeDefinitionModuleDeclaration::LocalVariableDeclaration(_)
-						| TypeDefinitionModuleDeclaration::Comment(_) => unreachable!(),
+						| TypeDefinitionModuleDeclaration::Comment(_)
+						| TypeDefinitionModuleDeclaration::ExportDefault { .. }
+						| TypeDefinitionModuleDeclaration::Export(_) => unreachable!(),
 					})
 			}
 			_ => throw_unexpected_token_with_token(
@@ -302,6 +319,7 @@ impl crate::ASTNode for Declaration {
 			// TODO should skip these under no types
 			Declaration::DeclareFunction(dfd) => dfd.to_string_from_buffer(buf, options, local),
 			Declaration::DeclareVariable(dvd) => dvd.to_string_from_buffer(buf, options, local),
+			Declaration::DeclareNamespace(dn) => dn.to_string_from_buffer(buf, options, local),
 			Declaration::DeclareInterface(did) => {
 				buf.push_str("declare ");
 				did.to_string_from_buffer(buf, options, local);
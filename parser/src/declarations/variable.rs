@@ -203,6 +203,12 @@ pub enum VariableDeclaration {
 		declarations: Vec<VariableDeclarationItem<Option<Expression>>>,
 		position: Span,
 	},
+	/// `using` and `await using`. The value is disposed at the end of the scope
+	UsingDeclaration {
+		declarations: Vec<VariableDeclarationItem<Expression>>,
+		is_await: bool,
+		position: Span,
+	},
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Visitable)]
@@ -211,6 +217,8 @@ pub enum VariableDeclaration {
 pub enum VariableDeclarationKeyword {
 	Const,
 	Let,
+	Using,
+	AwaitUsing,
 }
 
 impl VariableDeclarationKeyword {
@@ -219,15 +227,41 @@ impl VariableDeclarationKeyword {
 		matches!(token, TSXToken::Keyword(TSXKeyword::Const | TSXKeyword::Let))
 	}
 
-	pub(crate) fn from_token(token: Token<TSXToken, crate::TokenStart>) -> ParseResult<Self> {
-		match token {
-			Token(TSXToken::Keyword(TSXKeyword::Const), _) => Ok(Self::Const),
-			Token(TSXToken::Keyword(TSXKeyword::Let), _) => Ok(Self::Let),
-			token => throw_unexpected_token_with_token(
-				token,
-				&[TSXToken::Keyword(TSXKeyword::Const), TSXToken::Keyword(TSXKeyword::Let)],
-			),
-		}
+	/// `await using` is two tokens, so this also reads from the reader
+	pub(crate) fn from_reader(
+		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
+		state: &mut crate::ParsingState,
+	) -> ParseResult<(Self, crate::TokenStart)> {
+		let token = reader.next().ok_or_else(parse_lexing_error)?;
+		let start = token.1;
+		let kind = match token {
+			Token(TSXToken::Keyword(TSXKeyword::Const), _) => Self::Const,
+			Token(TSXToken::Keyword(TSXKeyword::Let), _) => Self::Let,
+			Token(TSXToken::Keyword(TSXKeyword::Using), _) => Self::Using,
+			Token(TSXToken::Keyword(TSXKeyword::Await), _) => {
+				state.append_keyword_at_pos(start.0, TSXKeyword::Await);
+				let using_start = reader.expect_next(TSXToken::Keyword(TSXKeyword::Using))?;
+				state.append_keyword_at_pos(using_start.0, TSXKeyword::Using);
+				return Ok((Self::AwaitUsing, start));
+			}
+			token => {
+				return throw_unexpected_token_with_token(
+					token,
+					&[
+						TSXToken::Keyword(TSXKeyword::Const),
+						TSXToken::Keyword(TSXKeyword::Let),
+						TSXToken::Keyword(TSXKeyword::Using),
+					],
+				)
+			}
+		};
+		let keyword = match kind {
+			Self::Const => TSXKeyword::Const,
+			Self::Let => TSXKeyword::Let,
+			Self::Using | Self::AwaitUsing => TSXKeyword::Using,
+		};
+		state.append_keyword_at_pos(start.0, keyword);
+		Ok((kind, start))
 	}
 
 	#[must_use]
@@ -235,6 +269,8 @@ impl VariableDeclarationKeyword {
 		match self {
 			VariableDeclarationKeyword::Const => "const ",
 			VariableDeclarationKeyword::Let => "let ",
+			VariableDeclarationKeyword::Using => "using ",
+			VariableDeclarationKeyword::AwaitUsing => "await using ",
 		}
 	}
 }
@@ -245,12 +281,9 @@ impl ASTNode for VariableDeclaration {
 		state: &mut crate::ParsingState,
 		options: &ParseOptions,
 	) -> ParseResult<Self> {
-		let token = reader.next().ok_or_else(parse_lexing_error)?;
-		let start = token.1;
-		let kind = VariableDeclarationKeyword::from_token(token)?;
+		let (kind, start) = VariableDeclarationKeyword::from_reader(reader, state)?;
 		Ok(match kind {
 			VariableDeclarationKeyword::Let => {
-				state.append_keyword_at_pos(start.0, TSXKeyword::Let);
 				let mut declarations = Vec::new();
 				loop {
 					let value = VariableDeclarationItem::<Option<Expression>>::from_reader(
@@ -278,12 +311,21 @@ impl ASTNode for VariableDeclaration {
 					declarations,
 				}
 			}
-			VariableDeclarationKeyword::Const => {
-				state.append_keyword_at_pos(start.0, TSXKeyword::Const);
+			VariableDeclarationKeyword::Const
+			| VariableDeclarationKeyword::Using
+			| VariableDeclarationKeyword::AwaitUsing => {
 				let mut declarations = Vec::new();
 				loop {
 					let value =
 						VariableDeclarationItem::<Expression>::from_reader(reader, state, options)?;
+					if kind != VariableDeclarationKeyword::Const
+						&& !matches!(value.name.get_ast_ref(), VariableField::Name(_))
+					{
+						return Err(crate::ParseError::new(
+							crate::ParseErrors::UsingDeclarationRequiresIdentifier,
+							*value.name.get_ast_ref().get_position(),
+						));
+					}
 					declarations.push(value);
 					if let Some(Token(TSXToken::Comma, _)) = reader.peek() {
 						reader.next();
@@ -291,9 +333,12 @@ impl ASTNode for VariableDeclaration {
 						break;
 					}
 				}
-				VariableDeclaration::ConstDeclaration {
-					position: start.union(declarations.last().unwrap().get_position()),
-					declarations,
+				let position = start.union(declarations.last().unwrap().get_position());
+				if let VariableDeclarationKeyword::Const = kind {
+					VariableDeclaration::ConstDeclaration { position, declarations }
+				} else {
+					let is_await = matches!(kind, VariableDeclarationKeyword::AwaitUsing);
+					VariableDeclaration::UsingDeclaration { declarations, is_await, position }
 				}
 			}
 		})
@@ -320,6 +365,16 @@ impl ASTNode for VariableDeclaration {
 				buf.push_str("const ");
 				declarations_to_string(declarations, buf, options, local);
 			}
+			VariableDeclaration::UsingDeclaration { declarations, is_await, .. } => {
+				if declarations.is_empty() {
+					return;
+				}
+				if *is_await {
+					buf.push_str("await ");
+				}
+				buf.push_str("using ");
+				declarations_to_string(declarations, buf, options, local);
+			}
 		}
 	}
 
@@ -331,7 +386,11 @@ impl ASTNode for VariableDeclaration {
 impl VariableDeclaration {
 	#[must_use]
 	pub fn is_constant(&self) -> bool {
-		matches!(self, VariableDeclaration::ConstDeclaration { .. })
+		matches!(
+			self,
+			VariableDeclaration::ConstDeclaration { .. }
+				| VariableDeclaration::UsingDeclaration { .. }
+		)
 	}
 }
 
@@ -21,6 +21,7 @@ pub enum ParseErrors<'a> {
 	ExpectedCatchOrFinally,
 	InvalidDeclareItem(&'static str),
 	DestructuringRequiresValue,
+	UsingDeclarationRequiresIdentifier,
 	CannotAccessObjectLiteralDirectly,
 	TrailingCommaNotAllowedHere,
 }
@@ -157,6 +158,9 @@ impl<'a> Display for ParseErrors<'a> {
 			ParseErrors::DestructuringRequiresValue => {
 				write!(f, "RHS of destructured declaration requires expression")
 			}
+			ParseErrors::UsingDeclarationRequiresIdentifier => {
+				write!(f, "using declarations cannot be destructured")
+			}
 			ParseErrors::CannotAccessObjectLiteralDirectly => {
 				write!(f, "Cannot get property on object literal directly")
 			}
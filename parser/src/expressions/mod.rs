@@ -292,7 +292,7 @@ impl Expression {
 				} else {
 					None
 				};
-				let end = reader.expect_next(TSXToken::OpenParentheses)?;
+				let end = reader.expect_next(TSXToken::CloseParentheses)?;
 				Expression::DynamicImport {
 					path: Box::new(path),
 					options,
@@ -1453,9 +1453,14 @@ impl Expression {
 			Self::NewTarget(..) => {
 				buf.push_str("new.target");
 			}
-			Self::DynamicImport { path, .. } => {
+			Self::DynamicImport { path, options: import_options, .. } => {
 				buf.push_str("import(");
 				path.to_string_from_buffer(buf, options, local);
+				if let Some(import_options) = import_options {
+					buf.push(',');
+					options.push_gap_optionally(buf);
+					import_options.to_string_from_buffer(buf, options, local);
+				}
 				buf.push(')');
 			}
 			Self::PropertyAccess { parent, property, is_optional, position, .. } => {
@@ -350,11 +350,14 @@ impl ASTNode for JSXAttribute {
 				buf.push_str(key.as_str());
 			}
 			JSXAttribute::Spread(expr, _) => {
-				buf.push_str("...");
+				buf.push_str("{...");
 				expr.to_string_from_buffer(buf, options, local);
+				buf.push('}');
 			}
 			JSXAttribute::Shorthand(expr) => {
+				buf.push('{');
 				expr.to_string_from_buffer(buf, options, local);
+				buf.push('}');
 			}
 		}
 	}
@@ -390,10 +393,11 @@ impl JSXElement {
 					let attribute = if let Some(Token(TSXToken::Spread, _)) = reader.peek() {
 						let spread_token = reader.next().unwrap();
 						let expr = Expression::from_reader(reader, state, options)?;
-						reader.expect_next(TSXToken::CloseBrace)?;
+						reader.expect_next(TSXToken::JSXExpressionEnd)?;
 						JSXAttribute::Spread(expr, spread_token.get_span())
 					} else {
 						let expr = Expression::from_reader(reader, state, options)?;
+						reader.expect_next(TSXToken::JSXExpressionEnd)?;
 						JSXAttribute::Shorthand(expr)
 					};
 					attributes.push(attribute);
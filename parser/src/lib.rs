@@ -112,6 +112,10 @@ pub struct ParseOptions {
 	pub interpolation_points: bool,
 	/// For LSP
 	pub partial_syntax: bool,
+	/// Rather than stopping at the first syntax error, skip to the next statement or declaration
+	/// and continue. Errors are collected in [`ParsingState::errors`] and broken regions are
+	/// replaced with [`Marker`]s
+	pub error_recovery: bool,
 }
 
 impl ParseOptions {
@@ -139,6 +143,7 @@ impl ParseOptions {
 			// Only used in the AST-generator
 			interpolation_points: false,
 			partial_syntax: true,
+			error_recovery: false,
 		}
 	}
 }
@@ -159,6 +164,7 @@ impl Default for ParseOptions {
 			record_keyword_positions: false,
 			interpolation_points: false,
 			partial_syntax: false,
+			error_recovery: false,
 		}
 	}
 }
@@ -270,8 +276,7 @@ impl LocalToStringInformation {
 		LocalToStringInformation { under: self.under, depth: self.depth + 1 }
 	}
 
-	/// TODO for bundling
-	pub(crate) fn _change_source(self, new: SourceId) -> LocalToStringInformation {
+	pub(crate) fn change_source(self, new: SourceId) -> LocalToStringInformation {
 		LocalToStringInformation { under: new, depth: self.depth }
 	}
 }
@@ -323,7 +328,6 @@ pub trait ASTNode: Sized + Clone + PartialEq + std::fmt::Debug + Sync + Send + '
 	}
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 #[doc(hidden)]
 pub fn lex_and_parse_script<T: ASTNode>(
 	line_starts: source_map::LineStarts,
@@ -331,6 +335,18 @@ pub fn lex_and_parse_script<T: ASTNode>(
 	script: &str,
 	offset: Option<u32>,
 ) -> Result<(T, ParsingState), ParseError> {
+	lex_and_parse_script_keeping_errors(line_starts, options, script, offset)
+		.map_err(|(error, _)| error)
+}
+
+/// On failure, also returns the errors recovered from before it
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn lex_and_parse_script_keeping_errors<T: ASTNode>(
+	line_starts: source_map::LineStarts,
+	options: ParseOptions,
+	script: &str,
+	offset: Option<u32>,
+) -> Result<(T, ParsingState), (ParseError, Vec<ParseError>)> {
 	let (mut sender, mut reader) =
 		tokenizer_lib::ParallelTokenQueue::new_with_buffer_size(options.buffer_size);
 	let lex_options = options.get_lex_options();
@@ -340,6 +356,8 @@ pub fn lex_and_parse_script<T: ASTNode>(
 		thread = thread.stack_size(stack_size);
 	}
 
+	let error_recovery = options.error_recovery;
+
 	let parsing_thread = thread
 		.spawn(move || {
 			let mut state = ParsingState {
@@ -350,18 +368,38 @@ pub fn lex_and_parse_script<T: ASTNode>(
 					.record_keyword_positions
 					.then_some(KeywordPositions::new()),
 				partial_points: Default::default(),
+				errors: Default::default(),
 			};
-			let res = T::from_reader(&mut reader, &mut state, &options);
-			if res.is_ok() {
-				reader.expect_next(TSXToken::EOS)?;
+			let res = T::from_reader(&mut reader, &mut state, &options).and_then(|res| {
+				// Under recovery the token stream may have been cut short by a lexing error
+				if !(options.error_recovery && reader.peek().is_none()) {
+					reader.expect_next(TSXToken::EOS)?;
+				}
+				Ok(res)
+			});
+			match res {
+				Ok(res) => Ok((res, state)),
+				Err(error) => Err((error, state.errors)),
 			}
-			res.map(|res| (res, state))
 		})
 		.unwrap();
 
 	let lex_result = lexer::lex_script(script, &mut sender, &lex_options, offset);
 	if let Err((reason, pos)) = lex_result {
-		return Err(ParseError::new(reason, pos));
+		let error = ParseError::new(reason, pos);
+		if error_recovery {
+			// Keep what was parsed before the lexing error
+			drop(sender);
+			return match parsing_thread.join() {
+				Ok(Ok((ast, state))) => Ok((ast, state.with_lexing_error(error))),
+				Ok(Err((_, mut errors))) => {
+					retain_errors_before_lexing_error(&mut errors, &error);
+					Err((error, errors))
+				}
+				Err(_) => Err((error, Vec::new())),
+			};
+		}
+		return Err((error, Vec::new()));
 	}
 	drop(sender);
 	parsing_thread.join().expect("Parsing panicked")
@@ -369,19 +407,21 @@ pub fn lex_and_parse_script<T: ASTNode>(
 
 // WASM has no threads, so this is a sequential version
 #[cfg(target_arch = "wasm32")]
-#[doc(hidden)]
-pub fn lex_and_parse_script<T: ASTNode>(
+pub(crate) fn lex_and_parse_script_keeping_errors<T: ASTNode>(
 	line_starts: source_map::LineStarts,
 	options: ParseOptions,
 	script: &str,
 	offset: Option<u32>,
-) -> Result<(T, ParsingState), ParseError> {
+) -> Result<(T, ParsingState), (ParseError, Vec<ParseError>)> {
 	let mut queue = tokenizer_lib::BufferedTokenQueue::new();
 	let lex_result = lexer::lex_script(script, &mut queue, &options.get_lex_options(), offset);
 
-	if let Err((reason, pos)) = lex_result {
-		return Err(ParseError::new(reason, pos));
-	}
+	let lexing_error = match lex_result {
+		Ok(()) => None,
+		// Keep what was parsed before the lexing error
+		Err((reason, pos)) if options.error_recovery => Some(ParseError::new(reason, pos)),
+		Err((reason, pos)) => return Err((ParseError::new(reason, pos), Vec::new())),
+	};
 
 	let mut state = ParsingState {
 		line_starts,
@@ -389,12 +429,33 @@ pub fn lex_and_parse_script<T: ASTNode>(
 		constant_imports: Default::default(),
 		keyword_positions: options.record_keyword_positions.then_some(KeywordPositions::new()),
 		partial_points: Default::default(),
+		errors: Default::default(),
 	};
 	let res = T::from_reader(&mut queue, &mut state, &options);
-	if res.is_ok() {
+	if let Some(error) = lexing_error {
+		return match res {
+			Ok(res) => Ok((res, state.with_lexing_error(error))),
+			Err(_) => {
+				retain_errors_before_lexing_error(&mut state.errors, &error);
+				Err((error, state.errors))
+			}
+		};
+	}
+	let res = res.and_then(|res| {
 		queue.expect_next(TSXToken::EOS)?;
+		Ok(res)
+	});
+	match res {
+		Ok(res) => Ok((res, state)),
+		Err(error) => Err((error, state.errors)),
 	}
-	res.map(|res| (res, state))
+}
+
+/// Errors after the lexing error are caused by the token stream ending early
+fn retain_errors_before_lexing_error(errors: &mut Vec<ParseError>, error: &ParseError) {
+	errors.retain(|e| {
+		e.position.end < error.position.start && e.position != source_map::Nullable::NULL
+	});
 }
 
 pub(crate) fn throw_unexpected_token<T>(
@@ -420,9 +481,17 @@ pub struct ParsingState {
 	pub(crate) constant_imports: Vec<String>,
 	pub keyword_positions: Option<KeywordPositions>,
 	pub partial_points: Vec<TokenStart>,
+	/// Errors skipped over under [`ParseOptions::error_recovery`]
+	pub errors: Vec<ParseError>,
 }
 
 impl ParsingState {
+	fn with_lexing_error(mut self, error: ParseError) -> Self {
+		retain_errors_before_lexing_error(&mut self.errors, &error);
+		self.errors.push(error);
+		self
+	}
+
 	pub(crate) fn expect_keyword(
 		&mut self,
 		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
@@ -1048,12 +1117,35 @@ pub enum VariableKeyword {
 	Const,
 	Let,
 	Var,
+	Using,
+	AwaitUsing,
 }
 
 impl VariableKeyword {
 	#[must_use]
 	pub fn is_token_variable_keyword(token: &TSXToken) -> bool {
-		matches!(token, TSXToken::Keyword(TSXKeyword::Const | TSXKeyword::Let | TSXKeyword::Var))
+		matches!(
+			token,
+			TSXToken::Keyword(
+				TSXKeyword::Const | TSXKeyword::Let | TSXKeyword::Var | TSXKeyword::Using
+			)
+		)
+	}
+
+	/// Reads a keyword (including the two token `await using`) if there is one next
+	pub(crate) fn optional_from_reader(
+		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
+	) -> Option<(crate::TokenStart, Self)> {
+		if matches!(reader.peek(), Some(Token(TSXToken::Keyword(TSXKeyword::Await), _)))
+			&& matches!(reader.peek_n(1), Some(Token(TSXToken::Keyword(TSXKeyword::Using), _)))
+		{
+			let Token(_, start) = reader.next().unwrap();
+			reader.next();
+			return Some((start, Self::AwaitUsing));
+		}
+		reader
+			.conditional_next(Self::is_token_variable_keyword)
+			.map(|token| (token.1, Self::from_reader(token).unwrap()))
 	}
 
 	pub(crate) fn from_reader(token: Token<TSXToken, crate::TokenStart>) -> ParseResult<Self> {
@@ -1061,12 +1153,14 @@ impl VariableKeyword {
 			Token(TSXToken::Keyword(TSXKeyword::Const), _) => Ok(Self::Const),
 			Token(TSXToken::Keyword(TSXKeyword::Let), _) => Ok(Self::Let),
 			Token(TSXToken::Keyword(TSXKeyword::Var), _) => Ok(Self::Var),
+			Token(TSXToken::Keyword(TSXKeyword::Using), _) => Ok(Self::Using),
 			token => crate::throw_unexpected_token_with_token(
 				token,
 				&[
 					TSXToken::Keyword(TSXKeyword::Const),
 					TSXToken::Keyword(TSXKeyword::Let),
 					TSXToken::Keyword(TSXKeyword::Var),
+					TSXToken::Keyword(TSXKeyword::Using),
 				],
 			),
 		}
@@ -1078,6 +1172,8 @@ impl VariableKeyword {
 			Self::Const => "const ",
 			Self::Let => "let ",
 			Self::Var => "var ",
+			Self::Using => "using ",
+			Self::AwaitUsing => "await using ",
 		}
 	}
 }
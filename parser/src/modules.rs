@@ -2,6 +2,7 @@ use tokenizer_lib::sized_tokens::TokenStart;
 
 use crate::{
 	block::{parse_statements_and_declarations, statements_and_declarations_to_string},
+	declarations::ExportDeclaration,
 	errors::parse_lexing_error,
 	extensions::decorators::decorators_from_reader,
 	throw_unexpected_token_with_token,
@@ -9,12 +10,12 @@ use crate::{
 		declares::{
 			DeclareClassDeclaration, DeclareFunctionDeclaration, DeclareVariableDeclaration,
 		},
-		namespace::Namespace,
+		namespace::{is_module_keyword, Namespace},
 		type_alias::TypeAlias,
 		InterfaceDeclaration,
 	},
-	BlockLike, BlockLikeMut, Decorated, Decorator, LocalToStringInformation, ParseOptions,
-	ParseResult, StatementOrDeclaration, TSXKeyword, VisitOptions,
+	BlockLike, BlockLikeMut, Decorated, Decorator, LocalToStringInformation, ParseErrors,
+	ParseOptions, ParseResult, StatementOrDeclaration, TSXKeyword, VisitOptions,
 };
 
 use super::{ASTNode, ParseError, Span, TSXToken, Token, TokenReader};
@@ -52,14 +53,45 @@ impl ASTNode for Module {
 		options: &ParseOptions,
 	) -> ParseResult<Self> {
 		let end = state.length_of_source;
-		parse_statements_and_declarations(reader, state, options).map(|statements| Module {
-			items: statements,
-			span: Span { start: 0, source: (), end },
-		})
+		let mut items = parse_statements_and_declarations(reader, state, options)?;
+		if options.error_recovery {
+			// Unbalanced closing braces end the statement list early
+			while let Some(Token(TSXToken::CloseBrace, _)) = reader.peek() {
+				let token = reader.next().unwrap();
+				let position = token.get_span();
+				// Otherwise probably a result of an earlier error
+				if state.errors.is_empty() {
+					state.errors.push(ParseError::new(
+						ParseErrors::UnexpectedToken { expected: &[TSXToken::EOS], found: token.0 },
+						position,
+					));
+				}
+				items.push(StatementOrDeclaration::Marker(
+					state.new_partial_point_marker(token.1),
+					position,
+				));
+				items.extend(parse_statements_and_declarations(reader, state, options)?);
+			}
+		}
+		Ok(Module { items, span: Span { start: 0, source: (), end } })
 	}
 }
 
 impl Module {
+	/// Parses with [`ParseOptions::error_recovery`], so returns every syntax error found alongside
+	/// the statements and declarations that could be parsed. If parsing could not recover, the
+	/// error it stopped at is returned next to the errors recovered from before it
+	#[allow(clippy::needless_pass_by_value)]
+	pub fn from_string_with_recovery(
+		script: String,
+		mut options: ParseOptions,
+	) -> Result<(Self, Vec<ParseError>), (ParseError, Vec<ParseError>)> {
+		options.error_recovery = true;
+		let line_starts = source_map::LineStarts::new(script.as_str());
+		crate::lex_and_parse_script_keeping_errors(line_starts, options, &script, None)
+			.map(|(module, state)| (module, state.errors))
+	}
+
 	pub fn to_string_with_source_map(
 		&self,
 		options: &crate::ToStringOptions,
@@ -75,6 +107,38 @@ impl Module {
 		buf.build(fs)
 	}
 
+	/// Prints `modules` one after another, for example for a bundle. Mappings for each module
+	/// point to the [`source_map::SourceId`] it is paired with
+	pub fn multiple_to_string_with_source_map<'a>(
+		modules: impl IntoIterator<Item = (source_map::SourceId, &'a Module)>,
+		options: &crate::ToStringOptions,
+		fs: &impl source_map::FileSystem,
+	) -> (String, Option<source_map::SourceMap>) {
+		use source_map::ToString;
+
+		let mut buf = source_map::StringWithOptionalSourceMap::new(true);
+		let local = LocalToStringInformation { depth: 0, under: source_map::Nullable::NULL };
+		// `None` before the first module
+		let mut last_requires_semi_colon: Option<bool> = None;
+		for (source, module) in modules {
+			if module.items.is_empty() {
+				continue;
+			}
+			if let Some(last_requires_semi_colon) = last_requires_semi_colon {
+				if last_requires_semi_colon && !options.trailing_semicolon {
+					buf.push(';');
+				}
+				if options.pretty {
+					buf.push_new_line();
+				}
+			}
+			module.to_string_from_buffer(&mut buf, options, local.change_source(source));
+			last_requires_semi_colon =
+				module.items.last().map(StatementOrDeclaration::requires_semi_colon);
+		}
+		buf.build(fs)
+	}
+
 	// #[must_use]
 	// pub fn length(&self, options: &crate::ToStringOptions) -> usize {
 	// 	let mut buf = source_map::Counter::new();
@@ -153,6 +217,8 @@ impl<'a> From<&'a mut Module> for BlockLikeMut<'a> {
 
 /// Statements for '.d.ts' files
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
 pub enum TypeDefinitionModuleDeclaration {
 	Variable(DeclareVariableDeclaration),
 	Function(DeclareFunctionDeclaration),
@@ -166,6 +232,15 @@ pub enum TypeDefinitionModuleDeclaration {
 	LocalTypeAlias(TypeAlias),
 	// Variable without declare in front. Found in namespaces
 	LocalVariableDeclaration(DeclareVariableDeclaration),
+	/// `export = name` and `export default name`. `declaration` is set for
+	/// `export default function name(): ...` etc
+	ExportDefault {
+		name: String,
+		declaration: Option<Box<Self>>,
+		position: Span,
+	},
+	/// `export { ... }`, `export { ... } from "..."` and `export * from "..."`
+	Export(Box<ExportDeclaration>),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -239,6 +314,78 @@ impl ASTNode for TypeDefinitionModuleDeclaration {
 				let declare_span = reader.next().unwrap().1;
 				parse_declare_item(reader, state, options, decorators, declare_span)
 			}
+			Token(TSXToken::Keyword(TSXKeyword::Export), _) => {
+				let is_export_list =
+					matches!(
+						reader.peek_n(1),
+						Some(Token(TSXToken::OpenBrace | TSXToken::Multiply, _))
+					) || (matches!(
+						reader.peek_n(1),
+						Some(Token(TSXToken::Keyword(TSXKeyword::Type), _))
+					) && matches!(reader.peek_n(2), Some(Token(TSXToken::OpenBrace, _))));
+
+				if is_export_list {
+					return ExportDeclaration::from_reader(reader, state, options)
+						.map(|export| TypeDefinitionModuleDeclaration::Export(Box::new(export)));
+				}
+
+				let start = reader.next().unwrap().1;
+
+				let is_default = reader
+					.conditional_next(|token| {
+						matches!(token, TSXToken::Assign | TSXToken::Keyword(TSXKeyword::Default))
+					})
+					.is_some();
+
+				if !is_default {
+					// Everything in a definition file is exported, so `export` does not change
+					// anything (except `type` aliases are no longer local)
+					return match TypeDefinitionModuleDeclaration::from_reader(
+						reader, state, options,
+					)? {
+						TypeDefinitionModuleDeclaration::LocalTypeAlias(alias) => {
+							Ok(TypeDefinitionModuleDeclaration::TypeAlias(alias))
+						}
+						declaration => Ok(declaration),
+					};
+				}
+
+				if let Some(Token(TSXToken::Identifier(_), _)) = reader.peek() {
+					let Some(Token(TSXToken::Identifier(name), name_start)) = reader.next() else {
+						unreachable!()
+					};
+					let position = start.union(name_start.get_end_after(name.len()));
+					return Ok(TypeDefinitionModuleDeclaration::ExportDefault {
+						name,
+						declaration: None,
+						position,
+					});
+				}
+				let declaration =
+					TypeDefinitionModuleDeclaration::from_reader(reader, state, options)?;
+				let (name, end) = match &declaration {
+					TypeDefinitionModuleDeclaration::Function(function) => {
+						(function.name.clone(), function.position.get_end())
+					}
+					TypeDefinitionModuleDeclaration::Interface(interface) => {
+						(interface.on.name.clone(), interface.on.position.get_end())
+					}
+					_ => {
+						return Err(ParseError::new(
+							ParseErrors::ExpectedIdent {
+								found: TSXToken::Keyword(TSXKeyword::Default),
+								at_location: "default export of definition file",
+							},
+							start.with_length(6),
+						));
+					}
+				};
+				Ok(TypeDefinitionModuleDeclaration::ExportDefault {
+					name,
+					declaration: Some(Box::new(declaration)),
+					position: start.union(end),
+				})
+			}
 			Token(TSXToken::Keyword(TSXKeyword::Interface), _) => {
 				let on = InterfaceDeclaration::from_reader(reader, state, options)?;
 				Ok(TypeDefinitionModuleDeclaration::Interface(Decorated::new(decorators, on)))
@@ -255,6 +402,24 @@ impl ASTNode for TypeDefinitionModuleDeclaration {
 					)?,
 				))
 			}
+			// Items in a namespace do not need `declare`
+			Token(
+				TSXToken::Keyword(
+					TSXKeyword::Const
+					| TSXKeyword::Let
+					| TSXKeyword::Function
+					| TSXKeyword::Class
+					| TSXKeyword::Namespace,
+				),
+				start,
+			) => {
+				let start = *start;
+				parse_declare_item(reader, state, options, decorators, start)
+			}
+			Token(token, start) if is_module_keyword(token) => {
+				let start = *start;
+				parse_declare_item(reader, state, options, decorators, start)
+			}
 			Token(TSXToken::Comment(_) | TSXToken::MultiLineComment(_), _) => {
 				let (TSXToken::MultiLineComment(comment) | TSXToken::Comment(comment)) =
 					reader.next().unwrap().0
@@ -270,6 +435,7 @@ impl ASTNode for TypeDefinitionModuleDeclaration {
 					TSXToken::Keyword(TSXKeyword::Interface),
 					TSXToken::Keyword(TSXKeyword::Type),
 					TSXToken::Keyword(TSXKeyword::Var),
+					TSXToken::Keyword(TSXKeyword::Export),
 					TSXToken::At,
 				],
 			),
@@ -278,11 +444,50 @@ impl ASTNode for TypeDefinitionModuleDeclaration {
 
 	fn to_string_from_buffer<T: source_map::ToString>(
 		&self,
-		_buf: &mut T,
-		_options: &crate::ToStringOptions,
-		_local: crate::LocalToStringInformation,
+		buf: &mut T,
+		options: &crate::ToStringOptions,
+		local: crate::LocalToStringInformation,
 	) {
-		todo!("tdms to_string_from_buffer");
+		match self {
+			TypeDefinitionModuleDeclaration::Variable(variable)
+			| TypeDefinitionModuleDeclaration::LocalVariableDeclaration(variable) => {
+				variable.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::Function(function) => {
+				function.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::Class(class) => {
+				class.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::Interface(interface) => {
+				interface.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::TypeAlias(alias)
+			| TypeDefinitionModuleDeclaration::LocalTypeAlias(alias) => {
+				alias.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::Namespace(namespace) => {
+				namespace.to_string_from_buffer(buf, options, local);
+			}
+			TypeDefinitionModuleDeclaration::Comment(comment) => {
+				if options.should_add_comment(comment.starts_with('*')) {
+					buf.push_str("/*");
+					buf.push_str(comment);
+					buf.push_str("*/");
+				}
+			}
+			TypeDefinitionModuleDeclaration::ExportDefault { name, declaration, .. } => {
+				buf.push_str("export default ");
+				if let Some(declaration) = declaration {
+					declaration.to_string_from_buffer(buf, options, local);
+				} else {
+					buf.push_str(name);
+				}
+			}
+			TypeDefinitionModuleDeclaration::Export(export) => {
+				export.to_string_from_buffer(buf, options, local);
+			}
+		}
 	}
 
 	fn get_position(&self) -> &Span {
@@ -332,6 +537,11 @@ pub(crate) fn parse_declare_item(
 				reader, state, options,
 			)?))
 		}
+		Some(Token(token, _)) if is_module_keyword(token) => {
+			Ok(TypeDefinitionModuleDeclaration::Namespace(Namespace::from_reader(
+				reader, state, options,
+			)?))
+		}
 		_ => throw_unexpected_token_with_token(
 			reader.next().ok_or_else(parse_lexing_error)?,
 			&[
@@ -129,6 +129,9 @@ impl ASTNode for ForLoopCondition {
 					_ => {}
 				}
 				destructuring_depth == 0
+			} else if let TSXToken::Keyword(TSXKeyword::Await) = token {
+				// Could be `await using`
+				false
 			} else {
 				ate_variable_specifier = true;
 				!VariableKeyword::is_token_variable_keyword(token)
@@ -137,10 +140,7 @@ impl ASTNode for ForLoopCondition {
 
 		let condition = match next {
 			Some(Token(TSXToken::Keyword(TSXKeyword::Of), _)) => {
-				let (start, keyword) = reader
-					.conditional_next(VariableKeyword::is_token_variable_keyword)
-					.map(|token| (token.1, VariableKeyword::from_reader(token).unwrap()))
-					.unzip();
+				let (start, keyword) = VariableKeyword::optional_from_reader(reader).unzip();
 
 				let variable =
 					WithComment::<VariableField<_>>::from_reader(reader, state, options)?;
@@ -154,10 +154,7 @@ impl ASTNode for ForLoopCondition {
 				Self::ForOf { variable, keyword, of, position }
 			}
 			Some(Token(TSXToken::Keyword(TSXKeyword::In), _)) => {
-				let (start, keyword) = reader
-					.conditional_next(VariableKeyword::is_token_variable_keyword)
-					.map(|token| (token.1, VariableKeyword::from_reader(token).unwrap()))
-					.unzip();
+				let (start, keyword) = VariableKeyword::optional_from_reader(reader).unzip();
 
 				let variable =
 					WithComment::<VariableField<_>>::from_reader(reader, state, options)?;
@@ -171,22 +168,34 @@ impl ASTNode for ForLoopCondition {
 				Self::ForIn { variable, keyword, r#in, position }
 			}
 			_ => {
+				let is_await_using =
+					matches!(reader.peek(), Some(Token(TSXToken::Keyword(TSXKeyword::Await), _)))
+						&& matches!(
+							reader.peek_n(1),
+							Some(Token(TSXToken::Keyword(TSXKeyword::Using), _))
+						);
 				let peek = reader.peek();
-				let initializer =
-					if let Some(Token(TSXToken::Keyword(TSXKeyword::Const | TSXKeyword::Let), _)) =
-						peek
-					{
-						let declaration = VariableDeclaration::from_reader(reader, state, options)?;
-						Some(ForLoopStatementInitializer::VariableDeclaration(declaration))
-					} else if let Some(Token(TSXToken::Keyword(TSXKeyword::Var), _)) = peek {
-						let stmt = VarVariableStatement::from_reader(reader, state, options)?;
-						Some(ForLoopStatementInitializer::VarStatement(stmt))
-					} else if let Some(Token(TSXToken::SemiColon, _)) = peek {
-						None
-					} else {
-						let expr = MultipleExpression::from_reader(reader, state, options)?;
-						Some(ForLoopStatementInitializer::Expression(expr))
-					};
+				let initializer = if is_await_using
+					|| matches!(
+						peek,
+						Some(Token(
+							TSXToken::Keyword(
+								TSXKeyword::Const | TSXKeyword::Let | TSXKeyword::Using
+							),
+							_
+						))
+					) {
+					let declaration = VariableDeclaration::from_reader(reader, state, options)?;
+					Some(ForLoopStatementInitializer::VariableDeclaration(declaration))
+				} else if let Some(Token(TSXToken::Keyword(TSXKeyword::Var), _)) = peek {
+					let stmt = VarVariableStatement::from_reader(reader, state, options)?;
+					Some(ForLoopStatementInitializer::VarStatement(stmt))
+				} else if let Some(Token(TSXToken::SemiColon, _)) = peek {
+					None
+				} else {
+					let expr = MultipleExpression::from_reader(reader, state, options)?;
+					Some(ForLoopStatementInitializer::Expression(expr))
+				};
 
 				let semi_colon_one = reader.expect_next(TSXToken::SemiColon)?;
 				let start = initializer.as_ref().map_or(semi_colon_one, |init| match init {
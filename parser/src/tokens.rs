@@ -294,12 +294,12 @@ impl Eq for TSXToken {}
 #[enum_variants_strings_transform(transform = "lower_case")]
 #[rustfmt::skip]
 pub enum TSXKeyword {
-    Const, Var, Let,
+    Const, Var, Let, Using,
     If, Else, For, While, Do, Switch,
     Class, Function, Constructor,
     New, This, Super,
     Case, Yield, Return, Continue, Break,
-    Import, Export, Default, From,
+    Import, Export, Default, From, With,
     In, Of,
     TypeOf, InstanceOf, Void, Delete, 
     Debugger,
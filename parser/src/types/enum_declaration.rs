@@ -61,6 +61,14 @@ impl ASTNode for EnumDeclaration {
 		options: &crate::ToStringOptions,
 		local: crate::LocalToStringInformation,
 	) {
+		if !options.include_types {
+			// `const enum`s are inlined so have no output
+			if !self.is_constant {
+				self.to_javascript_from_buffer(buf, options, local);
+			}
+			return;
+		}
+
 		if self.is_constant {
 			buf.push_str("const ");
 		}
@@ -85,6 +93,102 @@ impl ASTNode for EnumDeclaration {
 	}
 }
 
+impl EnumDeclaration {
+	/// Same output as `tsc`. Numeric members have a reverse mapping
+	///
+	/// ```js
+	/// var Direction;
+	/// (function (Direction) {
+	///     Direction[Direction["Up"] = 0] = "Up";
+	/// })(Direction || (Direction = {}));
+	/// ```
+	fn to_javascript_from_buffer<T: source_map::ToString>(
+		&self,
+		buf: &mut T,
+		options: &crate::ToStringOptions,
+		local: crate::LocalToStringInformation,
+	) {
+		let name = self.name.as_str();
+		buf.push_str("var ");
+		buf.push_str(name);
+		buf.push(';');
+		if options.pretty {
+			buf.push_new_line();
+			options.add_indent(local.depth, buf);
+		}
+		buf.push_str("(function");
+		options.push_gap_optionally(buf);
+		buf.push('(');
+		buf.push_str(name);
+		buf.push(')');
+		options.push_gap_optionally(buf);
+		buf.push('{');
+
+		// The value of the next member without an initialiser
+		// (or the member it follows)
+		let mut next_value: Result<f64, &str> = Ok(0f64);
+		for member in &self.members {
+			let EnumMember::Variant { name: member_name, value, .. } = member;
+			if options.pretty {
+				buf.push_new_line();
+				options.add_indent(local.depth + 1, buf);
+			}
+			let is_string = matches!(value, Some(Expression::StringLiteral(..)));
+			if !is_string {
+				buf.push_str(name);
+				buf.push('[');
+			}
+			buf.push_str(name);
+			buf.push_str("[\"");
+			buf.push_str(member_name);
+			buf.push_str("\"]");
+			buf.push_str(if options.pretty { " = " } else { "=" });
+			match value {
+				Some(value) => {
+					value.to_string_from_buffer(buf, options, local);
+					next_value = if let Expression::NumberLiteral(number, _) = value {
+						f64::try_from(number.clone())
+							.map(|value| value + 1f64)
+							.map_err(|()| member_name.as_str())
+					} else {
+						Err(member_name)
+					};
+				}
+				None => match next_value {
+					Ok(value) => {
+						buf.push_str(&crate::NumberRepresentation::from(value).as_js_string());
+						next_value = Ok(value + 1f64);
+					}
+					Err(previous) => {
+						buf.push_str(name);
+						buf.push_str("[\"");
+						buf.push_str(previous);
+						buf.push_str("\"]");
+						buf.push_str(if options.pretty { " + 1" } else { "+1" });
+						next_value = Err(member_name);
+					}
+				},
+			}
+			if !is_string {
+				buf.push(']');
+				buf.push_str(if options.pretty { " = \"" } else { "=\"" });
+				buf.push_str(member_name);
+				buf.push('"');
+			}
+			buf.push(';');
+		}
+		if options.pretty {
+			buf.push_new_line();
+			options.add_indent(local.depth, buf);
+		}
+		buf.push_str("})(");
+		buf.push_str(name);
+		buf.push_str(if options.pretty { " || (" } else { "||(" });
+		buf.push_str(name);
+		buf.push_str(if options.pretty { " = {}));" } else { "={}));" });
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Visitable)]
 #[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
 #[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
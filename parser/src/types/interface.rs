@@ -142,6 +142,9 @@ impl ASTNode for InterfaceDeclaration {
 					buf.push_new_line();
 				}
 			}
+			if options.pretty && !self.members.is_empty() {
+				options.add_indent(local.depth, buf);
+			}
 			buf.push('}');
 		}
 	}
@@ -208,6 +211,8 @@ pub enum InterfaceMember {
 		parameter: String,
 		rule: TypeRule,
 		matching_type: Box<TypeAnnotation>,
+		/// Key remapping e.g. `as Uppercase<K>`
+		as_type: Option<Box<TypeAnnotation>>,
 		optionality: Optionality,
 		is_readonly: bool,
 		output_type: Box<TypeAnnotation>,
@@ -396,6 +401,18 @@ impl ASTNode for InterfaceMember {
 										};
 										let matching_type =
 											TypeAnnotation::from_reader(reader, state, options)?;
+										let as_type = if reader
+											.conditional_next(|token| {
+												matches!(token, TSXToken::Keyword(TSXKeyword::As))
+											})
+											.is_some()
+										{
+											Some(Box::new(TypeAnnotation::from_reader(
+												reader, state, options,
+											)?))
+										} else {
+											None
+										};
 										reader.expect_next(TSXToken::CloseBracket)?;
 										// TODO the -?: ?: : stuff '-?:' should be a token
 										let token = reader.next().ok_or_else(parse_lexing_error)?;
@@ -429,6 +446,7 @@ impl ASTNode for InterfaceMember {
 											optionality,
 											is_readonly: readonly_position.is_some(),
 											matching_type: Box::new(matching_type),
+											as_type,
 											rule,
 											output_type: Box::new(output_type),
 											position,
@@ -546,15 +564,7 @@ impl ASTNode for InterfaceMember {
 						})
 					}
 					Token(TSXToken::Colon, _) => {
-						let mut type_annotation =
-							TypeAnnotation::from_reader(reader, state, options)?;
-
-						if readonly_position.is_some() {
-							// TODO positioning:
-							let position = start.union(type_annotation.get_position());
-							type_annotation =
-								TypeAnnotation::Readonly(Box::new(type_annotation), position);
-						}
+						let type_annotation = TypeAnnotation::from_reader(reader, state, options)?;
 						let position = start.union(type_annotation.get_position());
 						Ok(InterfaceMember::Property {
 							position,
@@ -596,11 +606,16 @@ impl ASTNode for InterfaceMember {
 		local: crate::LocalToStringInformation,
 	) {
 		match self {
-			InterfaceMember::Property { name, type_annotation, is_readonly, .. } => {
+			InterfaceMember::Property {
+				name, type_annotation, is_readonly, is_optional, ..
+			} => {
 				if *is_readonly {
 					buf.push_str("readonly ");
 				}
 				name.to_string_from_buffer(buf, options, local);
+				if *is_optional {
+					buf.push('?');
+				}
 				buf.push(':');
 				options.push_gap_optionally(buf);
 				type_annotation.to_string_from_buffer(buf, options, local);
@@ -640,9 +655,69 @@ impl ASTNode for InterfaceMember {
 				options.push_gap_optionally(buf);
 				return_type.to_string_from_buffer(buf, options, local);
 			}
-			InterfaceMember::Constructor { .. } => todo!(),
-			InterfaceMember::Caller { .. } => todo!(),
-			InterfaceMember::Rule { .. } => todo!(),
+			InterfaceMember::Constructor {
+				parameters,
+				type_parameters,
+				return_type,
+				is_readonly,
+				..
+			}
+			| InterfaceMember::Caller {
+				parameters,
+				type_parameters,
+				return_type,
+				is_readonly,
+				..
+			} => {
+				if *is_readonly {
+					buf.push_str("readonly ");
+				}
+				if let InterfaceMember::Constructor { .. } = self {
+					buf.push_str("new ");
+				}
+				if let Some(type_parameters) = &type_parameters {
+					to_string_bracketed(type_parameters, ('<', '>'), buf, options, local);
+				}
+				parameters.to_string_from_buffer(buf, options, local);
+				if let Some(return_type) = return_type {
+					buf.push(':');
+					options.push_gap_optionally(buf);
+					return_type.to_string_from_buffer(buf, options, local);
+				}
+			}
+			InterfaceMember::Rule {
+				parameter,
+				rule,
+				matching_type,
+				as_type,
+				optionality,
+				is_readonly,
+				output_type,
+				..
+			} => {
+				if *is_readonly {
+					buf.push_str("readonly ");
+				}
+				buf.push('[');
+				buf.push_str(parameter);
+				buf.push_str(match rule {
+					TypeRule::In => " in ",
+					TypeRule::InKeyOf => " in keyof ",
+				});
+				matching_type.to_string_from_buffer(buf, options, local);
+				if let Some(as_type) = as_type {
+					buf.push_str(" as ");
+					as_type.to_string_from_buffer(buf, options, local);
+				}
+				buf.push(']');
+				buf.push_str(match optionality {
+					Optionality::Default => ":",
+					Optionality::Optional => "?:",
+					Optionality::Required => "-?:",
+				});
+				options.push_gap_optionally(buf);
+				output_type.to_string_from_buffer(buf, options, local);
+			}
 			InterfaceMember::Comment(_, _is_multiline, _) => todo!(),
 		}
 	}
@@ -1,43 +1,121 @@
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Namespace(String, Vec<crate::TypeDefinitionModuleDeclaration>);
+use source_map::Span;
+use tokenizer_lib::{sized_tokens::TokenReaderWithTokenEnds, Token};
 
-impl crate::ASTNode for Namespace {
+use crate::{
+	errors::parse_lexing_error, throw_unexpected_token_with_token, tokens::token_as_identifier,
+	ASTNode, ParseOptions, ParseResult, TSXKeyword, TSXToken, TokenReader,
+	TypeDefinitionModuleDeclaration,
+};
+
+/// `namespace Name { ... }` or `module "name" { ... }`. Found in definition files and after `declare`
+#[derive(Debug, Clone, PartialEq, Eq, get_field_by_type::GetFieldByType)]
+#[get_field_by_type_target(Span)]
+#[cfg_attr(feature = "self-rust-tokenize", derive(self_rust_tokenize::SelfRustTokenize))]
+#[cfg_attr(feature = "serde-serialize", derive(serde::Serialize))]
+pub struct Namespace {
+	pub name: String,
+	/// `module "name"`. Rather than a namespace, the declarations are the exports of the module `name`
+	pub is_module: bool,
+	/// Written with `module` rather than `namespace` (always the case if `is_module`)
+	pub module_keyword: bool,
+	pub declarations: Vec<TypeDefinitionModuleDeclaration>,
+	pub position: Span,
+}
+
+/// `module` is only a keyword with `extras`
+pub(crate) fn is_module_keyword(token: &TSXToken) -> bool {
+	#[cfg(feature = "extras")]
+	if let TSXToken::Keyword(TSXKeyword::Module) = token {
+		return true;
+	}
+	matches!(token, TSXToken::Identifier(name) if name == "module")
+}
+
+impl ASTNode for Namespace {
 	fn from_reader(
-		reader: &mut impl tokenizer_lib::TokenReader<crate::TSXToken, crate::TokenStart>,
+		reader: &mut impl TokenReader<TSXToken, crate::TokenStart>,
 		state: &mut crate::ParsingState,
-		options: &crate::ParseOptions,
-	) -> crate::ParseResult<Self> {
-		reader.expect_next(crate::TSXToken::Keyword(crate::TSXKeyword::Namespace))?;
-		let (namespace_name, _) = crate::tokens::token_as_identifier(
-			reader.next().ok_or_else(crate::errors::parse_lexing_error)?,
-			"namespace name",
-		)?;
-		reader.expect_next(crate::TSXToken::OpenBrace)?;
+		options: &ParseOptions,
+	) -> ParseResult<Self> {
+		let Token(keyword, start) = reader.peek().ok_or_else(parse_lexing_error)?;
+		let (start, module_keyword) = (*start, is_module_keyword(keyword));
+		let (name, is_module) = match reader.next().ok_or_else(parse_lexing_error)? {
+			Token(TSXToken::Keyword(TSXKeyword::Namespace), _) => {
+				let (name, _) = token_as_identifier(
+					reader.next().ok_or_else(parse_lexing_error)?,
+					"namespace name",
+				)?;
+				(name, false)
+			}
+			Token(token, _) if is_module_keyword(&token) => {
+				match reader.next().ok_or_else(parse_lexing_error)? {
+					Token(TSXToken::StringLiteral(name, _), _) => (name, true),
+					// `module Name { }` is the old syntax for namespaces
+					token => (token_as_identifier(token, "module name")?.0, false),
+				}
+			}
+			token => {
+				return throw_unexpected_token_with_token(
+					token,
+					&[TSXToken::Keyword(TSXKeyword::Namespace)],
+				)
+			}
+		};
+		reader.expect_next(TSXToken::OpenBrace)?;
 		let mut declarations = Vec::new();
 		while let Some(token) = reader.peek() {
-			if let tokenizer_lib::Token(crate::TSXToken::CloseBrace, _) = token {
+			if let Token(TSXToken::CloseBrace, _) = token {
 				break;
 			}
 			declarations
-				.push(crate::TypeDefinitionModuleDeclaration::from_reader(reader, state, options)?);
-			if let Some(tokenizer_lib::Token(crate::TSXToken::SemiColon, _)) = reader.peek() {
+				.push(TypeDefinitionModuleDeclaration::from_reader(reader, state, options)?);
+			if let Some(Token(TSXToken::SemiColon, _)) = reader.peek() {
 				reader.next();
 			}
 		}
-		reader.next();
-		Ok(Self(namespace_name, declarations))
+		let position = start.union(reader.expect_next_get_end(TSXToken::CloseBrace)?);
+		Ok(Self { name, is_module, module_keyword, declarations, position })
 	}
 
 	fn to_string_from_buffer<T: source_map::ToString>(
 		&self,
-		_buf: &mut T,
-		_options: &crate::ToStringOptions,
-		_local: crate::LocalToStringInformation,
+		buf: &mut T,
+		options: &crate::ToStringOptions,
+		local: crate::LocalToStringInformation,
 	) {
-		todo!()
+		if options.include_types {
+			buf.push_str(if self.module_keyword {
+				"declare module "
+			} else {
+				"declare namespace "
+			});
+			if self.is_module {
+				buf.push('"');
+				buf.push_str(&self.name);
+				buf.push('"');
+			} else {
+				buf.push_str(&self.name);
+			}
+			options.push_gap_optionally(buf);
+			buf.push('{');
+			if options.pretty && !self.declarations.is_empty() {
+				buf.push_new_line();
+			}
+			for declaration in &self.declarations {
+				options.add_indent(local.depth + 1, buf);
+				declaration.to_string_from_buffer(buf, options, local.next_level());
+				if options.pretty {
+					buf.push_new_line();
+				}
+			}
+			if options.pretty && !self.declarations.is_empty() {
+				options.add_indent(local.depth, buf);
+			}
+			buf.push('}');
+		}
 	}
 
-	fn get_position(&self) -> &source_map::Span {
-		todo!()
+	fn get_position(&self) -> &Span {
+		&self.position
 	}
 }
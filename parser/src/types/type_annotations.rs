@@ -73,6 +73,8 @@ pub enum TypeAnnotation {
 	Index(Box<TypeAnnotation>, Box<TypeAnnotation>, Span),
 	/// KeyOf
 	KeyOf(Box<TypeAnnotation>, Span),
+	/// Declares a type to be inferred, in the extends type of a conditional type e.g. `infer U`
+	Infer(String, Span),
 	/// For operation precedence reasons
 	ParenthesizedReference(Box<TypeAnnotation>, Span),
 	Conditional {
@@ -316,7 +318,11 @@ impl ASTNode for TypeAnnotation {
 					}
 				}
 			}
-			Self::NamespacedName(..) => todo!(),
+			Self::NamespacedName(namespace, name, _) => {
+				buf.push_str(namespace);
+				buf.push('.');
+				buf.push_str(name);
+			}
 			Self::ObjectLiteral(members, _) => {
 				buf.push('{');
 				for (at_end, member) in members.iter().endiate() {
@@ -350,6 +356,10 @@ impl ASTNode for TypeAnnotation {
 				buf.push_str("keyof ");
 				item.to_string_from_buffer(buf, options, local);
 			}
+			Self::Infer(name, _) => {
+				buf.push_str("infer ");
+				buf.push_str(name);
+			}
 			Self::Conditional { condition, resolve_true, resolve_false, .. } => {
 				condition.to_string_from_buffer(buf, options, local);
 				buf.push_str(" ? ");
@@ -582,6 +592,14 @@ impl TypeAnnotation {
 				let position = start.union(key_of_type.get_position());
 				return Ok(TypeAnnotation::KeyOf(Box::new(key_of_type), position));
 			}
+			Token(TSXToken::Keyword(TSXKeyword::Infer), start) => {
+				let (name, name_position) = token_as_identifier(
+					reader.next().ok_or_else(parse_lexing_error)?,
+					"infer name",
+				)?;
+				let position = start.union(name_position);
+				Self::Infer(name, position)
+			}
 			Token(TSXToken::Keyword(TSXKeyword::New), start) => {
 				let type_parameters = reader
 					.conditional_next(|token| *token == TSXToken::OpenChevron)
@@ -616,9 +634,17 @@ impl TypeAnnotation {
 		// Namespaced name
 		if let Some(Token(TSXToken::Dot, _)) = reader.peek() {
 			reader.next();
-			let Self::Name(name, start) = reference else { panic!() };
-			let (namespace_member, end) =
+			let Self::Name(mut name, start) = reference else { panic!() };
+			let (mut namespace_member, mut end) =
 				token_as_identifier(reader.next().unwrap(), "namespace name")?;
+			// Nested namespaces, `A.B.C` has the namespace `A.B`
+			while let Some(Token(TSXToken::Dot, _)) = reader.peek() {
+				reader.next();
+				name.push('.');
+				name.push_str(&namespace_member);
+				(namespace_member, end) =
+					token_as_identifier(reader.next().unwrap(), "namespace name")?;
+			}
 			let position = start.union(end);
 			return Ok(TypeAnnotation::NamespacedName(name, namespace_member, position));
 		}
@@ -664,8 +690,9 @@ impl TypeAnnotation {
 		match reader.peek() {
 			Some(Token(TSXToken::Keyword(TSXKeyword::Extends), _)) => {
 				reader.next();
+				// Unions and intersections are allowed here, e.g. `T extends string | number ? ...`
 				let extends_type = TypeAnnotation::from_reader_with_config(
-					reader, state, options, true, false, start,
+					reader, state, options, false, false, start,
 				)?;
 				// TODO local
 				let position = reference.get_position().union(extends_type.get_position());
@@ -863,7 +890,8 @@ impl ASTNode for TypeAnnotationFunctionParameters {
 		options: &crate::ToStringOptions,
 		local: crate::LocalToStringInformation,
 	) {
-		for parameter in &self.parameters {
+		buf.push('(');
+		for (at_end, parameter) in self.parameters.iter().endiate() {
 			if let Some(ref name) = parameter.name {
 				name.to_string_from_buffer(buf, options, local);
 			}
@@ -873,12 +901,17 @@ impl ASTNode for TypeAnnotationFunctionParameters {
 				buf.push_str(": ");
 			}
 			parameter.type_annotation.to_string_from_buffer(buf, options, local);
+			if !at_end || self.rest_parameter.is_some() {
+				buf.push_str(", ");
+			}
 		}
 		if let Some(ref rest_parameter) = self.rest_parameter {
 			buf.push_str("...");
 			buf.push_str(&rest_parameter.name);
+			buf.push_str(": ");
 			rest_parameter.type_annotation.to_string_from_buffer(buf, options, local);
 		}
+		buf.push(')');
 	}
 }
 
@@ -243,11 +243,13 @@ mod ast {
 		crate::types::type_alias::TypeAlias,
 		crate::types::declares::DeclareFunctionDeclaration,
 		crate::types::declares::DeclareVariableDeclaration,
+		crate::types::namespace::Namespace,
 		crate::VariableIdentifier,
 		crate::PropertyReference,
 		crate::Quoted,
 		crate::declarations::ImportExportName,
 		crate::declarations::ImportLocation,
+		crate::declarations::ImportAttributes,
 		crate::functions::FunctionHeader,
 		crate::functions::MethodHeader,
 		crate::VariableKeyword
@@ -0,0 +1,78 @@
+use ezno_parser::{ASTNode, Module, ParseOptions, StatementOrDeclaration, ToStringOptions};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn multiple_errors() {
+	let input = r"
+const a = 2;
+const b = ;
+function func() {
+	let c = 4 5;
+	return c
+}
+const d = (;
+const e = 6
+"
+	.trim_start()
+	.replace("    ", "\t");
+
+	let (module, errors) =
+		Module::from_string_with_recovery(input, ParseOptions::default()).unwrap();
+
+	assert_eq!(errors.len(), 3, "{errors:?}");
+
+	let markers = module
+		.items
+		.iter()
+		.filter(|item| matches!(item, StatementOrDeclaration::Marker(..)))
+		.count();
+	assert_eq!(markers, 2);
+
+	let output = module
+		.to_string(&ToStringOptions { expect_markers: true, ..ToStringOptions::typescript() });
+
+	assert!(output.contains("const a = 2"), "{output}");
+	assert!(output.contains("return c"), "{output}");
+	assert!(output.contains("const e = 6"), "{output}");
+}
+
+#[test]
+fn unbalanced_braces() {
+	let input = r"
+const a = 2;
+}
+const b = 3
+"
+	.trim_start()
+	.replace("    ", "\t");
+
+	let (module, errors) =
+		Module::from_string_with_recovery(input, ParseOptions::default()).unwrap();
+
+	assert_eq!(errors.len(), 1, "{errors:?}");
+	assert_eq!(module.items.len(), 3);
+}
+
+#[test]
+fn without_recovery() {
+	let input = "const a = ;\nconst b = ;".to_owned();
+
+	assert!(Module::from_string(input.clone(), ParseOptions::default()).is_err());
+
+	let (_module, errors) =
+		Module::from_string_with_recovery(input, ParseOptions::default()).unwrap();
+	assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn lexing_error() {
+	let input = "const a = 2;\nconst b = ;\nconst c = \"abc\nconst d = 4".to_owned();
+
+	let (module, errors) =
+		Module::from_string_with_recovery(input, ParseOptions::default()).unwrap();
+
+	// Statements before the lexing error are kept
+	assert_eq!(errors.len(), 2, "{errors:?}");
+	assert_eq!(errors[1].reason, "String literals cannot contain new lines");
+	assert!(matches!(module.items[0], StatementOrDeclaration::Declaration(_)));
+}
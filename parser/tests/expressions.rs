@@ -65,3 +65,18 @@ const c = { async e() {
 	let output = module.to_string(&ezno_parser::ToStringOptions::typescript());
 	assert_eq!(output, input);
 }
+
+#[test]
+fn jsx_attributes() {
+	let input = r#"
+const a = <div {...props} class="x"/>;
+const b = <Button {onClick} disabled>Hello</Button>;
+const c = <>Hello {name}</>
+    "#
+	.trim();
+
+	let module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+
+	let output = module.to_string(&ezno_parser::ToStringOptions::typescript());
+	assert_eq!(output, input);
+}
@@ -102,6 +102,33 @@ try {
 	assert_eq!(output, input);
 }
 
+#[test]
+fn using_declarations() {
+	let input = r"
+using handle = openDatabase(), other = openDatabase();
+await using connection = connect();
+for (using item of items) {
+    item.run()
+}
+for (await using item of items) {
+    item.run()
+}
+for (using x = open(); x.next();) {
+    x.run()
+}
+using(handle);
+const using = 2"
+		.trim_start()
+		.replace("    ", "\t");
+
+	let module = Module::from_string(input.clone(), Default::default()).unwrap();
+	let output = module.to_string(&Default::default());
+	assert_eq!(output, input);
+
+	let destructured = "using { a } = resource;".to_owned();
+	assert!(Module::from_string(destructured, Default::default()).is_err());
+}
+
 #[test]
 fn imports() {
 	// Taken from MDN
@@ -133,6 +160,21 @@ import { a, } from "module-name"
 	Module::from_string(input.to_owned(), Default::default()).unwrap();
 }
 
+#[test]
+fn import_attributes() {
+	let input = r#"
+import data from "./data.json" with { type: "json" };
+import "./styles.css" with { type: "css", "other": 'value' };
+export { data } from "./data.json" with { type: "json" };
+export * as all from "./data.json" with { type: "json" };
+const module = import("./data.json", { with: { type: "json" } })"#
+		.trim_start();
+
+	let module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+	let output = module.to_string(&ToStringOptions::typescript());
+	assert_eq!(output, input);
+}
+
 #[test]
 fn exports() {
 	// Taken from MDN
@@ -180,6 +222,8 @@ export default function* () {
 }
 
 export interface X { property: number }
+export enum Direction { Up, Down }
+export const enum Flags { A = 1, B = 2 }
 
 // Aggregating modules
 export * from "module-name";
@@ -198,6 +242,42 @@ export type { name1, /* …, */ nameN } from "module-name";"#
 	// assert_eq!(output, input);
 }
 
+#[test]
+fn enums() {
+	let input = r#"
+enum Direction { Up, Down = 4, Left }
+enum Named { A = "a", B = "b" }
+const enum Inlined { A, B }
+export enum Computed { A = getValue(), B }
+"#
+	.trim_start();
+
+	let expected = r#"
+var Direction;
+(function (Direction) {
+    Direction[Direction["Up"] = 0] = "Up";
+    Direction[Direction["Down"] = 4] = "Down";
+    Direction[Direction["Left"] = 5] = "Left";
+})(Direction || (Direction = {}));
+var Named;
+(function (Named) {
+    Named["A"] = "a";
+    Named["B"] = "b";
+})(Named || (Named = {}));
+
+export var Computed;
+(function (Computed) {
+    Computed[Computed["A"] = getValue()] = "A";
+    Computed[Computed["B"] = Computed["A"] + 1] = "B";
+})(Computed || (Computed = {}));"#
+		.trim_start()
+		.replace("    ", "\t");
+
+	let module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+	let output = module.to_string(&ToStringOptions::default());
+	assert_eq!(output, expected);
+}
+
 #[cfg(feature = "extras")]
 #[test]
 fn reversed_imports() {
@@ -241,3 +321,49 @@ worker function a() {}
 	// let output = module.to_string(&ezno_parser::ToStringOptions::typescript());
 	// assert_eq!(output, input);
 }
+
+#[test]
+fn source_maps() {
+	use ezno_parser::source_map::{FileSystem, MapFileStore, NoPathMap};
+
+	let input = "const a = 1;\nfunction f(b) {\n\treturn b\n}";
+
+	let mut fs = MapFileStore::<NoPathMap>::default();
+	let source = fs.new_source_id("input.js".into(), input.to_owned());
+
+	let module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+	let (output, source_map) =
+		module.to_string_with_source_map(&ToStringOptions::default(), source, &fs);
+
+	assert_eq!(output, input);
+	// Each statement and identifier maps back to the same position in the input
+	assert_eq!(source_map.unwrap().mappings, "AAAA,MAAM;AACN,WAAW;CACV,OAAO;");
+}
+
+#[cfg(feature = "extras")]
+#[test]
+fn namespaces() {
+	let input = r#"
+declare namespace App {
+	interface User {
+		name: string
+	}
+	declare function getUser(): User
+	declare namespace Settings {
+		declare const theme: Theme
+	}
+}
+declare module "fs" {
+	declare function readFileSync(path: string): string
+}
+declare module Legacy {
+	declare const version: string
+}
+const user: App.Settings.Theme = getTheme()
+	"#
+	.trim();
+
+	let module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+	let output = module.to_string(&ezno_parser::ToStringOptions::typescript());
+	assert_eq!(output, input);
+}
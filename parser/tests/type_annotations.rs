@@ -63,3 +63,50 @@ fn expression_level_expressions() {
 	)
 	.is_err());
 }
+
+#[test]
+fn type_operators() {
+	let input = r"
+type X = keyof Y;
+type Z<T> = T extends Array<infer U> ? U : never;
+type C = new (a: string) => Y"
+		.trim_start()
+		.replace("    ", "\t");
+
+	let module = Module::from_string(input.clone(), Default::default()).unwrap();
+	let output = module.to_string(&ToStringOptions::typescript());
+
+	assert_eq!(output, input);
+}
+
+#[test]
+fn call_and_construct_signatures() {
+	let input = r"
+interface X {
+	(a: string): number
+	readonly new <T>(a: T): Y
+	version: string
+}"
+	.trim_start()
+	.replace("    ", "\t");
+
+	let module = Module::from_string(input.clone(), Default::default()).unwrap();
+	let output = module.to_string(&ToStringOptions::typescript());
+
+	assert_eq!(output, input);
+}
+
+#[test]
+fn mapped_types() {
+	let input = r"
+type Partial<T> = {[P in keyof T]?: T[P]};
+type Getters<T> = {readonly [P in keyof T as `get${P}`]-?: () => T[P]};
+type Record<K, T> = {[P in K]: T}"
+		.trim_start()
+		.replace("    ", "\t");
+
+	let module = Module::from_string(input.clone(), Default::default()).unwrap();
+	let output = module.to_string(&ToStringOptions::typescript());
+
+	assert_eq!(output, input);
+}
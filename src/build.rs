@@ -5,8 +5,8 @@ use std::{
 
 use checker::{DiagnosticsContainer, TypeCheckOptions};
 use parser::{
-	source_map::{MapFileStore, WithPathMap},
-	ToStringOptions,
+	source_map::{FileSystem, MapFileStore, WithPathMap},
+	Declaration, Decorated, StatementOrDeclaration, ToStringOptions,
 };
 
 use crate::check::CheckingOutputWithoutDiagnostics;
@@ -15,6 +15,7 @@ use crate::check::CheckingOutputWithoutDiagnostics;
 pub struct Output {
 	pub output_path: PathBuf,
 	pub content: String,
+	/// Source map (v3 JSON) for `content`. Empty if source maps are not enabled
 	pub mappings: String,
 }
 
@@ -41,6 +42,13 @@ pub struct FailedBuildOutput {
 pub struct BuildConfig {
 	#[cfg_attr(target_family = "wasm", serde(default))]
 	pub strip_whitespace: bool,
+	/// Generate source maps (and reference them from the output)
+	#[cfg_attr(target_family = "wasm", serde(default))]
+	pub source_maps: bool,
+	/// Output a single file containing all modules. Otherwise the input tree is mirrored into `output_path`.
+	/// If `output_path` has an extension, it is used for the entry and other modules are placed next to it
+	#[cfg_attr(target_family = "wasm", serde(default))]
+	pub bundle: bool,
 }
 
 pub type EznoParsePostCheckVisitors =
@@ -49,17 +57,24 @@ pub type EznoParsePostCheckVisitors =
 pub fn build<T: crate::ReadFromFS>(
 	input_paths: Vec<PathBuf>,
 	fs_resolver: &T,
-	type_definition_module: Option<&Path>,
+	type_definition_modules: &[PathBuf],
+	type_check_options: Option<TypeCheckOptions>,
 	output_path: &Path,
 	config: &BuildConfig,
 	transformers: Option<EznoParsePostCheckVisitors>,
 ) -> Result<BuildOutput, FailedBuildOutput> {
 	// TODO parse options + non_standard_library & non_standard_syntax
-	let type_check_options =
-		TypeCheckOptions { store_expression_type_mappings: true, ..Default::default() };
+	let type_check_options = TypeCheckOptions {
+		store_expression_type_mappings: true,
+		..type_check_options.unwrap_or_default()
+	};
 
-	let result =
-		crate::check(input_paths, fs_resolver, type_definition_module, Some(type_check_options));
+	let result = crate::check(
+		input_paths.clone(),
+		fs_resolver,
+		type_definition_modules,
+		Some(type_check_options),
+	);
 
 	let mut data = crate::check::CheckingOutputWithoutDiagnostics {
 		module_contents: result.module_contents,
@@ -69,25 +84,24 @@ pub fn build<T: crate::ReadFromFS>(
 	};
 
 	if !result.diagnostics.has_error() {
-		// TODO For all modules
 		let keys = data.modules.keys().cloned().collect::<Vec<_>>();
 
 		let null_module =
 			parser::Module { items: Default::default(), span: parser::source_map::Nullable::NULL };
 
-		let mut outputs = Vec::new();
-
 		let mut transformers = transformers.unwrap_or_default();
+		// Required as `const enum` declarations are removed
+		transformers
+			.expression_visitors_mut
+			.push(Box::new(crate::transformers::enums::ConstEnumInliner));
 
 		for source in keys {
-			// Remove the module
+			// Take the module out so that `data` can be borrowed by the transformers
 			let mut module = mem::replace(
 				&mut data.modules.get_mut(&source).unwrap().content,
 				null_module.clone(),
 			);
 
-			// TODO bundle using main_module.imports
-
 			module.visit_mut::<CheckingOutputWithoutDiagnostics>(
 				&mut transformers,
 				&mut data,
@@ -95,24 +109,214 @@ pub fn build<T: crate::ReadFromFS>(
 				source,
 			);
 
-			let to_string_options = if config.strip_whitespace {
-				ToStringOptions::minified()
+			data.modules.get_mut(&source).unwrap().content = module;
+		}
+
+		let to_string_options = if config.strip_whitespace {
+			ToStringOptions::minified()
+		} else {
+			ToStringOptions::default()
+		};
+
+		let outputs = if config.bundle {
+			let entries = input_paths
+				.iter()
+				.filter_map(|path| data.module_contents.get_source_at_path(path))
+				.collect::<Vec<_>>();
+
+			let modules = crate::bundle::bundle(&entries, &mut data);
+			let (content, source_map) = parser::Module::multiple_to_string_with_source_map(
+				modules.iter().map(|(source, module)| (*source, module)),
+				&to_string_options,
+				&data.module_contents,
+			);
+
+			vec![create_output(content, source_map, output_path.to_path_buf(), config, &data)]
+		} else {
+			let entry = input_paths.first();
+			let (output_directory, entry_output) = if output_path.extension().is_some() {
+				(output_path.parent().unwrap_or(Path::new("")), Some(output_path))
 			} else {
-				ToStringOptions::default()
+				(output_path, None)
 			};
 
-			let content = parser::ASTNode::to_string(&module, &to_string_options);
+			let mut sources = data.modules.keys().cloned().collect::<Vec<_>>();
+			sources.sort_by_key(|source| data.module_contents.get_file_path(*source));
 
-			outputs.push(Output {
-				output_path: output_path.to_path_buf(),
-				content,
-				// TODO module.to_string_with_map
-				mappings: String::new(),
-			})
-		}
+			// Paths are relative to the directory containing every module, so modules with the
+			// same name in different directories do not write to the same output
+			let base = sources
+				.iter()
+				.map(|source| data.module_contents.get_file_path(*source))
+				.filter_map(|path| path.parent().map(Path::to_path_buf))
+				.reduce(|base, directory| common_ancestor(&base, &directory))
+				.unwrap_or_default();
+
+			sources
+				.into_iter()
+				.map(|source| {
+					let path = data.module_contents.get_file_path(source);
+					let relative = path.strip_prefix(&base).unwrap_or(&path);
+					let output_path = match entry_output {
+						Some(entry_output) if Some(&path) == entry => entry_output.to_path_buf(),
+						_ => output_directory.join(relative).with_extension("js"),
+					};
+
+					let module = data.modules.get_mut(&source).unwrap();
+					rewrite_import_extensions(&mut module.content, &module.imports);
+
+					let module = &data.modules[&source].content;
+					let (content, source_map) = module.to_string_with_source_map(
+						&to_string_options,
+						source,
+						&data.module_contents,
+					);
+					create_output(content, source_map, output_path, config, &data)
+				})
+				.collect()
+		};
 
 		Ok(BuildOutput { outputs, diagnostics: result.diagnostics, fs: data.module_contents })
 	} else {
 		Err(FailedBuildOutput { diagnostics: result.diagnostics, fs: data.module_contents })
 	}
 }
+
+fn create_output(
+	mut content: String,
+	source_map: Option<parser::source_map::SourceMap>,
+	output_path: PathBuf,
+	config: &BuildConfig,
+	data: &CheckingOutputWithoutDiagnostics,
+) -> Output {
+	let mappings = match source_map {
+		Some(source_map) if config.source_maps => {
+			let map_file_name = format!(
+				"{}.map",
+				output_path.file_name().and_then(|name| name.to_str()).unwrap_or_default()
+			);
+			content.push_str("\n//# sourceMappingURL=");
+			content.push_str(&map_file_name);
+			source_map.to_json(&data.module_contents)
+		}
+		_ => String::new(),
+	};
+	Output { output_path, content, mappings }
+}
+
+fn common_ancestor(a: &Path, b: &Path) -> PathBuf {
+	a.components().zip(b.components()).take_while(|(a, b)| a == b).map(|(a, _)| a).collect()
+}
+
+/// Relative imports of modules that are part of the output are changed to point to their `.js` output
+fn rewrite_import_extensions(
+	module: &mut parser::Module,
+	imports: &std::collections::HashMap<String, parser::SourceId>,
+) {
+	use parser::declarations::{export::Exportable, ExportDeclaration, ImportLocation};
+
+	for item in &mut module.items {
+		let from = match item {
+			StatementOrDeclaration::Declaration(Declaration::Import(import)) => &mut import.from,
+			StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+				on:
+					ExportDeclaration::Variable {
+						exported:
+							Exportable::ImportAll { from, .. } | Exportable::ImportParts { from, .. },
+						..
+					},
+				..
+			})) => from,
+			_ => continue,
+		};
+		if let ImportLocation::Quoted(path, _) = from {
+			// Aliases and packages are resolved by whatever runs the output
+			let is_relative = path.starts_with("./") || path.starts_with("../");
+			if !is_relative || !imports.contains_key(path.as_str()) {
+				continue;
+			}
+			let stem = [".ts", ".tsx", ".mts"].iter().find_map(|ext| path.strip_suffix(ext));
+			*path = match stem {
+				Some(stem) => format!("{stem}.js"),
+				None if path.ends_with(".js") => continue,
+				None => format!("{path}.js"),
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+	};
+
+	use parser::{source_map::Nullable, ASTNode, Module, SourceId, ToStringOptions};
+
+	use super::{build, rewrite_import_extensions, BuildConfig};
+
+	#[test]
+	fn rewrites_relative_imports() {
+		let input = r#"
+import { a } from "./a";
+import { b } from "../b.ts";
+import { c } from "./c.module";
+import { d } from "./d.js";
+import { button } from "@/components/button";
+import { e } from "./e";
+export * from "./f.tsx""#
+			.trim_start();
+
+		let mut module = Module::from_string(input.to_owned(), Default::default()).unwrap();
+		let imports = ["./a", "../b.ts", "./c.module", "./d.js", "@/components/button", "./f.tsx"]
+			.into_iter()
+			.map(|path| (path.to_owned(), SourceId::NULL))
+			.collect();
+		rewrite_import_extensions(&mut module, &imports);
+
+		let expected = r#"
+import { a } from "./a.js";
+import { b } from "../b.js";
+import { c } from "./c.module.js";
+import { d } from "./d.js";
+import { button } from "@/components/button";
+import { e } from "./e";
+export * from "./f.js""#
+			.trim_start();
+		assert_eq!(module.to_string(&ToStringOptions::typescript()), expected);
+	}
+
+	#[test]
+	fn modules_with_the_same_name() {
+		let files: HashMap<PathBuf, String> = [
+			("src/index.ts", "import { x } from '../lib/index';\nexport const y = x;"),
+			("lib/index.ts", "export const x = 2;"),
+		]
+		.into_iter()
+		.map(|(path, content)| (PathBuf::from(path), content.to_owned()))
+		.collect();
+		let read_file = |path: &Path| files.get(path).cloned();
+		let config = BuildConfig { strip_whitespace: false, source_maps: false, bundle: false };
+
+		let Ok(output) = build(
+			vec!["src/index.ts".into()],
+			&read_file,
+			&[],
+			None,
+			Path::new("out"),
+			&config,
+			None,
+		) else {
+			panic!("build failed");
+		};
+
+		let mut output_paths =
+			output.outputs.into_iter().map(|output| output.output_path).collect::<Vec<_>>();
+		output_paths.sort();
+		assert_eq!(
+			output_paths,
+			[PathBuf::from("out/lib/index.js"), PathBuf::from("out/src/index.js")]
+		);
+	}
+}
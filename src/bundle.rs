@@ -0,0 +1,859 @@
+//! Combines the modules of a build into a single module
+//!
+//! - Modules are hoisted in dependency order (using the import information on [`SynthesisedModule`])
+//! - Top level bindings are given unique names across all modules
+//! - `import`s of bundled modules are removed (or become objects for `import * as ...`) and references
+//!   to them are renamed to the exported binding
+//! - `export`s become plain declarations. The exports of the entry modules are gathered in a single
+//!   `export { ... }` at the end
+//!
+//! Relies on the checker storing `variable_references` (`store_expression_type_mappings`)
+//!
+//! [`SynthesisedModule`]: checker::features::modules::SynthesisedModule
+
+use std::{
+	collections::{HashMap, HashSet},
+	mem,
+};
+
+use checker::VariableId;
+use parser::{
+	declarations::{
+		export::{ExportPart, Exportable},
+		import::ImportedItems,
+		variable::{VariableDeclaration, VariableDeclarationItem},
+		ExportDeclaration, ImportExportName, ImportPart,
+	},
+	expressions::{
+		assignments::{LHSOfAssignment, VariableOrPropertyAccess},
+		object_literal::{ObjectLiteral, ObjectLiteralMember},
+	},
+	source_map::{FileSystem, Nullable},
+	visiting::{Chain, VisitOptions, VisitorMutReceiver},
+	ArrayDestructuringField, Declaration, Decorated, Expression, Module, ObjectDestructuringField,
+	PropertyKey, SourceId, Span, Statement, StatementOrDeclaration, VariableField,
+	VariableFieldInSourceCode, VariableIdentifier, WithComment,
+};
+
+use crate::check::CheckingOutputWithoutDiagnostics;
+
+/// What an import binding refers to in the imported module
+enum Imported {
+	Default,
+	Named(String),
+	Namespace,
+}
+
+/// A binding introduced by an `import` (or `export ... from ...`)
+struct ImportBinding {
+	local: String,
+	imported: Imported,
+	/// Matches the position used for the import site [`VariableId`]
+	position: Span,
+}
+
+/// Returns the modules in the order they should be printed, with their imports and exports rewritten
+pub(crate) fn bundle(
+	entries: &[SourceId],
+	data: &mut CheckingOutputWithoutDiagnostics,
+) -> Vec<(SourceId, Module)> {
+	let mut order = Vec::new();
+	let mut visited = HashSet::new();
+	for entry in entries {
+		dependency_order(*entry, data, &mut visited, &mut order);
+	}
+
+	let null_module = Module { items: Default::default(), span: Nullable::NULL };
+	let modules: Vec<(SourceId, Module)> = order
+		.iter()
+		.map(|source| {
+			let module = &mut data.modules.get_mut(source).unwrap().content;
+			(*source, mem::replace(module, null_module.clone()))
+		})
+		.collect();
+
+	let mut names = UniqueNames::default();
+	// Final names of top level variables, default exports and import sites
+	let mut bindings: HashMap<VariableId, String> = HashMap::new();
+	let mut default_exports: HashMap<SourceId, String> = HashMap::new();
+
+	// Name imports of things that are not bundled first so they keep their name if possible. Then
+	// variables (in declaration order) and default exports
+	for (source, module) in &modules {
+		let imports = &data.modules[source].imports;
+		for (specifier, import_bindings) in module.items.iter().filter_map(get_import_bindings) {
+			if imports.contains_key(specifier) {
+				continue;
+			}
+			for binding in import_bindings {
+				let name = names.new_name(&binding.local);
+				bindings.insert(VariableId(*source, binding.position.start), name);
+			}
+		}
+	}
+	for (source, module) in &modules {
+		let mut declared_variables: Vec<_> =
+			data.modules[source].declared_variables.iter().collect();
+		declared_variables.sort_by_key(|(_, id)| id.1);
+		for (name, id) in declared_variables {
+			if !bindings.contains_key(id) {
+				bindings.insert(*id, names.new_name(name));
+			}
+		}
+
+		let has_default_export = module.items.iter().any(|item| {
+			matches!(
+				item,
+				StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+					on: ExportDeclaration::Default { .. },
+					..
+				}))
+			)
+		});
+		if has_default_export {
+			let path = data.module_contents.get_file_path(*source);
+			let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("module");
+			let stem = stem.split('.').next().unwrap_or(stem);
+			let stem: String =
+				stem.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+			default_exports.insert(*source, names.new_name(&format!("{stem}_default")));
+		}
+	}
+
+	// Imports of bundled modules point to the exported binding
+	for (source, module) in &modules {
+		let imports = &data.modules[source].imports;
+		for (specifier, import_bindings) in module.items.iter().filter_map(get_import_bindings) {
+			let Some(dependency) = imports.get(specifier) else { continue };
+			for binding in import_bindings {
+				let target = match binding.imported {
+					Imported::Default => default_exports.get(dependency).cloned(),
+					Imported::Named(ref name) => data.modules[dependency]
+						.exported
+						.named
+						.iter()
+						.find_map(|(export, (id, _))| (export == name).then_some(id))
+						.and_then(|id| bindings.get(id).cloned()),
+					// Declared as a variable
+					Imported::Namespace => None,
+				};
+				if let Some(target) = target {
+					bindings.insert(VariableId(*source, binding.position.start), target);
+				}
+			}
+		}
+	}
+
+	let mut references: HashMap<SourceId, HashMap<u32, String>> = HashMap::new();
+	for ((source, start), id) in &data.type_mappings.variable_references {
+		if let Some(name) = bindings.get(id) {
+			references.entry(*source).or_default().insert(*start, name.clone());
+		}
+	}
+
+	let mut exports: Vec<(String, String)> = Vec::new();
+	for entry in entries {
+		let module = &data.modules[entry];
+		for (name, (id, _)) in &module.exported.named {
+			if let Some(binding) = bindings.get(id) {
+				if !exports.iter().any(|(exported, _)| exported == name) {
+					exports.push((name.clone(), binding.clone()));
+				}
+			}
+		}
+		if let Some(default) = default_exports.get(entry) {
+			if !exports.iter().any(|(exported, _)| exported == "default") {
+				exports.push(("default".to_owned(), default.clone()));
+			}
+		}
+	}
+
+	let mut bundled = Vec::with_capacity(modules.len());
+	for (source, mut module) in modules {
+		let synthesised = &data.modules[&source];
+		let namespace_object = |dependency: &SourceId| {
+			let mut members: Vec<(String, String)> = data.modules[dependency]
+				.exported
+				.named
+				.iter()
+				.filter_map(|(name, (id, _))| Some((name.clone(), bindings.get(id)?.clone())))
+				.collect();
+			if let Some(default) = default_exports.get(dependency) {
+				members.push(("default".to_owned(), default.clone()));
+			}
+			members
+		};
+
+		let rename_declared = |name: &str| {
+			synthesised.declared_variables.get(name).and_then(|id| bindings.get(id)).cloned()
+		};
+
+		let items = mem::take(&mut module.items);
+		for item in items {
+			let item = match item {
+				StatementOrDeclaration::Declaration(Declaration::Import(mut import)) => {
+					let specifier = import.from.get_path().unwrap_or_default();
+					if let Some(dependency) = synthesised.imports.get(specifier) {
+						if let ImportedItems::All { under: VariableIdentifier::Standard(name, _) } =
+							&import.items
+						{
+							let name = rename_declared(name).unwrap_or_else(|| name.clone());
+							module.items.push(namespace_declaration(
+								name,
+								namespace_object(dependency),
+								import.position,
+							));
+						}
+						continue;
+					}
+					rename_import(&mut import, source, &bindings);
+					StatementOrDeclaration::Declaration(Declaration::Import(import))
+				}
+				StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+					on: ExportDeclaration::Default { expression, position },
+					..
+				})) => {
+					let name = default_exports[&source].clone();
+					let declaration = VariableDeclaration::ConstDeclaration {
+						declarations: vec![VariableDeclarationItem {
+							name: WithComment::None(VariableField::Name(
+								VariableIdentifier::Standard(name, position),
+							)),
+							type_annotation: None,
+							expression: *expression,
+							position,
+						}],
+						position,
+					};
+					StatementOrDeclaration::Declaration(Declaration::Variable(declaration))
+				}
+				StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+					on: ExportDeclaration::Variable { exported, position },
+					decorators,
+					..
+				})) => match exported {
+					Exportable::Class(class) => StatementOrDeclaration::Declaration(
+						Declaration::Class(Decorated::new(decorators, class)),
+					),
+					Exportable::Function(function) => StatementOrDeclaration::Declaration(
+						Declaration::Function(Decorated::new(decorators, function)),
+					),
+					Exportable::Variable(variable) => {
+						StatementOrDeclaration::Declaration(Declaration::Variable(variable))
+					}
+					Exportable::Interface(interface) => StatementOrDeclaration::Declaration(
+						Declaration::Interface(Decorated::new(decorators, interface)),
+					),
+					Exportable::TypeAlias(alias) => {
+						StatementOrDeclaration::Declaration(Declaration::TypeAlias(alias))
+					}
+					Exportable::Enum(r#enum) => StatementOrDeclaration::Declaration(
+						Declaration::Enum(Decorated::new(decorators, r#enum)),
+					),
+					Exportable::Parts(_) => continue,
+					Exportable::ImportAll { r#as, from, attributes } => {
+						let specifier = from.get_path().unwrap_or_default();
+						if let Some(dependency) = synthesised.imports.get(specifier) {
+							if let Some(VariableIdentifier::Standard(name, _)) = &r#as {
+								let name = rename_declared(name).unwrap_or_else(|| name.clone());
+								module.items.push(namespace_declaration(
+									name,
+									namespace_object(dependency),
+									position,
+								));
+							}
+							continue;
+						}
+						if !entries.contains(&source) {
+							continue;
+						}
+						StatementOrDeclaration::Declaration(Declaration::Export(Decorated::new(
+							decorators,
+							ExportDeclaration::Variable {
+								exported: Exportable::ImportAll { r#as, from, attributes },
+								position,
+							},
+						)))
+					}
+					exported @ Exportable::ImportParts { .. } => {
+						let Exportable::ImportParts { ref from, .. } = exported else {
+							unreachable!()
+						};
+						let specifier = from.get_path().unwrap_or_default();
+						if synthesised.imports.contains_key(specifier) || !entries.contains(&source)
+						{
+							continue;
+						}
+						StatementOrDeclaration::Declaration(Declaration::Export(Decorated::new(
+							decorators,
+							ExportDeclaration::Variable { exported, position },
+						)))
+					}
+				},
+				item => item,
+			};
+			module.items.push(item);
+		}
+
+		for item in &mut module.items {
+			rename_top_level_declaration(item, &rename_declared);
+		}
+
+		if let Some(references) = references.remove(&source) {
+			module.visit_mut(
+				&mut ReferenceRenamer { references },
+				&mut (),
+				&VisitOptions::default(),
+				source,
+			);
+		}
+
+		bundled.push((source, module));
+	}
+
+	if !exports.is_empty() {
+		if let Some((_, last)) = bundled.last_mut() {
+			last.items.push(export_list(exports));
+		}
+	}
+
+	bundled
+}
+
+/// Post order, so dependencies come before the modules that import them
+fn dependency_order(
+	source: SourceId,
+	data: &CheckingOutputWithoutDiagnostics,
+	visited: &mut HashSet<SourceId>,
+	order: &mut Vec<SourceId>,
+) {
+	if !visited.insert(source) {
+		return;
+	}
+	let Some(module) = data.modules.get(&source) else { return };
+	for item in &module.content.items {
+		let dependency = get_import_specifier(item).and_then(|path| module.imports.get(path));
+		if let Some(dependency) = dependency {
+			dependency_order(*dependency, data, visited, order);
+		}
+	}
+	order.push(source);
+}
+
+fn get_import_specifier(item: &StatementOrDeclaration) -> Option<&str> {
+	match item {
+		StatementOrDeclaration::Declaration(Declaration::Import(import)) => import.from.get_path(),
+		StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+			on:
+				ExportDeclaration::Variable {
+					exported:
+						Exportable::ImportAll { from, .. } | Exportable::ImportParts { from, .. },
+					..
+				},
+			..
+		})) => from.get_path(),
+		_ => None,
+	}
+}
+
+/// Mirrors how the checker registers import bindings
+fn get_import_bindings(item: &StatementOrDeclaration) -> Option<(&str, Vec<ImportBinding>)> {
+	let mut bindings = Vec::new();
+	match item {
+		StatementOrDeclaration::Declaration(Declaration::Import(import)) => {
+			if let Some(VariableIdentifier::Standard(name, position)) = &import.default {
+				bindings.push(ImportBinding {
+					local: name.clone(),
+					imported: Imported::Default,
+					position: *position,
+				});
+			}
+			match &import.items {
+				ImportedItems::Parts(parts) => {
+					for part in parts.iter().flatten() {
+						if let Some(binding) = import_part_binding(part) {
+							bindings.push(binding);
+						}
+					}
+				}
+				ImportedItems::All { under: VariableIdentifier::Standard(name, position) } => {
+					bindings.push(ImportBinding {
+						local: name.clone(),
+						imported: Imported::Namespace,
+						position: *position,
+					});
+				}
+				ImportedItems::All { under: VariableIdentifier::Marker(..) } => {}
+			}
+			Some((import.from.get_path()?, bindings))
+		}
+		StatementOrDeclaration::Declaration(Declaration::Export(Decorated {
+			on: ExportDeclaration::Variable { exported, .. },
+			..
+		})) => match exported {
+			Exportable::ImportAll { r#as, from, .. } => {
+				if let Some(VariableIdentifier::Standard(name, position)) = r#as {
+					bindings.push(ImportBinding {
+						local: name.clone(),
+						imported: Imported::Namespace,
+						position: *position,
+					});
+				}
+				Some((from.get_path()?, bindings))
+			}
+			Exportable::ImportParts { parts, from, .. } => {
+				for part in parts {
+					if let Some(binding) = export_part_binding(part) {
+						bindings.push(binding);
+					}
+				}
+				Some((from.get_path()?, bindings))
+			}
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn import_part_binding(part: &ImportPart) -> Option<ImportBinding> {
+	match part {
+		ImportPart::Name(VariableIdentifier::Standard(name, position)) => Some(ImportBinding {
+			local: name.clone(),
+			imported: Imported::Named(name.clone()),
+			position: *position,
+		}),
+		ImportPart::NameWithAlias { name, alias, position } => Some(ImportBinding {
+			local: name.clone(),
+			imported: Imported::Named(import_export_name(alias)?),
+			position: *position,
+		}),
+		ImportPart::PrefixComment(_, part, _) => part.as_deref().and_then(import_part_binding),
+		ImportPart::PostfixComment(part, _, _) => import_part_binding(part),
+		ImportPart::Name(VariableIdentifier::Marker(..)) => None,
+	}
+}
+
+fn export_part_binding(part: &ExportPart) -> Option<ImportBinding> {
+	match part {
+		ExportPart::Name(VariableIdentifier::Standard(name, position)) => Some(ImportBinding {
+			local: name.clone(),
+			imported: Imported::Named(name.clone()),
+			position: *position,
+		}),
+		ExportPart::NameWithAlias { name, alias, position } => Some(ImportBinding {
+			local: import_export_name(alias)?,
+			imported: Imported::Named(name.clone()),
+			position: *position,
+		}),
+		ExportPart::PrefixComment(_, part, _) => part.as_deref().and_then(export_part_binding),
+		ExportPart::PostfixComment(part, _, _) => export_part_binding(part),
+		ExportPart::Name(VariableIdentifier::Marker(..)) => None,
+	}
+}
+
+fn import_export_name(name: &ImportExportName) -> Option<String> {
+	match name {
+		ImportExportName::Reference(name) | ImportExportName::Quoted(name, _) => Some(name.clone()),
+		ImportExportName::Marker(_) => None,
+	}
+}
+
+/// For imports that are kept, aliases bindings that have been renamed
+fn rename_import(
+	import: &mut parser::declarations::ImportDeclaration,
+	source: SourceId,
+	bindings: &HashMap<VariableId, String>,
+) {
+	let get_name = |position: &Span| bindings.get(&VariableId(source, position.start)).cloned();
+
+	if let Some(VariableIdentifier::Standard(name, position)) = &mut import.default {
+		if let Some(new_name) = get_name(position) {
+			*name = new_name;
+		}
+	}
+	match &mut import.items {
+		ImportedItems::Parts(parts) => {
+			for part in parts.iter_mut().flatten() {
+				rename_import_part(part, &get_name);
+			}
+		}
+		ImportedItems::All { under: VariableIdentifier::Standard(name, position) } => {
+			if let Some(new_name) = get_name(position) {
+				*name = new_name;
+			}
+		}
+		ImportedItems::All { under: VariableIdentifier::Marker(..) } => {}
+	}
+}
+
+fn rename_import_part(part: &mut ImportPart, get_name: &impl Fn(&Span) -> Option<String>) {
+	match part {
+		ImportPart::Name(VariableIdentifier::Standard(name, position)) => {
+			if let Some(new_name) = get_name(position).filter(|new_name| new_name != name) {
+				*part = ImportPart::NameWithAlias {
+					alias: ImportExportName::Reference(mem::take(name)),
+					name: new_name,
+					position: *position,
+				};
+			}
+		}
+		ImportPart::NameWithAlias { name, position, .. } => {
+			if let Some(new_name) = get_name(position) {
+				*name = new_name;
+			}
+		}
+		ImportPart::PrefixComment(_, Some(part), _) | ImportPart::PostfixComment(part, _, _) => {
+			rename_import_part(part, get_name);
+		}
+		ImportPart::PrefixComment(_, None, _)
+		| ImportPart::Name(VariableIdentifier::Marker(..)) => {}
+	}
+}
+
+fn rename_top_level_declaration(
+	item: &mut StatementOrDeclaration,
+	rename: &impl Fn(&str) -> Option<String>,
+) {
+	let rename_identifier = |identifier: &mut VariableIdentifier| {
+		if let VariableIdentifier::Standard(name, _) = identifier {
+			if let Some(new_name) = rename(name) {
+				*name = new_name;
+			}
+		}
+	};
+
+	match item {
+		StatementOrDeclaration::Declaration(Declaration::Variable(
+			VariableDeclaration::ConstDeclaration { declarations, .. }
+			| VariableDeclaration::UsingDeclaration { declarations, .. },
+		)) => {
+			for declaration in declarations {
+				rename_variable_field(declaration.name.get_ast_mut(), rename);
+			}
+		}
+		StatementOrDeclaration::Declaration(Declaration::Variable(
+			VariableDeclaration::LetDeclaration { declarations, .. },
+		)) => {
+			for declaration in declarations {
+				rename_variable_field(declaration.name.get_ast_mut(), rename);
+			}
+		}
+		StatementOrDeclaration::Statement(Statement::VarVariable(var)) => {
+			for declaration in &mut var.declarations {
+				rename_variable_field(declaration.name.get_ast_mut(), rename);
+			}
+		}
+		StatementOrDeclaration::Declaration(Declaration::Function(function)) => {
+			rename_identifier(&mut function.on.name.0);
+		}
+		StatementOrDeclaration::Declaration(Declaration::Class(class)) => {
+			rename_identifier(&mut class.on.name.0);
+		}
+		StatementOrDeclaration::Declaration(Declaration::Enum(r#enum)) => {
+			if let Some(new_name) = rename(&r#enum.on.name) {
+				r#enum.on.name = new_name;
+			}
+		}
+		_ => {}
+	}
+}
+
+fn rename_variable_field(
+	field: &mut VariableField<VariableFieldInSourceCode>,
+	rename: &impl Fn(&str) -> Option<String>,
+) {
+	match field {
+		VariableField::Name(VariableIdentifier::Standard(name, _)) => {
+			if let Some(new_name) = rename(name) {
+				*name = new_name;
+			}
+		}
+		VariableField::Name(VariableIdentifier::Marker(..)) => {}
+		VariableField::Array(members, _) => {
+			for member in members {
+				match member.get_ast_mut() {
+					ArrayDestructuringField::Spread(VariableIdentifier::Standard(name, _), _) => {
+						if let Some(new_name) = rename(name) {
+							*name = new_name;
+						}
+					}
+					ArrayDestructuringField::Name(field, _) => rename_variable_field(field, rename),
+					ArrayDestructuringField::Spread(VariableIdentifier::Marker(..), _)
+					| ArrayDestructuringField::None => {}
+				}
+			}
+		}
+		VariableField::Object(members, _) => {
+			for member in members {
+				let member = member.get_ast_mut();
+				match member {
+					ObjectDestructuringField::Name(
+						VariableIdentifier::Standard(name, position),
+						default_value,
+						_,
+					) => {
+						// `{ x }` becomes `{ x: x$1 }`
+						if let Some(new_name) = rename(name) {
+							let position = *position;
+							*member = ObjectDestructuringField::Map {
+								from: PropertyKey::Ident(mem::take(name), position, ()),
+								name: WithComment::None(VariableField::Name(
+									VariableIdentifier::Standard(new_name, position),
+								)),
+								default_value: default_value.take(),
+								position,
+							};
+						}
+					}
+					ObjectDestructuringField::Spread(VariableIdentifier::Standard(name, _), _) => {
+						if let Some(new_name) = rename(name) {
+							*name = new_name;
+						}
+					}
+					ObjectDestructuringField::Map { name, .. } => {
+						rename_variable_field(name.get_ast_mut(), rename);
+					}
+					ObjectDestructuringField::Name(VariableIdentifier::Marker(..), ..)
+					| ObjectDestructuringField::Spread(VariableIdentifier::Marker(..), _) => {}
+				}
+			}
+		}
+	}
+}
+
+/// `const name = { exported: binding, ... }`
+fn namespace_declaration(
+	name: String,
+	members: Vec<(String, String)>,
+	position: Span,
+) -> StatementOrDeclaration {
+	let members = members
+		.into_iter()
+		.map(|(key, binding)| {
+			ObjectLiteralMember::Property(
+				WithComment::None(PropertyKey::Ident(key, position, ())),
+				Expression::VariableReference(binding, position),
+				position,
+			)
+		})
+		.collect();
+
+	let declaration = VariableDeclaration::ConstDeclaration {
+		declarations: vec![VariableDeclarationItem {
+			name: WithComment::None(VariableField::Name(VariableIdentifier::Standard(
+				name, position,
+			))),
+			type_annotation: None,
+			expression: Expression::ObjectLiteral(ObjectLiteral { members, position }),
+			position,
+		}],
+		position,
+	};
+	StatementOrDeclaration::Declaration(Declaration::Variable(declaration))
+}
+
+/// `export { binding as exported, ... }`
+fn export_list(exports: Vec<(String, String)>) -> StatementOrDeclaration {
+	let position = Span::NULL;
+	let parts = exports
+		.into_iter()
+		.map(|(exported, binding)| {
+			if exported == binding {
+				ExportPart::Name(VariableIdentifier::Standard(binding, position))
+			} else {
+				ExportPart::NameWithAlias {
+					name: binding,
+					alias: ImportExportName::Reference(exported),
+					position,
+				}
+			}
+		})
+		.collect();
+
+	StatementOrDeclaration::Declaration(Declaration::Export(Decorated::new_empty(
+		ExportDeclaration::Variable { exported: Exportable::Parts(parts), position },
+	)))
+}
+
+/// Hands out names that have not been used before, by appending `$n`
+#[derive(Default)]
+struct UniqueNames(HashSet<String>);
+
+impl UniqueNames {
+	fn new_name(&mut self, name: &str) -> String {
+		let mut new_name = name.to_owned();
+		let mut counter = 1;
+		while self.0.contains(&new_name) {
+			new_name = format!("{name}${counter}");
+			counter += 1;
+		}
+		self.0.insert(new_name.clone());
+		new_name
+	}
+}
+
+/// Renames references (by position) to top level bindings
+struct ReferenceRenamer {
+	references: HashMap<u32, String>,
+}
+
+impl ReferenceRenamer {
+	fn rename(&self, name: &mut String, position: &Span) {
+		if let Some(new_name) = self.references.get(&position.start) {
+			new_name.clone_into(name);
+		}
+	}
+}
+
+impl VisitorMutReceiver<()> for ReferenceRenamer {
+	fn visit_expression_mut(
+		&mut self,
+		expression: &mut Expression,
+		_data: &mut (),
+		_chain: &Chain,
+	) {
+		match expression {
+			Expression::VariableReference(name, position)
+			| Expression::Assignment {
+				lhs:
+					LHSOfAssignment::VariableOrPropertyAccess(VariableOrPropertyAccess::Variable(
+						name,
+						position,
+					)),
+				..
+			}
+			| Expression::BinaryAssignmentOperation {
+				lhs: VariableOrPropertyAccess::Variable(name, position),
+				..
+			}
+			| Expression::UnaryPrefixAssignmentOperation {
+				operand: VariableOrPropertyAccess::Variable(name, position),
+				..
+			}
+			| Expression::UnaryPostfixAssignmentOperation {
+				operand: VariableOrPropertyAccess::Variable(name, position),
+				..
+			} => self.rename(name, position),
+			Expression::ObjectLiteral(ObjectLiteral { members, .. }) => {
+				for member in members {
+					if let ObjectLiteralMember::Shorthand(name, position) = member {
+						// `{ x }` becomes `{ x: x$1 }`
+						let new_name = self.references.get(&position.start);
+						if let Some(new_name) = new_name.filter(|new_name| *new_name != name) {
+							let position = *position;
+							*member = ObjectLiteralMember::Property(
+								WithComment::None(PropertyKey::Ident(
+									mem::take(name),
+									position,
+									(),
+								)),
+								Expression::VariableReference(new_name.clone(), position),
+								position,
+							);
+						}
+					}
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+	};
+
+	use crate::build::{build, BuildConfig};
+
+	fn bundle(files: &[(&str, &str)]) -> String {
+		let files: HashMap<PathBuf, String> = files
+			.iter()
+			.map(|(path, content)| (PathBuf::from(path), (*content).to_owned()))
+			.collect();
+		let read_file = |path: &Path| files.get(path).cloned();
+		let config = BuildConfig { strip_whitespace: false, source_maps: false, bundle: true };
+		let Ok(output) = build(
+			vec!["main.ts".into()],
+			&read_file,
+			&[],
+			None,
+			Path::new("out.js"),
+			&config,
+			None,
+		) else {
+			panic!("build failed");
+		};
+		assert_eq!(output.outputs.len(), 1);
+		output.outputs.into_iter().next().unwrap().content
+	}
+
+	#[test]
+	fn renames_colliding_top_level_names() {
+		let output = bundle(&[
+			(
+				"main.ts",
+				"import { value } from './a';\nfunction helper() { return 1 }\nexport const result = value + helper();",
+			),
+			("a.ts", "function helper() { return 2 }\nexport const value = helper();"),
+		]);
+		// The dependency keeps its name as it is declared first
+		assert!(output.contains("function helper() {\n\treturn 2\n}\nconst value = helper();"));
+		assert!(output.contains("function helper$1() {\n\treturn 1\n}"));
+		assert!(output.contains("const result = value + helper$1();"));
+		assert!(output.ends_with("export { result }"));
+		assert!(!output.contains("import"));
+	}
+
+	#[test]
+	fn re_exports() {
+		let output = bundle(&[
+			(
+				"main.ts",
+				"export { value } from './a';\nexport * from './b';\nimport { other as renamed } from './c';\nexport const c = renamed;",
+			),
+			("a.ts", "export const value = 1;"),
+			("b.ts", "export const fromB = 2;"),
+			("c.ts", "export { x as other } from './d';"),
+			("d.ts", "export const x = 3;"),
+		]);
+		assert!(output.contains("const c = x;"));
+		assert!(output.ends_with("export { value, fromB, c }"));
+		assert!(!output.contains("import") && !output.contains("from '"));
+	}
+
+	#[test]
+	fn cyclic_imports() {
+		let output = bundle(&[
+			("main.ts", "import { a } from './a';\nexport const result = a();"),
+			("a.ts", "import { b } from './b';\nexport function a() { return b() }\nexport const name = 'a';"),
+			("b.ts", "import { name } from './a';\nexport function b() { return name }"),
+		]);
+		// Each module is included once, dependencies first
+		let b = output.find("function b()").unwrap();
+		let a = output.find("function a()").unwrap();
+		let result = output.find("const result").unwrap();
+		assert!(b < a && a < result);
+		assert_eq!(output.matches("function a()").count(), 1);
+		assert!(!output.contains("import"));
+	}
+
+	#[test]
+	fn default_exports() {
+		let output = bundle(&[
+			(
+				"main.ts",
+				"import create from './create';\nimport value, * as all from './value';\nexport default create(value);\nexport const everything = all;",
+			),
+			("create.ts", "export default function (n: number) { return n }"),
+			("value.ts", "export default 4;\nexport const other = 2;"),
+		]);
+		assert!(output.contains("const create_default = function (n) {"));
+		assert!(output.contains("const value_default = 4;"));
+		assert!(output.contains("const all = { other: other, default: value_default };"));
+		assert!(output.contains("const main_default = create_default(value_default);"));
+		assert!(output.ends_with("export { everything, main_default as default }"));
+	}
+}
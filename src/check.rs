@@ -21,28 +21,18 @@ impl CheckingOutputWithoutDiagnostics {
 	pub fn is_function_called(&self, function_id: checker::FunctionId) -> bool {
 		self.types.called_functions.contains(&function_id)
 	}
-
-	pub fn get_type_at_position(&self, _path: &str, _pos: u32) -> Option<String> {
-		todo!("print_type needs context, should take facts chain")
-		// let source =
-		// 	self.module_contents.get_source_at_path(path.into()).expect("no source at path");
-		// self.type_mappings.expressions_to_instances.get(pos).map(|instance| {
-		// 	let value = instance.get_value();
-		// 	checker::types::printing::print_type(value, types, ctx, debug)
-		// })
-	}
 }
 
 pub fn check<T: crate::ReadFromFS>(
 	entry_points: Vec<PathBuf>,
 	read_from_filesystem: &T,
-	type_definition_module: Option<&Path>,
+	type_definition_modules: &[PathBuf],
 	type_check_options: Option<checker::TypeCheckOptions>,
 ) -> CheckOutput<checker::synthesis::EznoParser> {
-	let definitions = if let Some(tdm) = type_definition_module {
-		HashSet::from_iter(std::iter::once(tdm.into()))
-	} else {
+	let definitions = if type_definition_modules.is_empty() {
 		HashSet::from_iter(std::iter::once(checker::INTERNAL_DEFINITION_FILE_PATH.into()))
+	} else {
+		type_definition_modules.iter().cloned().collect()
 	};
 
 	let read_from_fs = |path: &Path| {
@@ -55,3 +45,43 @@ pub fn check<T: crate::ReadFromFS>(
 
 	checker::check_project(entry_points, definitions, read_from_fs, type_check_options, ())
 }
+
+/// Checks `entry_points` against a root loaded from a cache created by [`pack_definition_file`]
+pub fn check_with_definition_cache<T: crate::ReadFromFS>(
+	entry_points: Vec<PathBuf>,
+	read_from_filesystem: &T,
+	definition_cache: &[u8],
+	type_check_options: Option<checker::TypeCheckOptions>,
+) -> CheckOutput<checker::synthesis::EznoParser> {
+	let read_from_fs = |path: &Path| read_from_filesystem.get_content_at_path(path);
+
+	checker::check_project_with_definition_cache(
+		entry_points,
+		definition_cache,
+		read_from_fs,
+		type_check_options,
+		(),
+	)
+}
+
+/// Synthesises a definition file into a binary cache which can be used with [`check_with_definition_cache`]
+pub fn pack_definition_file<T: crate::ReadFromFS>(
+	definition_file: &Path,
+	read_from_filesystem: &T,
+) -> Result<
+	Vec<u8>,
+	(
+		checker::DiagnosticsContainer,
+		parser::source_map::MapFileStore<parser::source_map::WithPathMap>,
+	),
+> {
+	let read_from_fs = |path: &Path| {
+		if path == Path::new(checker::INTERNAL_DEFINITION_FILE_PATH) {
+			Some(checker::INTERNAL_DEFINITION_FILE.to_owned())
+		} else {
+			read_from_filesystem.get_content_at_path(path)
+		}
+	};
+
+	checker::definition_file_to_buffer::<_, EznoParser>(definition_file, read_from_fs, ())
+}
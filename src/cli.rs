@@ -3,19 +3,22 @@ use std::{
 	collections::HashSet,
 	env, fs,
 	path::{Path, PathBuf},
-	process::Command,
+	process::{Command, ExitCode},
 	time::Instant,
 };
 
 use crate::{
 	build::{build, BuildOutput, FailedBuildOutput},
 	build::{BuildConfig, EznoParsePostCheckVisitors},
-	check::check,
-	error_handling::emit_ezno_diagnostic,
+	check::{check, check_with_definition_cache},
+	config::{ProjectConfig, CONFIG_FILE_NAME},
+	error_handling::{emit_ezno_diagnostic, emit_ezno_diagnostics, DiagnosticsOutputFormat},
 	utilities::print_to_cli,
+	watch::run_or_watch,
 };
 use argh::FromArgs;
-use checker::CheckOutput;
+use base64::Engine;
+use checker::{CheckOutput, DiagnosticKind};
 
 /// Ezno Compiler
 #[derive(FromArgs, Debug)]
@@ -32,9 +35,10 @@ enum CompilerSubCommand {
 	Check(CheckArguments),
 	Experimental(ExperimentalArguments),
 	Repl(crate::repl::ReplArguments),
+	#[cfg(not(target_family = "wasm"))]
+	Lsp(LspArguments),
+	Pack(PackArguments),
 	// Run(RunArguments),
-	// #[cfg(debug_assertions)]
-	// Pack(Pack),
 }
 
 /// Display Ezno information
@@ -57,21 +61,19 @@ pub(crate) enum ExperimentalSubcommand {
 }
 
 /// Build project
-/// TODO definition file as list
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "build")]
 // TODO: Can be refactored with bit to reduce memory
 #[allow(clippy::struct_excessive_bools)]
 pub(crate) struct BuildArguments {
-	/// path to input file
-	#[argh(positional)]
-	pub input: PathBuf,
-	/// path to output
-	#[argh(positional)]
-	pub output: Option<PathBuf>,
+	/// path to input file then path to output. Default to the entry points and output in
+	/// `ezno.json`. Modules other than the entry are written next to the output unless bundling
+	// `argh` only allows the last positional to be optional, so both are collected here
+	#[argh(positional, arg_name = "input [output]")]
+	pub paths: Vec<PathBuf>,
 	/// paths to definition files
 	#[argh(option, short = 'd')]
-	pub definition_file: Option<PathBuf>,
+	pub definition_file: Vec<PathBuf>,
 
 	/// whether to minify build output
 	#[argh(switch, short = 'm')]
@@ -82,6 +84,9 @@ pub(crate) struct BuildArguments {
 	/// build source maps
 	#[argh(switch)]
 	pub source_maps: bool,
+	/// bundle all modules into a single output file
+	#[argh(switch)]
+	pub bundle: bool,
 
 	/// enable non standard syntax
 	#[argh(switch)]
@@ -97,29 +102,53 @@ pub(crate) struct BuildArguments {
 	/// whether to display compile times
 	#[argh(switch)]
 	pub timings: bool,
-	// /// whether to re-build on file changes
-	// #[argh(switch)]
-	// watch: bool,
+	/// whether to re-build on file changes
+	#[argh(switch)]
+	pub watch: bool,
 }
 
 /// Type check project
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "check")]
 pub(crate) struct CheckArguments {
-	/// path to input file
+	/// path to input file. Defaults to the entry points in `ezno.json`
 	#[argh(positional)]
-	pub input: PathBuf,
+	pub input: Option<PathBuf>,
 	/// paths to definition files
 	#[argh(option, short = 'd')]
-	pub definition_file: Option<PathBuf>,
+	pub definition_file: Vec<PathBuf>,
+	/// path to a definition cache created by `pack`, used instead of definition files
+	#[argh(option)]
+	pub definition_cache: Option<PathBuf>,
 	/// whether to re-check on file changes
 	#[argh(switch)]
 	pub watch: bool,
 	/// whether to display check time
 	#[argh(switch)]
 	pub timings: bool,
+	/// how to output diagnostics: 'text' (default), 'json' or 'sarif'
+	#[argh(option, default = "DiagnosticsOutputFormat::Text")]
+	pub format: DiagnosticsOutputFormat,
+}
+
+/// Synthesise a definition file into a cache, for use with `check --definition-cache`
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "pack")]
+pub(crate) struct PackArguments {
+	/// path to definition file
+	#[argh(positional)]
+	pub input: PathBuf,
+	/// path to output
+	#[argh(option, short = 'o')]
+	pub output: PathBuf,
 }
 
+/// Run the language server (over stdio)
+#[cfg(not(target_family = "wasm"))]
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "lsp")]
+pub(crate) struct LspArguments {}
+
 // /// Run project using Deno
 // #[derive(FromArgs, PartialEq, Debug)]
 // #[argh(subcommand, name = "run")]
@@ -137,6 +166,49 @@ pub(crate) struct CheckArguments {
 // 	watch: bool,
 // }
 
+/// Discovers `ezno.json` (or `tsconfig.json`). `input` and `definition_files` from the CLI replace
+/// the ones in the configuration
+fn load_project_config<T: crate::ReadFromFS>(
+	read_file: &T,
+	input: Option<PathBuf>,
+	definition_files: Vec<PathBuf>,
+) -> Result<ProjectConfig, String> {
+	// When the input is given, the configuration only supplies options and so it being
+	// invalid is not fatal
+	let mut config = match ProjectConfig::discover(read_file, input.is_none()) {
+		Ok(config) => config.unwrap_or_default(),
+		Err(err) if input.is_some() => {
+			eprintln!("Ignoring project configuration: {err}");
+			ProjectConfig::default()
+		}
+		Err(err) => return Err(err),
+	};
+
+	if let Some(input) = input {
+		config.entry_points = vec![input];
+	}
+	if !definition_files.is_empty() {
+		config.definition_files = definition_files;
+	}
+
+	if config.entry_points.is_empty() {
+		Err(format!("no input file given and no entry points found in {CONFIG_FILE_NAME}"))
+	} else {
+		Ok(config)
+	}
+}
+
+/// Reads a cache written by `pack`
+fn read_definition_cache<T: crate::ReadFromFS>(
+	path: &Path,
+	read_file: &T,
+) -> Result<Vec<u8>, String> {
+	let content = read_file
+		.get_content_at_path(path)
+		.ok_or_else(|| format!("could not find {}", path.display()))?;
+	base64::engine::general_purpose::STANDARD.decode(content.trim()).map_err(|err| err.to_string())
+}
+
 #[allow(unused)]
 fn file_system_resolver(path: &Path) -> Option<String> {
 	// Cheaty
@@ -154,12 +226,13 @@ pub fn run_cli<T: crate::ReadFromFS, U: crate::WriteToFS, V: crate::CLIInputReso
 	read_file: &T,
 	write_file: U,
 	cli_input_resolver: V,
-) {
+) -> ExitCode {
 	let command = match FromArgs::from_args(&["ezno-cli"], cli_arguments) {
 		Ok(TopLevel { nested }) => nested,
 		Err(err) => {
 			print_to_cli(format_args!("{}", err.output));
-			return;
+			// `--help` is also reported through here
+			return if err.status.is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE };
 		}
 	};
 
@@ -168,73 +241,194 @@ pub fn run_cli<T: crate::ReadFromFS, U: crate::WriteToFS, V: crate::CLIInputReso
 			crate::utilities::print_info();
 		}
 		CompilerSubCommand::Check(check_arguments) => {
-			let CheckArguments { input, watch: _, definition_file, timings } = check_arguments;
-			let entry_points = vec![input];
-
-			#[cfg(not(target_family = "wasm"))]
-			let start = timings.then(std::time::Instant::now);
+			let CheckArguments { input, watch, definition_file, definition_cache, timings, format } =
+				check_arguments;
 
-			let CheckOutput { diagnostics, module_contents, .. } =
-				check(entry_points, read_file, definition_file.as_deref(), None);
+			let config = match load_project_config(read_file, input, definition_file) {
+				Ok(config) => config,
+				Err(err) => {
+					eprintln!("Could not load project: {err}");
+					return ExitCode::FAILURE;
+				}
+			};
 
-			#[cfg(not(target_family = "wasm"))]
-			if let Some(start) = start {
-				eprintln!("Checked in {:?}", start.elapsed());
+			let definition_cache = match definition_cache
+				.map(|path| read_definition_cache(&path, read_file))
+				.transpose()
+			{
+				Ok(definition_cache) => definition_cache,
+				Err(err) => {
+					eprintln!("Could not read definition cache: {err}");
+					return ExitCode::FAILURE;
+				}
 			};
 
-			if !diagnostics.has_error() {
-				print_to_cli(format_args!("No type errors found 🎉"))
+			let mut has_error = false;
+
+			let result = run_or_watch(read_file, watch, |read_file| {
+				#[cfg(not(target_family = "wasm"))]
+				let start = timings.then(std::time::Instant::now);
+
+				let CheckOutput { diagnostics, module_contents, .. } =
+					if let Some(ref definition_cache) = definition_cache {
+						check_with_definition_cache(
+							config.entry_points.clone(),
+							read_file,
+							definition_cache,
+							Some(config.type_check_options.clone()),
+						)
+					} else {
+						check(
+							config.entry_points.clone(),
+							read_file,
+							&config.definition_files,
+							Some(config.type_check_options.clone()),
+						)
+					};
+
+				#[cfg(not(target_family = "wasm"))]
+				if let Some(start) = start {
+					eprintln!("Checked in {:?}", start.elapsed());
+				};
+
+				has_error = diagnostics.has_error();
+				let diagnostics = diagnostics.get_diagnostics();
+
+				let errors = diagnostics
+					.iter()
+					.filter(|diagnostic| matches!(diagnostic.kind(), DiagnosticKind::Error))
+					.count();
+				let warnings = diagnostics.len() - errors;
+
+				if !watch && !has_error && format == DiagnosticsOutputFormat::Text {
+					print_to_cli(format_args!("No type errors found 🎉"))
+				}
+
+				emit_ezno_diagnostics(diagnostics, &module_contents, format).unwrap();
+
+				if watch {
+					eprintln!("Found {errors} error(s) and {warnings} warning(s)");
+				}
+			});
+
+			if let Err(err) = result {
+				eprintln!("Could not watch files: {err}");
+				return ExitCode::FAILURE;
 			}
-			for diagnostic in diagnostics.into_iter() {
-				emit_ezno_diagnostic(diagnostic, &module_contents).unwrap();
+
+			if has_error && !watch {
+				return ExitCode::FAILURE;
 			}
 		}
 		CompilerSubCommand::Experimental(ExperimentalArguments {
 			nested: ExperimentalSubcommand::Build(build_config),
 		}) => {
-			let output_path = build_config.output.unwrap_or("ezno_output.js".into());
-
-			// TODO
-			let default_builders = EznoParsePostCheckVisitors {
-				expression_visitors_mut: vec![Box::new(
-					crate::transformers::optimisations::ExpressionOptimiser,
-				)],
-				statement_visitors_mut: vec![Box::new(
-					crate::transformers::optimisations::StatementOptimiser,
-				)],
-				variable_visitors_mut: Default::default(),
-				block_visitors_mut: Default::default(),
+			let (input, output) = match build_config.paths.as_slice() {
+				[] => (None, None),
+				[input] => (Some(input.clone()), None),
+				[input, output] => (Some(input.clone()), Some(output.clone())),
+				_ => {
+					eprintln!("Expected at most an input and an output path");
+					return ExitCode::FAILURE;
+				}
 			};
 
-			let input_paths = vec![build_config.input];
+			let config = match load_project_config(read_file, input, build_config.definition_file) {
+				Ok(config) => config,
+				Err(err) => {
+					eprintln!("Could not load project: {err}");
+					return ExitCode::FAILURE;
+				}
+			};
 
-			let output = build(
-				input_paths,
-				read_file,
-				build_config.definition_file.as_deref(),
-				&output_path,
-				&BuildConfig { strip_whitespace: build_config.minify },
-				Some(default_builders),
-			);
+			// Switches can only enable settings from the configuration
+			let build_settings = BuildConfig {
+				strip_whitespace: build_config.minify || config.build.minify,
+				source_maps: build_config.source_maps || config.build.source_maps,
+				bundle: build_config.bundle || config.build.bundle,
+			};
+
+			let output_path = output.or(config.build.output).unwrap_or("ezno_output.js".into());
+
+			let result = run_or_watch(read_file, build_config.watch, |read_file| {
+				// TODO
+				let default_builders = EznoParsePostCheckVisitors {
+					expression_visitors_mut: vec![Box::new(
+						crate::transformers::optimisations::ExpressionOptimiser,
+					)],
+					statement_visitors_mut: vec![Box::new(
+						crate::transformers::optimisations::StatementOptimiser,
+					)],
+					variable_visitors_mut: Default::default(),
+					block_visitors_mut: Default::default(),
+				};
+
+				let output = build(
+					config.entry_points.clone(),
+					read_file,
+					&config.definition_files,
+					Some(config.type_check_options.clone()),
+					&output_path,
+					&build_settings,
+					Some(default_builders),
+				);
 
-			match output {
-				Ok(BuildOutput { diagnostics, fs, outputs }) => {
-					for output in outputs {
-						write_file(output.output_path.as_path(), output.content);
+				match output {
+					Ok(BuildOutput { diagnostics, fs, outputs }) => {
+						for output in outputs {
+							if !output.mappings.is_empty() {
+								let mut map_path = output.output_path.clone().into_os_string();
+								map_path.push(".map");
+								write_file(Path::new(&map_path), output.mappings);
+							}
+							write_file(output.output_path.as_path(), output.content);
+						}
+						for diagnostic in diagnostics {
+							emit_ezno_diagnostic(diagnostic, &fs).unwrap();
+						}
+
+						print_to_cli(format_args!("Project built successfully 🎉"))
 					}
-					for diagnostic in diagnostics {
-						emit_ezno_diagnostic(diagnostic, &fs).unwrap();
+					Err(FailedBuildOutput { fs, diagnostics }) => {
+						for diagnostic in diagnostics {
+							emit_ezno_diagnostic(diagnostic, &fs).unwrap();
+						}
+						if build_config.watch {
+							eprintln!("Build failed");
+						}
 					}
+				}
+			});
 
-					print_to_cli(format_args!("Project built successfully 🎉"))
+			if let Err(err) = result {
+				eprintln!("Could not watch files: {err}");
+				return ExitCode::FAILURE;
+			}
+		}
+		CompilerSubCommand::Pack(PackArguments { input, output }) => {
+			match crate::check::pack_definition_file(&input, read_file) {
+				Ok(buffer) => {
+					// The file system callbacks work with strings
+					write_file(&output, base64::engine::general_purpose::STANDARD.encode(buffer));
+					print_to_cli(format_args!("Wrote definition cache to {}", output.display()));
 				}
-				Err(FailedBuildOutput { fs, diagnostics }) => {
+				Err((diagnostics, fs)) => {
 					for diagnostic in diagnostics {
-						emit_ezno_diagnostic(diagnostic, &fs).unwrap();
+						if let Err(err) = emit_ezno_diagnostic(diagnostic, &fs) {
+							eprintln!("Could not emit diagnostic: {err}");
+						}
 					}
+					return ExitCode::FAILURE;
 				}
 			}
 		}
+		#[cfg(not(target_family = "wasm"))]
+		CompilerSubCommand::Lsp(_) => {
+			if let Err(err) = lsp::run_stdio() {
+				eprintln!("Language server error: {err}");
+				return ExitCode::FAILURE;
+			}
+		}
 		CompilerSubCommand::ASTExplorer(mut repl) => repl.run(read_file, cli_input_resolver),
 		CompilerSubCommand::Repl(argument) => crate::repl::run_repl(cli_input_resolver, argument),
 		// CompilerSubCommand::Run(run_arguments) => {
@@ -258,20 +452,116 @@ pub fn run_cli<T: crate::ReadFromFS, U: crate::WriteToFS, V: crate::CLIInputReso
 		// 			.unwrap();
 		// 	}
 		// }
-		// #[cfg(debug_assertions)]
-		// CompilerSubCommand::Pack(Pack { input, output }) => {
-		// 	let file = checker::definition_file_to_buffer(
-		// 		&file_system_resolver,
-		// 		&env::current_dir().unwrap(),
-		// 		&input,
-		// 	)
-		// 	.unwrap();
-
-		// 	std::fs::write(&output, &file).unwrap();
-		// 	// println!("Wrote binary context out to {}", output.display());
-
-		// 	let _root_ctx = checker::root_context_from_bytes(file);
-		// 	println!("Registered {} types", _root_ctx.types.len());
-		// }
+	}
+
+	ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		cell::RefCell,
+		collections::HashMap,
+		path::{Path, PathBuf},
+		process::ExitCode,
+	};
+
+	use super::{load_project_config, run_cli};
+
+	const CONFIG: &str = r#"{
+		"entry_points": ["config.ts"],
+		"definition_files": ["config.d.ts"],
+		"build": { "output": "config.js" }
+	}"#;
+
+	fn files(config: &str) -> HashMap<PathBuf, String> {
+		[
+			("ezno.json", config),
+			("config.ts", "export const a = 1;"),
+			("cli.ts", "export const b = 2;"),
+			("config.d.ts", ""),
+		]
+		.into_iter()
+		.map(|(path, content)| (PathBuf::from(path), content.to_owned()))
+		.collect()
+	}
+
+	#[test]
+	fn cli_arguments_override_config() {
+		let files = files(CONFIG);
+		let read_file = |path: &Path| files.get(path).cloned();
+
+		let config = load_project_config(&read_file, None, Vec::new()).unwrap();
+		assert_eq!(config.entry_points, [PathBuf::from("config.ts")]);
+		assert_eq!(config.definition_files, [PathBuf::from("config.d.ts")]);
+
+		let config =
+			load_project_config(&read_file, Some("cli.ts".into()), vec!["cli.d.ts".into()])
+				.unwrap();
+		assert_eq!(config.entry_points, [PathBuf::from("cli.ts")]);
+		assert_eq!(config.definition_files, [PathBuf::from("cli.d.ts")]);
+	}
+
+	#[test]
+	fn invalid_config_with_cli_input() {
+		let files = files("{ \"entry-points\": [] }");
+		let read_file = |path: &Path| files.get(path).cloned();
+
+		assert!(load_project_config(&read_file, None, Vec::new()).is_err());
+		let config = load_project_config(&read_file, Some("cli.ts".into()), Vec::new()).unwrap();
+		assert_eq!(config.entry_points, [PathBuf::from("cli.ts")]);
+	}
+
+	#[test]
+	fn build_output_falls_back_to_config() {
+		let files = files(r#"{ "build": { "output": "config.js" } }"#);
+		let read_file = |path: &Path| files.get(path).cloned();
+
+		let build = |arguments: &[&str]| {
+			let written = RefCell::new(Vec::new());
+			let arguments = [&["experimental", "build"], arguments].concat();
+			let _ = run_cli(
+				&arguments,
+				&read_file,
+				|path: &Path, _: String| written.borrow_mut().push(path.to_owned()),
+				|_: &str| None,
+			);
+			written.into_inner()
+		};
+
+		assert_eq!(build(&["cli.ts", "cli.js"]), [PathBuf::from("cli.js")]);
+		assert_eq!(build(&["cli.ts"]), [PathBuf::from("config.js")]);
+	}
+
+	#[test]
+	fn pack_and_check_with_definition_cache() {
+		let files = RefCell::new(files(CONFIG));
+		files.borrow_mut().extend([
+			("add.d.ts".into(), "declare function add(a: number, b: number): number;".to_owned()),
+			("valid.ts".into(), "add(1, 2) satisfies number;".to_owned()),
+			("invalid.ts".into(), "add(1, 2) satisfies string;".to_owned()),
+		]);
+		let read_file = |path: &Path| files.borrow().get(path).cloned();
+		let run = |arguments: &[&str]| {
+			run_cli(
+				arguments,
+				&read_file,
+				|path: &Path, content: String| {
+					files.borrow_mut().insert(path.to_owned(), content);
+				},
+				|_: &str| None,
+			)
+		};
+
+		assert_eq!(run(&["pack", "add.d.ts", "--output", "add.cache"]), ExitCode::SUCCESS);
+		assert!(files.borrow().contains_key(Path::new("add.cache")));
+
+		let check = |input| run(&["check", input, "--definition-cache", "add.cache"]);
+		assert_eq!(check("valid.ts"), ExitCode::SUCCESS);
+		assert_eq!(check("invalid.ts"), ExitCode::FAILURE);
+		assert_eq!(
+			run(&["check", "valid.ts", "--definition-cache", "missing.cache"]),
+			ExitCode::FAILURE
+		);
 	}
 }
@@ -0,0 +1,405 @@
+//! Project configuration from a `ezno.json` file. Falls back to the equivalent parts of a
+//! `tsconfig.json`. Explicit CLI arguments take precedence over either
+
+use std::{
+	collections::HashMap,
+	path::{Component, Path, PathBuf},
+};
+
+use checker::TypeCheckOptions;
+
+pub const CONFIG_FILE_NAME: &str = "ezno.json";
+pub const TSCONFIG_FILE_NAME: &str = "tsconfig.json";
+
+/// Extensions of files picked up by `include` globs
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "js", "jsx", "mjs"];
+
+/// Contents of `ezno.json`. Paths (and globs) are relative to the file
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+	/// Files to start checking (or building) from
+	pub entry_points: Vec<PathBuf>,
+	/// Globs of additional entry points. A pattern without wildcards that names a directory
+	/// includes everything under it
+	pub include: Vec<String>,
+	/// Globs removed from the matches of `include`
+	pub exclude: Vec<String>,
+	/// Used instead of the internal definitions
+	pub definition_files: Vec<PathBuf>,
+	pub type_check_options: TypeCheckOptions,
+	pub build: BuildSettings,
+}
+
+/// Defaults for `experimental build`
+#[derive(Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildSettings {
+	pub output: Option<PathBuf>,
+	pub minify: bool,
+	pub source_maps: bool,
+	pub bundle: bool,
+}
+
+/// The parts of `tsconfig.json` that map to [`ProjectConfig`]
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TSConfig {
+	files: Vec<PathBuf>,
+	include: Option<Vec<String>>,
+	exclude: Vec<String>,
+	compiler_options: TSCompilerOptions,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TSCompilerOptions {
+	base_url: Option<PathBuf>,
+	paths: HashMap<String, Vec<String>>,
+	out_dir: Option<PathBuf>,
+	out_file: Option<PathBuf>,
+	source_map: bool,
+	jsx_factory: Option<String>,
+}
+
+impl From<TSConfig> for ProjectConfig {
+	fn from(ts_config: TSConfig) -> Self {
+		let TSConfig { files, include, exclude, compiler_options } = ts_config;
+
+		// Without `files` or `include`, TypeScript includes everything
+		let include = include.unwrap_or_else(|| {
+			if files.is_empty() {
+				vec!["**/*".to_owned()]
+			} else {
+				Vec::new()
+			}
+		});
+
+		let mut type_check_options = TypeCheckOptions::default();
+		type_check_options.import_aliases.base_url = compiler_options.base_url;
+		type_check_options.import_aliases.paths = compiler_options.paths;
+		if let Some(jsx_factory) = compiler_options.jsx_factory {
+			type_check_options.jsx_factory = jsx_factory;
+		}
+
+		let build = BuildSettings {
+			bundle: compiler_options.out_file.is_some(),
+			output: compiler_options.out_file.or(compiler_options.out_dir),
+			source_maps: compiler_options.source_map,
+			minify: false,
+		};
+
+		Self {
+			entry_points: files,
+			include,
+			exclude,
+			definition_files: Vec::new(),
+			type_check_options,
+			build,
+		}
+	}
+}
+
+impl ProjectConfig {
+	/// Looks for `ezno.json` in the current directory and its ancestors, then for `tsconfig.json`.
+	/// Returned paths are relative to the current directory. `include` globs are expanded into
+	/// `entry_points` if `expand_include` (not needed when the CLI names the inputs)
+	pub fn discover<T: crate::ReadFromFS>(
+		read_file: &T,
+		expand_include: bool,
+	) -> Result<Option<Self>, String> {
+		// Not available on some platforms, in which case only the current directory is searched
+		let depth = std::env::current_dir().map_or(1, |cwd| cwd.ancestors().count());
+
+		for (file_name, is_ts_config) in [(CONFIG_FILE_NAME, false), (TSCONFIG_FILE_NAME, true)] {
+			for up in 0..depth {
+				// `repeat_n` requires Rust 1.82
+				#[allow(clippy::manual_repeat_n)]
+				let directory = std::iter::repeat(Component::ParentDir).take(up).collect::<PathBuf>();
+				let path = directory.join(file_name);
+
+				if let Some(content) = read_file.get_content_at_path(&path) {
+					let config = Self::from_string(&content, is_ts_config)
+						.map_err(|err| format!("{}: {err}", path.display()))?;
+					return Ok(Some(config.relative_to(&directory, expand_include)));
+				}
+			}
+		}
+
+		Ok(None)
+	}
+
+	pub fn from_string(content: &str, is_ts_config: bool) -> Result<Self, String> {
+		if is_ts_config {
+			// `tsconfig.json` files allow comments and trailing commas
+			serde_json::from_str::<TSConfig>(&strip_json_extensions(content))
+				.map(Self::from)
+				.map_err(|err| err.to_string())
+		} else {
+			serde_json::from_str(content).map_err(|err| err.to_string())
+		}
+	}
+
+	/// Makes the paths relative to `directory` (where the config was found) and optionally expands
+	/// `include`
+	fn relative_to(mut self, directory: &Path, expand_include: bool) -> Self {
+		for path in self.entry_points.iter_mut().chain(self.definition_files.iter_mut()) {
+			*path = directory.join(&*path);
+		}
+		if let Some(ref mut output) = self.build.output {
+			*output = directory.join(&*output);
+		}
+
+		// Like TypeScript, `paths` without a `baseUrl` are relative to the config
+		let aliases = &mut self.type_check_options.import_aliases;
+		if let Some(ref mut base_url) = aliases.base_url {
+			*base_url = directory.join(&*base_url);
+		} else if !aliases.paths.is_empty() {
+			aliases.base_url = Some(directory.to_path_buf());
+		}
+
+		if expand_include && !self.include.is_empty() {
+			let mut included = Vec::new();
+			find_files(directory, PathBuf::new(), &mut |relative| {
+				let relative = relative.to_string_lossy().replace('\\', "/");
+				if self.include.iter().any(|pattern| matches_include(pattern, &relative))
+					&& !self.exclude.iter().any(|pattern| matches_include(pattern, &relative))
+				{
+					included.push(directory.join(relative));
+				}
+			});
+			included.sort();
+
+			for path in included {
+				if !self.entry_points.contains(&path) {
+					self.entry_points.push(path);
+				}
+			}
+		}
+
+		self
+	}
+}
+
+/// Calls `cb` with source files under `root`, as paths relative to it. Skips `node_modules`
+fn find_files(root: &Path, relative: PathBuf, cb: &mut impl FnMut(&Path)) {
+	let directory = root.join(&relative);
+	let directory = if directory.as_os_str().is_empty() { Path::new(".") } else { &directory };
+	let Ok(entries) = std::fs::read_dir(directory) else {
+		return;
+	};
+
+	for entry in entries.flatten() {
+		let name = entry.file_name();
+		let path = relative.join(&name);
+		let Ok(file_type) = entry.file_type() else {
+			continue;
+		};
+
+		if file_type.is_dir() {
+			if name != "node_modules" {
+				find_files(root, path, cb);
+			}
+		} else {
+			let name = name.to_string_lossy();
+			let is_source = !name.ends_with(".d.ts")
+				&& path.extension().is_some_and(|extension| {
+					SOURCE_EXTENSIONS.iter().any(|source_extension| extension == *source_extension)
+				});
+			if is_source {
+				cb(&path);
+			}
+		}
+	}
+}
+
+/// A pattern without wildcards or an extension is a directory and so matches the files under it
+fn matches_include(pattern: &str, path: &str) -> bool {
+	let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+	let is_directory = !pattern.contains(['*', '?'])
+		&& Path::new(pattern).extension().is_none()
+		&& !pattern.is_empty();
+
+	if is_directory {
+		matches_glob(&format!("{pattern}/**/*"), path)
+	} else {
+		matches_glob(pattern, path)
+	}
+}
+
+/// `*` matches within a segment, `**` matches any number of segments and `?` matches one character
+fn matches_glob(pattern: &str, path: &str) -> bool {
+	fn matches_segments(pattern: &[&str], path: &[&str]) -> bool {
+		match (pattern.first(), path.first()) {
+			(Some(&"**"), _) => {
+				matches_segments(&pattern[1..], path)
+					|| (!path.is_empty() && matches_segments(pattern, &path[1..]))
+			}
+			(Some(segment_pattern), Some(segment)) => {
+				matches_segment(segment_pattern.as_bytes(), segment.as_bytes())
+					&& matches_segments(&pattern[1..], &path[1..])
+			}
+			(None, None) => true,
+			(Some(_), None) | (None, Some(_)) => false,
+		}
+	}
+
+	fn matches_segment(pattern: &[u8], segment: &[u8]) -> bool {
+		match (pattern.first(), segment.first()) {
+			(Some(b'*'), _) => {
+				matches_segment(&pattern[1..], segment)
+					|| (!segment.is_empty() && matches_segment(pattern, &segment[1..]))
+			}
+			(Some(b'?'), Some(_)) => matches_segment(&pattern[1..], &segment[1..]),
+			(Some(a), Some(b)) if a == b => matches_segment(&pattern[1..], &segment[1..]),
+			(None, None) => true,
+			_ => false,
+		}
+	}
+
+	let pattern = pattern.split('/').collect::<Vec<_>>();
+	let path = path.split('/').collect::<Vec<_>>();
+	matches_segments(&pattern, &path)
+}
+
+/// Removes comments and trailing commas (outside of strings) so the content can be parsed as JSON
+fn strip_json_extensions(content: &str) -> String {
+	let mut output = String::with_capacity(content.len());
+	let mut chars = content.chars().peekable();
+	// Index of a comma that may be trailing
+	let mut pending_comma: Option<usize> = None;
+
+	while let Some(chr) = chars.next() {
+		match chr {
+			'"' => {
+				pending_comma = None;
+				output.push(chr);
+				while let Some(chr) = chars.next() {
+					output.push(chr);
+					match chr {
+						'\\' => output.extend(chars.next()),
+						'"' => break,
+						_ => {}
+					}
+				}
+			}
+			'/' if chars.peek() == Some(&'/') => {
+				for chr in chars.by_ref() {
+					if chr == '\n' {
+						output.push('\n');
+						break;
+					}
+				}
+			}
+			'/' if chars.peek() == Some(&'*') => {
+				chars.next();
+				let mut last = '\0';
+				for chr in chars.by_ref() {
+					if last == '*' && chr == '/' {
+						break;
+					}
+					last = chr;
+				}
+			}
+			',' => {
+				pending_comma = Some(output.len());
+				output.push(chr);
+			}
+			'}' | ']' => {
+				if let Some(index) = pending_comma.take() {
+					output.replace_range(index..=index, " ");
+				}
+				output.push(chr);
+			}
+			chr if chr.is_whitespace() => output.push(chr),
+			chr => {
+				pending_comma = None;
+				output.push(chr);
+			}
+		}
+	}
+
+	output
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+	};
+
+	use super::{matches_include, strip_json_extensions, ProjectConfig};
+
+	fn discover(files: &[(&str, &str)]) -> Result<Option<ProjectConfig>, String> {
+		let files: HashMap<PathBuf, String> = files
+			.iter()
+			.map(|(path, content)| (PathBuf::from(path), (*content).to_owned()))
+			.collect();
+		let read_file = |path: &Path| files.get(path).cloned();
+		ProjectConfig::discover(&read_file, false)
+	}
+
+	#[test]
+	fn globs() {
+		assert!(matches_include("src/**/*.ts", "src/a.ts"));
+		assert!(matches_include("src/**/*.ts", "src/nested/deep/a.ts"));
+		assert!(!matches_include("src/**/*.ts", "other/a.ts"));
+		assert!(matches_include("src", "src/nested/a.ts"));
+		assert!(matches_include("**/*.test.ts", "src/a.test.ts"));
+		assert!(!matches_include("**/*.test.ts", "src/a.ts"));
+		assert!(matches_include("./src/?.ts", "src/a.ts"));
+	}
+
+	#[test]
+	fn ts_config() {
+		let content = r#"{
+			// Comment
+			"compilerOptions": {
+				"outDir": "dist", /* "sourceMap": false */
+				"sourceMap": true,
+				"paths": { "@/*": ["src/*"], },
+				"jsxFactory": "h",
+			},
+			"files": ["main.ts", "// not a comment"],
+		}"#;
+
+		assert!(strip_json_extensions(content).contains("\"// not a comment\""));
+
+		let config = ProjectConfig::from_string(content, true).unwrap();
+		assert_eq!(config.entry_points.len(), 2);
+		assert!(config.include.is_empty());
+		assert!(config.build.source_maps);
+		assert_eq!(config.build.output.as_deref(), Some(std::path::Path::new("dist")));
+		assert_eq!(config.type_check_options.import_aliases.paths["@/*"], ["src/*"]);
+		assert_eq!(config.type_check_options.jsx_factory, "h");
+	}
+
+	#[test]
+	fn discovery_order() {
+		let ezno = ("ezno.json", r#"{ "entry_points": ["a.ts"] }"#);
+		let parent_ezno = ("../ezno.json", r#"{ "entry_points": ["b.ts"] }"#);
+		let ts_config = ("tsconfig.json", r#"{ "files": ["c.ts"] }"#);
+
+		let entry_points =
+			|files: &[(&str, &str)]| discover(files).unwrap().map(|config| config.entry_points);
+
+		assert_eq!(entry_points(&[ezno, parent_ezno, ts_config]), Some(vec!["a.ts".into()]));
+		// `ezno.json` in an ancestor takes precedence over `tsconfig.json`
+		assert_eq!(entry_points(&[parent_ezno, ts_config]), Some(vec!["../b.ts".into()]));
+		assert_eq!(entry_points(&[ts_config]), Some(vec!["c.ts".into()]));
+		assert_eq!(entry_points(&[]), None);
+
+		// `include` is not expanded when not requested
+		assert_eq!(entry_points(&[("tsconfig.json", "{}")]), Some(Vec::new()));
+
+		let error = discover(&[("ezno.json", "{"), ts_config]).err().unwrap();
+		assert!(error.starts_with("ezno.json: "));
+	}
+
+	#[test]
+	fn unknown_fields() {
+		assert!(ProjectConfig::from_string(r#"{ "entry_points": ["a.ts"] }"#, false).is_ok());
+		assert!(ProjectConfig::from_string(r#"{ "entry-points": ["a.ts"] }"#, false).is_err());
+	}
+}
@@ -1,11 +1,236 @@
-use std::iter;
+use std::{iter, str::FromStr};
 
 use codespan_reporting::{
 	diagnostic::{Diagnostic, Label, Severity},
 	files::Files,
 	term::{emit, Config},
 };
-use parser::source_map::FileSystem;
+use parser::source_map::{encodings::Utf8, FileSystem, SpanWithSource};
+
+use crate::utilities::print_to_cli;
+
+/// How `check` reports diagnostics
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DiagnosticsOutputFormat {
+	/// Human readable, via `codespan_reporting`
+	#[default]
+	Text,
+	/// An array of [`ReportedDiagnostic`]s
+	Json,
+	/// [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) for CI annotations
+	Sarif,
+}
+
+impl FromStr for DiagnosticsOutputFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"text" => Ok(Self::Text),
+			"json" => Ok(Self::Json),
+			"sarif" => Ok(Self::Sarif),
+			s => Err(format!("unknown format '{s}', expected 'text', 'json' or 'sarif'")),
+		}
+	}
+}
+
+/// Lines and columns start at one. Columns are in Unicode code points
+#[derive(serde::Serialize)]
+struct LineColumn {
+	line: u32,
+	column: u32,
+}
+
+#[derive(serde::Serialize)]
+struct Location {
+	path: String,
+	start: LineColumn,
+	end: LineColumn,
+}
+
+impl Location {
+	fn new(span: SpanWithSource, fs: &impl FileSystem) -> Self {
+		let path = fs.get_file_path(span.source).display().to_string().replace('\\', "/");
+		let span = span.into_line_column_span::<Utf8>(fs);
+		Self {
+			path,
+			start: LineColumn { line: span.line_start + 1, column: span.column_start + 1 },
+			end: LineColumn { line: span.line_end + 1, column: span.column_end + 1 },
+		}
+	}
+}
+
+#[derive(serde::Serialize)]
+struct ReportedLabel {
+	message: String,
+	#[serde(flatten)]
+	location: Location,
+}
+
+/// A [`checker::Diagnostic`] with its positions resolved to paths and line-columns
+#[derive(serde::Serialize)]
+struct ReportedDiagnostic {
+	kind: checker::DiagnosticKind,
+	message: String,
+	#[serde(flatten, skip_serializing_if = "Option::is_none")]
+	location: Option<Location>,
+	labels: Vec<ReportedLabel>,
+	notes: Vec<String>,
+}
+
+impl ReportedDiagnostic {
+	fn new(diagnostic: checker::Diagnostic, fs: &impl FileSystem) -> Self {
+		match diagnostic {
+			checker::Diagnostic::Global { reason, kind } => Self {
+				kind,
+				message: reason,
+				location: None,
+				labels: Vec::new(),
+				notes: Vec::new(),
+			},
+			checker::Diagnostic::Position { reason, position, kind } => Self {
+				kind,
+				message: reason,
+				location: Some(Location::new(position, fs)),
+				labels: Vec::new(),
+				notes: Vec::new(),
+			},
+			checker::Diagnostic::PositionWithAdditionalLabels {
+				reason,
+				position,
+				labels,
+				kind,
+			} => {
+				let mut notes = Vec::new();
+				let labels = labels
+					.into_iter()
+					.filter_map(|(message, position)| {
+						if let Some(position) = position {
+							Some(ReportedLabel { message, location: Location::new(position, fs) })
+						} else {
+							notes.push(message);
+							None
+						}
+					})
+					.collect();
+
+				Self {
+					kind,
+					message: reason,
+					location: Some(Location::new(position, fs)),
+					labels,
+					notes,
+				}
+			}
+		}
+	}
+
+	fn into_sarif_result(self) -> serde_json::Value {
+		fn physical_location(location: &Location) -> serde_json::Value {
+			serde_json::json!({
+				"artifactLocation": { "uri": location.path },
+				"region": {
+					"startLine": location.start.line,
+					"startColumn": location.start.column,
+					"endLine": location.end.line,
+					"endColumn": location.end.column,
+				}
+			})
+		}
+
+		// Diagnostics do not have codes yet, so the rule is the kind
+		let (rule_id, level) = match self.kind {
+			checker::DiagnosticKind::Error => ("error", "error"),
+			checker::DiagnosticKind::Warning => ("warning", "warning"),
+			checker::DiagnosticKind::Info => ("info", "note"),
+		};
+
+		let text = iter::once(self.message).chain(self.notes).collect::<Vec<_>>().join("\n");
+
+		let locations = self
+			.location
+			.iter()
+			.map(|location| serde_json::json!({ "physicalLocation": physical_location(location) }))
+			.collect::<Vec<_>>();
+
+		let related_locations = self
+			.labels
+			.iter()
+			.map(|label| {
+				serde_json::json!({
+					"message": { "text": label.message },
+					"physicalLocation": physical_location(&label.location),
+				})
+			})
+			.collect::<Vec<_>>();
+
+		serde_json::json!({
+			"ruleId": rule_id,
+			"level": level,
+			"message": { "text": text },
+			"locations": locations,
+			"relatedLocations": related_locations,
+		})
+	}
+}
+
+/// Text diagnostics are emitted one by one (to stderr). JSON and SARIF are printed as a single
+/// document so that the output can be parsed
+pub(crate) fn emit_ezno_diagnostics(
+	diagnostics: impl IntoIterator<Item = checker::Diagnostic>,
+	fs: &impl FileSystem,
+	format: DiagnosticsOutputFormat,
+) -> Result<(), codespan_reporting::files::Error> {
+	let output = match format {
+		DiagnosticsOutputFormat::Text => {
+			return diagnostics
+				.into_iter()
+				.try_for_each(|diagnostic| emit_ezno_diagnostic(diagnostic, fs));
+		}
+		DiagnosticsOutputFormat::Json => diagnostics_to_json(diagnostics, fs),
+		DiagnosticsOutputFormat::Sarif => diagnostics_to_sarif(diagnostics, fs),
+	};
+
+	print_to_cli(format_args!("{}", serde_json::to_string_pretty(&output).unwrap()));
+	Ok(())
+}
+
+fn diagnostics_to_json(
+	diagnostics: impl IntoIterator<Item = checker::Diagnostic>,
+	fs: &impl FileSystem,
+) -> serde_json::Value {
+	let diagnostics = diagnostics
+		.into_iter()
+		.map(|diagnostic| ReportedDiagnostic::new(diagnostic, fs))
+		.collect::<Vec<_>>();
+	serde_json::to_value(diagnostics).unwrap()
+}
+
+fn diagnostics_to_sarif(
+	diagnostics: impl IntoIterator<Item = checker::Diagnostic>,
+	fs: &impl FileSystem,
+) -> serde_json::Value {
+	let results = diagnostics
+		.into_iter()
+		.map(|diagnostic| ReportedDiagnostic::new(diagnostic, fs).into_sarif_result())
+		.collect::<Vec<_>>();
+
+	serde_json::json!({
+		"$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+		"version": "2.1.0",
+		"runs": [{
+			"tool": {
+				"driver": {
+					"name": env!("CARGO_PKG_NAME"),
+					"version": env!("CARGO_PKG_VERSION"),
+					"informationUri": env!("CARGO_PKG_REPOSITORY"),
+				}
+			},
+			"columnKind": "unicodeCodePoints",
+			"results": results,
+		}]
+	})
+}
 
 pub(crate) fn emit_ezno_diagnostic(
 	diagnostic: checker::Diagnostic,
@@ -61,7 +286,6 @@ fn emit_diagnostic<'files, F: Files<'files>>(
 	diagnostic: &Diagnostic<<F as Files<'files>>::FileId>,
 	files: &'files F,
 ) -> Result<(), codespan_reporting::files::Error> {
-	use crate::utilities::print_to_cli;
 	use codespan_reporting::term::termcolor::Buffer;
 
 	let config = Config::default();
@@ -86,3 +310,162 @@ fn emit_diagnostic<'files, F: Files<'files>>(
 	let mut lock = writer.lock();
 	emit(&mut lock, &config, files, diagnostic)
 }
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		collections::HashMap,
+		path::{Path, PathBuf},
+		process::ExitCode,
+	};
+
+	use parser::source_map::{FileSystem, MapFileStore, NoPathMap, SpanWithSource};
+
+	use super::{diagnostics_to_json, diagnostics_to_sarif};
+
+	const SOURCE: &str = "let a = 2;\nconst b: string = a;\n";
+
+	fn diagnostics() -> (Vec<checker::Diagnostic>, MapFileStore<NoPathMap>) {
+		let mut fs = MapFileStore::<NoPathMap>::default();
+		let source = fs.new_source_id("src/main.ts".into(), SOURCE.to_owned());
+		let span = |start: u32, end: u32| SpanWithSource { start, end, source };
+		let diagnostics = vec![
+			checker::Diagnostic::PositionWithAdditionalLabels {
+				reason: "Type 2 is not assignable to type string".to_owned(),
+				position: span(29, 30),
+				labels: vec![
+					("Variable declared here".to_owned(), Some(span(4, 5))),
+					("Consider a cast".to_owned(), None),
+				],
+				kind: checker::DiagnosticKind::Error,
+			},
+			checker::Diagnostic::Position {
+				reason: "Unused variable".to_owned(),
+				position: span(17, 18),
+				kind: checker::DiagnosticKind::Warning,
+			},
+			checker::Diagnostic::Global {
+				reason: "Checked 1 file".to_owned(),
+				kind: checker::DiagnosticKind::Info,
+			},
+		];
+		(diagnostics, fs)
+	}
+
+	#[test]
+	fn json() {
+		let (diagnostics, fs) = diagnostics();
+		let output = diagnostics_to_json(diagnostics, &fs);
+
+		let expected = serde_json::json!([
+			{
+				"kind": "error",
+				"message": "Type 2 is not assignable to type string",
+				"path": "src/main.ts",
+				"start": { "line": 2, "column": 19 },
+				"end": { "line": 2, "column": 20 },
+				"labels": [{
+					"message": "Variable declared here",
+					"path": "src/main.ts",
+					"start": { "line": 1, "column": 5 },
+					"end": { "line": 1, "column": 6 },
+				}],
+				"notes": ["Consider a cast"],
+			},
+			{
+				"kind": "warning",
+				"message": "Unused variable",
+				"path": "src/main.ts",
+				"start": { "line": 2, "column": 7 },
+				"end": { "line": 2, "column": 8 },
+				"labels": [],
+				"notes": [],
+			},
+			{
+				"kind": "info",
+				"message": "Checked 1 file",
+				"labels": [],
+				"notes": [],
+			},
+		]);
+		assert_eq!(output, expected);
+	}
+
+	#[test]
+	fn sarif() {
+		let (diagnostics, fs) = diagnostics();
+		let output = diagnostics_to_sarif(diagnostics, &fs);
+
+		assert_eq!(output["$schema"], "https://json.schemastore.org/sarif-2.1.0.json");
+		assert_eq!(output["version"], "2.1.0");
+
+		let run = &output["runs"][0];
+		assert_eq!(run["tool"]["driver"]["name"], env!("CARGO_PKG_NAME"));
+		assert_eq!(run["columnKind"], "unicodeCodePoints");
+
+		let expected = serde_json::json!([
+			{
+				"ruleId": "error",
+				"level": "error",
+				"message": { "text": "Type 2 is not assignable to type string\nConsider a cast" },
+				"locations": [{
+					"physicalLocation": {
+						"artifactLocation": { "uri": "src/main.ts" },
+						"region": { "startLine": 2, "startColumn": 19, "endLine": 2, "endColumn": 20 },
+					}
+				}],
+				"relatedLocations": [{
+					"message": { "text": "Variable declared here" },
+					"physicalLocation": {
+						"artifactLocation": { "uri": "src/main.ts" },
+						"region": { "startLine": 1, "startColumn": 5, "endLine": 1, "endColumn": 6 },
+					}
+				}],
+			},
+			{
+				"ruleId": "warning",
+				"level": "warning",
+				"message": { "text": "Unused variable" },
+				"locations": [{
+					"physicalLocation": {
+						"artifactLocation": { "uri": "src/main.ts" },
+						"region": { "startLine": 2, "startColumn": 7, "endLine": 2, "endColumn": 8 },
+					}
+				}],
+				"relatedLocations": [],
+			},
+			{
+				"ruleId": "info",
+				"level": "note",
+				"message": { "text": "Checked 1 file" },
+				"locations": [],
+				"relatedLocations": [],
+			},
+		]);
+		assert_eq!(run["results"], expected);
+	}
+
+	#[test]
+	fn exit_code_on_errors() {
+		let files: HashMap<PathBuf, String> = [
+			(PathBuf::from("valid.ts"), "const a: number = 2;".to_owned()),
+			(PathBuf::from("invalid.ts"), "const a: number = \"hi\";".to_owned()),
+		]
+		.into_iter()
+		.collect();
+		let read_file = |path: &Path| files.get(path).cloned();
+
+		for format in ["json", "sarif"] {
+			let run = |input| {
+				crate::cli::run_cli(
+					&["check", input, "--format", format],
+					&read_file,
+					|_: &Path, _: String| {},
+					|_: &str| None,
+				)
+			};
+			assert_eq!(run("valid.ts"), ExitCode::SUCCESS);
+			assert_eq!(run("invalid.ts"), ExitCode::FAILURE);
+		}
+	}
+}
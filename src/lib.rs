@@ -1,8 +1,11 @@
 mod ast_explorer;
 mod build;
+mod bundle;
 mod check;
+mod config;
 mod error_handling;
 mod repl;
+mod watch;
 
 pub(crate) mod utilities;
 
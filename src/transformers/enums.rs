@@ -0,0 +1,52 @@
+use checker::{Constant, Type};
+use parser::{visiting::VisitorMut, ASTNode, Expression, NumberRepresentation, Quoted};
+
+use crate::check::CheckingOutputWithoutDiagnostics;
+
+/// Replaces accesses of `const enum` members with their value. Required as `const enum` declarations
+/// do not appear in the output
+pub struct ConstEnumInliner;
+
+impl VisitorMut<Expression, CheckingOutputWithoutDiagnostics> for ConstEnumInliner {
+	fn visit_mut(
+		&mut self,
+		item: &mut Expression,
+		data: &mut CheckingOutputWithoutDiagnostics,
+		chain: &parser::visiting::Chain,
+	) {
+		let (Expression::PropertyAccess { parent, position, .. }
+		| Expression::Index { indexee: parent, position, .. }) = item
+		else {
+			return;
+		};
+
+		let Some(instances) = data.type_mappings.expressions_to_instances.get(&chain.get_module())
+		else {
+			return;
+		};
+		let on_const_enum = instances
+			.get_exact(*parent.get_position())
+			.is_some_and(|on| data.type_mappings.const_enums.contains(&on.get_value_on_ref()));
+
+		if !on_const_enum {
+			return;
+		}
+
+		let Some(value) = instances.get_exact(*position) else { return };
+		let position = *position;
+		if let Type::Constant(constant) = data.types.get_type_by_id(value.get_value_on_ref()) {
+			match constant {
+				Constant::Number(number) => {
+					*item = Expression::NumberLiteral(
+						NumberRepresentation::from(number.into_inner()),
+						position,
+					);
+				}
+				Constant::String(value) => {
+					*item = Expression::StringLiteral(value.clone(), Quoted::Double, position);
+				}
+				_ => {}
+			}
+		}
+	}
+}
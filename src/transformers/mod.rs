@@ -1,3 +1,4 @@
+pub mod enums;
 pub mod optimisations;
 
 use parser::{visiting::BlockItemMut, Declaration, Module, StatementOrDeclaration};
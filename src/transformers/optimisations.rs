@@ -111,6 +111,7 @@ impl VisitorMut<BlockItemMut<'_>, CheckingOutputWithoutDiagnostics> for Statemen
 				| parser::Declaration::TypeAlias(_)
 				| parser::Declaration::DeclareVariable(_)
 				| parser::Declaration::DeclareFunction(_)
+				| parser::Declaration::DeclareNamespace(_)
 				| parser::Declaration::DeclareInterface(_)
 				| parser::Declaration::Export(_) => {}
 			}
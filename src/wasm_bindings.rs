@@ -29,9 +29,10 @@ pub fn experimental_build_wasm(
 	let result = crate::build::build(
 		vec![entry_path.into()],
 		&fs_resolver,
+		&[],
 		None,
 		Path::new("out.js"),
-		&crate::build::BuildConfig { strip_whitespace: minify },
+		&crate::build::BuildConfig { strip_whitespace: minify, source_maps: false, bundle: true },
 		None,
 	);
 
@@ -48,9 +49,8 @@ impl WASMCheckOutput {
 		serde_wasm_bindgen::to_value(&self.0.diagnostics).unwrap()
 	}
 
-	pub fn get_type_at_position(&self, path: &str, pos: u32) -> String {
-		todo!("requires facts_chain change")
-		// self.0.get_type_at_position(path, pos)
+	pub fn get_type_at_position(&self, path: &str, pos: u32) -> Option<String> {
+		self.0.get_type_at_position(Path::new(path), pos, false)
 	}
 }
 
@@ -64,7 +64,7 @@ pub fn check_wasm(entry_path: String, fs_resolver_js: &js_sys::Function) -> WASM
 
 		res.ok().and_then(|res| res.as_string())
 	};
-	WASMCheckOutput(crate::check::check(vec![entry_path.into()], &fs_resolver, None, None))
+	WASMCheckOutput(crate::check::check(vec![entry_path.into()], &fs_resolver, &[], None))
 }
 
 #[wasm_bindgen(js_name = check_with_options)]
@@ -83,7 +83,7 @@ pub fn check_wasm_with_options(
 
 		res.ok().and_then(|res| res.as_string())
 	};
-	WASMCheckOutput(crate::check::check(vec![entry_path.into()], &fs_resolver, None, Some(options)))
+	WASMCheckOutput(crate::check::check(vec![entry_path.into()], &fs_resolver, &[], Some(options)))
 }
 
 #[wasm_bindgen(js_name = run_cli)]
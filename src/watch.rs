@@ -0,0 +1,266 @@
+//! Re-running `check` and `build` when files in the module graph change
+
+use std::{
+	cell::RefCell,
+	collections::HashSet,
+	path::{Path, PathBuf},
+};
+
+use parser::source_map::{FileSystem, MapFileStore, WithPathMap};
+
+/// Where changes come from. [`NotifyEventSource`] for the file system, tests can use a fake
+pub trait WatchEventSource {
+	/// Called after each run with the paths of the module graph (every file that was read)
+	fn watch(&mut self, paths: &HashSet<PathBuf>);
+
+	/// Blocks until some of the watched files change. `None` stops watching
+	fn next_changes(&mut self) -> Option<HashSet<PathBuf>>;
+}
+
+/// Reads files through `read_file` once and afterwards only when they change. Also records the
+/// paths that are read so that they can be watched
+pub struct WatchedFiles<'a, T: crate::ReadFromFS> {
+	read_file: &'a T,
+	files: RefCell<MapFileStore<WithPathMap>>,
+	changed: RefCell<HashSet<PathBuf>>,
+	module_graph: RefCell<HashSet<PathBuf>>,
+}
+
+impl<'a, T: crate::ReadFromFS> WatchedFiles<'a, T> {
+	pub fn new(read_file: &'a T) -> Self {
+		Self {
+			read_file,
+			files: Default::default(),
+			changed: Default::default(),
+			module_graph: Default::default(),
+		}
+	}
+}
+
+impl<'a, T: crate::ReadFromFS> crate::ReadFromFS for WatchedFiles<'a, T> {
+	fn get_content_at_path(&self, path: &Path) -> Option<String> {
+		self.module_graph.borrow_mut().insert(path.to_path_buf());
+
+		let mut files = self.files.borrow_mut();
+		let existing = files.get_source_at_path(path);
+		if let (Some(source), false) = (existing, self.changed.borrow_mut().remove(path)) {
+			return Some(files.get_file_content(source));
+		}
+
+		let content = self.read_file.get_content_at_path(path)?;
+		files.create_or_update_file_at_path(path, content.clone());
+		Some(content)
+	}
+}
+
+/// Runs `run` and then again each time `events` reports a change to a file it read
+pub fn watch<T: crate::ReadFromFS, E: WatchEventSource>(
+	read_file: &T,
+	events: &mut E,
+	mut run: impl FnMut(&WatchedFiles<T>),
+) {
+	let files = WatchedFiles::new(read_file);
+
+	loop {
+		run(&files);
+
+		// Printed to stderr so that stdout can be parsed (for `--format json`)
+		let module_graph = files.module_graph.take();
+		events.watch(&module_graph);
+		eprintln!("Watching {} files for changes", module_graph.len());
+
+		let Some(changes) = events.next_changes() else {
+			break;
+		};
+
+		let mut changed_paths =
+			changes.iter().map(|path| path.display().to_string()).collect::<Vec<_>>();
+		changed_paths.sort();
+		eprintln!("{} changed, re-running", changed_paths.join(", "));
+
+		files.changed.borrow_mut().extend(changes);
+	}
+}
+
+/// Runs `run` once, or with `watch` each time the files it reads change
+pub fn run_or_watch<T: crate::ReadFromFS>(
+	read_file: &T,
+	watch: bool,
+	mut run: impl FnMut(&WatchedFiles<T>),
+) -> Result<(), String> {
+	if !watch {
+		run(&WatchedFiles::new(read_file));
+		return Ok(());
+	}
+
+	#[cfg(not(target_family = "wasm"))]
+	{
+		let mut events = NotifyEventSource::new().map_err(|err| err.to_string())?;
+		self::watch(read_file, &mut events, run);
+		Ok(())
+	}
+
+	#[cfg(target_family = "wasm")]
+	Err("watching is not supported on this platform".to_owned())
+}
+
+/// Changes from the file system, through `notify`
+#[cfg(not(target_family = "wasm"))]
+pub struct NotifyEventSource {
+	watcher: notify::RecommendedWatcher,
+	receiver: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+	/// Directories are watched (rather than the files) as editors often save by replacing the file.
+	/// Keys are the path under the canonical directory, values are the path as read
+	watching: std::collections::HashMap<PathBuf, PathBuf>,
+	watched_directories: HashSet<PathBuf>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl NotifyEventSource {
+	/// Editors can emit several events for one save, so events this close together are batched
+	const DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(50);
+
+	pub fn new() -> notify::Result<Self> {
+		let (sender, receiver) = std::sync::mpsc::channel();
+		let watcher = notify::recommended_watcher(sender)?;
+		Ok(Self {
+			watcher,
+			receiver,
+			watching: Default::default(),
+			watched_directories: Default::default(),
+		})
+	}
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl WatchEventSource for NotifyEventSource {
+	fn watch(&mut self, paths: &HashSet<PathBuf>) {
+		use notify::{RecursiveMode, Watcher};
+
+		for path in paths {
+			let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+				continue;
+			};
+			let parent = if parent.as_os_str().is_empty() { Path::new(".") } else { parent };
+			// Skips paths that are not on the file system (for example `internal.d.ts`)
+			let (true, Ok(directory)) = (path.exists(), parent.canonicalize()) else {
+				continue;
+			};
+
+			if !self.watched_directories.contains(&directory) {
+				if self.watcher.watch(&directory, RecursiveMode::NonRecursive).is_err() {
+					continue;
+				}
+				self.watched_directories.insert(directory.clone());
+			}
+			self.watching.insert(directory.join(file_name), path.clone());
+		}
+	}
+
+	fn next_changes(&mut self) -> Option<HashSet<PathBuf>> {
+		use std::sync::mpsc::RecvTimeoutError;
+
+		let mut changes = HashSet::new();
+		let mut event = self.receiver.recv().ok()?;
+
+		loop {
+			if let Ok(notify::Event { kind, paths, .. }) = event {
+				if !kind.is_access() {
+					changes.extend(
+						paths.into_iter().filter_map(|path| self.watching.get(&path).cloned()),
+					);
+				}
+			}
+
+			// Blocks until a change to a watched file
+			event = if changes.is_empty() {
+				self.receiver.recv().ok()?
+			} else {
+				match self.receiver.recv_timeout(Self::DEBOUNCE) {
+					Ok(event) => event,
+					Err(RecvTimeoutError::Timeout) => return Some(changes),
+					Err(RecvTimeoutError::Disconnected) => return None,
+				}
+			};
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		cell::RefCell,
+		collections::{HashMap, HashSet},
+		path::{Path, PathBuf},
+	};
+
+	use super::{watch, WatchEventSource};
+
+	/// Each batch updates the files and then is reported as changed
+	struct FakeEvents<'a> {
+		files: &'a RefCell<HashMap<PathBuf, String>>,
+		batches: Vec<Vec<(&'static str, &'static str)>>,
+		watched: Vec<HashSet<PathBuf>>,
+	}
+
+	impl<'a> WatchEventSource for FakeEvents<'a> {
+		fn watch(&mut self, paths: &HashSet<PathBuf>) {
+			self.watched.push(paths.clone());
+		}
+
+		fn next_changes(&mut self) -> Option<HashSet<PathBuf>> {
+			if self.batches.is_empty() {
+				return None;
+			}
+			let mut files = self.files.borrow_mut();
+			Some(
+				self.batches
+					.remove(0)
+					.into_iter()
+					.map(|(path, content)| {
+						files.insert(path.into(), content.to_owned());
+						PathBuf::from(path)
+					})
+					.collect(),
+			)
+		}
+	}
+
+	#[test]
+	fn rechecks_on_changes() {
+		let files = RefCell::new(HashMap::from([
+			(PathBuf::from("main.ts"), "import { a } from './a'; const b: number = a;".to_owned()),
+			(PathBuf::from("a.ts"), "export const a = 2;".to_owned()),
+		]));
+		let reads = RefCell::new(Vec::new());
+
+		let read_file = |path: &Path| {
+			reads.borrow_mut().push(path.to_path_buf());
+			files.borrow().get(path).cloned()
+		};
+
+		let mut events = FakeEvents {
+			files: &files,
+			batches: vec![
+				vec![("a.ts", "export const a = 'hi';")],
+				vec![("main.ts", "import { a } from './a'; const b: string = a;")],
+			],
+			watched: Vec::new(),
+		};
+
+		let mut results = Vec::new();
+		watch(&read_file, &mut events, |files| {
+			let output = crate::check(vec!["main.ts".into()], files, &[], None);
+			results.push(output.diagnostics.has_error());
+		});
+
+		assert_eq!(results, [false, true, false]);
+		assert_eq!(events.watched.len(), 3);
+		assert_eq!(events.watched[0], HashSet::from(["main.ts".into(), "a.ts".into()]));
+
+		// Unchanged files are not read again
+		let reads = reads.into_inner();
+		assert_eq!(reads.iter().filter(|path| *path == Path::new("main.ts")).count(), 2);
+		assert_eq!(reads.iter().filter(|path| *path == Path::new("a.ts")).count(), 2);
+	}
+}